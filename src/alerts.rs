@@ -0,0 +1,558 @@
+//! Threshold alerts on processes and system memory.
+//!
+//! [`AlertEngine::evaluate`] runs once per tick after the process list
+//! is refreshed. Alerts are edge-triggered: each fires once when its
+//! condition starts holding and re-arms only after it clears, so a
+//! process pinned at 100% produces one alert rather than one per tick.
+//!
+//! Watch rules fire instead on the PID-set diff: a process matching one
+//! of the `[alerts] watch` patterns is reported once, when it first
+//! shows up.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::time::{Instant, SystemTime};
+
+use crate::app::KERNEL_TASK;
+use crate::http::Url;
+use crate::json::{self, Object};
+use crate::monitor::{Pid, ProcessSample};
+use crate::pattern::Pattern;
+
+/// Alerts kept for the history, oldest dropped first.
+const HISTORY_LEN: usize = 100;
+
+/// What an alert is about.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AlertKind {
+    /// A process stayed above the CPU threshold.
+    ProcessCpu,
+    /// System memory use crossed the threshold.
+    Memory,
+    /// A process matching a watch rule started.
+    ProcessStart,
+    /// A `[kill]` rule matched a process.
+    KillRule,
+    /// `kernel_task` stayed above its CPU threshold, the sign of thermal throttling.
+    KernelTask,
+    /// A process's thread count grew faster than the threshold.
+    ThreadGrowth,
+    /// A process held about one full core with a single running thread.
+    SingleThread,
+}
+
+impl AlertKind {
+    /// Every kind, in config-name order.
+    const ALL: [Self; 7] = [
+        Self::ProcessCpu,
+        Self::Memory,
+        Self::ProcessStart,
+        Self::KillRule,
+        Self::KernelTask,
+        Self::ThreadGrowth,
+        Self::SingleThread,
+    ];
+
+    /// Parses a kind identifier as written in config.
+    pub fn parse(id: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|k| k.id() == id)
+    }
+
+    /// Identifier used in config keys and payloads.
+    pub fn id(self) -> &'static str {
+        match self {
+            Self::ProcessCpu => "process_cpu",
+            Self::Memory => "memory",
+            Self::ProcessStart => "process_start",
+            Self::KillRule => "kill_rule",
+            Self::KernelTask => "kernel_task",
+            Self::ThreadGrowth => "thread_growth",
+            Self::SingleThread => "single_thread",
+        }
+    }
+}
+
+/// What a watch rule does when a matching process appears.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WatchAction {
+    /// Highlight the process's row; no alert.
+    Highlight,
+    /// Highlight and raise an alert in the status bar and history.
+    #[default]
+    Alert,
+    /// Like [`WatchAction::Alert`], and also send it to the webhook.
+    Notify,
+}
+
+/// A process-name pattern from `[alerts] watch`.
+#[derive(Debug, Clone)]
+pub struct WatchRule {
+    /// Pattern matched against new processes' names.
+    pub pattern: Pattern,
+    /// What happens on a match.
+    pub action: WatchAction,
+}
+
+impl WatchRule {
+    /// Parses `<pattern> [=> highlight|alert|notify]`.
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let (pattern, action) = match text.rsplit_once("=>") {
+            Some((pattern, action)) => {
+                let action = match action.trim() {
+                    "highlight" => WatchAction::Highlight,
+                    "alert" => WatchAction::Alert,
+                    "notify" => WatchAction::Notify,
+                    other => {
+                        return Err(format!(
+                            "unknown action `{other}` (expected highlight, alert, or notify)"
+                        ));
+                    }
+                };
+                (pattern.trim(), action)
+            }
+            None => (text.trim(), WatchAction::default()),
+        };
+        if pattern.is_empty() {
+            return Err("missing pattern".into());
+        }
+        Ok(Self {
+            pattern: Pattern::new(pattern)?,
+            action,
+        })
+    }
+}
+
+/// Alert settings from the `[alerts]` config section.
+#[derive(Debug, Clone)]
+pub struct AlertConfig {
+    /// Whether [`AlertKind::ProcessCpu`] is enabled.
+    pub process_cpu: bool,
+    /// Per-process CPU percentage that counts as busy.
+    pub cpu_threshold: f64,
+    /// Seconds a process must stay busy before the alert fires.
+    pub cpu_seconds: f64,
+    /// Whether [`AlertKind::Memory`] is enabled.
+    pub memory: bool,
+    /// Used-memory percentage that fires the memory alert.
+    pub memory_threshold: f64,
+    /// Whether [`AlertKind::KernelTask`] is enabled.
+    pub kernel_task: bool,
+    /// `kernel_task` CPU percentage that suggests throttling.
+    pub kernel_threshold: f64,
+    /// Seconds `kernel_task` must stay above the threshold.
+    pub kernel_seconds: f64,
+    /// Whether [`AlertKind::ThreadGrowth`] is enabled.
+    pub thread_growth: bool,
+    /// Threads gained per minute that fire the thread-growth alert.
+    pub thread_growth_threshold: f64,
+    /// Whether [`AlertKind::SingleThread`] is enabled.
+    pub single_thread: bool,
+    /// Lowest CPU percentage that counts as one saturated core.
+    pub single_thread_min: f64,
+    /// Highest CPU percentage that still counts as one core, not two.
+    pub single_thread_max: f64,
+    /// Seconds a process must stay saturated before it is flagged.
+    pub single_thread_seconds: f64,
+    /// Endpoint that receives a JSON payload for each alert.
+    pub webhook_url: Option<Url>,
+    /// Patterns reported when a matching process starts.
+    pub watch: Vec<WatchRule>,
+}
+
+impl Default for AlertConfig {
+    fn default() -> Self {
+        Self {
+            process_cpu: true,
+            cpu_threshold: 90.0,
+            cpu_seconds: 30.0,
+            memory: true,
+            memory_threshold: 90.0,
+            kernel_task: true,
+            kernel_threshold: 50.0,
+            kernel_seconds: 30.0,
+            thread_growth: true,
+            thread_growth_threshold: 100.0,
+            single_thread: true,
+            single_thread_min: 95.0,
+            single_thread_max: 110.0,
+            single_thread_seconds: 60.0,
+            webhook_url: None,
+            watch: Vec::new(),
+        }
+    }
+}
+
+/// A fired alert.
+#[derive(Debug, Clone)]
+pub struct Alert {
+    /// Alert type.
+    pub kind: AlertKind,
+    /// Process the alert is about, for per-process alerts.
+    pub subject: Option<(Pid, String)>,
+    /// Extra context, e.g. the matched watch pattern and the process's parent.
+    pub detail: Option<String>,
+    /// Observed value.
+    pub value: f64,
+    /// Threshold the value crossed.
+    pub threshold: f64,
+    /// When the alert fired.
+    pub time: SystemTime,
+    /// Whether the alert goes to the webhook.
+    pub notify: bool,
+}
+
+impl Alert {
+    /// One-line human-readable description.
+    pub fn message(&self) -> String {
+        match (&self.subject, self.kind) {
+            (Some((pid, name)), AlertKind::ProcessStart) => {
+                let detail = self.detail.as_deref().unwrap_or_default();
+                format!("{name} ({pid}) started: {detail}")
+            }
+            (Some((pid, name)), AlertKind::KillRule) => {
+                let detail = self.detail.as_deref().unwrap_or_default();
+                format!("{name} ({pid}) {detail}")
+            }
+            (Some((pid, name)), AlertKind::KernelTask) => format!(
+                "{name} ({pid}) above {:.0}% CPU: {:.1}% — the Mac is likely throttling",
+                self.threshold, self.value
+            ),
+            (Some((pid, name)), AlertKind::SingleThread) => {
+                let detail = self.detail.as_deref().unwrap_or_default();
+                format!(
+                    "{name} ({pid}) held one core at {:.0}% on a single running thread \
+                     ({detail}) — likely a single-thread bottleneck",
+                    self.value
+                )
+            }
+            (Some((pid, name)), AlertKind::ThreadGrowth) => format!(
+                "{name} ({pid}) gaining threads faster than {:.0}/min: {:+.0}/min",
+                self.threshold, self.value
+            ),
+            (Some((pid, name)), _) => format!(
+                "{name} ({pid}) above {:.0}% CPU: {:.1}%",
+                self.threshold, self.value
+            ),
+            (None, AlertKind::Memory) => format!(
+                "memory use above {:.0}%: {:.1}%",
+                self.threshold, self.value
+            ),
+            (None, kind) => format!("{}: {:.1}", kind.id(), self.value),
+        }
+    }
+
+    /// The alert as a JSON webhook payload.
+    ///
+    /// `text` repeats [`Alert::message`] so chat webhooks (Slack and the
+    /// like) display something readable without a template.
+    pub fn to_json(&self, host: &str) -> String {
+        Object::new()
+            .str("type", self.kind.id())
+            .opt("subject", self.subject_json())
+            .opt("detail", self.detail.as_deref().map(json::string))
+            .num("value", self.value)
+            .num("threshold", self.threshold)
+            .str("timestamp", &json::timestamp(self.time))
+            .str("hostname", host)
+            .str("text", &format!("syswatch on {host}: {}", self.message()))
+            .finish()
+    }
+
+    /// The alert as a `--stream` line: `"type": "alert"`, with the alert
+    /// type under `kind` and the members of [`Alert::to_json`] otherwise.
+    pub fn stream_json(&self) -> String {
+        Object::new()
+            .str("type", "alert")
+            .str("kind", self.kind.id())
+            .opt("subject", self.subject_json())
+            .opt("detail", self.detail.as_deref().map(json::string))
+            .num("value", self.value)
+            .num("threshold", self.threshold)
+            .str("timestamp", &json::timestamp(self.time))
+            .str("text", &self.message())
+            .finish()
+    }
+
+    /// The subject process as a JSON object.
+    fn subject_json(&self) -> Option<String> {
+        self.subject
+            .as_ref()
+            .map(|(pid, name)| Object::new().int("pid", *pid).str("name", name).finish())
+    }
+}
+
+/// Tracks alert conditions across ticks and keeps the alert history.
+#[derive(Debug)]
+pub struct AlertEngine {
+    config: AlertConfig,
+    /// When each PID first went above the CPU threshold in its current run.
+    busy_since: HashMap<Pid, Instant>,
+    /// PIDs whose CPU alert has fired and not yet cleared.
+    cpu_fired: HashSet<Pid>,
+    /// PIDs whose thread-growth alert has fired and not yet cleared.
+    growth_fired: HashSet<Pid>,
+    /// When each PID entered its current single-core saturation streak.
+    saturated_since: HashMap<Pid, Instant>,
+    /// PIDs flagged as single-thread bottlenecks until their streak ends.
+    pub saturated: HashSet<Pid>,
+    /// Whether the memory alert has fired and not yet cleared.
+    memory_fired: bool,
+    /// When `kernel_task` went above its threshold, and whether that run has fired.
+    kernel_busy: Option<(Instant, bool)>,
+    /// PIDs present at the previous evaluation; `None` before the first.
+    known: Option<HashSet<Pid>>,
+    /// PIDs highlighted by a watch rule, for the rest of their lifetime.
+    pub watched: HashSet<Pid>,
+    /// Process never given a per-process alert (syswatch itself with `[ui] hide_self`).
+    pub ignored: Option<Pid>,
+    /// Recently fired alerts, oldest first.
+    pub history: VecDeque<Alert>,
+}
+
+impl AlertEngine {
+    /// Replaces the thresholds and rules, keeping what has already fired.
+    pub fn configure(&mut self, config: AlertConfig) {
+        self.config = config;
+    }
+
+    /// Creates an engine with no alerts fired.
+    pub fn new(config: AlertConfig) -> Self {
+        Self {
+            config,
+            busy_since: HashMap::new(),
+            cpu_fired: HashSet::new(),
+            growth_fired: HashSet::new(),
+            saturated_since: HashMap::new(),
+            saturated: HashSet::new(),
+            memory_fired: false,
+            kernel_busy: None,
+            known: None,
+            watched: HashSet::new(),
+            ignored: None,
+            history: VecDeque::new(),
+        }
+    }
+
+    /// Checks this tick's metrics and returns the alerts that just fired.
+    pub fn evaluate(&mut self, processes: &[ProcessSample], mem_pct: f64) -> Vec<Alert> {
+        let mut fired = Vec::new();
+        let now = Instant::now();
+
+        if self.config.process_cpu {
+            let threshold = self.config.cpu_threshold;
+            let alive: HashSet<Pid> = processes.iter().map(|p| p.pid).collect();
+            self.busy_since.retain(|pid, _| alive.contains(pid));
+            self.cpu_fired.retain(|pid| alive.contains(pid));
+
+            for p in processes.iter().filter(|p| Some(p.pid) != self.ignored) {
+                let cpu = f64::from(p.cpu_usage);
+                if cpu < threshold {
+                    self.busy_since.remove(&p.pid);
+                    self.cpu_fired.remove(&p.pid);
+                    continue;
+                }
+                let since = *self.busy_since.entry(p.pid).or_insert(now);
+                if now.duration_since(since).as_secs_f64() >= self.config.cpu_seconds
+                    && self.cpu_fired.insert(p.pid)
+                {
+                    fired.push(Alert {
+                        kind: AlertKind::ProcessCpu,
+                        subject: Some((p.pid, p.name.clone())),
+                        value: cpu,
+                        threshold,
+                        detail: None,
+                        time: SystemTime::now(),
+                        notify: true,
+                    });
+                }
+            }
+        }
+
+        if self.config.kernel_task {
+            self.watch_kernel(processes, now, &mut fired);
+        }
+
+        if self.config.thread_growth {
+            self.watch_growth(processes, &mut fired);
+        }
+
+        if self.config.single_thread {
+            self.watch_saturation(processes, now, &mut fired);
+        } else {
+            self.saturated_since.clear();
+            self.saturated.clear();
+        }
+
+        if !self.config.watch.is_empty() {
+            self.watch_new(processes, &mut fired);
+        }
+
+        if self.config.memory {
+            let threshold = self.config.memory_threshold;
+            let over = mem_pct >= threshold;
+            if over && !self.memory_fired {
+                fired.push(Alert {
+                    kind: AlertKind::Memory,
+                    subject: None,
+                    value: mem_pct,
+                    threshold,
+                    detail: None,
+                    time: SystemTime::now(),
+                    notify: true,
+                });
+            }
+            self.memory_fired = over;
+        }
+
+        for alert in &fired {
+            self.record(alert.clone());
+        }
+        fired
+    }
+
+    /// Appends an alert raised elsewhere (e.g. a kill rule) to the history.
+    pub fn record(&mut self, alert: Alert) {
+        if self.history.len() >= HISTORY_LEN {
+            self.history.pop_front();
+        }
+        self.history.push_back(alert);
+    }
+    /// Fires once per sustained run of `kernel_task` above its threshold.
+    fn watch_kernel(&mut self, processes: &[ProcessSample], now: Instant, fired: &mut Vec<Alert>) {
+        let threshold = self.config.kernel_threshold;
+        let Some(kernel) = processes
+            .iter()
+            .find(|p| p.name == KERNEL_TASK && f64::from(p.cpu_usage) >= threshold)
+        else {
+            self.kernel_busy = None;
+            return;
+        };
+
+        let (since, done) = self.kernel_busy.get_or_insert((now, false));
+        if !*done && now.duration_since(*since).as_secs_f64() >= self.config.kernel_seconds {
+            *done = true;
+            fired.push(Alert {
+                kind: AlertKind::KernelTask,
+                subject: Some((kernel.pid, kernel.name.clone())),
+                value: f64::from(kernel.cpu_usage),
+                threshold,
+                detail: None,
+                time: SystemTime::now(),
+                notify: true,
+            });
+        }
+    }
+
+    /// Fires once per process each time its thread growth rises to the threshold.
+    fn watch_growth(&mut self, processes: &[ProcessSample], fired: &mut Vec<Alert>) {
+        let threshold = self.config.thread_growth_threshold;
+        let growing: HashSet<Pid> = processes
+            .iter()
+            .filter(|p| Some(p.pid) != self.ignored)
+            .filter(|p| p.thread_growth.is_some_and(|g| g >= threshold))
+            .map(|p| p.pid)
+            .collect();
+        for p in processes.iter().filter(|p| growing.contains(&p.pid)) {
+            if !self.growth_fired.contains(&p.pid) {
+                fired.push(Alert {
+                    kind: AlertKind::ThreadGrowth,
+                    subject: Some((p.pid, p.name.clone())),
+                    value: p.thread_growth.unwrap_or_default(),
+                    threshold,
+                    detail: p.threads.map(|n| format!("{n} threads")),
+                    time: SystemTime::now(),
+                    notify: true,
+                });
+            }
+        }
+        self.growth_fired = growing;
+    }
+
+    /// Flags processes holding one core — CPU within the configured band
+    /// with exactly one running thread — for the configured time, firing
+    /// once per streak.
+    ///
+    /// A process with no run count (another user's, unprivileged) is
+    /// never flagged, since a multi-threaded one at 100% looks the same.
+    fn watch_saturation(
+        &mut self,
+        processes: &[ProcessSample],
+        now: Instant,
+        fired: &mut Vec<Alert>,
+    ) {
+        let (min, max) = (self.config.single_thread_min, self.config.single_thread_max);
+        let saturated: HashSet<Pid> = processes
+            .iter()
+            .filter(|p| Some(p.pid) != self.ignored && p.running == Some(1))
+            .filter(|p| (min..=max).contains(&f64::from(p.cpu_usage)))
+            .map(|p| p.pid)
+            .collect();
+        self.saturated_since
+            .retain(|pid, _| saturated.contains(pid));
+        self.saturated.retain(|pid| saturated.contains(pid));
+
+        for p in processes.iter().filter(|p| saturated.contains(&p.pid)) {
+            let since = *self.saturated_since.entry(p.pid).or_insert(now);
+            if now.duration_since(since).as_secs_f64() >= self.config.single_thread_seconds
+                && self.saturated.insert(p.pid)
+            {
+                fired.push(Alert {
+                    kind: AlertKind::SingleThread,
+                    subject: Some((p.pid, p.name.clone())),
+                    value: f64::from(p.cpu_usage),
+                    threshold: min,
+                    detail: Some(format!(
+                        "1 of {} threads running, for {:.0}s",
+                        p.threads.unwrap_or(1),
+                        self.config.single_thread_seconds
+                    )),
+                    time: SystemTime::now(),
+                    notify: true,
+                });
+            }
+        }
+    }
+
+    /// Matches processes that appeared since the last tick against the watch rules.
+    ///
+    /// The first call only records the PID set, so processes already
+    /// running at startup aren't reported.
+    fn watch_new(&mut self, processes: &[ProcessSample], fired: &mut Vec<Alert>) {
+        let alive: HashSet<Pid> = processes.iter().map(|p| p.pid).collect();
+        self.watched.retain(|pid| alive.contains(pid));
+        let Some(known) = self.known.replace(alive) else {
+            return;
+        };
+
+        for p in processes.iter().filter(|p| !known.contains(&p.pid)) {
+            let Some(rule) = self
+                .config
+                .watch
+                .iter()
+                .find(|r| r.pattern.is_match(&p.name))
+            else {
+                continue;
+            };
+            self.watched.insert(p.pid);
+            if rule.action == WatchAction::Highlight {
+                continue;
+            }
+
+            let parent = p
+                .parent
+                .map_or_else(|| "?".to_string(), |pid| pid.to_string());
+            let uid = p.uid.map_or_else(|| "?".to_string(), |uid| uid.to_string());
+            fired.push(Alert {
+                kind: AlertKind::ProcessStart,
+                subject: Some((p.pid, p.name.clone())),
+                value: f64::from(p.cpu_usage),
+                threshold: 0.0,
+                detail: Some(format!(
+                    "matches `{}`, parent {parent}, uid {uid}",
+                    rule.pattern
+                )),
+                time: SystemTime::now(),
+                notify: rule.action == WatchAction::Notify,
+            });
+        }
+    }
+}
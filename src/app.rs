@@ -1,162 +1,473 @@
 //! Application state and system-data collection.
 //!
-//! [`App`] owns the `sysinfo::System` handle, CPU tick history,
-//! process list, and all derived metrics displayed by the UI.
+//! [`App`] takes one [`Snapshot`] from its [`Monitor`] per tick and owns
+//! everything derived from it: history, alerts, the sorted process list,
+//! and the UI state.
 
-use std::collections::VecDeque;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fs;
+use std::io::{self, Write};
 use std::mem;
+use std::path::PathBuf;
+use std::time::{Duration, Instant, SystemTime};
 
 use ratatui::widgets::TableState;
-use sysinfo::{ProcessRefreshKind, ProcessesToUpdate, System};
+use sysinfo::Networks;
 
-/// Type alias for a macOS process identifier.
-type Pid = u32;
+use crate::alerts::{Alert, AlertEngine, AlertKind};
+use crate::battery::Battery;
+use crate::children::Timeline;
+use crate::cli::Options;
+use crate::collector::{
+    Compressor, MachMessages, MemoryPressure, ProcessIo, euid, hostname, mach_messages, port_count,
+    process_io,
+};
+use crate::config::{Config, MemoryMode};
+use crate::dashboard::Dashboard;
+use crate::disks::DiskIo;
+use crate::enforce::{self, Enforcer, KillMode};
+use crate::export::{self, Scope, Target};
+use crate::external;
+use crate::monitor::{DetailExtras, Monitor, MonitorOptions, Pid, ProcessSample};
+use crate::notify::{self, Channel, Dispatcher, Sink, Snooze};
+use crate::profile::{LOW_POWER_PROFILE, Profile, Settings};
+use crate::record::{Recorder, Sample};
+use crate::reload::ConfigWatch;
+use crate::sample::SampleJob;
+use crate::series::{Bucket, Rollup, Series};
+use crate::snapshot::Snapshot;
+use crate::socket::Server;
+use crate::state::{self, UiState};
+use crate::thermal::{ThermalLevel, ThermalMonitor};
+use crate::tick::Ticker;
+use crate::view::{COLUMNS, ProcessView, ViewRow};
+use crate::webhook::Webhook;
 
-/// Sliding-window width in seconds (3 minutes).
-const WINDOW: f64 = 180.0;
+/// Name of the kernel's own task, whose CPU and memory are tracked separately.
+pub const KERNEL_TASK: &str = "kernel_task";
 
-/// Maximum number of data-points kept per history deque.
-const HISTORY_LEN: usize = 180;
+/// Selectable chart window widths in seconds, shortest first (3 m … 24 h).
+pub const ZOOM_WINDOWS: [f64; 5] = [180.0, 900.0, 3600.0, 21_600.0, 86_400.0];
 
-// ── macOS mach FFI ──────────────────────────────────────────
+/// Width of one coarse-tier bucket in seconds.
+const MINUTE: f64 = 60.0;
 
-/// Mach host_statistics flavor for CPU load info.
-const HOST_CPU_LOAD_INFO: i32 = 3;
+/// Number of minute buckets kept in the coarse tier (24 hours).
+const MINUTE_HISTORY_LEN: usize = 1440;
 
-#[repr(C)]
-struct HostCpuLoadInfo {
-    cpu_ticks: [u32; 4],
+/// Seconds of history kept at tick resolution before rolling up to minutes.
+const HISTORY_SECONDS: f64 = 3600.0;
+
+/// Narrowest history slot in seconds; faster ticks share a slot.
+const MIN_HISTORY_SLOT: f64 = 0.1;
+
+/// Samples kept per process for the detail pane's CPU sparkline.
+const TRAIL_LEN: usize = 120;
+
+/// Seconds of memory history kept for the process in the detail view.
+const MEMORY_TRAIL_SECONDS: f64 = 3600.0;
+
+/// Rows moved by successive presses of a held `j` / `k`; the last step repeats.
+const NAV_STEPS: [i32; 5] = [1, 1, 2, 4, 8];
+
+/// Presses closer together than this count as one held key.
+const NAV_REPEAT_WINDOW: Duration = Duration::from_millis(200);
+
+/// Slot width and count of the fine tier at tick `interval`: one slot
+/// per tick, as many as [`HISTORY_SECONDS`] needs.
+fn history_slots(interval: Duration) -> (f64, usize) {
+    let slot = interval.as_secs_f64().max(MIN_HISTORY_SLOT);
+    (slot, (HISTORY_SECONDS / slot).ceil() as usize)
 }
 
-unsafe extern "C" {
-    fn mach_host_self() -> u32;
-    unsafe fn host_statistics(host: u32, flavor: i32, info: *mut i32, count: *mut u32) -> i32;
+/// Slots the detail view's memory trail needs at slot width `slot`.
+fn trail_slots(slot: f64) -> usize {
+    (MEMORY_TRAIL_SECONDS / slot).ceil() as usize
 }
 
-/// Returns the cached Mach host port (evaluated once).
-fn cached_host_port() -> u32 {
-    use std::sync::OnceLock;
-    static PORT: OnceLock<u32> = OnceLock::new();
-    *PORT.get_or_init(|| unsafe { mach_host_self() })
+/// Key-repeat acceleration for the navigation keys.
+#[derive(Debug, Default)]
+struct NavAccel {
+    /// Direction and time of the previous press.
+    last: Option<(i32, Instant)>,
+    /// Position in [`NAV_STEPS`] of the current run of presses.
+    streak: usize,
 }
 
-/// Reads aggregate CPU ticks from the Mach kernel.
-///
-/// Returns `[user, system, idle, nice]` as `u64`, or `None` on failure.
-fn get_cpu_ticks() -> Option<[u64; 4]> {
-    unsafe {
-        let mut info: HostCpuLoadInfo = mem::zeroed();
-        let mut count = (mem::size_of::<HostCpuLoadInfo>() / mem::size_of::<u32>()) as u32;
-        let ret = host_statistics(
-            cached_host_port(),
-            HOST_CPU_LOAD_INFO,
-            (&raw mut info).cast::<i32>(),
-            &mut count,
-        );
-        if ret == 0 {
-            Some(info.cpu_ticks.map(u64::from))
+impl NavAccel {
+    /// Returns the rows to move for a press in `direction` (±1) at `now`.
+    ///
+    /// A pause longer than [`NAV_REPEAT_WINDOW`] or a change of direction
+    /// starts over at one row.
+    fn step(&mut self, direction: i32, now: Instant) -> i32 {
+        let held = self.last.is_some_and(|(dir, at)| {
+            dir == direction && now.duration_since(at) < NAV_REPEAT_WINDOW
+        });
+        self.streak = if held {
+            (self.streak + 1).min(NAV_STEPS.len() - 1)
         } else {
-            None
+            0
+        };
+        self.last = Some((direction, now));
+        NAV_STEPS[self.streak]
+    }
+}
+
+/// The app's notification channels, as a [`Sink`] for the dispatcher.
+struct Outputs<'a> {
+    webhook: Option<&'a Webhook>,
+    host: &'a str,
+    flash: &'a mut bool,
+}
+
+impl Sink for Outputs<'_> {
+    fn deliver(&mut self, channel: Channel, alert: &Alert) {
+        match channel {
+            Channel::Bell => {
+                let mut out = io::stdout();
+                let _ = out.write_all(b"\x07").and_then(|()| out.flush());
+            }
+            Channel::Visual => *self.flash = true,
+            Channel::Notify => notify::banner("syswatch", &alert.message()),
+            Channel::Webhook => {
+                if let Some(webhook) = self.webhook {
+                    webhook.send(alert.to_json(self.host));
+                }
+            }
         }
     }
 }
 
-// ── macOS libproc FFI (per-process thread count) ────────────
-
-/// `proc_pidinfo` flavor for task-level info.
-const PROC_PIDTASKINFO: i32 = 4;
-
-#[repr(C)]
-struct ProcTaskInfo {
-    pti_virtual_size: u64,
-    pti_resident_size: u64,
-    pti_total_user: u64,
-    pti_total_system: u64,
-    pti_threads_user: u64,
-    pti_threads_system: u64,
-    pti_policy: i32,
-    pti_faults: i32,
-    pti_pageins: i32,
-    pti_cow_faults: i32,
-    pti_messages_sent: i32,
-    pti_messages_received: i32,
-    pti_syscalls_mach: i32,
-    pti_syscalls_unix: i32,
-    pti_csw: i32,
-    pti_threadnum: i32,
-    pti_numrunning: i32,
-    pti_priority: i32,
+/// Recent CPU percentages per process, for the detail pane's sparkline.
+#[derive(Debug, Default)]
+pub struct CpuTrails {
+    trails: HashMap<Pid, VecDeque<u64>>,
+}
+
+impl CpuTrails {
+    /// Appends one sample for `pid`, dropping the oldest beyond [`TRAIL_LEN`].
+    pub fn push(&mut self, pid: Pid, cpu: f32) {
+        let trail = self.trails.entry(pid).or_default();
+        if trail.len() == TRAIL_LEN {
+            trail.pop_front();
+        }
+        trail.push_back(cpu.round() as u64);
+    }
+
+    /// Returns `pid`'s samples, oldest first.
+    pub fn get(&self, pid: Pid) -> Option<&VecDeque<u64>> {
+        self.trails.get(&pid)
+    }
+
+    /// Forgets PIDs that are no longer alive.
+    pub fn prune(&mut self, alive: &HashSet<Pid>) {
+        self.trails.retain(|pid, _| alive.contains(pid));
+    }
 }
 
-unsafe extern "C" {
-    unsafe fn proc_pidinfo(
-        pid: i32,
-        flavor: i32,
-        arg: u64,
-        buffer: *mut libc::c_void,
-        buffersize: i32,
-    ) -> i32;
-    unsafe fn proc_listallpids(buffer: *mut libc::c_void, buffersize: i32) -> i32;
+/// Resident memory history of the process in the detail view.
+///
+/// Only one process is tracked: the buffer starts over whenever the
+/// detail view moves to another PID.
+#[derive(Debug)]
+pub struct MemoryTrail {
+    /// Process being tracked.
+    pub pid: Pid,
+    /// Chart x coordinate of the first sample.
+    pub since: f64,
+    /// Resident bytes, one sample per tick.
+    pub history: Series,
 }
 
-/// Sums thread counts across every running process via `proc_pidinfo`.
-fn total_thread_count() -> usize {
-    unsafe {
-        let num_pids = proc_listallpids(std::ptr::null_mut(), 0);
-        if num_pids <= 0 {
-            return 0;
+/// Mach IPC activity of the process in the detail view.
+///
+/// Like [`MemoryTrail`], only one process is tracked, so keeping the
+/// previous reading for a rate costs one task-info call per tick.
+#[derive(Debug)]
+pub struct IpcActivity {
+    /// Process being tracked.
+    pub pid: Pid,
+    /// Counters at the previous tick and when they were read.
+    last: Option<(MachMessages, Instant)>,
+    /// Messages sent and received per second over the last tick.
+    pub rates: Option<(f64, f64)>,
+    /// Why the message counters can't be read.
+    pub messages_error: Option<i32>,
+    /// Mach port names in the process's IPC space, or why they can't be counted.
+    pub ports: Result<u32, i32>,
+}
+
+/// Disk throughput of the process in the activity view.
+///
+/// Only the activity view's process is read, and the rates start over
+/// when its selection moves to another PID.
+#[derive(Debug)]
+pub struct DiskActivity {
+    /// Process being tracked.
+    pub pid: Pid,
+    /// Counters at the previous tick and when they were read.
+    last: Option<(ProcessIo, Instant)>,
+    /// Bytes read and written per second over the last tick.
+    pub rates: Option<(f64, f64)>,
+    /// Why the counters can't be read.
+    pub error: Option<i32>,
+}
+
+// ── Data ────────────────────────────────────────────────────
+
+/// Pre-formatted process-table cells, rebuilt only when the data changes.
+///
+/// Navigation redraws between ticks reuse these strings instead of
+/// re-formatting every cell of every row.
+#[derive(Debug, Default)]
+pub struct RowCache {
+    /// Cell text of the visible columns, parallel to `App::view.rows`
+    /// (separator rows carry their label in the name cell).
+    pub rows: Vec<Vec<String>>,
+    /// Whether `rows` reflects the current snapshot and settings.
+    pub valid: bool,
+}
+
+impl RowCache {
+    /// Marks the cache stale so the next frame re-formats every row.
+    pub fn invalidate(&mut self) {
+        self.valid = false;
+    }
+}
+
+/// Counters about syswatch itself, shown in the self-monitoring overlay.
+#[derive(Debug, Default)]
+pub struct DebugStats {
+    /// Whether the overlay is shown.
+    pub visible: bool,
+    /// Wall time spent drawing the previous frame.
+    pub frame_time: Duration,
+    /// Rows re-formatted while drawing the previous frame.
+    pub rows_formatted: usize,
+    /// Rows re-formatted over the whole session.
+    pub rows_formatted_total: u64,
+    /// Frames drawn over the whole session.
+    pub frames: u64,
+}
+
+/// View `--page` opens on at startup.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Page {
+    /// The process table, as without `--page`.
+    Processes,
+    /// The memory breakdown (`M`).
+    Memory,
+    /// Per-device disk throughput (`I`).
+    Disks,
+}
+
+impl Page {
+    /// Names accepted by [`Page::parse`], for error messages.
+    pub const NAMES: [&str; 3] = ["processes", "memory", "disk"];
+
+    /// Parses a page name as given on the command line.
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "processes" => Some(Self::Processes),
+            "memory" => Some(Self::Memory),
+            "disk" | "disks" => Some(Self::Disks),
+            _ => None,
         }
+    }
 
-        let mut pids = vec![0i32; num_pids as usize * 2];
-        let bufsize = (pids.len() * mem::size_of::<i32>()) as i32;
-        let actual = proc_listallpids(pids.as_mut_ptr().cast::<libc::c_void>(), bufsize);
-        if actual <= 0 {
-            return 0;
+    /// The popup showing the page, or `None` for the main view.
+    pub fn popup(self) -> Option<Popup> {
+        match self {
+            Self::Processes => None,
+            Self::Memory => Some(Popup::Memory),
+            Self::Disks => Some(Popup::Disks { selected: 0 }),
         }
+    }
+}
 
-        let expected = mem::size_of::<ProcTaskInfo>() as i32;
-        pids[..actual as usize]
-            .iter()
-            .map(|&pid| {
-                let mut info: ProcTaskInfo = mem::zeroed();
-                let ret = proc_pidinfo(
-                    pid,
-                    PROC_PIDTASKINFO,
-                    0,
-                    (&raw mut info).cast::<libc::c_void>(),
-                    expected,
-                );
-                if ret == expected {
-                    info.pti_threadnum.max(0) as usize
-                } else {
-                    0
-                }
-            })
-            .sum()
+/// Modal overlay drawn above the main layout.
+#[derive(Debug)]
+pub enum Popup {
+    /// Call-stack sample of a single process.
+    Sample(SampleJob),
+    /// Detail view of one process, locked to its PID.
+    Detail {
+        /// Process shown in the view.
+        pid: Pid,
+    },
+    /// Live CPU, memory, I/O, fd and thread tiles for the selected
+    /// process, following the selection.
+    Activity,
+    /// Spawn/exit timeline of [`App::children`].
+    Children {
+        /// Index of the first record shown.
+        scroll: usize,
+    },
+    /// System memory breakdown with the compressor's activity.
+    Memory,
+    /// Per-device disk throughput.
+    Disks {
+        /// Highlighted index into the device list.
+        selected: usize,
+    },
+    /// Target picker for exporting the chart history.
+    Export {
+        /// Highlighted index into [`export::TARGETS`].
+        selected: usize,
+        /// Range of history exported.
+        scope: Scope,
+    },
+    /// Picker over the configured "open with" command templates.
+    OpenWith {
+        /// Highlighted template index.
+        selected: usize,
+    },
+    /// Signal picker and confirmation for killing a process.
+    Kill {
+        /// Process to signal.
+        pid: Pid,
+        /// Name shown in the confirmation.
+        name: String,
+        /// Highlighted index into [`SIGNALS`].
+        selected: usize,
+        /// Process group to signal instead of the process, if toggled on.
+        group: Option<Pid>,
+    },
+    /// Final confirmation before signalling a whole process group.
+    GroupKill {
+        /// Group to signal.
+        pgid: Pid,
+        /// Index into [`SIGNALS`].
+        signal: usize,
+        /// `(pid, name)` of every member in the current snapshot.
+        members: Vec<(Pid, String)>,
+    },
+    /// Snooze length picker for notifications.
+    Snooze {
+        /// Highlighted index into [`Snooze::ALL`]; one past the end is "resume".
+        selected: usize,
+    },
+    /// Column order and visibility editor.
+    Columns(ColumnEditor),
+    /// Sort column menu.
+    Sort {
+        /// Highlighted index into [`COLUMNS`].
+        selected: usize,
+    },
+    /// Filter preset list; row 0 is "no filter", row `i` is preset `i - 1`.
+    Filters {
+        /// Highlighted row.
+        selected: usize,
+    },
+    /// Key binding reference, optionally filtered.
+    Help {
+        /// Case-insensitive text the bindings are filtered by.
+        query: String,
+        /// Whether keystrokes are editing `query`.
+        editing: bool,
+    },
+}
+
+impl Popup {
+    /// The unfiltered help overlay.
+    pub fn help() -> Self {
+        Self::Help {
+            query: String::new(),
+            editing: false,
+        }
     }
 }
 
-// ── Data ────────────────────────────────────────────────────
+/// Draft column layout being edited in [`Popup::Columns`].
+///
+/// Every column is listed, visible ones first in their current order;
+/// nothing changes on screen until the draft is applied.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ColumnEditor {
+    /// Every column as `(index into COLUMNS, visible)`, in display order.
+    pub order: Vec<(usize, bool)>,
+    /// Highlighted row.
+    pub selected: usize,
+}
 
-/// Snapshot of a single process shown in the table.
-#[derive(Debug, Clone)]
-pub struct ProcessInfo {
-    /// macOS process identifier.
-    pub pid: Pid,
-    /// Display name of the process.
-    pub name: String,
-    /// Instantaneous CPU usage percentage.
-    pub cpu_usage: f32,
-    /// Resident memory in bytes.
-    pub memory: u64,
+impl ColumnEditor {
+    /// A draft of the layout `visible`, with hidden columns after it.
+    pub fn new(visible: &[usize]) -> Self {
+        let hidden = (0..COLUMNS.len()).filter(|c| !visible.contains(c));
+        Self {
+            order: visible
+                .iter()
+                .map(|&c| (c, true))
+                .chain(hidden.map(|c| (c, false)))
+                .collect(),
+            selected: 0,
+        }
+    }
+
+    /// Moves the highlight by `delta` rows, clamped to the list.
+    pub fn select(&mut self, delta: isize) {
+        self.selected = self
+            .selected
+            .saturating_add_signed(delta)
+            .min(self.order.len() - 1);
+    }
+
+    /// Moves the highlighted column `delta` places, keeping it highlighted.
+    pub fn shift(&mut self, delta: isize) {
+        let from = self.selected;
+        self.select(delta);
+        self.order.swap(from, self.selected);
+    }
+
+    /// Shows or hides the highlighted column; the last visible one stays.
+    pub fn toggle(&mut self) {
+        let visible = self.order.iter().filter(|&&(_, v)| v).count();
+        let row = &mut self.order[self.selected];
+        if !row.1 || visible > 1 {
+            row.1 = !row.1;
+        }
+    }
+
+    /// The visible columns, in order.
+    pub fn visible(&self) -> Vec<usize> {
+        self.order
+            .iter()
+            .filter(|&&(_, v)| v)
+            .map(|&(c, _)| c)
+            .collect()
+    }
 }
 
-/// Central application state — owns system handles, metrics, and UI state.
+/// Signals offered by the kill dialog, most common first.
+pub const SIGNALS: [(&str, i32); 6] = [
+    ("TERM", libc::SIGTERM),
+    ("KILL", libc::SIGKILL),
+    ("INT", libc::SIGINT),
+    ("HUP", libc::SIGHUP),
+    ("STOP", libc::SIGSTOP),
+    ("CONT", libc::SIGCONT),
+];
+
+/// How long a status-bar message stays visible.
+const STATUS_TTL: Duration = Duration::from_secs(5);
+
+/// Percentage points above `[battery] low_power` the charge must reach
+/// before low-power mode disengages, so it doesn't flap.
+const LOW_POWER_HYSTERESIS: f64 = 5.0;
+
+/// Central application state — owns the monitor, metrics, and UI state.
 #[derive(Debug)]
 pub struct App {
-    sys: System,
-    prev_ticks: Option<[u64; 4]>,
-    tick_count: u64,
+    /// Settings loaded from the config file.
+    pub config: Config,
+    /// `--config` path, re-read on reload.
+    config_path: Option<PathBuf>,
+    /// Change detection for the config file.
+    config_watch: ConfigWatch,
+    /// Source of each tick's system data.
+    pub monitor: Monitor,
+    networks: Networks,
+    last_net_refresh: Instant,
 
     /// System (kernel) CPU percentage.
     pub system_pct: f64,
@@ -165,177 +476,1721 @@ pub struct App {
     /// Idle CPU percentage.
     pub idle_pct: f64,
 
-    /// Time-series of `(tick, system_pct)` for the chart.
-    pub system_history: VecDeque<(f64, f64)>,
-    /// Time-series of `(tick, user_pct)` for the chart.
-    pub user_history: VecDeque<(f64, f64)>,
+    /// Per-tick `system_pct` history for the chart.
+    pub system_history: Series,
+    /// Per-tick `user_pct` history for the chart.
+    pub user_history: Series,
+    /// Utilization of the busiest core this tick; `None` unless `[cpu] per_core` is on.
+    pub max_core_pct: Option<f64>,
+    /// Per-tick `max_core_pct` history, aligned with `system_history`.
+    pub max_core_history: Series,
+    /// Per-tick used-memory percentage history.
+    pub memory_history: Series,
+    /// Minute mean/max of total CPU, fed by samples rolling off the fine tier.
+    pub cpu_minutes: Rollup,
+    /// Minute mean/max of used-memory percentage, fed like `cpu_minutes`.
+    pub memory_minutes: Rollup,
+    /// Thermal pressure source; `None` when the OS doesn't publish it.
+    thermal: Option<ThermalMonitor>,
+    /// Latest thermal pressure level.
+    pub thermal_level: Option<ThermalLevel>,
+    /// Battery charge and drain over the session.
+    pub battery: Battery,
+    /// Per-device disk throughput.
+    pub disks: DiskIo,
+    /// Per-second thermal level, aligned sample-for-sample with the CPU history.
+    thermal_history: Series,
+    /// Minute buckets of thermal level, fed like `cpu_minutes`; `max` is the worst level.
+    thermal_minutes: Rollup,
+    /// Chart samples that came late, as `(x, seconds since the previous
+    /// sample)`, oldest first; pruned with the fine tier.
+    late_samples: VecDeque<(f64, f64)>,
+    /// Index into the chart window widths (0 = 3 minutes).
+    pub zoom: usize,
+    /// Whether the CPU chart's first (system / max), second (user / mean),
+    /// and third (max core) series are plotted.
+    pub series_visible: [bool; 3],
 
-    /// Total thread count across all processes.
+    /// Total thread count across all inspectable processes.
     pub thread_count: usize,
+    /// Processes in App Nap or suspended by the OS.
+    pub napping: usize,
+    /// When syswatch started.
+    pub started: Instant,
+    /// Tick interval scheduler (normal / idle).
+    pub ticker: Ticker,
+    /// Destination for `--record` / `--influx-url` samples.
+    pub recorder: Option<Recorder>,
+    /// Query socket fed with a snapshot every tick.
+    pub server: Option<Server>,
+    /// `--http` status page, fed like `server`.
+    pub dashboard: Option<Dashboard>,
+    /// Alert conditions and history.
+    pub alerts: AlertEngine,
+    /// Alerts fired during the latest tick. Every output (recording,
+    /// `--stream`) reads them after the tick; the next tick clears them.
+    pub events: Vec<Alert>,
+    /// Delivery of fired alerts to `[alerts] webhook_url`.
+    pub webhook: Option<Webhook>,
+    /// MQTT publisher for `--mqtt`.
+    #[cfg(feature = "mqtt")]
+    pub mqtt: Option<crate::mqtt::Publisher>,
+    /// Routes fired alerts to their `[notify]` channels.
+    dispatcher: Dispatcher,
+    /// Whether the status bar is inverted for the next frame (visual bell).
+    pub flash: bool,
+    /// `[kill]` rule evaluation.
+    enforcer: Enforcer,
+    /// Whether `auto` kill rules may send signals (`--enable-auto-kill`).
+    auto_kill: bool,
+    /// Host name reported in alert payloads.
+    hostname: String,
+    /// PIDs of other syswatch instances running at startup.
+    pub other_instances: Vec<Pid>,
     /// Total physical memory in bytes.
     pub total_memory: u64,
-    /// Used physical memory in bytes.
+    /// Used physical memory in bytes, per `memory_mode`.
     pub used_memory: u64,
+    /// Mode that produced `used_memory` (falls back to sysinfo if VM stats fail).
+    pub memory_mode: MemoryMode,
+    /// Wired kernel memory in bytes, when VM statistics are readable.
+    pub wired_memory: Option<u64>,
+    /// Compressor-held memory in bytes, when VM statistics are readable.
+    pub compressed_memory: Option<u64>,
+    /// File-backed cached memory in bytes, when VM statistics are readable.
+    pub cached_memory: Option<u64>,
+    /// Memory compressor sizes and counters, when VM statistics are readable.
+    pub compressor: Option<Compressor>,
+    /// When `compressor` was read.
+    compressor_time: SystemTime,
+    /// Pages compressed and decompressed per second over the last tick.
+    pub compressor_rates: Option<(f64, f64)>,
+    /// Compressions per second, one sample per tick.
+    pub compression_history: Series,
+    /// Decompressions per second, one sample per tick.
+    pub decompression_history: Series,
+    /// Kernel memory-pressure level, when readable.
+    pub memory_pressure: Option<MemoryPressure>,
+    /// `kernel_task`'s CPU percentage, if it is visible.
+    pub kernel_cpu: Option<f32>,
+    /// Total swap space in bytes.
+    pub total_swap: u64,
+    /// Used swap space in bytes.
+    pub used_swap: u64,
 
-    /// Process list sorted by descending CPU usage.
-    pub processes: Vec<ProcessInfo>,
+    /// Network receive rate in bytes per second (all non-loopback interfaces).
+    pub net_rx_rate: f64,
+    /// Network transmit rate in bytes per second (all non-loopback interfaces).
+    pub net_tx_rate: f64,
+
+    /// Process snapshot, sorted by the view's sort key.
+    pub processes: Vec<ProcessSample>,
+    /// Sort, filter, and tree settings plus the resulting table rows.
+    pub view: ProcessView,
+    /// Whether keystrokes are currently editing the name filter.
+    pub filter_editing: bool,
     /// Ratatui table selection state.
     pub table_state: TableState,
+    /// Rows that fit in the table at the last draw; one PgUp / PgDn step.
+    pub page_rows: usize,
+    /// Formatted table cells for `processes`.
+    pub row_cache: RowCache,
+    /// Self-monitoring counters.
+    pub debug: DebugStats,
     selected_pid: Option<Pid>,
+    /// Whether syswatch runs as root, enabling the fd/socket/cwd/env collectors.
+    pub privileged: bool,
+    /// Extras for the process shown in the detail view.
+    pub detail: Option<DetailExtras>,
+    /// Descendant timeline of the process locked by the children view.
+    pub children: Option<Timeline>,
+    /// Per-process CPU history for the detail pane.
+    pub cpu_trails: CpuTrails,
+    /// Mach IPC activity of the detail view's process, if it is open.
+    pub ipc: Option<IpcActivity>,
+    /// Memory history of the detail or activity view's process, if one is open.
+    pub memory_trail: Option<MemoryTrail>,
+    /// Disk throughput of the activity view's process, if it is open.
+    pub disk_activity: Option<DiskActivity>,
+    /// Whether the detail pane is docked beside the table.
+    pub dock: bool,
+    /// Acceleration state for held `j` / `k`.
+    nav: NavAccel,
+    /// Modal overlay currently shown, if any.
+    pub popup: Option<Popup>,
+    /// External command waiting for the event loop to suspend the TUI and run it.
+    pub pending_command: Option<external::Expanded>,
+    /// Transient message for the status bar and when it was set.
+    status: Option<(String, Instant)>,
+    /// Whether the single-line compact layout is forced on.
+    pub compact: bool,
+    /// Whether table cells are colored relative to the table's largest value.
+    pub relative_colors: bool,
     /// Whether the event loop should keep running.
     pub running: bool,
+    /// Index of the active profile in `config.profiles`.
+    pub profile: Option<usize>,
+    /// Whether `-c` was given, which wins over every profile.
+    cli_compact: bool,
+    /// Whether the low-power layer is applied on top of the profile.
+    pub low_power: bool,
+    /// Whether the process on screen gets its detail extras.
+    pub extras: bool,
+    /// Shortest time between network and disk refreshes; zero is every tick.
+    io_interval: Duration,
+    /// Choices remembered from earlier sessions.
+    ui_state: UiState,
+    /// Where `ui_state` is saved, if there is a home directory.
+    ui_state_path: Option<PathBuf>,
 }
 
 impl App {
     /// Creates a new `App`, performing an initial full system refresh.
-    pub fn new() -> Self {
-        let mut sys = System::new_all();
-        sys.refresh_all();
+    pub fn new(opts: &Options, config: Config) -> Self {
+        let monitor = Monitor::new(Self::monitor_options(&config));
+        Self::with_monitor(opts, config, monitor, state::default_path())
+    }
 
+    /// What a monitor should collect for `config`.
+    pub fn monitor_options(config: &Config) -> MonitorOptions {
+        MonitorOptions {
+            memory_mode: config.memory_mode,
+            per_core: config.per_core,
+            privileged: euid() == 0,
+            high_count: config.high_count,
+            lean: false,
+        }
+    }
+
+    /// Creates an `App` fed by `monitor`, remembering interface choices
+    /// at `ui_state_path`.
+    ///
+    /// A [`Monitor::from_snapshots`] monitor with no state path runs the
+    /// app on prepared data without touching the user's files.
+    pub fn with_monitor(
+        opts: &Options,
+        config: Config,
+        monitor: Monitor,
+        ui_state_path: Option<PathBuf>,
+    ) -> Self {
+        let privileged = monitor.privileged();
         let mut table_state = TableState::default();
         table_state.select(Some(0));
+        let mut view = ProcessView {
+            bands: config.cpu_bands,
+            demote_kernel: config.demote_kernel_task,
+            tree: opts.tree,
+            groups: config.tree_groups,
+            hidden: config.hide_self.then(std::process::id),
+            ..ProcessView::default()
+        };
+        let ui_state = ui_state_path
+            .as_deref()
+            .map(UiState::load)
+            .unwrap_or_default();
+        if let Some(columns) = ui_state.columns.as_ref().or(config.columns.as_ref()) {
+            view.columns.clone_from(columns);
+        }
+        let status = config
+            .warnings
+            .first()
+            .map(|w| (format!("config: {w}"), Instant::now()));
+        let ticker = Ticker::new(config.tick_interval, config.adaptive_tick);
+        let (slot, slots) = history_slots(config.tick_interval);
+        let mut alerts = AlertEngine::new(config.alerts.clone());
+        alerts.ignored = view.hidden;
+        let webhook = config.alerts.webhook_url.clone().map(Webhook::spawn);
+        let enforcer = Enforcer::new(config.kill_rules.clone());
+        let dispatcher = Dispatcher::new(config.notify.clone());
+        let dock = config.detail_dock;
+        let relative_colors = config.relative_colors;
 
-        Self {
-            sys,
-            prev_ticks: get_cpu_ticks(),
-            tick_count: 0,
+        let mut app = Self {
+            config,
+            config_path: opts.config.clone(),
+            config_watch: ConfigWatch::new(Config::path(opts.config.as_deref())),
+            monitor,
+            networks: Networks::new_with_refreshed_list(),
+            last_net_refresh: Instant::now(),
             system_pct: 0.0,
             user_pct: 0.0,
             idle_pct: 0.0,
-            system_history: VecDeque::with_capacity(HISTORY_LEN),
-            user_history: VecDeque::with_capacity(HISTORY_LEN),
+            system_history: Series::new(0.0, slot, slots),
+            user_history: Series::new(0.0, slot, slots),
+            max_core_pct: None,
+            max_core_history: Series::new(0.0, slot, slots),
+            memory_history: Series::new(0.0, slot, slots),
+            cpu_minutes: Rollup::new(MINUTE, MINUTE_HISTORY_LEN),
+            memory_minutes: Rollup::new(MINUTE, MINUTE_HISTORY_LEN),
+            thermal: ThermalMonitor::new(),
+            thermal_level: None,
+            battery: Battery::default(),
+            disks: DiskIo::default(),
+            thermal_history: Series::new(0.0, slot, slots),
+            thermal_minutes: Rollup::new(MINUTE, MINUTE_HISTORY_LEN),
+            late_samples: VecDeque::new(),
+            zoom: 0,
+            series_visible: [true; 3],
             thread_count: 0,
+            napping: 0,
+            started: Instant::now(),
+            ticker,
+            recorder: None,
+            server: None,
+            dashboard: None,
+            alerts,
+            events: Vec::new(),
+            webhook,
+            #[cfg(feature = "mqtt")]
+            mqtt: None,
+            dispatcher,
+            flash: false,
+            enforcer,
+            auto_kill: opts.enable_auto_kill,
+            hostname: hostname(),
+            other_instances: Vec::new(),
             total_memory: 0,
             used_memory: 0,
+            memory_mode: MemoryMode::Sysinfo,
+            wired_memory: None,
+            compressed_memory: None,
+            cached_memory: None,
+            compressor: None,
+            compressor_time: SystemTime::UNIX_EPOCH,
+            compressor_rates: None,
+            compression_history: Series::new(0.0, slot, slots),
+            decompression_history: Series::new(0.0, slot, slots),
+            memory_pressure: None,
+            kernel_cpu: None,
+            total_swap: 0,
+            used_swap: 0,
+            net_rx_rate: 0.0,
+            net_tx_rate: 0.0,
             processes: Vec::new(),
+            view,
+            filter_editing: false,
             table_state,
+            page_rows: 1,
+            row_cache: RowCache::default(),
+            debug: DebugStats::default(),
             selected_pid: None,
+            privileged,
+            detail: None,
+            children: None,
+            cpu_trails: CpuTrails::default(),
+            ipc: None,
+            disk_activity: None,
+            memory_trail: None,
+            dock,
+            nav: NavAccel::default(),
+            popup: opts.page.and_then(Page::popup),
+            pending_command: None,
+            status,
+            compact: opts.compact,
+            relative_colors,
             running: true,
+            profile: None,
+            cli_compact: opts.compact,
+            low_power: false,
+            extras: true,
+            io_interval: Duration::ZERO,
+            ui_state,
+            ui_state_path,
+        };
+        if let Some(name) = &opts.profile {
+            let index = app.config.profiles.iter().position(|p| p.name == *name);
+            app.apply_profile(index);
         }
+        app
     }
 
-    /// Advances state by one tick: refreshes CPU, memory, processes, threads.
+    /// Advances state by one tick: samples the system and updates everything derived from it.
     pub fn tick(&mut self) {
-        self.update_cpu_split();
-        self.update_processes();
-        self.thread_count = total_thread_count();
-        self.tick_count += 1;
+        let late = self.ticker.on_applied();
+        self.events.clear();
+        let snapshot = self.monitor.sample();
+        self.update_cpu(&snapshot);
+        if let Some(gap) = late {
+            self.mark_late(gap);
+        }
+        self.update_processes(snapshot);
+        self.check_alerts();
+        self.battery.poll();
+        self.update_low_power();
+        if self.last_net_refresh.elapsed() >= self.io_interval {
+            self.update_network();
+            self.disks.update(self.sample_x());
+        }
+        self.record_sample();
+        if self.server.is_some() || self.dashboard.is_some() {
+            let snapshot = Snapshot::from_app(self);
+            if let Some(dashboard) = &self.dashboard {
+                dashboard.publish(snapshot.clone());
+            }
+            if let Some(server) = &self.server {
+                server.publish(snapshot);
+            }
+        }
+        #[cfg(feature = "mqtt")]
+        if let Some(mut mqtt) = self.mqtt.take() {
+            mqtt.on_tick(|| crate::mqtt::summary(self, &self.hostname));
+            self.mqtt = Some(mqtt);
+        }
+        if self.config_watch.due() {
+            self.reload_config();
+        }
+    }
+
+    /// Re-reads the config file and applies it in place, keeping all
+    /// collected history.
+    ///
+    /// Settings toggled at runtime (bands, relative colors, the dock) are
+    /// only re-applied if their config value changed. Collector settings
+    /// would splice two different measurements into the history, so they
+    /// keep their running values and are reported as needing a restart.
+    pub fn reload_config(&mut self) {
+        let mut config = match Config::load(self.config_path.as_deref()) {
+            Ok(config) => config,
+            Err(err) => {
+                self.set_status(format!("config not reloaded: {err}"));
+                return;
+            }
+        };
+
+        let mut restart = Vec::new();
+        if config.memory_mode != self.config.memory_mode {
+            restart.push("[memory] mode");
+            config.memory_mode = self.config.memory_mode;
+        }
+        if config.per_core != self.config.per_core {
+            restart.push("[cpu] per_core");
+            config.per_core = self.config.per_core;
+        }
+        if config.high_count != self.config.high_count {
+            restart.push("[tick] high_count");
+            config.high_count = self.config.high_count;
+        }
+        let old = mem::replace(&mut self.config, config);
+        let new = &self.config;
+
+        if new.cpu_bands != old.cpu_bands {
+            self.view.bands = new.cpu_bands;
+        }
+        if new.demote_kernel_task != old.demote_kernel_task {
+            self.view.demote_kernel = new.demote_kernel_task;
+        }
+        self.view.groups = new.tree_groups;
+        if new.relative_colors != old.relative_colors {
+            self.relative_colors = new.relative_colors;
+        }
+        if new.detail_dock != old.detail_dock {
+            self.dock = new.detail_dock;
+        }
+        self.view.hidden = new.hide_self.then(std::process::id);
+        self.alerts.ignored = self.view.hidden;
+        self.alerts.configure(new.alerts.clone());
+        if new.alerts.webhook_url != old.alerts.webhook_url {
+            self.webhook = new.alerts.webhook_url.clone().map(Webhook::spawn);
+        }
+        self.dispatcher.configure(new.notify.clone());
+        self.enforcer.configure(new.kill_rules.clone());
+
+        let active = self.profile.map(|i| &old.profiles[i]);
+        let profile = active.and_then(|a| new.profiles.iter().position(|p| p.name == a.name));
+        let profile_changed = active != profile.map(|i| &new.profiles[i]);
+        if profile_changed
+            || new.columns != old.columns
+            || new.tick_interval != old.tick_interval
+            || new.adaptive_tick != old.adaptive_tick
+        {
+            self.apply_profile(profile);
+        } else {
+            self.profile = profile;
+            let preset = |config: &Config, name: &str| {
+                config.filters.iter().find(|(n, _)| n == name).cloned()
+            };
+            if let Some(name) = self.view.preset.clone()
+                && preset(&old, &name) != preset(&self.config, &name)
+            {
+                match self.preset_index() {
+                    Some(i) => self.apply_preset(i),
+                    None => self.clear_filter(),
+                }
+            }
+            self.resort();
+            if self.low_power {
+                // An edited [profile.low-power] applies at once.
+                let settings = self.settings();
+                self.apply_costs(&settings);
+            }
+        }
+
+        let mut msg = String::from("config reloaded");
+        if let Some(warning) = self.config.warnings.first() {
+            msg.push_str(&format!(": {warning}"));
+        }
+        if !restart.is_empty() {
+            msg.push_str(&format!("; restart to apply {}", restart.join(", ")));
+        }
+        self.set_status(msg);
+    }
+
+    /// Moves the selection one step in `direction` (±1), accelerating while the key is held.
+    pub fn navigate(&mut self, direction: i32) {
+        let step = if self.config.accelerate_keys {
+            self.nav.step(direction, Instant::now())
+        } else {
+            1
+        };
+        self.select_process(direction * step);
     }
 
     /// Moves the process-table selection by `offset` rows (clamped).
+    ///
+    /// Separator rows are skipped in the direction of travel; they only
+    /// sit between bands, so a process row is always found.
     pub fn select_process(&mut self, offset: i32) {
-        let len = self.processes.len();
+        let len = self.view.rows.len() as i32;
         if len == 0 {
             return;
         }
 
         let current = self.table_state.selected().unwrap_or(0) as i32;
-        let next = (current + offset).clamp(0, len as i32 - 1) as usize;
+        let step = if offset < 0 { -1 } else { 1 };
+        let mut next = (current + offset).clamp(0, len - 1);
+        while self.view.rows[next as usize].index().is_none() && (0..len).contains(&(next + step)) {
+            next += step;
+        }
 
-        self.table_state.select(Some(next));
-        self.selected_pid = Some(self.processes[next].pid);
+        let Some(index) = self.view.rows[next as usize].index() else {
+            return;
+        };
+        self.table_state.select(Some(next as usize));
+        self.selected_pid = Some(self.processes[index].pid);
+        self.update_detail();
     }
 
-    /// Returns `[start, end]` x-axis bounds for the CPU chart.
-    pub fn history_bounds(&self) -> [f64; 2] {
-        let end = (self.tick_count as f64).max(WINDOW);
-        let start = end - WINDOW;
-        [start, end]
+    /// Moves the selection and the visible rows by one page in `direction` (±1).
+    ///
+    /// Scrolling by the same amount keeps the cursor's place on the page,
+    /// so consecutive pages neither overlap nor skip rows.
+    pub fn page(&mut self, direction: i32) {
+        let step = self.page_rows as i32;
+        self.select_process(direction * step);
+        let last_page = (self.view.rows.len() as i32 - step).max(0);
+        let offset = self.table_state.offset() as i32 + direction * step;
+        *self.table_state.offset_mut() = offset.clamp(0, last_page) as usize;
+    }
+
+    /// Returns the process under the table cursor, if any.
+    pub fn selected_process(&self) -> Option<&ProcessSample> {
+        self.table_state
+            .selected()
+            .and_then(|i| self.view.rows.get(i))
+            .and_then(ViewRow::index)
+            .and_then(|index| self.processes.get(index))
+    }
+
+    /// Advances to the next sort column.
+    pub fn cycle_sort(&mut self) {
+        self.view.set_sort(self.view.sort.next());
+        self.resort();
     }
 
-    /// Computes user / system / idle CPU percentages from Mach tick deltas.
-    fn update_cpu_split(&mut self) {
-        let Some(now) = get_cpu_ticks() else {
-            push_bounded(
-                &mut self.system_history,
-                (self.tick_count as f64, self.system_pct),
-                HISTORY_LEN,
-            );
-            push_bounded(
-                &mut self.user_history,
-                (self.tick_count as f64, self.user_pct),
-                HISTORY_LEN,
-            );
+    /// Opens the sort menu with the current column highlighted.
+    pub fn open_sort_menu(&mut self) {
+        self.popup = Some(Popup::Sort {
+            selected: self.view.sort.column_index(),
+        });
+    }
+
+    /// Opens the column settings with the current layout as the draft.
+    pub fn open_columns(&mut self) {
+        self.popup = Some(Popup::Columns(ColumnEditor::new(&self.view.columns)));
+    }
+
+    /// Shows the columns `visible`, in order, closes the column settings,
+    /// and remembers the layout for later sessions.
+    pub fn apply_columns(&mut self, visible: Vec<usize>) {
+        self.popup = None;
+        if visible.is_empty() {
             return;
+        }
+        self.view.columns.clone_from(&visible);
+        self.ui_state.columns = Some(visible);
+        self.resort();
+        if let Some(path) = &self.ui_state_path
+            && let Err(err) = self.ui_state.save(path)
+        {
+            self.set_status(format!("column layout not saved: {err}"));
+        }
+    }
+
+    /// Sorts by the column at `index` in [`COLUMNS`] and closes the menu.
+    pub fn choose_sort(&mut self, index: usize) {
+        self.popup = None;
+        if let Some(column) = COLUMNS.get(index) {
+            self.view.set_sort(column.sort);
+            self.resort();
+        }
+    }
+
+    /// Flips the sort direction.
+    pub fn reverse_sort(&mut self) {
+        self.view.descending = !self.view.descending;
+        self.resort();
+    }
+
+    /// Toggles the CPU band separators.
+    pub fn toggle_bands(&mut self) {
+        self.view.bands = !self.view.bands;
+        self.refresh_view();
+    }
+
+    /// Switches between the flat list and the parent/child tree.
+    pub fn toggle_tree(&mut self) {
+        self.view.tree = !self.view.tree;
+        self.refresh_view();
+    }
+
+    /// Toggles showing only processes run from external volumes.
+    pub fn toggle_external_only(&mut self) {
+        self.view.external_only = !self.view.external_only;
+        self.refresh_view();
+    }
+
+    /// Starts editing the name filter.
+    pub fn start_filter(&mut self) {
+        self.filter_editing = true;
+    }
+
+    /// Appends a character to the name filter.
+    pub fn push_filter(&mut self, c: char) {
+        self.view.filter.push(c);
+        self.view.preset = None;
+        self.refresh_view();
+    }
+
+    /// Removes the last character of the name filter.
+    pub fn pop_filter(&mut self) {
+        self.view.filter.pop();
+        self.view.preset = None;
+        self.refresh_view();
+    }
+
+    /// Clears the name filter and stops editing it.
+    pub fn clear_filter(&mut self) {
+        self.filter_editing = false;
+        self.view.filter.clear();
+        self.view.preset = None;
+        self.refresh_view();
+    }
+
+    /// Index of the active filter preset in `config.filters`.
+    fn preset_index(&self) -> Option<usize> {
+        let name = self.view.preset.as_deref()?;
+        self.config.filters.iter().position(|(n, _)| n == name)
+    }
+
+    /// Switches to profile `index` (`None` for the config alone), re-resolving every setting.
+    pub fn apply_profile(&mut self, index: Option<usize>) {
+        self.profile = index.filter(|&i| i < self.config.profiles.len());
+        let settings = self.settings();
+
+        self.apply_costs(&settings);
+        self.compact = settings.compact;
+        self.view.columns = settings.columns;
+        self.view.sort = settings.sort;
+        self.view.descending = settings.descending;
+        let preset = settings
+            .filter
+            .and_then(|name| self.config.filters.iter().position(|(n, _)| *n == name));
+        match preset {
+            Some(i) => self.apply_preset(i),
+            None => self.clear_filter(),
+        }
+        self.resort();
+    }
+
+    /// Resolves the active profile, with the low-power layer while it is engaged.
+    fn settings(&self) -> Settings {
+        let profile = self.profile.map(|i| &self.config.profiles[i]);
+        let low_power = self.low_power.then(|| {
+            self.config
+                .profiles
+                .iter()
+                .find(|p| p.name == LOW_POWER_PROFILE)
+                .cloned()
+                .unwrap_or_else(Profile::low_power)
+        });
+        Settings::resolve(
+            &self.config,
+            self.ui_state.columns.as_deref(),
+            profile,
+            low_power.as_ref(),
+            self.cli_compact,
+        )
+    }
+
+    /// Applies the settings that decide what a tick costs, leaving the
+    /// layout, sort and filter as they are.
+    fn apply_costs(&mut self, settings: &Settings) {
+        self.ticker.configure(settings.interval, settings.adaptive);
+        self.resize_history(settings.interval);
+        self.monitor.set_lean(!settings.threads);
+        self.extras = settings.extras;
+        self.io_interval = settings.io_interval;
+    }
+
+    /// Engages low-power mode on battery at or below `[battery] low_power`,
+    /// and reverts it on external power or once the charge is
+    /// [`LOW_POWER_HYSTERESIS`] points above the threshold.
+    fn update_low_power(&mut self) {
+        let reading = self.battery.reading.filter(|r| r.on_battery);
+        let engaged = match (self.config.low_power, reading) {
+            (Some(threshold), Some(r)) if self.low_power => {
+                r.percent < threshold + LOW_POWER_HYSTERESIS
+            }
+            (Some(threshold), Some(r)) => r.percent <= threshold,
+            _ => false,
         };
+        if engaged == self.low_power {
+            return;
+        }
+        self.low_power = engaged;
+        let settings = self.settings();
+        self.apply_costs(&settings);
+        match reading {
+            Some(r) if engaged => self.set_status(format!(
+                "low-power mode: battery at {:.0}%, expensive collectors paused",
+                r.percent
+            )),
+            _ => self.set_status("low-power mode off: settings restored"),
+        }
+    }
 
-        if let Some(prev) = self.prev_ticks {
-            let d_user = now[0].saturating_sub(prev[0]);
-            let d_system = now[1].saturating_sub(prev[1]);
-            let d_idle = now[2].saturating_sub(prev[2]);
-            let d_nice = now[3].saturating_sub(prev[3]);
-            let total = d_user + d_system + d_idle + d_nice;
+    /// Moves the fine tier onto slots matching tick `interval`, keeping
+    /// the same span; samples that no longer fit roll up into minutes.
+    fn resize_history(&mut self, interval: Duration) {
+        let (slot, slots) = history_slots(interval);
+        if slot == self.system_history.interval() {
+            return;
+        }
+        let sys = self.system_history.resample(slot, slots);
+        let usr = self.user_history.resample(slot, slots);
+        for ((x, sys), (_, usr)) in sys.into_iter().zip(usr) {
+            self.cpu_minutes.push(x, sys + usr);
+        }
+        for (x, y) in self.memory_history.resample(slot, slots) {
+            self.memory_minutes.push(x, y);
+        }
+        for (x, v) in self.thermal_history.resample(slot, slots) {
+            self.thermal_minutes.push(x, v);
+        }
+        self.max_core_history.resample(slot, slots);
+        self.compression_history.resample(slot, slots);
+        self.decompression_history.resample(slot, slots);
+        if let Some(trail) = &mut self.memory_trail {
+            trail.history.resample(slot, trail_slots(slot));
+        }
+        let start = self.system_history.start();
+        self.late_samples.retain(|&(x, _)| x >= start);
+    }
+
+    /// Switches to the next profile, or back to the config alone after the last one.
+    pub fn cycle_profile(&mut self) {
+        if self.config.profiles.is_empty() {
+            self.set_status("no [profile.<name>] sections configured");
+            return;
+        }
+        let next = self.profile.map_or(0, |i| i + 1);
+        self.apply_profile(Some(next));
+        let name = self
+            .profile
+            .map_or("none", |i| self.config.profiles[i].name.as_str());
+        self.set_status(format!("profile: {name}"));
+    }
+
+    /// Applies the next filter preset, or no filter after the last one.
+    pub fn cycle_preset(&mut self) {
+        if self.config.filters.is_empty() {
+            self.set_status("no [filters] presets configured");
+            return;
+        }
+        let next = self.preset_index().map_or(0, |i| i + 1);
+        self.apply_preset(next);
+    }
 
-            if total > 0 {
-                self.user_pct = (d_user + d_nice) as f64 / total as f64 * 100.0;
-                self.system_pct = d_system as f64 / total as f64 * 100.0;
-                self.idle_pct = d_idle as f64 / total as f64 * 100.0;
+    /// Opens the preset list with the active preset highlighted.
+    pub fn open_presets(&mut self) {
+        self.popup = Some(Popup::Filters {
+            selected: self.preset_index().map_or(0, |i| i + 1),
+        });
+    }
+
+    /// Applies the preset on `row` of the preset list and closes it.
+    pub fn choose_preset(&mut self, row: usize) {
+        self.popup = None;
+        match row.checked_sub(1) {
+            Some(index) => self.apply_preset(index),
+            None => self.clear_filter(),
+        }
+    }
+
+    /// Replaces the filter with preset `index`; out of range clears it.
+    fn apply_preset(&mut self, index: usize) {
+        self.filter_editing = false;
+        match self.config.filters.get(index) {
+            Some((name, pattern)) => {
+                self.view.filter.clone_from(pattern);
+                self.view.preset = Some(name.clone());
+                self.refresh_view();
             }
+            None => self.clear_filter(),
         }
+    }
 
-        self.prev_ticks = Some(now);
-        push_bounded(
-            &mut self.system_history,
-            (self.tick_count as f64, self.system_pct),
-            HISTORY_LEN,
-        );
-        push_bounded(
-            &mut self.user_history,
-            (self.tick_count as f64, self.user_pct),
-            HISTORY_LEN,
-        );
+    /// Opens the kill dialog for the selected process.
+    pub fn open_kill(&mut self) {
+        if let Some(proc) = self.selected_process() {
+            self.popup = Some(Popup::Kill {
+                pid: proc.pid,
+                name: proc.name.clone(),
+                selected: 0,
+                group: None,
+            });
+        }
     }
 
-    /// Refreshes the process list and memory counters from `sysinfo`.
-    fn update_processes(&mut self) {
-        self.sys.refresh_memory();
-        self.sys.refresh_processes_specifics(
-            ProcessesToUpdate::All,
-            true,
-            ProcessRefreshKind::nothing().with_cpu().with_memory(),
-        );
+    /// Sends `signal` to `pid` and reports the outcome in the status bar.
+    pub fn send_signal(&mut self, pid: Pid, signal: usize) {
+        self.popup = None;
+        let Some(&(name, signo)) = SIGNALS.get(signal) else {
+            return;
+        };
+        if let Some(why) = enforce::protected(pid) {
+            self.set_status(format!("not sending SIG{name} to {pid}: {why}"));
+            return;
+        }
 
-        self.total_memory = self.sys.total_memory();
-        self.used_memory = self.sys.used_memory();
-
-        let mut procs: Vec<ProcessInfo> = self
-            .sys
-            .processes()
-            .values()
-            .map(|p| ProcessInfo {
-                pid: p.pid().as_u32(),
-                name: p.name().to_string_lossy().into_owned(),
-                cpu_usage: p.cpu_usage(),
-                memory: p.memory(),
-            })
+        if unsafe { libc::kill(pid as i32, signo) } == 0 {
+            self.set_status(format!("sent SIG{name} to {pid}"));
+        } else {
+            let err = std::io::Error::last_os_error();
+            self.set_status(format!("SIG{name} to {pid} failed: {err}"));
+        }
+    }
+
+    /// Switches the open signal picker between the process and its group.
+    ///
+    /// A group that includes syswatch, or the session-wide groups 0 and
+    /// 1, is never offered: `kill` treats those as "everyone".
+    pub fn toggle_kill_group(&mut self) {
+        let Some(Popup::Kill { pid, group, .. }) = &self.popup else {
+            return;
+        };
+        let next = match (group, self.process(*pid).and_then(|p| p.pgid)) {
+            (Some(_), _) => None,
+            (None, Some(pgid))
+                if pgid > 1 && Some(pgid) != self.own_process().and_then(|p| p.pgid) =>
+            {
+                Some(pgid)
+            }
+            (None, Some(pgid)) => {
+                self.set_status(format!("won't signal process group {pgid}"));
+                return;
+            }
+            (None, None) => {
+                self.set_status("process group unknown");
+                return;
+            }
+        };
+        if let Some(Popup::Kill { group, .. }) = &mut self.popup {
+            *group = next;
+        }
+    }
+
+    /// Asks for confirmation before signalling every member of `pgid`,
+    /// listing the members found in the current snapshot.
+    pub fn confirm_group_kill(&mut self, pgid: Pid, signal: usize) {
+        let members = self
+            .processes
+            .iter()
+            .filter(|p| p.pgid == Some(pgid))
+            .map(|p| (p.pid, p.name.clone()))
             .collect();
+        self.popup = Some(Popup::GroupKill {
+            pgid,
+            signal,
+            members,
+        });
+    }
 
-        procs.sort_by(|a, b| {
-            b.cpu_usage
-                .partial_cmp(&a.cpu_usage)
-                .unwrap_or(std::cmp::Ordering::Equal)
+    /// Sends `signal` to every process in group `pgid`.
+    pub fn signal_group(&mut self, pgid: Pid, signal: usize, members: usize) {
+        self.popup = None;
+        let Some(&(name, signo)) = SIGNALS.get(signal) else {
+            return;
+        };
+        // Checked again: the guard in `toggle_kill_group` is what keeps
+        // this from becoming kill(-1) or kill(0).
+        if pgid <= 1 {
+            return;
+        }
+
+        if unsafe { libc::kill(-(pgid as i32), signo) } == 0 {
+            self.set_status(format!(
+                "sent SIG{name} to process group {pgid} ({members} processes)"
+            ));
+        } else {
+            let err = std::io::Error::last_os_error();
+            self.set_status(format!("SIG{name} to process group {pgid} failed: {err}"));
+        }
+    }
+
+    /// Shows `msg` in the status bar for a few seconds.
+    pub fn set_status(&mut self, msg: impl Into<String>) {
+        self.status = Some((msg.into(), Instant::now()));
+    }
+
+    /// Returns the status-bar message if it hasn't expired.
+    pub fn status(&self) -> Option<&str> {
+        self.status
+            .as_ref()
+            .filter(|(_, at)| at.elapsed() < STATUS_TTL)
+            .map(|(msg, _)| msg.as_str())
+    }
+
+    /// Starts sampling the selected process's call stacks in the background.
+    pub fn start_sample(&mut self) {
+        let Some(proc) = self.selected_process() else {
+            return;
+        };
+
+        let job = SampleJob::spawn(proc.pid, proc.name.clone());
+        self.popup = Some(Popup::Sample(job));
+    }
+
+    /// Returns syswatch's own entry in the process list, if present.
+    pub fn own_process(&self) -> Option<&ProcessSample> {
+        self.process(std::process::id())
+    }
+
+    /// Opens the detail view for the selected process.
+    pub fn open_detail(&mut self) {
+        if let Some(proc) = self.selected_process() {
+            self.popup = Some(Popup::Detail { pid: proc.pid });
+            self.update_detail();
+        }
+    }
+
+    /// Opens the activity view on the selected process.
+    pub fn open_activity(&mut self) {
+        if self.selected_process().is_some() {
+            self.popup = Some(Popup::Activity);
+            self.update_disk_activity();
+        }
+    }
+
+    /// Opens the children timeline, locking it to the selected process.
+    ///
+    /// Reopening on the same process keeps the timeline recorded so far.
+    pub fn open_children(&mut self) {
+        let Some(proc) = self.selected_process() else {
+            return;
+        };
+        if self.children.as_ref().is_none_or(|t| t.parent != proc.pid) {
+            self.children = Some(Timeline::new(proc, &self.processes));
+        }
+        self.popup = Some(Popup::Children { scroll: 0 });
+    }
+
+    /// Writes the children timeline as JSON to the working directory.
+    pub fn export_children(&mut self) {
+        let Some(timeline) = &self.children else {
+            return;
+        };
+        let path = format!("syswatch-children-{}.json", timeline.parent);
+        let msg = match fs::write(&path, timeline.to_json() + "\n") {
+            Ok(()) => format!("children timeline written to {path}"),
+            Err(err) => format!("{path}: {err}"),
+        };
+        self.set_status(msg);
+    }
+
+    /// Opens the chart history export picker, scoped to the visible window.
+    pub fn open_export(&mut self) {
+        self.popup = Some(Popup::Export {
+            selected: 0,
+            scope: Scope::default(),
         });
+    }
+
+    /// Chart x range `[from, to]` covered by `scope`.
+    pub fn scope_bounds(&self, scope: Scope) -> [f64; 2] {
+        match scope {
+            Scope::Window => self.history_bounds(),
+            Scope::Session => [0.0, self.system_history.end()],
+        }
+    }
+
+    /// Retained per-tick samples within `scope`, oldest first.
+    pub fn history_samples(&self, scope: Scope) -> Vec<export::Sample> {
+        let [from, to] = self.scope_bounds(scope);
+        let now = SystemTime::now();
+        let now_x = self.sample_x();
+        self.system_history
+            .range(from, to)
+            .zip(self.user_history.range(from, to))
+            .map(|((x, cpu_system), (_, cpu_user))| export::Sample {
+                time: now - Duration::from_secs_f64((now_x - x).max(0.0)),
+                cpu_system,
+                cpu_user,
+                max_core: self.max_core_history.at(x),
+                memory: self.memory_history.at(x),
+            })
+            .collect()
+    }
+
+    /// Exports the chart history within `scope` to `target`.
+    pub fn export_history(&mut self, target: Target, scope: Scope) {
+        self.popup = None;
+        let samples = self.history_samples(scope);
+        // Reaching before the oldest retained sample means the export is
+        // shorter than asked for; say so rather than pass it off as whole.
+        let truncated = self.scope_bounds(scope)[0] < self.system_history.start().floor();
+        let note = if truncated {
+            " (older samples are no longer retained)"
+        } else {
+            ""
+        };
+        let (what, result) = match target {
+            Target::Csv => {
+                let path = "syswatch-history.csv";
+                (path, fs::write(path, export::csv(&samples)))
+            }
+            Target::Json => {
+                let path = "syswatch-history.json";
+                (path, fs::write(path, export::json(&samples, scope) + "\n"))
+            }
+            Target::Clipboard => ("the clipboard", export::copy(&export::csv(&samples))),
+        };
+        let msg = match result {
+            Ok(()) => format!(
+                "{} samples ({}) exported to {what}{note}",
+                samples.len(),
+                scope.label()
+            ),
+            Err(err) => format!("{what}: {err}"),
+        };
+        self.set_status(msg);
+    }
+
+    /// Looks up a process in the current snapshot by PID.
+    pub fn process(&self, pid: Pid) -> Option<&ProcessSample> {
+        self.processes.iter().find(|p| p.pid == pid)
+    }
+
+    /// Opens the "open with" picker for the selected process.
+    pub fn open_with_picker(&mut self) {
+        if self.selected_process().is_some() && !self.config.open_with.is_empty() {
+            self.popup = Some(Popup::OpenWith { selected: 0 });
+        }
+    }
+
+    /// Queues the `index`-th "open with" template for the selected process.
+    pub fn choose_open_with(&mut self, index: usize) {
+        self.popup = None;
+        let (Some(template), Some(proc)) =
+            (self.config.open_with.get(index), self.selected_process())
+        else {
+            return;
+        };
+        self.pending_command = Some(external::expand(template, proc.pid, &proc.name));
+    }
+
+    /// Opens the snooze picker.
+    pub fn open_snooze(&mut self) {
+        self.popup = Some(Popup::Snooze { selected: 0 });
+    }
 
+    /// Snoozes notifications for the `index`-th [`Snooze::ALL`] length, or resumes
+    /// them when `index` is past the end.
+    pub fn choose_snooze(&mut self, index: usize) {
+        self.popup = None;
+        let now = Instant::now();
+        match Snooze::ALL.get(index) {
+            Some(&snooze) => {
+                let clock = notify::local_clock(SystemTime::now());
+                self.dispatcher.snooze(Some(now + snooze.duration(clock)));
+                self.set_status(format!(
+                    "notifications snoozed for {} (alerts are still logged)",
+                    snooze.label()
+                ));
+            }
+            None => {
+                self.dispatcher.snooze(None);
+                self.set_status("notifications resumed");
+            }
+        }
+    }
+
+    /// Time left on the notification snooze, if one is running.
+    pub fn snoozed(&self) -> Option<Duration> {
+        self.dispatcher.snoozed(Instant::now())
+    }
+
+    /// Collects results from background work without blocking.
+    pub fn poll_jobs(&mut self) {
+        if let Some(Popup::Sample(job)) = &mut self.popup {
+            job.poll();
+        }
+    }
+
+    /// Returns the chart window width in seconds for the current zoom level.
+    pub fn window(&self) -> f64 {
+        ZOOM_WINDOWS[self.zoom]
+    }
+
+    /// Steps the chart window to the next longer (`+1`) or shorter (`-1`) width.
+    pub fn zoom_by(&mut self, step: isize) {
+        self.zoom = self
+            .zoom
+            .saturating_add_signed(step)
+            .min(ZOOM_WINDOWS.len() - 1);
+    }
+
+    /// Whether the window exceeds the fine tier, so the chart uses minute buckets.
+    pub fn coarse_zoom(&self) -> bool {
+        self.window() > self.system_history.span()
+    }
+
+    /// Minute buckets of total CPU, coarse tier plus fine tier, that
+    /// start in the chart window, with x measured from its left edge.
+    pub fn cpu_minute_buckets(&self) -> Vec<(f64, Bucket)> {
+        let recent = self
+            .system_history
+            .points()
+            .zip(self.user_history.points())
+            .map(|((x, sys), (_, usr))| (x, sys + usr));
+        let [_, right] = self.history_bounds();
+        self.cpu_minutes.merged_window(recent, right, self.window())
+    }
+
+    /// Worst thermal level per chart point in the window, with x
+    /// measured from its left edge.
+    ///
+    /// Returns `None` when thermal pressure isn't available, so the UI
+    /// can omit the throttling strip entirely. Coarse zoom levels use the
+    /// per-minute maximum.
+    pub fn thermal_points(&self) -> Option<Vec<(f64, ThermalLevel)>> {
+        self.thermal.as_ref()?;
+
+        let [_, right] = self.history_bounds();
+        let window = self.window();
+        let points: Vec<(f64, f64)> = if self.coarse_zoom() {
+            self.thermal_minutes
+                .merged_window(self.thermal_history.points(), right, window)
+                .into_iter()
+                .map(|(x, b)| (x, b.max))
+                .collect()
+        } else {
+            self.thermal_history.window_points(right, window).collect()
+        };
+
+        Some(
+            points
+                .into_iter()
+                .map(|(x, v)| (x, ThermalLevel::from_value(v)))
+                .collect(),
+        )
+    }
+
+    /// Records the sample just taken as late, `gap` after the previous one.
+    pub(crate) fn mark_late(&mut self, gap: Duration) {
+        self.late_samples
+            .push_back((self.system_history.end(), gap.as_secs_f64()));
+        let start = self.system_history.start();
+        while self.late_samples.front().is_some_and(|&(x, _)| x < start) {
+            self.late_samples.pop_front();
+        }
+    }
+
+    /// Chart x ranges `(from, to)` bridging a late sample and the one
+    /// before it, for late samples in the window, with x measured from
+    /// its left edge like the plotted points.
+    pub fn late_segments(&self) -> impl Iterator<Item = (f64, f64)> + '_ {
+        let [_, right] = self.history_bounds();
+        let window = self.window();
+        self.late_samples
+            .iter()
+            .map(move |&(x, gap)| {
+                let to = self.system_history.window_x(x, right, window);
+                (to - gap, to)
+            })
+            .filter(|&(_, to)| to >= 0.0)
+    }
+
+    /// Returns `[start, end]` x-axis bounds for the CPU chart.
+    pub fn history_bounds(&self) -> [f64; 2] {
+        let window = self.window();
+        let end = self.system_history.end().max(window);
+        let start = end - window;
+        [start, end]
+    }
+
+    /// Takes this tick's CPU split and feeds the chart history and ticker.
+    fn update_cpu(&mut self, snapshot: &Snapshot) {
+        self.user_pct = snapshot.cpu_user;
+        self.system_pct = snapshot.cpu_system;
+        self.idle_pct = snapshot.cpu_idle;
+        self.max_core_pct = snapshot.max_core;
+        self.record_cpu_sample();
+        self.ticker.on_sample(self.user_pct + self.system_pct);
+    }
+
+    /// Appends the current CPU split and thermal level to the fine tier,
+    /// rolling evicted samples up.
+    fn record_cpu_sample(&mut self) {
+        let x = self.sample_x();
+        let sys = self.system_history.push_at(x, self.system_pct);
+        let usr = self.user_history.push_at(x, self.user_pct);
+        for ((x, sys), (_, usr)) in sys.into_iter().zip(usr) {
+            self.cpu_minutes.push(x, sys + usr);
+        }
+        if let Some(max_core) = self.max_core_pct {
+            // Fine tier only: minute buckets of total CPU already carry a max.
+            self.max_core_history.push_at(x, max_core);
+        }
+
+        self.thermal_level = self.thermal.as_ref().and_then(ThermalMonitor::level);
+        let level = self.thermal_level.unwrap_or(ThermalLevel::Nominal);
+        for (x, v) in self.thermal_history.push_at(x, level.value()) {
+            self.thermal_minutes.push(x, v);
+        }
+    }
+
+    /// Fires alerts for this tick, showing them in the status bar and
+    /// routing them to their `[notify]` channels.
+    fn check_alerts(&mut self) {
+        let mem_pct = if self.total_memory > 0 {
+            self.used_memory as f64 / self.total_memory as f64 * 100.0
+        } else {
+            0.0
+        };
+        let mut fired = self.alerts.evaluate(&self.processes, mem_pct);
+        fired.extend(self.enforce_rules());
+
+        if let Some(alert) = fired.last() {
+            self.set_status(format!("alert: {}", alert.message()));
+        }
+        let mut outputs = Outputs {
+            webhook: self.webhook.as_ref(),
+            host: &self.hostname,
+            flash: &mut self.flash,
+        };
+        let now = Instant::now();
+        let clock = notify::local_clock(SystemTime::now());
+        for alert in &fired {
+            self.dispatcher.dispatch(alert, now, clock, &mut outputs);
+        }
+        self.events.extend(fired);
+
+        if let Some(webhook) = &mut self.webhook {
+            // Only the first failure is shown; later ones are counted in the debug overlay.
+            let first = webhook.failures == 0;
+            if let Some(msg) = webhook.take_error()
+                && first
+            {
+                self.set_status(msg);
+            }
+        }
+    }
+
+    /// Applies `[kill]` rules that started matching, returning one alert per hit.
+    ///
+    /// Every hit is logged to the alert history with what was (or would
+    /// have been) done, so dry runs leave the same trail as real kills.
+    fn enforce_rules(&mut self) -> Vec<Alert> {
+        let hits: Vec<_> = self
+            .enforcer
+            .evaluate(&self.processes)
+            .into_iter()
+            .filter(|hit| Some(hit.process.pid) != self.view.hidden)
+            .map(|hit| {
+                let (pid, name) = (hit.process.pid, hit.process.name.clone());
+                let cpu = f64::from(hit.process.cpu_usage);
+                (
+                    hit.rule.source.clone(),
+                    hit.rule.mode,
+                    hit.rule.signal,
+                    pid,
+                    name,
+                    cpu,
+                )
+            })
+            .collect();
+
+        let mut fired = Vec::new();
+        for (source, mode, signal, pid, name, cpu) in hits {
+            let sig = SIGNALS[signal].0;
+            let outcome = if let Some(why) = enforce::protected(pid) {
+                format!("matched but is never signalled ({why})")
+            } else {
+                match mode {
+                    KillMode::AlertOnly => format!("would get SIG{sig} (alert-only)"),
+                    KillMode::Confirm if self.popup.is_none() => {
+                        self.popup = Some(Popup::Kill {
+                            pid,
+                            name: name.clone(),
+                            selected: signal,
+                            group: None,
+                        });
+                        format!("awaits confirmation of SIG{sig}")
+                    }
+                    KillMode::Confirm => {
+                        format!("would get SIG{sig} (confirm skipped: popup open)")
+                    }
+                    KillMode::Auto if self.auto_kill => {
+                        if unsafe { libc::kill(pid as i32, SIGNALS[signal].1) } == 0 {
+                            format!("was sent SIG{sig}")
+                        } else {
+                            let err = std::io::Error::last_os_error();
+                            format!("SIG{sig} failed: {err}")
+                        }
+                    }
+                    KillMode::Auto => {
+                        format!("would get SIG{sig} (auto needs --enable-auto-kill)")
+                    }
+                }
+            };
+            let alert = Alert {
+                kind: AlertKind::KillRule,
+                subject: Some((pid, name)),
+                value: cpu,
+                threshold: 0.0,
+                detail: Some(format!("{outcome}: rule `{source}`")),
+                time: SystemTime::now(),
+                notify: true,
+            };
+            self.alerts.record(alert.clone());
+            fired.push(alert);
+        }
+        fired
+    }
+
+    /// Hands this tick's metrics to the recorder, reporting failures in the status bar.
+    fn record_sample(&mut self) {
+        if self.recorder.is_none() {
+            return;
+        }
+        let sample = Sample::from_app(self);
+        let Some(recorder) = &mut self.recorder else {
+            return;
+        };
+
+        let error = match recorder.record(&sample, &self.events) {
+            Ok(()) => recorder.take_error(),
+            Err(err) => Some(format!("record: {err}")),
+        };
+        if let Some(msg) = error {
+            self.set_status(msg);
+        }
+    }
+
+    /// Chart x coordinate of a sample taken now: seconds since start.
+    fn sample_x(&self) -> f64 {
+        self.started.elapsed().as_secs_f64()
+    }
+
+    /// Takes this tick's memory counters and process list.
+    fn update_processes(&mut self, snapshot: Snapshot) {
+        self.total_memory = snapshot.mem_total;
+        self.used_memory = snapshot.mem_used;
+        self.memory_mode = snapshot.memory_mode;
+        self.wired_memory = snapshot.wired;
+        self.compressed_memory = snapshot.compressed;
+        self.cached_memory = snapshot.cached;
+        self.update_compressor(snapshot.compressor, snapshot.time);
+        self.memory_pressure = snapshot.memory_pressure;
+        self.total_swap = snapshot.swap_total;
+        self.used_swap = snapshot.swap_used;
+        self.thread_count = snapshot.threads;
+
+        let mem_pct = if self.total_memory > 0 {
+            self.used_memory as f64 / self.total_memory as f64 * 100.0
+        } else {
+            0.0
+        };
+        for (x, y) in self.memory_history.push_at(self.sample_x(), mem_pct) {
+            self.memory_minutes.push(x, y);
+        }
+
+        if snapshot.processes_skipped {
+            return;
+        }
+        let mut procs = snapshot.processes;
+        self.napping = procs.iter().filter(|p| p.nap.is_some()).count();
+        let alive: HashSet<Pid> = procs.iter().map(|p| p.pid).collect();
+        self.cpu_trails.prune(&alive);
+        for p in procs.iter().filter(|p| p.cpu_measured) {
+            self.cpu_trails.push(p.pid, p.cpu_usage);
+        }
+        self.kernel_cpu = procs
+            .iter()
+            .find(|p| p.name == KERNEL_TASK)
+            .map(|p| p.cpu_usage);
+
+        if let Some(timeline) = &mut self.children {
+            timeline.update(&procs);
+        }
+
+        self.view.partial_sort = self.monitor.high_count();
+        self.view.sort(&mut procs);
+        self.processes = procs;
+        self.refresh_view();
+        self.update_detail();
+        self.update_memory_trail();
+        self.update_ipc();
+        self.update_disk_activity();
+    }
+
+    /// Re-sorts the snapshot after a sort-setting change.
+    fn resort(&mut self) {
+        let mut procs = mem::take(&mut self.processes);
+        self.view.sort(&mut procs);
         self.processes = procs;
+        self.refresh_view();
+    }
+
+    /// Rebuilds the visible rows and keeps the selection on the same PID.
+    fn refresh_view(&mut self) {
+        self.view.rebuild(&self.processes);
+        self.row_cache.invalidate();
         self.restore_selection();
     }
 
-    /// Re-selects the previously highlighted PID after a sort shuffle.
-    fn restore_selection(&mut self) {
-        let Some(pid) = self.selected_pid else {
+    /// Toggles the docked detail pane.
+    pub fn toggle_dock(&mut self) {
+        self.dock = !self.dock;
+        self.update_detail();
+    }
+
+    /// PID whose details are on screen: the popup's, else the docked pane's.
+    fn detail_pid(&self) -> Option<Pid> {
+        match self.popup {
+            Some(Popup::Detail { pid }) => Some(pid),
+            _ if self.dock => self.selected_process().map(|p| p.pid),
+            _ => None,
+        }
+    }
+
+    /// Selected PID while the activity view is open.
+    fn activity_pid(&self) -> Option<Pid> {
+        match self.popup {
+            Some(Popup::Activity) => self.selected_process().map(|p| p.pid),
+            _ => None,
+        }
+    }
+
+    /// Appends the detail or activity view's process's memory to its
+    /// trail, starting over when the view shows a different PID.
+    fn update_memory_trail(&mut self) {
+        let pid = self.detail_pid().or_else(|| self.activity_pid());
+        let Some(proc) = pid.and_then(|pid| self.process(pid)) else {
+            self.memory_trail = None;
             return;
         };
+        let (pid, memory) = (proc.pid, proc.memory);
+        let x = self.sample_x();
+        let interval = self.system_history.interval();
+        let trail = match &mut self.memory_trail {
+            Some(trail) if trail.pid == pid => trail,
+            slot => slot.insert(MemoryTrail {
+                pid,
+                since: x,
+                history: Series::new(x, interval, trail_slots(interval)),
+            }),
+        };
+        trail.history.push_at(x, memory as f64);
+    }
 
-        if let Some(i) = self.processes.iter().position(|p| p.pid == pid) {
-            self.table_state.select(Some(i));
+    /// Reads the detail view's process's Mach counters and derives the
+    /// message rates, starting over when the view shows a different PID.
+    fn update_ipc(&mut self) {
+        let Some(pid) = self.detail_pid().filter(|_| self.extras) else {
+            self.ipc = None;
+            return;
+        };
+        let ipc = match &mut self.ipc {
+            Some(ipc) if ipc.pid == pid => ipc,
+            slot => slot.insert(IpcActivity {
+                pid,
+                last: None,
+                rates: None,
+                messages_error: None,
+                ports: Err(0),
+            }),
+        };
+        let now = Instant::now();
+        match mach_messages(pid) {
+            Ok(counts) => {
+                ipc.rates = ipc.last.map(|(earlier, at)| {
+                    counts.rates_since(&earlier, now.duration_since(at).as_secs_f64())
+                });
+                ipc.last = Some((counts, now));
+                ipc.messages_error = None;
+            }
+            Err(errno) => {
+                ipc.rates = None;
+                ipc.last = None;
+                ipc.messages_error = Some(errno);
+            }
+        }
+        ipc.ports = port_count(pid);
+    }
+
+    /// Reads the activity view's process's disk counters and derives the
+    /// throughput, starting over when the selection moves to another PID.
+    fn update_disk_activity(&mut self) {
+        let Some(pid) = self.activity_pid().filter(|_| self.extras) else {
+            self.disk_activity = None;
+            return;
+        };
+        let disk = match &mut self.disk_activity {
+            Some(disk) if disk.pid == pid => disk,
+            slot => slot.insert(DiskActivity {
+                pid,
+                last: None,
+                rates: None,
+                error: None,
+            }),
+        };
+        let now = Instant::now();
+        match process_io(pid) {
+            Ok(counters) => {
+                disk.rates = disk.last.map(|(earlier, at)| {
+                    counters.rates_since(&earlier, now.duration_since(at).as_secs_f64())
+                });
+                disk.last = Some((counters, now));
+                disk.error = None;
+            }
+            Err(errno) => {
+                disk.rates = None;
+                disk.last = None;
+                disk.error = Some(errno);
+            }
+        }
+    }
+
+    /// Refreshes cwd/environment for the detail view's PID when privileged
+    /// and extras are on.
+    fn update_detail(&mut self) {
+        let Some(pid) = self.detail_pid() else {
+            self.detail = None;
+            return;
+        };
+        if !self.privileged || !self.extras {
+            self.detail = None;
+            return;
+        }
+
+        self.detail = Some(self.monitor.details(pid));
+    }
+
+    /// Takes the compressor counters and derives per-second activity.
+    fn update_compressor(&mut self, now: Option<Compressor>, time: SystemTime) {
+        let secs = time
+            .duration_since(self.compressor_time)
+            .map_or(0.0, |d| d.as_secs_f64());
+        self.compressor_rates = match (self.compressor, now) {
+            (Some(earlier), Some(now)) if secs > 0.0 => Some(now.rates_since(&earlier, secs)),
+            _ => None,
+        };
+        self.compressor = now;
+        self.compressor_time = time;
+        if let Some((compressions, decompressions)) = self.compressor_rates {
+            let x = self.sample_x();
+            self.compression_history.push_at(x, compressions);
+            self.decompression_history.push_at(x, decompressions);
+        }
+    }
+
+    /// Refreshes interface counters and derives per-second network rates.
+    fn update_network(&mut self) {
+        self.networks.refresh(true);
+
+        let (rx, tx) = self
+            .networks
+            .iter()
+            .filter(|(name, _)| !name.starts_with("lo"))
+            .fold((0u64, 0u64), |(rx, tx), (_, data)| {
+                (rx + data.received(), tx + data.transmitted())
+            });
+
+        let elapsed = self.last_net_refresh.elapsed().as_secs_f64();
+        self.last_net_refresh = Instant::now();
+        if elapsed > 0.0 {
+            self.net_rx_rate = rx as f64 / elapsed;
+            self.net_tx_rate = tx as f64 / elapsed;
+        }
+    }
+
+    /// Re-selects the previously highlighted PID after a sort shuffle.
+    ///
+    /// When that PID is no longer visible the cursor is clamped to the
+    /// last row so it never points past the end of the table.
+    fn restore_selection(&mut self) {
+        let found = self.selected_pid.and_then(|pid| {
+            self.view
+                .rows
+                .iter()
+                .position(|row| row.index().is_some_and(|i| self.processes[i].pid == pid))
+        });
+
+        match found {
+            Some(i) => self.table_state.select(Some(i)),
+            None => {
+                let last = self.view.rows.len().saturating_sub(1);
+                let current = self.table_state.selected().unwrap_or(0);
+                self.table_state.select(Some(current.min(last)));
+                // Step off a separator the clamp may have landed on.
+                self.select_process(0);
+            }
         }
     }
 }
 
-/// Pushes `value` into `buf`, evicting the oldest entry when full.
-fn push_bounded<T>(buf: &mut VecDeque<T>, value: T, max: usize) {
-    if buf.len() >= max {
-        buf.pop_front();
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{Fixture, FixtureOptions};
+
+    /// An app fed by a fixture, touching no user files.
+    fn fixture_app(config: Config, options: FixtureOptions) -> App {
+        let monitor = Monitor::from_snapshots(App::monitor_options(&config), Fixture::new(options));
+        App::with_monitor(&Options::default(), config, monitor, None)
+    }
+
+    #[test]
+    fn app_ticks_on_fixture_snapshots() {
+        let options = FixtureOptions {
+            processes: 40,
+            ..FixtureOptions::default()
+        };
+        let mut app = fixture_app(Config::default(), options);
+        app.tick();
+        let first = Fixture::new(options).next().unwrap();
+        assert_eq!(app.processes.len(), 40);
+        assert_eq!(app.total_memory, first.mem_total);
+        assert_eq!(app.view.rows.len(), 40);
+        // Sorted by CPU, busiest first.
+        assert!(
+            app.processes
+                .windows(2)
+                .all(|w| w[0].cpu_usage >= w[1].cpu_usage)
+        );
+    }
+
+    #[test]
+    fn history_slots_follow_the_tick_interval() {
+        assert_eq!(history_slots(Duration::from_millis(500)), (0.5, 7200));
+        assert_eq!(history_slots(Duration::from_secs(1)), (1.0, 3600));
+        assert_eq!(history_slots(Duration::from_secs(2)), (2.0, 1800));
+        // Faster ticks share the narrowest slot, still covering the hour.
+        assert_eq!(history_slots(Duration::from_millis(20)), (0.1, 36000));
+    }
+
+    #[test]
+    fn interval_changes_resample_history_and_prune_late_samples() {
+        let config = Config {
+            tick_interval: Duration::from_millis(500),
+            ..Config::default()
+        };
+        let mut app = fixture_app(config, FixtureOptions::default());
+        // A full hour of half-second samples, each valued at its own time.
+        for i in 0..7200 {
+            let x = f64::from(i) * 0.5;
+            app.system_history.push_at(x, x);
+            app.user_history.push_at(x, 0.0);
+        }
+        app.late_samples.extend([(1.0, 0.5), (3000.0, 1.0)]);
+
+        app.resize_history(Duration::from_secs(2));
+        assert_eq!(app.system_history.interval(), 2.0);
+        assert_eq!(app.system_history.len(), 1800);
+        assert_eq!(app.system_history.span(), 3600.0);
+        assert_eq!(app.system_history.end(), 3600.0);
+        // Each slot keeps the last sample that rounds to it.
+        for (x, y) in app.system_history.points() {
+            assert!((x - y).abs() <= 1.0, "{x}: {y}");
+        }
+        // The oldest slot no longer fits and rolls up into the minutes,
+        // taking the late sample it held with it.
+        let start = app.system_history.start();
+        let rolled = app.cpu_minutes.merged(std::iter::empty());
+        assert_eq!(rolled.len(), 1);
+        assert!(rolled[0].0 < start);
+        assert_eq!(
+            rolled[0].1,
+            Bucket {
+                mean: 1.0,
+                max: 1.0
+            }
+        );
+        assert_eq!(app.late_samples, [(3000.0, 1.0)]);
+
+        // Back to half a second: each 2 s sample is held across four slots.
+        app.resize_history(Duration::from_millis(500));
+        assert_eq!(app.system_history.interval(), 0.5);
+        assert_eq!(app.system_history.start(), start);
+        assert_eq!(app.system_history.end(), 3600.0);
+        assert_eq!(app.system_history.at(3598.5), app.system_history.at(3600.0));
+        assert_ne!(app.system_history.at(3598.0), app.system_history.at(3600.0));
+        // Everything fits again, so nothing more rolls up or is pruned.
+        assert_eq!(app.cpu_minutes.merged(std::iter::empty()), rolled);
+        assert_eq!(app.late_samples, [(3000.0, 1.0)]);
+    }
+
+    #[test]
+    fn same_interval_leaves_history_alone() {
+        let mut app = fixture_app(Config::default(), FixtureOptions::default());
+        for i in 0..10 {
+            app.system_history.push_at(f64::from(i), f64::from(i));
+        }
+        let before: Vec<_> = app.system_history.points().collect();
+        app.resize_history(Config::default().tick_interval);
+        assert_eq!(app.system_history.points().collect::<Vec<_>>(), before);
+    }
+
+    /// Run with `cargo test --release -- --ignored`; timing in a debug
+    /// build says little.
+    #[test]
+    #[ignore = "benchmark"]
+    fn twenty_thousand_processes_tick_within_budget() {
+        /// Ticks timed after the first.
+        const TICKS: usize = 10;
+        /// Median tick allowed: 5% of the default one-second interval.
+        const BUDGET: Duration = Duration::from_millis(50);
+
+        let options = FixtureOptions {
+            processes: 20_000,
+            ..FixtureOptions::default()
+        };
+        let mut app = fixture_app(Config::default(), options);
+        app.tick();
+        assert!(app.monitor.high_count());
+        let mut times: Vec<Duration> = (0..TICKS)
+            .map(|_| {
+                let started = Instant::now();
+                app.tick();
+                started.elapsed()
+            })
+            .collect();
+        times.sort_unstable();
+        let median = times[TICKS / 2];
+        eprintln!("tick: median {median:?}, max {:?}", times[TICKS - 1]);
+        assert_eq!(app.processes.len(), 20_000);
+        assert!(median < BUDGET, "median tick {median:?} over {BUDGET:?}");
     }
-    buf.push_back(value);
 }
@@ -0,0 +1,291 @@
+//! Battery charge, drain rate, and session consumption.
+//!
+//! The charge is read from the IOKit power-source list every
+//! [`SAMPLE_EVERY`]; most ticks only check the clock. The drain rate is
+//! the least-squares slope over the last [`SMOOTHING`] readings taken on
+//! battery, so a single coarse percentage step doesn't whipsaw it, and
+//! the time to empty follows from it instead of from the system's own
+//! estimate. Plugging in or charging starts the rate over. Every drop
+//! in charge while on battery counts toward the session total. Other
+//! platforms have no source; [`Battery::poll`] then never yields a
+//! reading and the counts panel shows no battery line.
+
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+/// Time between two battery readings.
+pub const SAMPLE_EVERY: Duration = Duration::from_secs(30);
+
+/// Readings the drain rate is fitted over.
+const SMOOTHING: usize = 4;
+
+/// Seconds per hour, for the drain rate.
+const HOUR: f64 = 3600.0;
+
+/// One power-source reading.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Reading {
+    /// Charge in percent of full capacity.
+    pub percent: f64,
+    /// Whether the machine runs on battery rather than external power.
+    pub on_battery: bool,
+    /// Whether the battery is charging.
+    pub charging: bool,
+}
+
+/// Battery state and discharge history over the session.
+#[derive(Debug, Default)]
+pub struct Battery {
+    /// Latest reading; `None` without a battery.
+    pub reading: Option<Reading>,
+    /// When the latest reading was taken.
+    read_at: Option<Instant>,
+    /// `(seconds since the first, percent)` of recent on-battery readings.
+    samples: VecDeque<(f64, f64)>,
+    /// When the first of `samples` was taken.
+    since: Option<Instant>,
+    /// Percentage points lost while on battery this session.
+    pub consumed: f64,
+}
+
+impl Battery {
+    /// Reads the power source if [`SAMPLE_EVERY`] has passed since the
+    /// last reading (or there is none yet).
+    pub fn poll(&mut self) {
+        if self.read_at.is_some_and(|at| at.elapsed() < SAMPLE_EVERY) {
+            return;
+        }
+        let now = Instant::now();
+        self.read_at = Some(now);
+        match read() {
+            Some(reading) => self.record(reading, now),
+            None => self.reading = None,
+        }
+    }
+
+    /// Adds `reading`, taken at `now`, to the history.
+    pub fn record(&mut self, reading: Reading, now: Instant) {
+        let discharging = reading.on_battery && !reading.charging;
+        if let Some(last) = self.reading
+            && discharging
+            && last.on_battery
+        {
+            self.consumed += (last.percent - reading.percent).max(0.0);
+        }
+        self.reading = Some(reading);
+
+        if !discharging {
+            self.samples.clear();
+            self.since = None;
+            return;
+        }
+        let since = *self.since.get_or_insert(now);
+        if self.samples.len() == SMOOTHING {
+            self.samples.pop_front();
+        }
+        self.samples
+            .push_back((now.duration_since(since).as_secs_f64(), reading.percent));
+    }
+
+    /// Percentage points lost per hour, fitted over the recent readings;
+    /// `None` until two readings on battery exist.
+    pub fn drain_rate(&self) -> Option<f64> {
+        // Charge that rose between readings counts as no drain, not `-0.0`.
+        slope(&self.samples).map(|per_sec| if per_sec < 0.0 { -per_sec * HOUR } else { 0.0 })
+    }
+
+    /// Time until empty at the current drain rate; `None` while the rate
+    /// is unknown or zero.
+    pub fn time_to_empty(&self) -> Option<Duration> {
+        let rate = self.drain_rate().filter(|&r| r > 0.0)?;
+        let percent = self.reading?.percent;
+        Some(Duration::from_secs_f64(percent / rate * HOUR))
+    }
+}
+
+/// Least-squares slope of `points`, or `None` for fewer than two
+/// distinct x values.
+fn slope(points: &VecDeque<(f64, f64)>) -> Option<f64> {
+    let n = points.len() as f64;
+    if n < 2.0 {
+        return None;
+    }
+    let mean_x = points.iter().map(|&(x, _)| x).sum::<f64>() / n;
+    let mean_y = points.iter().map(|&(_, y)| y).sum::<f64>() / n;
+    let (mut cov, mut var) = (0.0, 0.0);
+    for &(x, y) in points {
+        cov += (x - mean_x) * (y - mean_y);
+        var += (x - mean_x) * (x - mean_x);
+    }
+    (var > 0.0).then(|| cov / var)
+}
+
+/// Formats a duration as `8h53m` or `41m`.
+pub fn fmt_remaining(remaining: Duration) -> String {
+    let minutes = remaining.as_secs() / 60;
+    if minutes >= 60 {
+        format!("{}h{:02}m", minutes / 60, minutes % 60)
+    } else {
+        format!("{minutes}m")
+    }
+}
+
+// ── IOKit power sources ─────────────────────────────────────
+
+/// Reads the internal battery, or `None` if the machine has none.
+#[cfg(target_os = "macos")]
+fn read() -> Option<Reading> {
+    use crate::iokit::*;
+
+    unsafe {
+        let blob = IOPSCopyPowerSourcesInfo();
+        if blob.is_null() {
+            return None;
+        }
+        let list = IOPSCopyPowerSourcesList(blob);
+        let mut reading = None;
+        if !list.is_null() {
+            for i in 0..CFArrayGetCount(list) {
+                let source = IOPSGetPowerSourceDescription(blob, CFArrayGetValueAtIndex(list, i));
+                if source.is_null() || !string_is(source, c"Type", c"InternalBattery") {
+                    continue;
+                }
+                let (Some(current), Some(max)) = (
+                    int(source, c"Current Capacity"),
+                    int(source, c"Max Capacity"),
+                ) else {
+                    continue;
+                };
+                if max <= 0 {
+                    continue;
+                }
+                reading = Some(Reading {
+                    percent: current as f64 / max as f64 * 100.0,
+                    on_battery: string_is(source, c"Power Source State", c"Battery Power"),
+                    charging: boolean(source, c"Is Charging").unwrap_or(false),
+                });
+                break;
+            }
+            CFRelease(list);
+        }
+        CFRelease(blob);
+        reading
+    }
+}
+
+/// No power-source API on this platform.
+#[cfg(not(target_os = "macos"))]
+fn read() -> Option<Reading> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A reading at `percent`, discharging on battery.
+    fn on_battery(percent: f64) -> Reading {
+        Reading {
+            percent,
+            on_battery: true,
+            charging: false,
+        }
+    }
+
+    /// Records `percents` [`SAMPLE_EVERY`] apart, starting at `start`.
+    fn record_all(battery: &mut Battery, start: Instant, percents: &[f64]) {
+        for (i, &percent) in percents.iter().enumerate() {
+            battery.record(on_battery(percent), start + SAMPLE_EVERY * i as u32);
+        }
+    }
+
+    #[test]
+    fn linear_discharge_gives_its_rate_and_time_to_empty() {
+        let mut battery = Battery::default();
+        let start = Instant::now();
+        record_all(&mut battery, start, &[80.0]);
+        assert_eq!(battery.drain_rate(), None);
+        assert_eq!(battery.time_to_empty(), None);
+        // Half a point every 30 s is 60 points an hour.
+        record_all(&mut battery, start + SAMPLE_EVERY, &[79.5, 79.0, 78.5]);
+        assert!((battery.drain_rate().unwrap() - 60.0).abs() < 1e-9);
+        let left = battery.time_to_empty().unwrap().as_secs_f64();
+        assert!((left - 78.5 * 60.0).abs() < 1e-6, "{left}");
+        assert_eq!(fmt_remaining(battery.time_to_empty().unwrap()), "1h18m");
+    }
+
+    #[test]
+    fn plugging_in_starts_the_rate_over() {
+        let mut battery = Battery::default();
+        let start = Instant::now();
+        record_all(&mut battery, start, &[80.0, 79.0, 78.0]);
+        assert!(battery.drain_rate().is_some());
+        let plugged = Reading {
+            on_battery: false,
+            ..on_battery(78.0)
+        };
+        battery.record(plugged, start + SAMPLE_EVERY * 3);
+        assert_eq!(battery.drain_rate(), None);
+        // Back on battery, one reading isn't a rate yet.
+        battery.record(on_battery(78.0), start + SAMPLE_EVERY * 4);
+        assert_eq!(battery.drain_rate(), None);
+        battery.record(on_battery(77.0), start + SAMPLE_EVERY * 5);
+        assert!((battery.drain_rate().unwrap() - 120.0).abs() < 1e-9);
+        // Charging on battery power resets it as well.
+        let charging = Reading {
+            charging: true,
+            ..on_battery(77.0)
+        };
+        battery.record(charging, start + SAMPLE_EVERY * 6);
+        assert_eq!(battery.drain_rate(), None);
+    }
+
+    #[test]
+    fn a_rise_between_readings_is_no_drain() {
+        let mut battery = Battery::default();
+        record_all(&mut battery, Instant::now(), &[50.0, 50.5, 51.0]);
+        assert_eq!(battery.drain_rate(), Some(0.0));
+        assert!(battery.drain_rate().unwrap().is_sign_positive());
+        assert_eq!(battery.time_to_empty(), None);
+        assert_eq!(battery.consumed, 0.0);
+    }
+
+    #[test]
+    fn rate_is_fitted_over_the_last_readings_only() {
+        let mut battery = Battery::default();
+        // A steep start, then a flat stretch as long as the window.
+        let mut percents = vec![90.0, 85.0, 80.0];
+        percents.extend([80.0; SMOOTHING]);
+        record_all(&mut battery, Instant::now(), &percents);
+        assert_eq!(battery.samples.len(), SMOOTHING);
+        assert_eq!(battery.drain_rate(), Some(0.0));
+
+        // Only 85, 80, 80, 80 are fitted: a slope of 0.05 points a second.
+        let mut battery = Battery::default();
+        record_all(
+            &mut battery,
+            Instant::now(),
+            &[90.0, 85.0, 80.0, 80.0, 80.0],
+        );
+        assert!((battery.drain_rate().unwrap() - 180.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn consumed_counts_every_drop_on_battery() {
+        let mut battery = Battery::default();
+        let start = Instant::now();
+        record_all(&mut battery, start, &[80.0, 79.0, 79.5, 78.0]);
+        // 1 + 0 (a rise) + 1.5.
+        assert!((battery.consumed - 2.5).abs() < 1e-9);
+        // A drop across a stretch on external power doesn't count.
+        let plugged = Reading {
+            on_battery: false,
+            ..on_battery(70.0)
+        };
+        battery.record(plugged, start + SAMPLE_EVERY * 4);
+        battery.record(on_battery(69.0), start + SAMPLE_EVERY * 5);
+        assert!((battery.consumed - 2.5).abs() < 1e-9);
+        battery.record(on_battery(68.0), start + SAMPLE_EVERY * 6);
+        assert!((battery.consumed - 3.5).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,166 @@
+//! Spawn/exit timeline of one process's descendants.
+//!
+//! A [`Timeline`] is locked to a parent PID when the view opens and is
+//! fed every tick's process list. Descendants found by walking parent
+//! links get a [`ChildRecord`] that tracks their peak CPU and memory
+//! until they exit. Records are keyed by PID and start time, so a reused
+//! PID starts a new one.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::json::{self, Object};
+use crate::monitor::{Pid, ProcessSample};
+
+/// Records kept per timeline; the oldest is dropped beyond this.
+pub const TIMELINE_LEN: usize = 200;
+
+/// One descendant seen during the session.
+#[derive(Debug, Clone)]
+pub struct ChildRecord {
+    /// Process identifier.
+    pub pid: Pid,
+    /// Parent at the last sample it was alive.
+    pub parent: Option<Pid>,
+    /// Display name.
+    pub name: String,
+    /// When the process started.
+    pub started: SystemTime,
+    /// First tick it was missing from the process list.
+    pub ended: Option<SystemTime>,
+    /// Whether it was already running when the timeline was locked.
+    pub preexisting: bool,
+    /// Highest measured CPU percentage.
+    pub peak_cpu: f32,
+    /// Highest resident memory in bytes.
+    pub peak_memory: u64,
+}
+
+impl ChildRecord {
+    /// Whether `p` is the process this record describes.
+    fn is(&self, p: &ProcessSample) -> bool {
+        self.pid == p.pid && self.started == start_time(p)
+    }
+}
+
+/// Descendants of one locked parent process, oldest first.
+#[derive(Debug)]
+pub struct Timeline {
+    /// Process whose descendants are tracked.
+    pub parent: Pid,
+    /// Its name when the timeline was locked.
+    pub parent_name: String,
+    /// When the timeline was locked.
+    pub since: SystemTime,
+    /// Tracked descendants, in order of appearance.
+    pub records: VecDeque<ChildRecord>,
+    /// Records dropped to stay within [`TIMELINE_LEN`].
+    pub dropped: usize,
+}
+
+impl Timeline {
+    /// Locks a timeline to `parent`, recording descendants already running.
+    pub fn new(parent: &ProcessSample, procs: &[ProcessSample]) -> Self {
+        let mut timeline = Self {
+            parent: parent.pid,
+            parent_name: parent.name.clone(),
+            since: SystemTime::now(),
+            records: VecDeque::new(),
+            dropped: 0,
+        };
+        timeline.observe(procs, true);
+        timeline
+    }
+
+    /// Folds in one tick's process list.
+    pub fn update(&mut self, procs: &[ProcessSample]) {
+        self.observe(procs, false);
+
+        let now = SystemTime::now();
+        for record in self.records.iter_mut().filter(|r| r.ended.is_none()) {
+            if !procs.iter().any(|p| record.is(p)) {
+                record.ended = Some(now);
+            }
+        }
+    }
+
+    /// Adds new descendants in `procs` and updates the peaks of known ones.
+    fn observe(&mut self, procs: &[ProcessSample], preexisting: bool) {
+        for p in descendants(self.parent, procs) {
+            let cpu = if p.cpu_measured { p.cpu_usage } else { 0.0 };
+            if let Some(record) = self.records.iter_mut().find(|r| r.is(p)) {
+                record.parent = p.parent;
+                record.peak_cpu = record.peak_cpu.max(cpu);
+                record.peak_memory = record.peak_memory.max(p.memory);
+                continue;
+            }
+            if self.records.len() == TIMELINE_LEN {
+                self.records.pop_front();
+                self.dropped += 1;
+            }
+            self.records.push_back(ChildRecord {
+                pid: p.pid,
+                parent: p.parent,
+                name: p.name.clone(),
+                started: start_time(p),
+                ended: None,
+                preexisting,
+                peak_cpu: cpu,
+                peak_memory: p.memory,
+            });
+        }
+    }
+
+    /// Encodes the timeline as one JSON object.
+    pub fn to_json(&self) -> String {
+        let children = self.records.iter().map(|r| {
+            Object::new()
+                .int("pid", r.pid)
+                .opt("parent", r.parent.map(|pid| pid.to_string()))
+                .str("name", &r.name)
+                .str("started", &json::timestamp(r.started))
+                .opt("ended", r.ended.map(|t| json::string(&json::timestamp(t))))
+                .raw("preexisting", if r.preexisting { "true" } else { "false" })
+                .num("peak_cpu", f64::from(r.peak_cpu))
+                .int("peak_memory", r.peak_memory)
+                .finish()
+        });
+        Object::new()
+            .int("parent", self.parent)
+            .str("parent_name", &self.parent_name)
+            .str("since", &json::timestamp(self.since))
+            .int("dropped", self.dropped as u64)
+            .raw("children", &json::array(children))
+            .finish()
+    }
+}
+
+/// Processes descended from `root`, parents before their children.
+fn descendants(root: Pid, procs: &[ProcessSample]) -> Vec<&ProcessSample> {
+    let mut children: HashMap<Pid, Vec<&ProcessSample>> = HashMap::new();
+    for p in procs {
+        if let Some(parent) = p.parent
+            && parent != p.pid
+        {
+            children.entry(parent).or_default().push(p);
+        }
+    }
+
+    let mut found = Vec::new();
+    let mut seen = HashSet::from([root]);
+    let mut queue = VecDeque::from([root]);
+    while let Some(pid) = queue.pop_front() {
+        for &child in children.get(&pid).into_iter().flatten() {
+            if seen.insert(child.pid) {
+                found.push(child);
+                queue.push_back(child.pid);
+            }
+        }
+    }
+    found
+}
+
+/// Start time of `p` as a [`SystemTime`].
+fn start_time(p: &ProcessSample) -> SystemTime {
+    UNIX_EPOCH + Duration::from_secs(p.started)
+}
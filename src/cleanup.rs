@@ -0,0 +1,41 @@
+//! Cleanup that must also happen when syswatch exits by panic.
+//!
+//! Lock files, the control socket and the terminal title are undone by
+//! their owners on a normal exit. A panic skips that, so each owner also
+//! chains its undo onto the panic hook here; the newest cleanup runs
+//! first, then the hooks installed before it.
+
+use std::fs;
+use std::panic;
+use std::path::PathBuf;
+
+/// Chains `cleanup` onto the panic hook, running it before the previous hook.
+pub fn chain_panic_cleanup(cleanup: impl Fn() + Send + Sync + 'static) {
+    let previous = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        cleanup();
+        previous(info);
+    }));
+}
+
+/// Chains removal of the file at `path` onto the panic hook.
+pub fn remove_on_panic(path: PathBuf) {
+    chain_panic_cleanup(move || {
+        let _ = fs::remove_file(&path);
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+
+    #[test]
+    fn file_is_removed_when_a_thread_panics() {
+        let path = std::env::temp_dir().join(format!("syswatch-cleanup-{}", std::process::id()));
+        fs::write(&path, "").unwrap();
+        remove_on_panic(path.clone());
+        assert!(thread::spawn(|| panic!("expected")).join().is_err());
+        assert!(!path.exists());
+    }
+}
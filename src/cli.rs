@@ -0,0 +1,410 @@
+//! Command-line argument parsing.
+//!
+//! Arguments are parsed by hand into an [`Options`] struct; syswatch
+//! has few enough flags that a full argument-parser crate is overkill.
+
+use std::fmt;
+use std::net::{Ipv4Addr, SocketAddr};
+use std::path::PathBuf;
+
+use crate::app::Page;
+use crate::http::Url;
+use crate::keys::KeysFormat;
+use crate::record::RecordFormat;
+use crate::rotate::Rotation;
+
+/// Usage text printed for `--help` and on argument errors.
+const USAGE: &str = "\
+Usage: syswatch [OPTIONS]
+
+Options:
+  -c, --compact         Start in the single-line compact layout
+      --tree            Start with the process tree shown (as `t` toggles)
+      --page <PAGE>     Start on processes (default), memory (`M`), or disk (`I`)
+      --plain           Print a plain-text report every tick instead of the TUI
+                        (for screen readers; Ctrl-C quits)
+      --stream          Print one JSON object per tick to stdout instead of the TUI
+      --stream-processes <N>
+                        Busiest processes embedded in each --stream line (default: 10; 0 = none)
+      --config <PATH>   Read settings from PATH instead of the default location
+      --profile <NAME>  Apply the config's [profile.NAME] section at startup
+      --check           Probe every data collector, print a capability report, and exit
+      --keys [FORMAT]   Print the key bindings as text (default) or markdown, and exit
+      --socket <PATH>   Serve queries on PATH instead of
+                        ~/Library/Application Support/syswatch/sock
+      --no-socket       Don't open the query socket
+      --http <[HOST:]PORT>
+                        Serve a status page at / and the snapshot at /snapshot.json
+                        (binds 127.0.0.1 unless HOST is given)
+      --force           Start even if another syswatch holds the same --socket or --record path
+      --test-webhook    Send a sample alert to the configured webhook, print the HTTP status, and exit
+      --enable-auto-kill
+                        Let [kill] rules in `auto` mode send their signal (otherwise they only log)
+      --record <PATH>   Append one sample per tick to PATH
+      --view <PATH>     Browse a --record CSV file (following it while it grows) and exit
+      --record-format <FORMAT>
+                        Sample encoding: csv (default) or influx (line protocol)
+      --record-rotate <SIZE|daily>
+                        Record to PATH's numbered siblings (name.1.csv, name.2.csv, …),
+                        starting the next at SIZE (e.g. 100MB) or at midnight
+      --record-keep <N> Keep only the newest N rotated files
+      --influx-url <URL>
+                        POST line protocol to an InfluxDB write URL (http:// only;
+                        token from $INFLUX_TOKEN) instead of writing a file
+      --mqtt <HOST[:PORT]>
+                        Publish a JSON summary to an MQTT broker (builds with the
+                        `mqtt` feature only; port defaults to 1883)
+      --mqtt-topic <TOPIC>
+                        MQTT topic (default: syswatch/<hostname>)
+      --mqtt-every <N>  Publish every N ticks (default: 5)
+  -h, --help            Print this help and exit";
+
+/// Ticks between MQTT publishes when `--mqtt-every` is not given.
+#[cfg(feature = "mqtt")]
+const DEFAULT_MQTT_EVERY: u32 = 5;
+
+/// Processes per `--stream` line when `--stream-processes` is not given.
+const DEFAULT_STREAM_PROCESSES: usize = 10;
+
+/// Options parsed from the command line.
+#[derive(Debug, Default)]
+pub struct Options {
+    /// Start in the single-line compact layout.
+    pub compact: bool,
+    /// Start with the process tree shown.
+    pub tree: bool,
+    /// View opened at startup; `None` is the process table.
+    pub page: Option<Page>,
+    /// Print line-oriented reports instead of running the TUI.
+    pub plain: bool,
+    /// Print JSON lines instead of running the TUI.
+    pub stream: bool,
+    /// Processes embedded in each `--stream` line.
+    pub stream_processes: usize,
+    /// Config file overriding the default location.
+    pub config: Option<PathBuf>,
+    /// `[profile.<name>]` section applied at startup.
+    pub profile: Option<String>,
+    /// Print the collector capability report instead of starting the TUI.
+    pub check: bool,
+    /// Print the key bindings in this format instead of starting the TUI.
+    pub keys: Option<KeysFormat>,
+    /// Send a sample alert to the configured webhook instead of starting the TUI.
+    pub test_webhook: bool,
+    /// Whether `auto` kill rules may send signals.
+    pub enable_auto_kill: bool,
+    /// Query socket path overriding the default location.
+    pub socket: Option<PathBuf>,
+    /// Whether the query socket is disabled.
+    pub no_socket: bool,
+    /// Start even when another instance holds the same socket or recording path.
+    pub force: bool,
+    /// Address the HTTP status page listens on.
+    pub http: Option<SocketAddr>,
+    /// File that receives one sample per tick.
+    pub record: Option<PathBuf>,
+    /// Recorded CSV file to browse instead of monitoring live.
+    pub view: Option<PathBuf>,
+    /// Encoding of recorded samples.
+    pub record_format: RecordFormat,
+    /// When the recording moves on to the next numbered file.
+    pub record_rotate: Option<Rotation>,
+    /// Rotated files kept; `None` keeps every file.
+    pub record_keep: Option<usize>,
+    /// InfluxDB write endpoint that receives line-protocol samples.
+    pub influx_url: Option<Url>,
+    /// MQTT broker as `host:port`.
+    #[cfg(feature = "mqtt")]
+    pub mqtt: Option<String>,
+    /// MQTT topic; `None` uses `syswatch/<hostname>`.
+    #[cfg(feature = "mqtt")]
+    pub mqtt_topic: Option<String>,
+    /// Ticks between MQTT publishes.
+    #[cfg(feature = "mqtt")]
+    pub mqtt_every: u32,
+}
+
+/// Outcome of parsing that does not yield [`Options`].
+#[derive(Debug)]
+pub enum CliError {
+    /// `--help` was requested; print usage and exit successfully.
+    Help,
+    /// An argument was not recognised.
+    Unknown(String),
+    /// An option that takes a value was given none.
+    MissingValue(&'static str),
+    /// An option's value was rejected; the message says why.
+    InvalidValue(&'static str, String),
+    /// Options that can't be used together.
+    Conflict(&'static str),
+}
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Help => f.write_str(USAGE),
+            Self::Unknown(arg) => write!(f, "unrecognised argument '{arg}'\n\n{USAGE}"),
+            Self::MissingValue(flag) => write!(f, "{flag} requires a value\n\n{USAGE}"),
+            Self::InvalidValue(flag, msg) => write!(f, "invalid {flag}: {msg}"),
+            Self::Conflict(msg) => write!(f, "{msg}"),
+        }
+    }
+}
+
+impl Options {
+    /// Parses the process arguments (excluding the program name).
+    pub fn from_env() -> Result<Self, CliError> {
+        Self::parse(std::env::args().skip(1))
+    }
+
+    /// Parses an argument list into [`Options`].
+    pub fn parse<I>(args: I) -> Result<Self, CliError>
+    where
+        I: IntoIterator<Item = String>,
+    {
+        let mut opts = Self::default();
+        let mut args = args.into_iter().peekable();
+        let mut format_given = false;
+        let mut stream_processes = None;
+        #[cfg(feature = "mqtt")]
+        let mut mqtt_every = None;
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "-c" | "--compact" => opts.compact = true,
+                "--tree" => opts.tree = true,
+                "--page" => {
+                    let name = args.next().ok_or(CliError::MissingValue("--page"))?;
+                    let page = Page::parse(&name).ok_or_else(|| {
+                        CliError::InvalidValue(
+                            "--page",
+                            format!("`{name}` (expected one of {})", Page::NAMES.join(", ")),
+                        )
+                    })?;
+                    opts.page = Some(page);
+                }
+                "--config" => {
+                    let path = args.next().ok_or(CliError::MissingValue("--config"))?;
+                    opts.config = Some(PathBuf::from(path));
+                }
+                "--profile" => {
+                    let name = args.next().ok_or(CliError::MissingValue("--profile"))?;
+                    opts.profile = Some(name);
+                }
+                "--check" => opts.check = true,
+                "--keys" => {
+                    let format = args.peek().and_then(|a| KeysFormat::parse(a));
+                    if format.is_some() {
+                        args.next();
+                    }
+                    opts.keys = Some(format.unwrap_or_default());
+                }
+                "--test-webhook" => opts.test_webhook = true,
+                "--enable-auto-kill" => opts.enable_auto_kill = true,
+                "--socket" => {
+                    let path = args.next().ok_or(CliError::MissingValue("--socket"))?;
+                    opts.socket = Some(PathBuf::from(path));
+                }
+                "--no-socket" => opts.no_socket = true,
+                "--force" => opts.force = true,
+                "--http" => {
+                    let value = args.next().ok_or(CliError::MissingValue("--http"))?;
+                    opts.http = Some(
+                        parse_listen(&value)
+                            .map_err(|msg| CliError::InvalidValue("--http", msg))?,
+                    );
+                }
+                "--plain" => opts.plain = true,
+                "--stream" => opts.stream = true,
+                "--stream-processes" => {
+                    let value = args
+                        .next()
+                        .ok_or(CliError::MissingValue("--stream-processes"))?;
+                    let n = value.parse().map_err(|_| {
+                        CliError::InvalidValue(
+                            "--stream-processes",
+                            format!("`{value}` (expected a process count)"),
+                        )
+                    })?;
+                    stream_processes = Some(n);
+                }
+                "--record" => {
+                    let path = args.next().ok_or(CliError::MissingValue("--record"))?;
+                    opts.record = Some(PathBuf::from(path));
+                }
+                "--view" => {
+                    let path = args.next().ok_or(CliError::MissingValue("--view"))?;
+                    opts.view = Some(PathBuf::from(path));
+                }
+                "--record-format" => {
+                    let value = args
+                        .next()
+                        .ok_or(CliError::MissingValue("--record-format"))?;
+                    opts.record_format = RecordFormat::parse(&value).ok_or_else(|| {
+                        CliError::InvalidValue(
+                            "--record-format",
+                            format!("`{value}` (expected csv or influx)"),
+                        )
+                    })?;
+                    format_given = true;
+                }
+                "--record-rotate" => {
+                    let value = args
+                        .next()
+                        .ok_or(CliError::MissingValue("--record-rotate"))?;
+                    let rotation = Rotation::parse(&value)
+                        .map_err(|msg| CliError::InvalidValue("--record-rotate", msg))?;
+                    opts.record_rotate = Some(rotation);
+                }
+                "--record-keep" => {
+                    let value = args.next().ok_or(CliError::MissingValue("--record-keep"))?;
+                    let keep = value.parse().ok().filter(|&n| n > 0).ok_or_else(|| {
+                        CliError::InvalidValue(
+                            "--record-keep",
+                            format!("`{value}` (expected a positive file count)"),
+                        )
+                    })?;
+                    opts.record_keep = Some(keep);
+                }
+                "--influx-url" => {
+                    let value = args.next().ok_or(CliError::MissingValue("--influx-url"))?;
+                    let url = Url::parse(&value)
+                        .map_err(|msg| CliError::InvalidValue("--influx-url", msg))?;
+                    opts.influx_url = Some(url);
+                }
+                #[cfg(feature = "mqtt")]
+                "--mqtt" => {
+                    let broker = args.next().ok_or(CliError::MissingValue("--mqtt"))?;
+                    opts.mqtt = Some(if broker.contains(':') {
+                        broker
+                    } else {
+                        format!("{broker}:{}", crate::mqtt::DEFAULT_PORT)
+                    });
+                }
+                #[cfg(feature = "mqtt")]
+                "--mqtt-topic" => {
+                    let topic = args.next().ok_or(CliError::MissingValue("--mqtt-topic"))?;
+                    opts.mqtt_topic = Some(topic);
+                }
+                #[cfg(feature = "mqtt")]
+                "--mqtt-every" => {
+                    let value = args.next().ok_or(CliError::MissingValue("--mqtt-every"))?;
+                    let every = value.parse().ok().filter(|&n| n > 0).ok_or_else(|| {
+                        CliError::InvalidValue(
+                            "--mqtt-every",
+                            format!("`{value}` (expected a positive tick count)"),
+                        )
+                    })?;
+                    mqtt_every = Some(every);
+                }
+                #[cfg(not(feature = "mqtt"))]
+                "--mqtt" | "--mqtt-topic" | "--mqtt-every" => {
+                    return Err(CliError::Conflict(
+                        "the --mqtt options need a build with `--features mqtt`",
+                    ));
+                }
+                "-h" | "--help" => return Err(CliError::Help),
+                _ => return Err(CliError::Unknown(arg)),
+            }
+        }
+
+        #[cfg(feature = "mqtt")]
+        {
+            if opts.mqtt.is_none() && (opts.mqtt_topic.is_some() || mqtt_every.is_some()) {
+                return Err(CliError::Conflict(
+                    "--mqtt-topic and --mqtt-every need --mqtt",
+                ));
+            }
+            opts.mqtt_every = mqtt_every.unwrap_or(DEFAULT_MQTT_EVERY);
+        }
+
+        if opts.no_socket && opts.socket.is_some() {
+            return Err(CliError::Conflict(
+                "--socket and --no-socket can't be used together",
+            ));
+        }
+
+        if opts.view.is_some() && (opts.record.is_some() || opts.influx_url.is_some()) {
+            return Err(CliError::Conflict(
+                "--view can't be combined with --record or --influx-url",
+            ));
+        }
+
+        // The compact layout draws neither the table nor a page over it.
+        if opts.compact && opts.tree {
+            return Err(CliError::Conflict(
+                "--tree can't be combined with --compact, which shows no process table",
+            ));
+        }
+        if opts.page.is_some_and(|p| p != Page::Processes) && opts.compact {
+            return Err(CliError::Conflict(
+                "--page memory and --page disk can't be combined with --compact",
+            ));
+        }
+        if opts.page.is_some() && (opts.plain || opts.stream || opts.view.is_some()) {
+            return Err(CliError::Conflict(
+                "--page only applies to the live display, not --plain, --stream, or --view",
+            ));
+        }
+        if opts.tree && (opts.stream || opts.view.is_some()) {
+            return Err(CliError::Conflict(
+                "--tree can't be combined with --stream or --view",
+            ));
+        }
+
+        if opts.http.is_some() && opts.view.is_some() {
+            return Err(CliError::Conflict("--http can't be combined with --view"));
+        }
+
+        if opts.plain && opts.view.is_some() {
+            return Err(CliError::Conflict("--plain can't be combined with --view"));
+        }
+
+        if opts.stream && (opts.plain || opts.view.is_some()) {
+            return Err(CliError::Conflict(
+                "--stream can't be combined with --plain or --view",
+            ));
+        }
+        if stream_processes.is_some() && !opts.stream {
+            return Err(CliError::Conflict("--stream-processes needs --stream"));
+        }
+        opts.stream_processes = stream_processes.unwrap_or(DEFAULT_STREAM_PROCESSES);
+
+        if opts.influx_url.is_some() {
+            if opts.record.is_some() {
+                return Err(CliError::Conflict(
+                    "--record and --influx-url can't be used together",
+                ));
+            }
+            if format_given && opts.record_format != RecordFormat::Influx {
+                return Err(CliError::Conflict(
+                    "--influx-url requires --record-format influx",
+                ));
+            }
+            opts.record_format = RecordFormat::Influx;
+        } else if format_given && opts.record.is_none() {
+            return Err(CliError::Conflict(
+                "--record-format needs --record or --influx-url",
+            ));
+        }
+
+        if opts.record_rotate.is_some() && opts.record.is_none() {
+            return Err(CliError::Conflict("--record-rotate needs --record"));
+        }
+        if opts.record_keep.is_some() && opts.record_rotate.is_none() {
+            return Err(CliError::Conflict("--record-keep needs --record-rotate"));
+        }
+
+        Ok(opts)
+    }
+}
+
+/// Parses `--http`'s `PORT` or `HOST:PORT`; a bare port means localhost.
+fn parse_listen(value: &str) -> Result<SocketAddr, String> {
+    if let Ok(port) = value.parse::<u16>() {
+        return Ok(SocketAddr::from((Ipv4Addr::LOCALHOST, port)));
+    }
+    value
+        .parse()
+        .map_err(|_| format!("`{value}` (expected a port or an address such as 0.0.0.0:9870)"))
+}
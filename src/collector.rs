@@ -0,0 +1,418 @@
+//! Platform data collectors and the capability check.
+//!
+//! The Mach and libproc FFI lives in the `macos` submodule; other
+//! targets compile `fallback`, which has the same functions but reports
+//! every platform-specific reading as unavailable, leaving what
+//! `sysinfo` provides. Each collector syswatch relies on also
+//! implements [`Collector`], so `syswatch --check` probes the exact code
+//! paths the TUI uses and reports what works and why not.
+
+use std::io::{self, Write};
+
+use sysinfo::{ProcessRefreshKind, ProcessesToUpdate, System};
+
+#[cfg(target_os = "macos")]
+mod macos;
+#[cfg(target_os = "macos")]
+use macos as platform;
+
+#[cfg(not(target_os = "macos"))]
+mod fallback;
+#[cfg(not(target_os = "macos"))]
+use fallback as platform;
+
+#[cfg(not(target_os = "macos"))]
+pub use fallback::MISSING;
+pub use platform::{
+    app_memory_bytes, block_devices, bsd_flags, cached_bytes, compressed_bytes, compressor,
+    cpu_ticks, device_driver, fd_counts, mach_messages, memory_pressure, mount_source, port_count,
+    process_io, task_counts, vm_statistics, wired_bytes,
+};
+
+/// System memory pressure as reported by the kernel's memorystatus.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+pub enum MemoryPressure {
+    /// Enough free memory.
+    Normal,
+    /// The system is compressing and reclaiming memory.
+    Warning,
+    /// Memory is exhausted; the system is swapping heavily or killing jobs.
+    Critical,
+}
+
+impl MemoryPressure {
+    /// Name used in JSON and CSV output.
+    pub fn id(self) -> &'static str {
+        match self {
+            Self::Normal => "normal",
+            Self::Warning => "warning",
+            Self::Critical => "critical",
+        }
+    }
+}
+
+/// Returns the effective user id; 0 (root) can inspect every process.
+pub fn euid() -> u32 {
+    unsafe { libc::geteuid() }
+}
+
+/// Returns the VM page size in bytes.
+pub fn page_size() -> u64 {
+    let size = unsafe { libc::sysconf(libc::_SC_PAGESIZE) };
+    u64::try_from(size).unwrap_or(4096)
+}
+
+/// Returns the machine's host name, or `"localhost"` if it can't be read.
+pub fn hostname() -> String {
+    let mut buf = [0u8; 256];
+    let rc = unsafe { libc::gethostname(buf.as_mut_ptr().cast(), buf.len()) };
+    let len = buf.iter().position(|&b| b == 0).unwrap_or(buf.len());
+    match std::str::from_utf8(&buf[..len]) {
+        Ok(name) if rc == 0 && !name.is_empty() => name.to_string(),
+        _ => "localhost".to_string(),
+    }
+}
+
+/// `pbi_flags` bit: the process was suspended by a resource-limit action.
+const PROC_FLAG_PA_SUSP: u32 = 0x1000;
+
+/// `pbi_flags` bit: a policy-control action (e.g. low swap) suspended the process.
+const PROC_FLAG_PC_SUSP: u32 = 0x400;
+
+/// `pbi_flags` mask of the policy-control action field.
+const PROC_FLAG_PC_MASK: u32 = 0x600;
+
+/// `pbi_flags` bit: the process is suppressed, which is how App Nap is applied.
+const PROC_FLAG_SUPPRESSED: u32 = 0x80_0000;
+
+/// How the OS is holding a process back, from its `proc_bsdinfo` flags.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+pub enum NapState {
+    /// In App Nap: timers coalesced and CPU and I/O throttled while in the background.
+    Napping,
+    /// Suspended outright by a resource or policy action.
+    Suspended,
+}
+
+impl NapState {
+    /// Interprets `proc_bsdinfo.pbi_flags`; `None` when nothing holds the process back.
+    ///
+    /// Values are the `PROC_FLAG_*` constants of `<sys/proc_info.h>`. The
+    /// policy-control field is a two-bit code, so only its suspend value
+    /// counts, not the throttle (`0x200`) or kill (`0x600`) values.
+    pub fn from_flags(flags: u32) -> Option<Self> {
+        if flags & PROC_FLAG_PA_SUSP != 0 || flags & PROC_FLAG_PC_MASK == PROC_FLAG_PC_SUSP {
+            Some(Self::Suspended)
+        } else if flags & PROC_FLAG_SUPPRESSED != 0 {
+            Some(Self::Napping)
+        } else {
+            None
+        }
+    }
+
+    /// Description for the detail view.
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Napping => "App Nap",
+            Self::Suspended => "suspended by the OS",
+        }
+    }
+}
+
+/// Thread counts of one task from a single `PROC_PIDTASKINFO` call.
+#[derive(Debug, Clone, Copy)]
+pub struct TaskCounts {
+    /// Threads in the task.
+    pub threads: u32,
+    /// Threads currently runnable.
+    pub running: u32,
+}
+
+/// Memory compressor state from one VM-statistics read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+pub struct Compressor {
+    /// Bytes of physical memory the compressor occupies.
+    pub stored: u64,
+    /// Bytes the pages it holds would take uncompressed.
+    pub original: u64,
+    /// Pages compressed since boot.
+    pub compressions: u64,
+    /// Pages decompressed since boot.
+    pub decompressions: u64,
+}
+
+impl Compressor {
+    /// Uncompressed-to-stored size ratio; `None` while nothing is stored.
+    pub fn ratio(&self) -> Option<f64> {
+        (self.stored > 0).then(|| self.original as f64 / self.stored as f64)
+    }
+
+    /// Pages compressed and decompressed per second since `earlier`,
+    /// read `secs` seconds before this one.
+    pub fn rates_since(&self, earlier: &Self, secs: f64) -> (f64, f64) {
+        let rate = |now: u64, then: u64| now.saturating_sub(then) as f64 / secs;
+        (
+            rate(self.compressions, earlier.compressions),
+            rate(self.decompressions, earlier.decompressions),
+        )
+    }
+}
+
+/// Mach messages a task has sent and received since it started.
+///
+/// Both are 32-bit kernel counters that wrap, so compare two readings
+/// with [`MachMessages::rates_since`] rather than subtracting.
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+pub struct MachMessages {
+    /// Messages sent.
+    pub sent: u32,
+    /// Messages received.
+    pub received: u32,
+}
+
+impl MachMessages {
+    /// Messages sent and received per second since `earlier`, read
+    /// `secs` seconds before this one.
+    pub fn rates_since(&self, earlier: &Self, secs: f64) -> (f64, f64) {
+        let rate = |now: u32, then: u32| f64::from(now.wrapping_sub(then)) / secs;
+        (
+            rate(self.sent, earlier.sent),
+            rate(self.received, earlier.received),
+        )
+    }
+}
+
+/// Cumulative disk I/O of one process.
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+pub struct ProcessIo {
+    /// Bytes read from storage over the process's lifetime.
+    pub read_bytes: u64,
+    /// Bytes written to storage over the process's lifetime.
+    pub written_bytes: u64,
+}
+
+impl ProcessIo {
+    /// Bytes read and written per second since `earlier`, read `secs`
+    /// seconds before this one.
+    pub fn rates_since(&self, earlier: &Self, secs: f64) -> (f64, f64) {
+        let rate = |now: u64, then: u64| now.saturating_sub(then) as f64 / secs;
+        (
+            rate(self.read_bytes, earlier.read_bytes),
+            rate(self.written_bytes, earlier.written_bytes),
+        )
+    }
+}
+
+/// Cumulative I/O of one block-storage driver instance: a physical disk,
+/// external drive, or attached disk image.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+pub struct BlockDevice {
+    /// Registry entry ID of the driver, unique while it is attached.
+    pub id: u64,
+    /// BSD name of the whole disk, e.g. `disk0`.
+    pub name: String,
+    /// Bytes read since the driver attached.
+    pub read_bytes: u64,
+    /// Bytes written since the driver attached.
+    pub write_bytes: u64,
+}
+
+impl BlockDevice {
+    /// Bytes read and written per second since `earlier`, read `secs`
+    /// seconds before this one.
+    pub fn rates_since(&self, earlier: &Self, secs: f64) -> (f64, f64) {
+        let rate = |now: u64, then: u64| now.saturating_sub(then) as f64 / secs;
+        (
+            rate(self.read_bytes, earlier.read_bytes),
+            rate(self.write_bytes, earlier.write_bytes),
+        )
+    }
+}
+
+// ── Capability probes ───────────────────────────────────────
+
+/// Outcome class of a collector probe.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Health {
+    /// Fully working.
+    Ok,
+    /// Working with reduced coverage (e.g. other users' processes hidden).
+    Degraded,
+    /// Not working; the corresponding UI data will be missing.
+    Failed,
+}
+
+impl Health {
+    /// Label printed in the report.
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Ok => "ok",
+            Self::Degraded => "degraded",
+            Self::Failed => "FAILED",
+        }
+    }
+}
+
+/// Result of probing one collector.
+#[derive(Debug, Clone)]
+pub struct Probe {
+    /// Outcome class.
+    pub health: Health,
+    /// What was read, or why it failed.
+    pub detail: String,
+}
+
+impl Probe {
+    /// A working probe.
+    fn ok(detail: impl Into<String>) -> Self {
+        Self {
+            health: Health::Ok,
+            detail: detail.into(),
+        }
+    }
+
+    /// A probe that worked with reduced coverage.
+    fn degraded(detail: impl Into<String>) -> Self {
+        Self {
+            health: Health::Degraded,
+            detail: detail.into(),
+        }
+    }
+
+    /// A failed probe.
+    fn failed(detail: impl Into<String>) -> Self {
+        Self {
+            health: Health::Failed,
+            detail: detail.into(),
+        }
+    }
+}
+
+/// A data source syswatch reads from.
+pub trait Collector {
+    /// Short name shown in the capability report.
+    fn name(&self) -> &'static str;
+
+    /// Performs one real collection and reports the outcome.
+    fn probe(&self) -> Probe;
+}
+
+/// `sysinfo` process and memory refresh.
+struct Sysinfo;
+
+impl Collector for Sysinfo {
+    fn name(&self) -> &'static str {
+        "sysinfo processes/memory"
+    }
+
+    fn probe(&self) -> Probe {
+        let mut sys = System::new();
+        sys.refresh_memory();
+        sys.refresh_processes_specifics(
+            ProcessesToUpdate::All,
+            true,
+            ProcessRefreshKind::nothing().with_cpu().with_memory(),
+        );
+
+        let procs = sys.processes().len();
+        let total = sys.total_memory() / (1024 * 1024);
+        let detail = format!("{procs} processes, {total} MiB total memory");
+        if procs == 0 || total == 0 {
+            Probe::failed(detail)
+        } else {
+            Probe::ok(detail)
+        }
+    }
+}
+
+/// Every collector, in report order: `sysinfo`, then the platform's own.
+pub fn collectors() -> Vec<Box<dyn Collector>> {
+    let mut all: Vec<Box<dyn Collector>> = vec![Box::new(Sysinfo)];
+    all.extend(platform::collectors());
+    all
+}
+
+/// Probes every collector and prints a report table to `out`.
+///
+/// Returns whether no collector failed outright.
+pub fn check(out: &mut impl Write) -> io::Result<bool> {
+    let euid = euid();
+    writeln!(
+        out,
+        "syswatch {} capability check (euid {euid}{})\n",
+        env!("CARGO_PKG_VERSION"),
+        if euid == 0 { ", elevated" } else { "" }
+    )?;
+    writeln!(out, "  {:<30} {:<9} DETAIL", "COLLECTOR", "STATUS")?;
+
+    let mut healthy = true;
+    for collector in collectors() {
+        let probe = collector.probe();
+        healthy &= probe.health != Health::Failed;
+        writeln!(
+            out,
+            "  {:<30} {:<9} {}",
+            collector.name(),
+            probe.health.label(),
+            probe.detail
+        )?;
+    }
+    Ok(healthy)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `PROC_FLAG_PC_THROTTLE`: policy control throttles the process.
+    const PC_THROTTLE: u32 = 0x200;
+    /// `PROC_FLAG_PC_KILL`: policy control kills the process.
+    const PC_KILL: u32 = 0x600;
+    /// `PROC_FLAG_PA_THROTTLE`: a resource-limit action throttles the process.
+    const PA_THROTTLE: u32 = 0x800;
+
+    #[test]
+    fn nap_state_from_bsdinfo_flags() {
+        assert_eq!(NapState::from_flags(0), None);
+        assert_eq!(
+            NapState::from_flags(PROC_FLAG_SUPPRESSED),
+            Some(NapState::Napping)
+        );
+        assert_eq!(
+            NapState::from_flags(PROC_FLAG_PC_SUSP),
+            Some(NapState::Suspended)
+        );
+        assert_eq!(
+            NapState::from_flags(PROC_FLAG_PA_SUSP),
+            Some(NapState::Suspended)
+        );
+        // Suspension wins over App Nap.
+        assert_eq!(
+            NapState::from_flags(PROC_FLAG_SUPPRESSED | PROC_FLAG_PA_SUSP),
+            Some(NapState::Suspended)
+        );
+    }
+
+    #[test]
+    fn policy_control_field_is_a_code_not_bits() {
+        // 0x600 is the kill action, even though it includes the 0x400 bit.
+        assert_eq!(NapState::from_flags(PC_KILL), None);
+        assert_eq!(NapState::from_flags(PC_THROTTLE), None);
+        assert_eq!(NapState::from_flags(PA_THROTTLE), None);
+        assert_eq!(
+            NapState::from_flags(PC_KILL | PROC_FLAG_SUPPRESSED),
+            Some(NapState::Napping)
+        );
+        // Unrelated bits (here PROC_FLAG_SYSTEM and PROC_FLAG_LP64) are ignored.
+        assert_eq!(
+            NapState::from_flags(0x11 | PROC_FLAG_PC_SUSP),
+            Some(NapState::Suspended)
+        );
+    }
+}
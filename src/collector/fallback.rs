@@ -0,0 +1,143 @@
+//! Stand-ins for the macOS collectors on other targets.
+//!
+//! Every function compiles and fails the way its macOS counterpart does
+//! when the kernel refuses, so the app's existing fallbacks take over:
+//! CPU load comes from `sysinfo` without the user/system split, memory
+//! from `sysinfo`'s used figure, and per-process counts show as `·`.
+
+use std::path::Path;
+
+use super::{
+    BlockDevice, Collector, Compressor, MachMessages, MemoryPressure, Probe, ProcessIo, TaskCounts,
+};
+use crate::monitor::Pid;
+
+/// Capabilities missing on this platform, for the status bar and `--check`.
+pub const MISSING: &[&str] = &[
+    "CPU user/system split",
+    "app, wired, compressed and cached memory",
+    "memory pressure",
+    "thread and run counts",
+    "fd and socket counts",
+    "per-process disk I/O",
+    "Mach message and port counts",
+    "thermal pressure",
+    "battery drain",
+    "per-device disk I/O",
+];
+
+/// Error code returned by every stub: "function not implemented".
+const UNSUPPORTED: i32 = libc::ENOSYS;
+
+/// Virtual-memory counters; never produced on this platform.
+#[derive(Debug, Clone, Copy)]
+pub enum VmStatistics64 {}
+
+/// Always fails: Mach CPU ticks are macOS-only.
+pub fn cpu_ticks() -> Result<[u64; 4], i32> {
+    Err(UNSUPPORTED)
+}
+
+/// Always fails: Mach VM statistics are macOS-only.
+pub fn vm_statistics() -> Result<VmStatistics64, i32> {
+    Err(UNSUPPORTED)
+}
+
+/// Unreachable: no [`VmStatistics64`] value exists here.
+pub fn app_memory_bytes(vm: &VmStatistics64, _page_size: u64) -> u64 {
+    match *vm {}
+}
+
+/// Unreachable: no [`VmStatistics64`] value exists here.
+pub fn wired_bytes(vm: &VmStatistics64, _page_size: u64) -> u64 {
+    match *vm {}
+}
+
+/// Unreachable: no [`VmStatistics64`] value exists here.
+pub fn compressed_bytes(vm: &VmStatistics64, _page_size: u64) -> u64 {
+    match *vm {}
+}
+
+/// Unreachable: no [`VmStatistics64`] value exists here.
+pub fn compressor(vm: &VmStatistics64, _page_size: u64) -> Compressor {
+    match *vm {}
+}
+
+/// Unreachable: no [`VmStatistics64`] value exists here.
+pub fn cached_bytes(vm: &VmStatistics64, _page_size: u64) -> u64 {
+    match *vm {}
+}
+
+/// Always `None`: memorystatus is macOS-only.
+pub fn memory_pressure() -> Option<MemoryPressure> {
+    None
+}
+
+/// Always fails: libproc is macOS-only.
+pub fn bsd_flags(_pid: Pid) -> Result<u32, i32> {
+    Err(UNSUPPORTED)
+}
+
+/// Always fails: libproc is macOS-only.
+pub fn task_counts(_pid: Pid) -> Result<TaskCounts, i32> {
+    Err(UNSUPPORTED)
+}
+
+/// Always fails: libproc is macOS-only.
+pub fn mach_messages(_pid: Pid) -> Result<MachMessages, i32> {
+    Err(UNSUPPORTED)
+}
+
+/// Always fails: Mach ports are macOS-only.
+pub fn port_count(_pid: Pid) -> Result<u32, i32> {
+    Err(UNSUPPORTED)
+}
+
+/// Always fails: libproc is macOS-only.
+pub fn fd_counts(_pid: Pid) -> Result<(u32, u32), i32> {
+    Err(UNSUPPORTED)
+}
+
+/// Always fails: `proc_pid_rusage` is macOS-only.
+pub fn process_io(_pid: Pid) -> Result<ProcessIo, i32> {
+    Err(UNSUPPORTED)
+}
+
+/// Always fails: IOKit block-storage statistics are macOS-only.
+pub fn block_devices() -> Result<Vec<BlockDevice>, i32> {
+    Err(UNSUPPORTED)
+}
+
+/// Never finds a driver: IOKit is macOS-only.
+pub fn device_driver(_bsd_name: &str) -> Option<u64> {
+    None
+}
+
+/// Never finds a source device; only used with IOKit.
+pub fn mount_source(_mount_point: &Path) -> Option<String> {
+    None
+}
+
+// ── Capability probes ───────────────────────────────────────
+
+/// Reports the platform-specific collectors that aren't built in.
+struct Unsupported;
+
+impl Collector for Unsupported {
+    fn name(&self) -> &'static str {
+        "platform collectors"
+    }
+
+    fn probe(&self) -> Probe {
+        Probe::degraded(format!(
+            "not available on {}: {}",
+            std::env::consts::OS,
+            MISSING.join(", ")
+        ))
+    }
+}
+
+/// The platform's collectors, in report order.
+pub fn collectors() -> Vec<Box<dyn Collector>> {
+    vec![Box::new(Unsupported)]
+}
@@ -0,0 +1,795 @@
+//! macOS collectors: Mach host statistics, libproc, and sysctl.
+//!
+//! Everything here is FFI into libSystem; [`super`] re-exports the
+//! functions and [`collectors`] supplies the matching probes for the
+//! capability check.
+
+use std::ffi::CStr;
+use std::io;
+use std::mem;
+use std::os::unix::ffi::OsStrExt;
+use std::path::Path;
+
+use super::{
+    BlockDevice, Collector, Compressor, MachMessages, MemoryPressure, Probe, ProcessIo, TaskCounts,
+    page_size,
+};
+use crate::format::fmt_bytes;
+use crate::iokit::{self, Entry};
+use crate::monitor::Pid;
+use crate::thermal::ThermalMonitor;
+
+// ── macOS mach FFI ──────────────────────────────────────────
+
+/// Mach host_statistics flavor for CPU load info.
+const HOST_CPU_LOAD_INFO: i32 = 3;
+
+#[repr(C)]
+struct HostCpuLoadInfo {
+    cpu_ticks: [u32; 4],
+}
+
+unsafe extern "C" {
+    fn mach_host_self() -> u32;
+    unsafe fn host_statistics(host: u32, flavor: i32, info: *mut i32, count: *mut u32) -> i32;
+}
+
+/// Returns the cached Mach host port (evaluated once).
+fn cached_host_port() -> u32 {
+    use std::sync::OnceLock;
+    static PORT: OnceLock<u32> = OnceLock::new();
+    *PORT.get_or_init(|| unsafe { mach_host_self() })
+}
+
+/// Reads aggregate CPU ticks from the Mach kernel.
+///
+/// Returns `[user, system, idle, nice]` as `u64`, or the `kern_return_t`
+/// on failure.
+pub fn cpu_ticks() -> Result<[u64; 4], i32> {
+    unsafe {
+        let mut info: HostCpuLoadInfo = mem::zeroed();
+        let mut count = (mem::size_of::<HostCpuLoadInfo>() / mem::size_of::<u32>()) as u32;
+        let ret = host_statistics(
+            cached_host_port(),
+            HOST_CPU_LOAD_INFO,
+            (&raw mut info).cast::<i32>(),
+            &mut count,
+        );
+        if ret == 0 {
+            Ok(info.cpu_ticks.map(u64::from))
+        } else {
+            Err(ret)
+        }
+    }
+}
+
+/// Mach host_statistics64 flavor for virtual-memory counters.
+const HOST_VM_INFO64: i32 = 4;
+
+/// `vm_statistics64` from `<mach/vm_statistics.h>`; counts are in pages.
+#[repr(C, align(8))]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct VmStatistics64 {
+    free_count: u32,
+    active_count: u32,
+    inactive_count: u32,
+    wire_count: u32,
+    zero_fill_count: u64,
+    reactivations: u64,
+    pageins: u64,
+    pageouts: u64,
+    faults: u64,
+    cow_faults: u64,
+    lookups: u64,
+    hits: u64,
+    purges: u64,
+    purgeable_count: u32,
+    speculative_count: u32,
+    decompressions: u64,
+    compressions: u64,
+    swapins: u64,
+    swapouts: u64,
+    compressor_page_count: u32,
+    throttled_count: u32,
+    external_page_count: u32,
+    internal_page_count: u32,
+    total_uncompressed_pages_in_compressor: u64,
+}
+
+unsafe extern "C" {
+    unsafe fn host_statistics64(host: u32, flavor: i32, info: *mut i32, count: *mut u32) -> i32;
+}
+
+/// Reads the kernel's virtual-memory counters, or the `kern_return_t` on failure.
+pub fn vm_statistics() -> Result<VmStatistics64, i32> {
+    unsafe {
+        let mut info = VmStatistics64::default();
+        let mut count = (mem::size_of::<VmStatistics64>() / mem::size_of::<i32>()) as u32;
+        let ret = host_statistics64(
+            cached_host_port(),
+            HOST_VM_INFO64,
+            (&raw mut info).cast::<i32>(),
+            &mut count,
+        );
+        if ret == 0 { Ok(info) } else { Err(ret) }
+    }
+}
+
+/// Derives Activity Monitor-style app memory in bytes.
+///
+/// `(internal + compressed − purgeable) × page size`: anonymous pages
+/// owned by processes, plus what the compressor holds on their behalf,
+/// minus purgeable pages the system may discard at will. File-backed
+/// (cache) pages and wired kernel memory are excluded.
+pub fn app_memory_bytes(vm: &VmStatistics64, page_size: u64) -> u64 {
+    let pages = u64::from(vm.internal_page_count) + u64::from(vm.compressor_page_count);
+    pages.saturating_sub(u64::from(vm.purgeable_count)) * page_size
+}
+
+/// Wired (kernel-locked) memory in bytes from VM statistics.
+pub fn wired_bytes(vm: &VmStatistics64, page_size: u64) -> u64 {
+    u64::from(vm.wire_count) * page_size
+}
+
+/// Compressor-held memory in bytes from VM statistics.
+pub fn compressed_bytes(vm: &VmStatistics64, page_size: u64) -> u64 {
+    u64::from(vm.compressor_page_count) * page_size
+}
+
+/// Compressor sizes in bytes and its cumulative page counters.
+///
+/// `compressor_page_count` is the physical pages the compressed data
+/// occupies, `total_uncompressed_pages_in_compressor` the pages it
+/// stands for.
+pub fn compressor(vm: &VmStatistics64, page_size: u64) -> Compressor {
+    Compressor {
+        stored: u64::from(vm.compressor_page_count) * page_size,
+        original: vm.total_uncompressed_pages_in_compressor * page_size,
+        compressions: vm.compressions,
+        decompressions: vm.decompressions,
+    }
+}
+
+/// File-backed (cached) memory in bytes from VM statistics.
+pub fn cached_bytes(vm: &VmStatistics64, page_size: u64) -> u64 {
+    u64::from(vm.external_page_count) * page_size
+}
+
+unsafe extern "C" {
+    unsafe fn sysctlbyname(
+        name: *const libc::c_char,
+        oldp: *mut libc::c_void,
+        oldlenp: *mut usize,
+        newp: *mut libc::c_void,
+        newlen: usize,
+    ) -> i32;
+}
+
+/// Reads `kern.memorystatus_vm_pressure_level`, or `None` if it is unavailable.
+pub fn memory_pressure() -> Option<MemoryPressure> {
+    let mut level: i32 = 0;
+    let mut len = mem::size_of::<i32>();
+    let rc = unsafe {
+        sysctlbyname(
+            c"kern.memorystatus_vm_pressure_level".as_ptr(),
+            (&raw mut level).cast::<libc::c_void>(),
+            &mut len,
+            std::ptr::null_mut(),
+            0,
+        )
+    };
+    if rc != 0 {
+        return None;
+    }
+    // Values of `kVMPressure*` in <sys/kern_memorystatus.h>.
+    match level {
+        1 => Some(MemoryPressure::Normal),
+        2 => Some(MemoryPressure::Warning),
+        4 => Some(MemoryPressure::Critical),
+        _ => None,
+    }
+}
+
+// ── macOS libproc FFI (per-process task info) ───────────────
+
+/// `proc_pidinfo` flavor for task-level info.
+const PROC_PIDTASKINFO: i32 = 4;
+
+#[repr(C)]
+struct ProcTaskInfo {
+    pti_virtual_size: u64,
+    pti_resident_size: u64,
+    pti_total_user: u64,
+    pti_total_system: u64,
+    pti_threads_user: u64,
+    pti_threads_system: u64,
+    pti_policy: i32,
+    pti_faults: i32,
+    pti_pageins: i32,
+    pti_cow_faults: i32,
+    pti_messages_sent: i32,
+    pti_messages_received: i32,
+    pti_syscalls_mach: i32,
+    pti_syscalls_unix: i32,
+    pti_csw: i32,
+    pti_threadnum: i32,
+    pti_numrunning: i32,
+    pti_priority: i32,
+}
+
+unsafe extern "C" {
+    unsafe fn proc_pidinfo(
+        pid: i32,
+        flavor: i32,
+        arg: u64,
+        buffer: *mut libc::c_void,
+        buffersize: i32,
+    ) -> i32;
+}
+
+/// `proc_pidinfo` flavor for BSD-level info (`struct proc_bsdinfo`).
+const PROC_PIDTBSDINFO: i32 = 3;
+
+#[repr(C)]
+struct ProcBsdInfo {
+    pbi_flags: u32,
+    pbi_status: u32,
+    pbi_xstatus: u32,
+    pbi_pid: u32,
+    pbi_ppid: u32,
+    pbi_uid: u32,
+    pbi_gid: u32,
+    pbi_ruid: u32,
+    pbi_rgid: u32,
+    pbi_svuid: u32,
+    pbi_svgid: u32,
+    rfu_1: u32,
+    pbi_comm: [u8; 16],
+    pbi_name: [u8; 32],
+    pbi_nfiles: u32,
+    pbi_pgid: u32,
+    pbi_pjobc: u32,
+    e_tdev: u32,
+    e_tpgid: u32,
+    pbi_nice: i32,
+    pbi_start_tvsec: u64,
+    pbi_start_tvusec: u64,
+}
+
+/// `proc_pidinfo` flavor listing a process's open file descriptors.
+const PROC_PIDLISTFDS: i32 = 1;
+
+/// `proc_fdinfo.proc_fdtype` value for sockets.
+const PROX_FDTYPE_SOCKET: u32 = 2;
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct ProcFdInfo {
+    proc_fd: i32,
+    proc_fdtype: u32,
+}
+
+/// Returns the calling thread's `errno`, defaulting to `EPERM`.
+fn last_errno() -> i32 {
+    std::io::Error::last_os_error()
+        .raw_os_error()
+        .unwrap_or(libc::EPERM)
+}
+
+/// Returns the total and runnable thread counts of `pid`, or the `errno` on failure.
+pub fn task_counts(pid: Pid) -> Result<TaskCounts, i32> {
+    task_info(pid).map(|t| TaskCounts {
+        threads: t.pti_threadnum.max(0) as u32,
+        running: t.pti_numrunning.max(0) as u32,
+    })
+}
+
+/// Returns `pid`'s Mach message counters, or the `errno` on failure.
+pub fn mach_messages(pid: Pid) -> Result<MachMessages, i32> {
+    // The kernel keeps these as 32-bit counters that wrap; read them unsigned.
+    task_info(pid).map(|t| MachMessages {
+        sent: t.pti_messages_sent as u32,
+        received: t.pti_messages_received as u32,
+    })
+}
+
+/// Fetches task-level info for `pid`, returning the `errno` on failure.
+fn task_info(pid: Pid) -> Result<ProcTaskInfo, i32> {
+    unsafe {
+        let mut info: ProcTaskInfo = mem::zeroed();
+        let expected = mem::size_of::<ProcTaskInfo>() as i32;
+        let ret = proc_pidinfo(
+            pid as i32,
+            PROC_PIDTASKINFO,
+            0,
+            (&raw mut info).cast::<libc::c_void>(),
+            expected,
+        );
+        if ret == expected {
+            Ok(info)
+        } else {
+            Err(last_errno())
+        }
+    }
+}
+
+/// Returns `pid`'s `proc_bsdinfo.pbi_flags`, or the `errno` on failure.
+///
+/// Unlike task info, BSD info is readable for other users' processes too.
+pub fn bsd_flags(pid: Pid) -> Result<u32, i32> {
+    unsafe {
+        let mut info: ProcBsdInfo = mem::zeroed();
+        let expected = mem::size_of::<ProcBsdInfo>() as i32;
+        let ret = proc_pidinfo(
+            pid as i32,
+            PROC_PIDTBSDINFO,
+            0,
+            (&raw mut info).cast::<libc::c_void>(),
+            expected,
+        );
+        if ret == expected {
+            Ok(info.pbi_flags)
+        } else {
+            Err(last_errno())
+        }
+    }
+}
+
+unsafe extern "C" {
+    static mach_task_self_: u32;
+    unsafe fn task_for_pid(target: u32, pid: i32, task: *mut u32) -> i32;
+    unsafe fn mach_port_names(
+        task: u32,
+        names: *mut *mut u32,
+        names_count: *mut u32,
+        types: *mut *mut u32,
+        types_count: *mut u32,
+    ) -> i32;
+    unsafe fn mach_port_deallocate(task: u32, name: u32) -> i32;
+    unsafe fn vm_deallocate(task: u32, address: usize, size: usize) -> i32;
+}
+
+/// Counts the Mach port names in `pid`'s IPC space, or returns the
+/// `kern_return_t` on failure.
+///
+/// Needs `task_for_pid`, which only root gets, and not even root for
+/// platform binaries protected by SIP.
+pub fn port_count(pid: Pid) -> Result<u32, i32> {
+    unsafe {
+        let own = mach_task_self_;
+        let mut task = 0;
+        let ret = task_for_pid(own, pid as i32, &mut task);
+        if ret != 0 {
+            return Err(ret);
+        }
+        let mut names = std::ptr::null_mut();
+        let mut types = std::ptr::null_mut();
+        let (mut names_count, mut types_count) = (0, 0);
+        let ret = mach_port_names(
+            task,
+            &mut names,
+            &mut names_count,
+            &mut types,
+            &mut types_count,
+        );
+        if ret == 0 {
+            let entry = mem::size_of::<u32>();
+            vm_deallocate(own, names as usize, names_count as usize * entry);
+            vm_deallocate(own, types as usize, types_count as usize * entry);
+        }
+        mach_port_deallocate(own, task);
+        if ret == 0 { Ok(names_count) } else { Err(ret) }
+    }
+}
+
+/// Counts `(open fds, sockets)` for `pid`, returning the `errno` on failure.
+pub fn fd_counts(pid: Pid) -> Result<(u32, u32), i32> {
+    const ENTRY: usize = mem::size_of::<ProcFdInfo>();
+
+    unsafe {
+        let needed = proc_pidinfo(pid as i32, PROC_PIDLISTFDS, 0, std::ptr::null_mut(), 0);
+        if needed <= 0 {
+            return Err(last_errno());
+        }
+
+        // Leave headroom for descriptors opened between the two calls.
+        let mut fds = vec![
+            ProcFdInfo {
+                proc_fd: 0,
+                proc_fdtype: 0,
+            };
+            needed as usize / ENTRY + 16
+        ];
+        let ret = proc_pidinfo(
+            pid as i32,
+            PROC_PIDLISTFDS,
+            0,
+            fds.as_mut_ptr().cast::<libc::c_void>(),
+            (fds.len() * ENTRY) as i32,
+        );
+        if ret <= 0 {
+            return Err(last_errno());
+        }
+
+        let fds = &fds[..ret as usize / ENTRY];
+        let sockets = fds
+            .iter()
+            .filter(|fd| fd.proc_fdtype == PROX_FDTYPE_SOCKET)
+            .count();
+        Ok((fds.len() as u32, sockets as u32))
+    }
+}
+
+/// `proc_pid_rusage` flavor for `struct rusage_info_v2`, the first with disk I/O.
+const RUSAGE_INFO_V2: i32 = 2;
+
+#[repr(C)]
+struct RusageInfoV2 {
+    ri_uuid: [u8; 16],
+    ri_user_time: u64,
+    ri_system_time: u64,
+    ri_pkg_idle_wkups: u64,
+    ri_interrupt_wkups: u64,
+    ri_pageins: u64,
+    ri_wired_size: u64,
+    ri_resident_size: u64,
+    ri_phys_footprint: u64,
+    ri_proc_start_abstime: u64,
+    ri_proc_exit_abstime: u64,
+    ri_child_user_time: u64,
+    ri_child_system_time: u64,
+    ri_child_pkg_idle_wkups: u64,
+    ri_child_interrupt_wkups: u64,
+    ri_child_pageins: u64,
+    ri_child_elapsed_abstime: u64,
+    ri_diskio_bytesread: u64,
+    ri_diskio_byteswritten: u64,
+}
+
+unsafe extern "C" {
+    unsafe fn proc_pid_rusage(pid: i32, flavor: i32, buffer: *mut libc::c_void) -> i32;
+}
+
+/// Returns `pid`'s lifetime disk I/O, or the `errno` on failure.
+///
+/// Like task info, this needs the same user or root.
+pub fn process_io(pid: Pid) -> Result<ProcessIo, i32> {
+    unsafe {
+        let mut info: RusageInfoV2 = mem::zeroed();
+        let ret = proc_pid_rusage(
+            pid as i32,
+            RUSAGE_INFO_V2,
+            (&raw mut info).cast::<libc::c_void>(),
+        );
+        if ret != 0 {
+            return Err(last_errno());
+        }
+        Ok(ProcessIo {
+            read_bytes: info.ri_diskio_bytesread,
+            written_bytes: info.ri_diskio_byteswritten,
+        })
+    }
+}
+
+// ── IOKit block storage ─────────────────────────────────────
+
+/// IOKit class whose instances keep per-disk I/O statistics.
+const BLOCK_STORAGE_DRIVER: &CStr = c"IOBlockStorageDriver";
+
+/// Reads every block-storage driver's byte counters, or returns the
+/// `kern_return_t` if the registry can't be searched.
+///
+/// Each driver instance is one physical disk or disk image; APFS
+/// containers and their volumes sit above it, so their I/O is already
+/// included. Drivers without a BSD-named disk under them (an empty card
+/// reader) or without statistics are skipped.
+pub fn block_devices() -> Result<Vec<BlockDevice>, i32> {
+    let mut devices = Vec::new();
+    for driver in iokit::services(BLOCK_STORAGE_DRIVER)? {
+        let Some(id) = driver.id() else {
+            continue;
+        };
+        let Some(name) = driver.child().and_then(|media| bsd_name(&media)) else {
+            continue;
+        };
+        let Some(stats) = driver.property(c"Statistics") else {
+            continue;
+        };
+        let counter = |key| unsafe { iokit::int(stats.get(), key) }.unwrap_or(0).max(0) as u64;
+        devices.push(BlockDevice {
+            id,
+            name,
+            read_bytes: counter(c"Bytes (Read)"),
+            write_bytes: counter(c"Bytes (Write)"),
+        });
+    }
+    Ok(devices)
+}
+
+/// Finds the block-storage driver BSD device `bsd_name` (a volume such
+/// as `disk3s1`) ultimately lives on, walking up through partitions and
+/// APFS containers; returns the driver's registry entry ID.
+pub fn device_driver(bsd_name: &str) -> Option<u64> {
+    let mut entry = iokit::bsd_media(bsd_name)?;
+    while !entry.conforms_to(BLOCK_STORAGE_DRIVER) {
+        entry = entry.parent()?;
+    }
+    entry.id()
+}
+
+/// BSD name of the device mounted at `mount_point`, e.g. `disk3s1s1`;
+/// `None` for mounts not backed by a disk (devfs, network shares).
+pub fn mount_source(mount_point: &Path) -> Option<String> {
+    let path = std::ffi::CString::new(mount_point.as_os_str().as_bytes()).ok()?;
+    let mut fs: libc::statfs = unsafe { mem::zeroed() };
+    if unsafe { libc::statfs(path.as_ptr(), &mut fs) } != 0 {
+        return None;
+    }
+    let source = unsafe { CStr::from_ptr(fs.f_mntfromname.as_ptr()) };
+    let source = source.to_str().ok()?;
+    source.strip_prefix("/dev/").map(str::to_string)
+}
+
+/// `BSD Name` property of a media entry.
+fn bsd_name(media: &Entry) -> Option<String> {
+    let name = media.property(c"BSD Name")?;
+    unsafe { iokit::to_string(name.get()) }
+}
+
+// ── Capability probes ───────────────────────────────────────
+
+/// System-wide CPU ticks (`host_statistics`).
+struct MachCpu;
+
+impl Collector for MachCpu {
+    fn name(&self) -> &'static str {
+        "mach host_statistics (cpu)"
+    }
+
+    fn probe(&self) -> Probe {
+        match cpu_ticks() {
+            Ok([user, system, idle, nice]) => Probe::ok(format!(
+                "ticks user {user}, system {system}, idle {idle}, nice {nice}"
+            )),
+            Err(kr) => Probe::failed(format!("kern_return {kr}; CPU split unavailable")),
+        }
+    }
+}
+
+/// Virtual-memory counters (`host_statistics64`).
+struct MachVm;
+
+impl Collector for MachVm {
+    fn name(&self) -> &'static str {
+        "mach host_statistics64 (vm)"
+    }
+
+    fn probe(&self) -> Probe {
+        match vm_statistics() {
+            Ok(vm) => Probe::ok(format!(
+                "app memory {} MiB",
+                app_memory_bytes(&vm, page_size()) / (1024 * 1024)
+            )),
+            Err(kr) => Probe::failed(format!(
+                "kern_return {kr}; app memory mode falls back to sysinfo"
+            )),
+        }
+    }
+}
+
+/// Per-process task info (`proc_pidinfo`) for one PID.
+struct LibprocTask {
+    /// Probe label.
+    name: &'static str,
+    /// Process to inspect.
+    pid: Pid,
+}
+
+impl Collector for LibprocTask {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn probe(&self) -> Probe {
+        match task_counts(self.pid) {
+            Ok(c) => Probe::ok(format!(
+                "pid {}: {} threads, {} running",
+                self.pid, c.threads, c.running
+            )),
+            Err(errno) if errno == libc::EPERM || errno == libc::EACCES => {
+                Probe::degraded(format!(
+                    "pid {}: {} (errno {errno}); other users' fields show as ·, run with sudo",
+                    self.pid,
+                    io::Error::from_raw_os_error(errno)
+                ))
+            }
+            Err(errno) => Probe::failed(format!(
+                "pid {}: {} (errno {errno})",
+                self.pid,
+                io::Error::from_raw_os_error(errno)
+            )),
+        }
+    }
+}
+
+/// Open file descriptor listing (`proc_pidinfo` `PROC_PIDLISTFDS`).
+struct LibprocFds;
+
+impl Collector for LibprocFds {
+    fn name(&self) -> &'static str {
+        "libproc listfds (self)"
+    }
+
+    fn probe(&self) -> Probe {
+        match fd_counts(std::process::id()) {
+            Ok((fds, sockets)) => Probe::ok(format!("{fds} fds, {sockets} sockets")),
+            Err(errno) => Probe::failed(format!(
+                "{} (errno {errno})",
+                io::Error::from_raw_os_error(errno)
+            )),
+        }
+    }
+}
+
+/// Per-process disk I/O counters (`proc_pid_rusage`).
+struct LibprocRusage;
+
+impl Collector for LibprocRusage {
+    fn name(&self) -> &'static str {
+        "libproc rusage (self)"
+    }
+
+    fn probe(&self) -> Probe {
+        match process_io(std::process::id()) {
+            Ok(usage) => Probe::ok(format!(
+                "{} read, {} written",
+                fmt_bytes(usage.read_bytes),
+                fmt_bytes(usage.written_bytes)
+            )),
+            Err(errno) => Probe::failed(format!(
+                "{} (errno {errno})",
+                io::Error::from_raw_os_error(errno)
+            )),
+        }
+    }
+}
+
+/// Thermal pressure via the Darwin notification center.
+struct ThermalNotify;
+
+impl Collector for ThermalNotify {
+    fn name(&self) -> &'static str {
+        "notify thermal pressure"
+    }
+
+    fn probe(&self) -> Probe {
+        match ThermalMonitor::new() {
+            Some(monitor) => match monitor.level() {
+                Some(level) => Probe::ok(format!("level {level:?}")),
+                None => Probe::degraded("registered, but notify_get_state failed"),
+            },
+            None => Probe::degraded("notify_register_check failed; throttling strip hidden"),
+        }
+    }
+}
+
+/// Per-disk I/O counters from IOKit's block-storage drivers.
+struct BlockStorage;
+
+impl Collector for BlockStorage {
+    fn name(&self) -> &'static str {
+        "iokit block storage"
+    }
+
+    fn probe(&self) -> Probe {
+        match block_devices() {
+            Ok(devices) if devices.is_empty() => Probe::degraded("no block-storage drivers found"),
+            Ok(devices) => {
+                let names: Vec<&str> = devices.iter().map(|d| d.name.as_str()).collect();
+                Probe::ok(format!("{} devices: {}", devices.len(), names.join(", ")))
+            }
+            Err(kr) => Probe::failed(format!("kern_return {kr}; disk popup empty")),
+        }
+    }
+}
+
+/// The platform's collectors, in report order.
+pub fn collectors() -> Vec<Box<dyn Collector>> {
+    vec![
+        Box::new(MachCpu),
+        Box::new(MachVm),
+        Box::new(LibprocTask {
+            name: "libproc pidinfo (self)",
+            pid: std::process::id(),
+        }),
+        Box::new(LibprocTask {
+            name: "libproc pidinfo (pid 1)",
+            pid: 1,
+        }),
+        Box::new(LibprocFds),
+        Box::new(LibprocRusage),
+        Box::new(ThermalNotify),
+        Box::new(BlockStorage),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Page size of Apple silicon Macs.
+    const PAGE: u64 = 16_384;
+
+    /// Counters in the shape `vm_stat` reports on a busy 16 GB machine.
+    fn fixture() -> VmStatistics64 {
+        VmStatistics64 {
+            free_count: 20_000,
+            active_count: 380_000,
+            inactive_count: 360_000,
+            wire_count: 150_000,
+            purgeable_count: 12_000,
+            speculative_count: 4_000,
+            compressor_page_count: 60_000,
+            external_page_count: 250_000,
+            internal_page_count: 490_000,
+            total_uncompressed_pages_in_compressor: 180_000,
+            ..VmStatistics64::default()
+        }
+    }
+
+    #[test]
+    fn app_memory_is_anonymous_plus_compressed_minus_purgeable() {
+        let vm = fixture();
+        assert_eq!(
+            app_memory_bytes(&vm, PAGE),
+            (490_000 + 60_000 - 12_000) * PAGE
+        );
+        // Cache and wired pages are reported on their own, not in app memory.
+        assert_eq!(cached_bytes(&vm, PAGE), 250_000 * PAGE);
+        assert_eq!(wired_bytes(&vm, PAGE), 150_000 * PAGE);
+        assert_eq!(compressed_bytes(&vm, PAGE), 60_000 * PAGE);
+    }
+
+    #[test]
+    fn purgeable_pages_never_make_app_memory_negative() {
+        let vm = VmStatistics64 {
+            internal_page_count: 1_000,
+            purgeable_count: 5_000,
+            ..VmStatistics64::default()
+        };
+        assert_eq!(app_memory_bytes(&vm, PAGE), 0);
+    }
+
+    #[test]
+    fn compressor_sizes_are_pages_times_page_size() {
+        let vm = VmStatistics64 {
+            compressions: 9_000,
+            decompressions: 4_000,
+            ..fixture()
+        };
+        assert_eq!(
+            compressor(&vm, PAGE),
+            Compressor {
+                stored: 60_000 * PAGE,
+                original: 180_000 * PAGE,
+                compressions: 9_000,
+                decompressions: 4_000,
+            }
+        );
+        assert_eq!(compressor(&vm, PAGE).ratio(), Some(3.0));
+        assert_eq!(compressor(&VmStatistics64::default(), PAGE).ratio(), None);
+    }
+
+    #[test]
+    fn compressor_rates_are_page_counts_per_second() {
+        let earlier = compressor(&fixture(), PAGE);
+        let now = Compressor {
+            compressions: earlier.compressions + 500,
+            decompressions: earlier.decompressions + 120,
+            ..earlier
+        };
+        assert_eq!(now.rates_since(&earlier, 2.0), (250.0, 60.0));
+        // Counters that went backwards (a reset) read as no activity.
+        assert_eq!(earlier.rates_since(&now, 2.0), (0.0, 0.0));
+    }
+}
@@ -0,0 +1,808 @@
+//! User configuration file.
+//!
+//! Read from `--config <path>`, `$XDG_CONFIG_HOME/syswatch/config.toml`,
+//! or `~/.config/syswatch/config.toml`, in that order. Only the small
+//! TOML subset syswatch needs is understood: `[section]` headers and
+//! `key = value` pairs whose values are strings, numbers, booleans, or
+//! (possibly multi-line) arrays of strings.
+
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::alerts::{AlertConfig, AlertKind, WatchRule};
+use crate::enforce::KillRule;
+use crate::http::Url;
+use crate::notify::{Channel, NotifyConfig, TimeRange};
+use crate::profile::Profile;
+use crate::rules::ColorRule;
+use crate::title;
+use crate::view::{COLUMNS, column_by_id, compile_filter};
+
+/// Commands offered by the "open with" picker when the config has none.
+const DEFAULT_OPEN_WITH: &[&str] = &[
+    "lsof -p {pid} | ${PAGER:-less}",
+    "vmmap {pid} | ${PAGER:-less}",
+    "kill -INFO {pid}",
+];
+
+/// Tick interval when `[tick] interval` is not set.
+const DEFAULT_TICK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Default process count above which high-count mode engages.
+const DEFAULT_HIGH_COUNT: usize = 10_000;
+
+/// Battery percentage at or below which low-power mode engages by default.
+const DEFAULT_LOW_POWER: f64 = 20.0;
+
+/// Settings loaded from the config file, with defaults for absent keys.
+#[derive(Debug, Clone)]
+pub struct Config {
+    /// Command templates for the "open with" picker (`{pid}` / `{name}`).
+    pub open_with: Vec<String>,
+    /// Whether the htop-style function-key bar is shown.
+    pub function_bar: bool,
+    /// Whether CPU band separators start enabled.
+    pub cpu_bands: bool,
+    /// Whether `kernel_task` is kept out of the top ranks of the CPU sort.
+    pub demote_kernel_task: bool,
+    /// Whether tree mode marks process-group boundaries.
+    pub tree_groups: bool,
+    /// Whether memory cells start colored relative to the table's largest value.
+    pub relative_colors: bool,
+    /// Whether relative coloring also replaces the CPU band row colors.
+    pub relative_cpu: bool,
+    /// Whether per-core CPU usage is sampled, enabling the "Max core" chart series.
+    pub per_core: bool,
+    /// Whether held `j` / `k` move the selection in growing steps.
+    pub accelerate_keys: bool,
+    /// Whether the detail pane starts docked beside the process table.
+    pub detail_dock: bool,
+    /// Whether syswatch's own process is left out of the table, summary, and alerts.
+    pub hide_self: bool,
+    /// How used memory is computed.
+    pub memory_mode: MemoryMode,
+    /// Visible process-table columns as indices into [`COLUMNS`]; `None` uses the defaults.
+    pub columns: Option<Vec<usize>>,
+    /// Unit of the process table's Memory column.
+    pub memory_unit: MemoryUnit,
+    /// What the row frozen below the process table shows.
+    pub table_footer: TableFooter,
+    /// Multiple between the units of displayed rates.
+    pub rate_base: RateBase,
+    /// Whether network rates are displayed in bits rather than bytes per second.
+    pub rate_bits: bool,
+    /// Whether the terminal title carries a live summary.
+    pub title: bool,
+    /// Template of that summary, with `{cpu}`, `{mem}`, and `{procs}` placeholders.
+    pub title_template: String,
+    /// Whether the session summary is printed after quitting.
+    pub exit_summary: bool,
+    /// Interval between ticks at the normal rate.
+    pub tick_interval: Duration,
+    /// Whether ticks slow down while the system is idle and untouched.
+    pub adaptive_tick: bool,
+    /// Process count above which the cheaper high-count sampling engages.
+    pub high_count: usize,
+    /// Battery percentage at or below which, on battery power, low-power
+    /// mode engages; `None` never engages it.
+    pub low_power: Option<f64>,
+    /// Alert thresholds, enable flags, and webhook.
+    pub alerts: AlertConfig,
+    /// Notification channels per alert kind and their rate limit.
+    pub notify: NotifyConfig,
+    /// Kill rules from `[kill] rules`, in evaluation order.
+    pub kill_rules: Vec<KillRule>,
+    /// Named filter presets as `(name, pattern)`, in file order.
+    pub filters: Vec<(String, String)>,
+    /// Named setting bundles from `[profile.<name>]`, in file order.
+    pub profiles: Vec<Profile>,
+    /// User highlight rules, in evaluation order.
+    pub color_rules: Vec<ColorRule>,
+    /// Non-fatal problems found while loading, e.g. rules that were skipped.
+    pub warnings: Vec<String>,
+}
+
+/// Source of the "used memory" figure.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MemoryMode {
+    /// `sysinfo`'s used memory, unchanged.
+    #[default]
+    Sysinfo,
+    /// Activity Monitor's "App Memory", derived from `vm_statistics64`.
+    App,
+}
+
+impl MemoryMode {
+    /// Parses a config value (`"sysinfo"` or `"app"`).
+    fn parse(s: &str) -> Option<Self> {
+        match s {
+            "sysinfo" | "raw" => Some(Self::Sysinfo),
+            "app" => Some(Self::App),
+            _ => None,
+        }
+    }
+
+    /// Short label shown next to the memory figure.
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Sysinfo => "raw",
+            Self::App => "app",
+        }
+    }
+}
+
+/// Unit policy for the process table's Memory column.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MemoryUnit {
+    /// Largest unit that keeps the value at least 1 (B, KB, MB, GB).
+    #[default]
+    Auto,
+    /// Always megabytes.
+    Mb,
+    /// Always gigabytes.
+    Gb,
+}
+
+impl MemoryUnit {
+    /// Parses a config value (`"auto"`, `"mb"`, or `"gb"`).
+    fn parse(s: &str) -> Option<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "auto" => Some(Self::Auto),
+            "mb" => Some(Self::Mb),
+            "gb" => Some(Self::Gb),
+            _ => None,
+        }
+    }
+}
+
+/// Unit base of displayed rates.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RateBase {
+    /// Powers of 1000: kB/s, MB/s, GB/s.
+    #[default]
+    Si,
+    /// Powers of 1024: KiB/s, MiB/s, GiB/s.
+    Iec,
+}
+
+impl RateBase {
+    /// Parses a config value (`"si"` or `"iec"`).
+    fn parse(s: &str) -> Option<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "si" => Some(Self::Si),
+            "iec" => Some(Self::Iec),
+            _ => None,
+        }
+    }
+}
+
+/// Content of the row frozen below the process table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TableFooter {
+    /// The selected process, with its name untruncated by the tree prefix.
+    #[default]
+    Selected,
+    /// Sums over the processes that pass the filter.
+    Totals,
+    /// No footer.
+    Off,
+}
+
+impl TableFooter {
+    /// Parses a config value (`"selected"`, `"totals"`, or `"off"`).
+    fn parse(s: &str) -> Option<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "selected" => Some(Self::Selected),
+            "totals" => Some(Self::Totals),
+            "off" => Some(Self::Off),
+            _ => None,
+        }
+    }
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            open_with: DEFAULT_OPEN_WITH.iter().map(ToString::to_string).collect(),
+            function_bar: false,
+            cpu_bands: false,
+            demote_kernel_task: false,
+            tree_groups: false,
+            relative_colors: false,
+            relative_cpu: false,
+            per_core: false,
+            accelerate_keys: true,
+            detail_dock: false,
+            hide_self: false,
+            memory_mode: MemoryMode::default(),
+            columns: None,
+            memory_unit: MemoryUnit::default(),
+            table_footer: TableFooter::default(),
+            rate_base: RateBase::default(),
+            rate_bits: false,
+            title: false,
+            title_template: title::DEFAULT_TEMPLATE.to_string(),
+            exit_summary: true,
+            tick_interval: DEFAULT_TICK_INTERVAL,
+            adaptive_tick: false,
+            high_count: DEFAULT_HIGH_COUNT,
+            low_power: Some(DEFAULT_LOW_POWER),
+            alerts: AlertConfig::default(),
+            notify: NotifyConfig::default(),
+            kill_rules: Vec::new(),
+            filters: Vec::new(),
+            profiles: Vec::new(),
+            color_rules: Vec::new(),
+            warnings: Vec::new(),
+        }
+    }
+}
+
+/// A config file that could not be read or parsed.
+#[derive(Debug)]
+pub struct ConfigError {
+    /// File the error refers to.
+    pub path: PathBuf,
+    /// 1-based line number, when the error is tied to one.
+    pub line: Option<usize>,
+    /// Human-readable description.
+    pub msg: String,
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.line {
+            Some(line) => write!(f, "{}:{line}: {}", self.path.display(), self.msg),
+            None => write!(f, "{}: {}", self.path.display(), self.msg),
+        }
+    }
+}
+
+/// A parsed right-hand side of `key = value`.
+#[derive(Debug)]
+enum Value {
+    /// Quoted string.
+    Str(String),
+    /// Integer or decimal number.
+    Num(f64),
+    /// `true` / `false`.
+    Bool(bool),
+    /// Array of strings.
+    List(Vec<String>),
+}
+
+impl Value {
+    /// Short type name used in error messages.
+    fn kind(&self) -> &'static str {
+        match self {
+            Self::Str(_) => "string",
+            Self::Num(_) => "number",
+            Self::Bool(_) => "boolean",
+            Self::List(_) => "array",
+        }
+    }
+}
+
+/// One `key = value` pair together with its section and source line.
+#[derive(Debug)]
+struct Entry {
+    section: String,
+    key: String,
+    value: Value,
+    line: usize,
+}
+
+impl Config {
+    /// Loads the config from `path`, or from the default location.
+    ///
+    /// A missing default file yields [`Config::default`]; a missing
+    /// explicitly requested file is an error.
+    pub fn load(path: Option<&Path>) -> Result<Self, ConfigError> {
+        let explicit = path.is_some();
+        let Some(path) = Self::path(path) else {
+            return Ok(Self::default());
+        };
+
+        let text = match std::fs::read_to_string(&path) {
+            Ok(text) => text,
+            Err(e) if !explicit && e.kind() == std::io::ErrorKind::NotFound => {
+                return Ok(Self::default());
+            }
+            Err(e) => {
+                return Err(ConfigError {
+                    path,
+                    line: None,
+                    msg: e.to_string(),
+                });
+            }
+        };
+
+        Self::parse(&text).map_err(|(line, msg)| ConfigError {
+            path,
+            line: Some(line),
+            msg,
+        })
+    }
+
+    /// File [`Config::load`] reads for `path`: `path` itself, else the
+    /// default location, if a home directory is known.
+    pub fn path(path: Option<&Path>) -> Option<PathBuf> {
+        path.map(Path::to_path_buf).or_else(default_path)
+    }
+
+    /// Parses config text, returning `(line, message)` on failure.
+    pub fn parse(text: &str) -> Result<Self, (usize, String)> {
+        let mut config = Self::default();
+        for entry in parse_entries(text)? {
+            config.apply(&entry).map_err(|msg| (entry.line, msg))?;
+        }
+        // Checked last, since presets may follow the profile that names them.
+        for profile in &config.profiles {
+            if let Some(filter) = &profile.filter
+                && !config.filters.iter().any(|(n, _)| n == filter)
+            {
+                config.warnings.push(format!(
+                    "profile `{}`: no [filters] preset named `{filter}`",
+                    profile.name
+                ));
+            }
+        }
+        let alerts = &config.alerts;
+        if alerts.single_thread && alerts.single_thread_min > alerts.single_thread_max {
+            config.warnings.push(format!(
+                "[alerts] single_thread_min ({}) is above single_thread_max ({}); \
+                 no process will be flagged",
+                alerts.single_thread_min, alerts.single_thread_max
+            ));
+        }
+        Ok(config)
+    }
+
+    /// Stores one entry into the matching field.
+    fn apply(&mut self, entry: &Entry) -> Result<(), String> {
+        if let Some(name) = entry.section.strip_prefix("profile.") {
+            return self.apply_profile(name, entry);
+        }
+        match (entry.section.as_str(), entry.key.as_str()) {
+            ("open_with", "commands") => self.open_with = expect_list(entry)?,
+            ("ui", "function_bar") => self.function_bar = expect_bool(entry)?,
+            ("ui", "cpu_bands") => self.cpu_bands = expect_bool(entry)?,
+            ("ui", "accelerate_keys") => self.accelerate_keys = expect_bool(entry)?,
+            ("ui", "detail_dock") => self.detail_dock = expect_bool(entry)?,
+            ("ui", "hide_self") => self.hide_self = expect_bool(entry)?,
+            ("ui", "exit_summary") => self.exit_summary = expect_bool(entry)?,
+            ("ui", "rate_base") => {
+                let base = expect_str(entry)?;
+                self.rate_base = RateBase::parse(&base).ok_or_else(|| {
+                    format!("unknown rate base `{base}` (expected \"si\" or \"iec\")")
+                })?;
+            }
+            ("ui", "rate_bits") => self.rate_bits = expect_bool(entry)?,
+            ("ui", "title") => self.title = expect_bool(entry)?,
+            ("ui", "title_template") => {
+                let template = expect_str(entry)?;
+                title::check_template(&template)?;
+                self.title_template = template;
+            }
+            ("cpu", "per_core") => self.per_core = expect_bool(entry)?,
+            ("tick", "interval") => {
+                self.tick_interval = Duration::from_secs_f64(expect_positive(entry)?);
+            }
+            ("tick", "adaptive") => self.adaptive_tick = expect_bool(entry)?,
+            ("tick", "high_count") => self.high_count = expect_positive(entry)? as usize,
+            ("battery", "low_power") => {
+                self.low_power = match entry.value {
+                    Value::Bool(false) => None,
+                    Value::Num(n) if (0.0..=100.0).contains(&n) => Some(n),
+                    Value::Num(n) => {
+                        return Err(format!(
+                            "`low_power` in [battery] must be a percentage (0 to 100), found {n}"
+                        ));
+                    }
+                    ref other => return Err(type_error(entry, "percentage or false", other)),
+                };
+            }
+            ("alerts", "process_cpu") => self.alerts.process_cpu = expect_bool(entry)?,
+            ("alerts", "cpu_threshold") => self.alerts.cpu_threshold = expect_positive(entry)?,
+            ("alerts", "cpu_seconds") => self.alerts.cpu_seconds = expect_positive(entry)?,
+            ("alerts", "memory") => self.alerts.memory = expect_bool(entry)?,
+            ("alerts", "memory_threshold") => {
+                self.alerts.memory_threshold = expect_positive(entry)?;
+            }
+            ("alerts", "kernel_task") => self.alerts.kernel_task = expect_bool(entry)?,
+            ("alerts", "kernel_threshold") => {
+                self.alerts.kernel_threshold = expect_positive(entry)?;
+            }
+            ("alerts", "kernel_seconds") => {
+                self.alerts.kernel_seconds = expect_positive(entry)?;
+            }
+            ("alerts", "thread_growth") => self.alerts.thread_growth = expect_bool(entry)?,
+            ("alerts", "thread_growth_threshold") => {
+                self.alerts.thread_growth_threshold = expect_positive(entry)?;
+            }
+            ("alerts", "single_thread") => self.alerts.single_thread = expect_bool(entry)?,
+            ("alerts", "single_thread_min") => {
+                self.alerts.single_thread_min = expect_positive(entry)?;
+            }
+            ("alerts", "single_thread_max") => {
+                self.alerts.single_thread_max = expect_positive(entry)?;
+            }
+            ("alerts", "single_thread_seconds") => {
+                self.alerts.single_thread_seconds = expect_positive(entry)?;
+            }
+            ("alerts", "webhook_url") => {
+                let url = expect_str(entry)?;
+                self.alerts.webhook_url = Some(Url::parse(&url)?);
+            }
+            ("alerts", "watch") => {
+                for rule in expect_list(entry)? {
+                    let compiled = WatchRule::parse(&rule)
+                        .map_err(|msg| format!("invalid watch rule `{rule}`: {msg}"))?;
+                    self.alerts.watch.push(compiled);
+                }
+            }
+            ("notify", "rate_limit") => {
+                self.notify.rate_limit = Duration::from_secs_f64(expect_positive(entry)?);
+            }
+            ("notify", key) if self.notify.channels_mut(key).is_some() => {
+                let channels = expect_list(entry)?
+                    .iter()
+                    .map(|name| {
+                        Channel::parse(name).ok_or_else(|| {
+                            format!(
+                                "unknown channel `{name}` (expected bell, visual, notify, or webhook)"
+                            )
+                        })
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                if let Some(slot) = self.notify.channels_mut(key) {
+                    *slot = channels;
+                }
+            }
+            ("quiet_hours", key) if key == "all" || AlertKind::parse(key).is_some() => {
+                for text in expect_list(entry)? {
+                    let range = TimeRange::parse(&text)
+                        .map_err(|msg| format!("invalid quiet hours `{text}`: {msg}"))?;
+                    self.notify.quiet_hours.push((AlertKind::parse(key), range));
+                }
+            }
+            ("kill", "rules") => {
+                for rule in expect_list(entry)? {
+                    let compiled = KillRule::parse(&rule)
+                        .map_err(|msg| format!("invalid kill rule `{rule}`: {msg}"))?;
+                    self.kill_rules.push(compiled);
+                }
+            }
+            ("colors", "rules") => {
+                for rule in expect_list(entry)? {
+                    match ColorRule::parse(&rule) {
+                        Ok(compiled) => self.color_rules.push(compiled),
+                        Err(msg) => self.warnings.push(format!(
+                            "line {}: ignoring color rule `{rule}`: {msg}",
+                            entry.line
+                        )),
+                    }
+                }
+            }
+            ("table", "demote_kernel_task") => self.demote_kernel_task = expect_bool(entry)?,
+            ("table", "tree_groups") => self.tree_groups = expect_bool(entry)?,
+            ("table", "relative_colors") => self.relative_colors = expect_bool(entry)?,
+            ("table", "relative_cpu") => self.relative_cpu = expect_bool(entry)?,
+            ("table", "columns") => self.columns = Some(expect_columns(entry)?),
+            ("table", "memory_unit") => {
+                let unit = expect_str(entry)?;
+                self.memory_unit = MemoryUnit::parse(&unit).ok_or_else(|| {
+                    format!("unknown memory unit `{unit}` (expected \"auto\", \"mb\", or \"gb\")")
+                })?;
+            }
+            ("table", "footer") => {
+                let footer = expect_str(entry)?;
+                self.table_footer = TableFooter::parse(&footer).ok_or_else(|| {
+                    format!(
+                        "unknown table footer `{footer}` (expected \"selected\", \"totals\", or \"off\")"
+                    )
+                })?;
+            }
+            ("memory", "mode") => {
+                let mode = expect_str(entry)?;
+                self.memory_mode = MemoryMode::parse(&mode).ok_or_else(|| {
+                    format!("unknown memory mode `{mode}` (expected \"sysinfo\" or \"app\")")
+                })?;
+            }
+            ("filters", name) => {
+                let pattern = expect_str(entry)?;
+                compile_filter(&pattern)
+                    .map_err(|msg| format!("invalid pattern for filter `{name}`: {msg}"))?;
+                self.filters.retain(|(n, _)| n != name);
+                self.filters.push((name.to_string(), pattern));
+            }
+            (section, key) => return Err(format!("unknown key `{key}` in [{section}]")),
+        }
+        Ok(())
+    }
+
+    /// Stores one entry of a `[profile.<name>]` section.
+    fn apply_profile(&mut self, name: &str, entry: &Entry) -> Result<(), String> {
+        if name.is_empty() {
+            return Err("profile sections need a name, as in [profile.dev]".into());
+        }
+        let index = match self.profiles.iter().position(|p| p.name == name) {
+            Some(i) => i,
+            None => {
+                self.profiles.push(Profile {
+                    name: name.to_string(),
+                    ..Profile::default()
+                });
+                self.profiles.len() - 1
+            }
+        };
+        let profile = &mut self.profiles[index];
+        match entry.key.as_str() {
+            "compact" => profile.compact = Some(expect_bool(entry)?),
+            "columns" => profile.columns = Some(expect_columns(entry)?),
+            "filter" => profile.filter = Some(expect_str(entry)?),
+            "sort" => {
+                let id = expect_str(entry)?;
+                let column = column_by_id(&id).ok_or_else(|| unknown_column(&id))?;
+                profile.sort = Some(COLUMNS[column].sort);
+            }
+            "descending" => profile.descending = Some(expect_bool(entry)?),
+            "interval" => {
+                profile.interval = Some(Duration::from_secs_f64(expect_positive(entry)?));
+            }
+            "adaptive" => profile.adaptive = Some(expect_bool(entry)?),
+            "threads" => profile.threads = Some(expect_bool(entry)?),
+            "extras" => profile.extras = Some(expect_bool(entry)?),
+            "io_interval" => {
+                profile.io_interval = Some(Duration::from_secs_f64(expect_positive(entry)?));
+            }
+            key => return Err(format!("unknown key `{key}` in [{}]", entry.section)),
+        }
+        Ok(())
+    }
+}
+
+/// Extracts a non-empty list of column ids as indices into [`COLUMNS`].
+fn expect_columns(entry: &Entry) -> Result<Vec<usize>, String> {
+    let columns = expect_list(entry)?
+        .iter()
+        .map(|id| column_by_id(id).ok_or_else(|| unknown_column(id)))
+        .collect::<Result<Vec<_>, _>>()?;
+    if columns.is_empty() {
+        return Err(format!(
+            "`{}` in [{}] must list at least one column",
+            entry.key, entry.section
+        ));
+    }
+    Ok(columns)
+}
+
+/// Error message for a column id that isn't in [`COLUMNS`].
+fn unknown_column(id: &str) -> String {
+    let known: Vec<_> = COLUMNS.iter().map(|c| c.id).collect();
+    format!(
+        "unknown column `{id}` (expected one of {})",
+        known.join(", ")
+    )
+}
+
+/// Returns the default config path, if a home directory is known.
+fn default_path() -> Option<PathBuf> {
+    let base = std::env::var_os("XDG_CONFIG_HOME")
+        .filter(|v| !v.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|h| PathBuf::from(h).join(".config")))?;
+    Some(base.join("syswatch").join("config.toml"))
+}
+
+/// Extracts a string array from `entry`; a lone string becomes one item.
+fn expect_list(entry: &Entry) -> Result<Vec<String>, String> {
+    match &entry.value {
+        Value::List(items) => Ok(items.clone()),
+        Value::Str(s) => Ok(vec![s.clone()]),
+        other => Err(type_error(entry, "array", other)),
+    }
+}
+
+/// Extracts a string from `entry` or reports a type mismatch.
+fn expect_str(entry: &Entry) -> Result<String, String> {
+    match &entry.value {
+        Value::Str(s) => Ok(s.clone()),
+        other => Err(type_error(entry, "string", other)),
+    }
+}
+
+/// Extracts a number greater than zero from `entry`.
+fn expect_positive(entry: &Entry) -> Result<f64, String> {
+    match entry.value {
+        Value::Num(n) if n > 0.0 => Ok(n),
+        Value::Num(n) => Err(format!(
+            "`{}` in [{}] must be greater than 0, found {n}",
+            entry.key, entry.section
+        )),
+        ref other => Err(type_error(entry, "number", other)),
+    }
+}
+
+/// Extracts a boolean from `entry` or reports a type mismatch.
+fn expect_bool(entry: &Entry) -> Result<bool, String> {
+    match entry.value {
+        Value::Bool(b) => Ok(b),
+        ref other => Err(type_error(entry, "boolean", other)),
+    }
+}
+
+/// Formats a "wrong value type" message for `entry`.
+fn type_error(entry: &Entry, want: &str, got: &Value) -> String {
+    format!(
+        "`{}` in [{}] must be a {want}, found a {}",
+        entry.key,
+        entry.section,
+        got.kind()
+    )
+}
+
+// ── Parser ──────────────────────────────────────────────────
+
+/// Splits config text into entries, tracking the current section.
+fn parse_entries(text: &str) -> Result<Vec<Entry>, (usize, String)> {
+    let mut entries = Vec::new();
+    let mut section = String::new();
+    let mut lines = text.lines().enumerate();
+
+    while let Some((idx, raw)) = lines.next() {
+        let line_no = idx + 1;
+        let line = strip_comment(raw).trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        if let Some(name) = line.strip_prefix('[') {
+            let name = name
+                .strip_suffix(']')
+                .ok_or((line_no, "unterminated section header".to_string()))?;
+            section = name.trim().to_string();
+            continue;
+        }
+
+        let (key, rest) = line
+            .split_once('=')
+            .ok_or((line_no, format!("expected `key = value`, found `{line}`")))?;
+        let key = key.trim().to_string();
+        let mut value_text = rest.trim().to_string();
+
+        // Arrays may continue over several lines until the closing bracket.
+        while value_text.starts_with('[') && !brackets_closed(&value_text) {
+            let (_, next) = lines
+                .next()
+                .ok_or((line_no, format!("unterminated array for `{key}`")))?;
+            value_text.push(' ');
+            value_text.push_str(strip_comment(next).trim());
+        }
+
+        let value = parse_value(&value_text).map_err(|msg| (line_no, msg))?;
+        entries.push(Entry {
+            section: section.clone(),
+            key,
+            value,
+            line: line_no,
+        });
+    }
+
+    Ok(entries)
+}
+
+/// Removes a trailing `# comment`, ignoring `#` inside quoted strings.
+fn strip_comment(line: &str) -> &str {
+    let mut quote = None;
+    let mut escaped = false;
+    for (i, c) in line.char_indices() {
+        match quote {
+            Some(q) => {
+                if escaped {
+                    escaped = false;
+                } else if c == '\\' && q == '"' {
+                    escaped = true;
+                } else if c == q {
+                    quote = None;
+                }
+            }
+            None if c == '"' || c == '\'' => quote = Some(c),
+            None if c == '#' => return &line[..i],
+            None => {}
+        }
+    }
+    line
+}
+
+/// Returns whether every `[` outside strings has a matching `]`.
+fn brackets_closed(text: &str) -> bool {
+    let mut depth = 0i32;
+    let mut quote = None;
+    for c in text.chars() {
+        match quote {
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            None if c == '"' || c == '\'' => quote = Some(c),
+            None if c == '[' => depth += 1,
+            None if c == ']' => depth -= 1,
+            None => {}
+        }
+    }
+    depth <= 0
+}
+
+/// Parses a single value: a string, a boolean, or an array of strings.
+fn parse_value(text: &str) -> Result<Value, String> {
+    let text = text.trim();
+
+    if text.starts_with('"') || text.starts_with('\'') {
+        let (s, rest) = parse_string(text)?;
+        if !rest.trim().is_empty() {
+            return Err(format!("unexpected text after string: `{}`", rest.trim()));
+        }
+        return Ok(Value::Str(s));
+    }
+
+    if let Some(mut rest) = text.strip_prefix('[') {
+        let mut items = Vec::new();
+        loop {
+            rest = rest.trim_start_matches(|c: char| c.is_whitespace() || c == ',');
+            if let Some(after) = rest.strip_prefix(']') {
+                if !after.trim().is_empty() {
+                    return Err(format!("unexpected text after array: `{}`", after.trim()));
+                }
+                return Ok(Value::List(items));
+            }
+            if rest.is_empty() {
+                return Err("unterminated array".into());
+            }
+            let (item, after) = parse_string(rest)?;
+            items.push(item);
+            rest = after;
+        }
+    }
+
+    match text {
+        "true" => Ok(Value::Bool(true)),
+        "false" => Ok(Value::Bool(false)),
+        _ => match text.replace('_', "").parse::<f64>() {
+            Ok(n) if n.is_finite() => Ok(Value::Num(n)),
+            _ => Err(format!(
+                "invalid value `{text}` (expected a string, number, boolean, or array)"
+            )),
+        },
+    }
+}
+
+/// Parses a leading quoted string, returning it and the remaining text.
+///
+/// Double-quoted strings support `\"`, `\\`, `\n`, and `\t` escapes;
+/// single-quoted strings are literal.
+fn parse_string(text: &str) -> Result<(String, &str), String> {
+    let mut chars = text.char_indices();
+    let Some((_, quote)) = chars.next().filter(|&(_, c)| c == '"' || c == '\'') else {
+        return Err(format!("expected a quoted string, found `{text}`"));
+    };
+
+    let mut out = String::new();
+    while let Some((i, c)) = chars.next() {
+        if c == quote {
+            return Ok((out, &text[i + c.len_utf8()..]));
+        }
+        if c == '\\' && quote == '"' {
+            match chars.next().map(|(_, e)| e) {
+                Some('n') => out.push('\n'),
+                Some('t') => out.push('\t'),
+                Some(e @ ('"' | '\\')) => out.push(e),
+                Some(e) => return Err(format!("unsupported escape `\\{e}`")),
+                None => break,
+            }
+        } else {
+            out.push(c);
+        }
+    }
+
+    Err("unterminated string".into())
+}
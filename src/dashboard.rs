@@ -0,0 +1,276 @@
+//! Read-only HTTP status page for checking on syswatch from a browser.
+//!
+//! A small TCP listener serves two routes, one connection at a time:
+//!
+//! - `/` — a self-contained HTML page with the headline figures and the
+//!   busiest [`TOP_PROCESSES`] processes, re-fetching every [`REFRESH`]
+//! - `/snapshot.json` — the latest [`Snapshot`], as the query socket's
+//!   `snapshot` command returns it
+//!
+//! The page carries no data of its own: its inline script renders
+//! whatever `/snapshot.json` returns, so both always agree. Like the
+//! query socket, requests only read the snapshot the event loop
+//! publishes each tick. Nothing here can change syswatch's state.
+
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::thread;
+use std::time::Duration;
+
+use crate::json::Object;
+use crate::snapshot::Snapshot;
+
+/// Longest accepted request head (request line plus headers) in bytes.
+const MAX_REQUEST: usize = 8192;
+
+/// How long a connection may sit idle before it is dropped.
+const CLIENT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Processes listed on the page, busiest first.
+const TOP_PROCESSES: usize = 20;
+
+/// Delay between the page's snapshot fetches.
+const REFRESH: Duration = Duration::from_secs(2);
+
+/// The page, with `{host}`, `{top}` and `{refresh_ms}` filled in at startup.
+const PAGE: &str = r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<meta name="viewport" content="width=device-width, initial-scale=1">
+<title>syswatch · {host}</title>
+<style>
+body { font: 15px -apple-system, system-ui, sans-serif; margin: 0; padding: 1em; background: #111; color: #ddd; }
+h1 { font-size: 1.2em; margin: 0 0 .2em; }
+#state { color: #888; font-size: .85em; margin-bottom: 1em; }
+#state.stale { color: #e66; }
+.cards { display: grid; grid-template-columns: repeat(auto-fit, minmax(9em, 1fr)); gap: .6em; margin-bottom: 1em; }
+.card { background: #1c1c1c; border-radius: 6px; padding: .6em .8em; }
+.label { color: #888; font-size: .8em; text-transform: uppercase; }
+.value { font-size: 1.6em; font-variant-numeric: tabular-nums; }
+.detail { color: #999; font-size: .8em; }
+table { width: 100%; border-collapse: collapse; font-variant-numeric: tabular-nums; }
+th, td { padding: .3em .4em; text-align: left; border-bottom: 1px solid #222; }
+th { color: #888; font-weight: normal; }
+.n { text-align: right; }
+td.name { overflow-wrap: anywhere; }
+</style>
+</head>
+<body>
+<h1>syswatch · {host}</h1>
+<div id="state">waiting for the first sample…</div>
+<div class="cards">
+<div class="card"><div class="label">CPU</div><div class="value" id="cpu">·</div><div class="detail" id="cpu-detail"></div></div>
+<div class="card"><div class="label">Memory</div><div class="value" id="memory">·</div><div class="detail" id="memory-detail"></div></div>
+<div class="card"><div class="label">Swap</div><div class="value" id="swap">·</div><div class="detail" id="swap-detail"></div></div>
+<div class="card"><div class="label">Processes</div><div class="value" id="processes">·</div><div class="detail" id="processes-detail"></div></div>
+</div>
+<table>
+<thead><tr><th class="n">PID</th><th>Name</th><th class="n">CPU %</th><th class="n">Memory</th><th class="n">Threads</th></tr></thead>
+<tbody id="rows"></tbody>
+</table>
+<script>
+const TOP = {top};
+const REFRESH_MS = {refresh_ms};
+
+function bytes(n) {
+  const units = ["B", "KB", "MB", "GB", "TB"];
+  let i = 0;
+  while (n >= 1024 && i < units.length - 1) { n /= 1024; i++; }
+  return n.toFixed(i ? 1 : 0) + " " + units[i];
+}
+
+function show(id, text) {
+  document.getElementById(id).textContent = text;
+}
+
+function render(s) {
+  show("cpu", (s.cpu.user + s.cpu.system).toFixed(1) + " %");
+  show("cpu-detail", "user " + s.cpu.user.toFixed(1) + " · system " + s.cpu.system.toFixed(1));
+  const m = s.memory;
+  show("memory", m.total ? (m.used / m.total * 100).toFixed(0) + " %" : "·");
+  show("memory-detail", bytes(m.used) + " of " + bytes(m.total) + (m.pressure ? " · " + m.pressure : ""));
+  show("swap", bytes(m.swap_used));
+  show("swap-detail", "of " + bytes(m.swap_total));
+  show("processes", s.process_count);
+  show("processes-detail", s.threads + " threads");
+
+  const cpu = p => p.cpu === null ? -1 : p.cpu;
+  const top = s.processes.slice().sort((a, b) => cpu(b) - cpu(a)).slice(0, TOP);
+  const rows = document.getElementById("rows");
+  rows.replaceChildren();
+  for (const p of top) {
+    const row = rows.insertRow();
+    for (const [text, cls] of [
+      [p.pid, "n"],
+      [p.name, "name"],
+      [p.cpu === null ? "·" : p.cpu.toFixed(1), "n"],
+      [bytes(p.memory), "n"],
+      [p.threads ?? "·", "n"],
+    ]) {
+      const cell = row.insertCell();
+      cell.textContent = text;
+      cell.className = cls;
+    }
+  }
+}
+
+async function load() {
+  const state = document.getElementById("state");
+  try {
+    const reply = await fetch("snapshot.json", { cache: "no-store" });
+    const s = await reply.json();
+    if (s.error) throw new Error(s.error);
+    render(s);
+    state.textContent = "updated " + new Date(s.timestamp).toLocaleTimeString();
+    state.className = "";
+  } catch (err) {
+    state.textContent = "syswatch is not answering (" + err.message + ")";
+    state.className = "stale";
+  }
+  setTimeout(load, REFRESH_MS);
+}
+
+load();
+</script>
+</body>
+</html>
+"#;
+
+/// Latest snapshot shared between the event loop and the listener.
+type Shared = Arc<Mutex<Option<Snapshot>>>;
+
+/// A listening status page.
+#[derive(Debug)]
+pub struct Dashboard {
+    /// Address actually bound, with the port resolved if 0 was asked for.
+    pub addr: SocketAddr,
+    latest: Shared,
+}
+
+impl Dashboard {
+    /// Binds `addr` and starts the listener thread; `host` titles the page.
+    pub fn start(addr: SocketAddr, host: &str) -> io::Result<Self> {
+        let listener = TcpListener::bind(addr)?;
+        let addr = listener.local_addr()?;
+        let page = PAGE
+            .replace("{host}", &escape(host))
+            .replace("{top}", &TOP_PROCESSES.to_string())
+            .replace("{refresh_ms}", &REFRESH.as_millis().to_string());
+
+        let latest = Shared::default();
+        let shared = Arc::clone(&latest);
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                // A misbehaving client only loses its own connection.
+                let _ = serve(stream, &shared, &page);
+            }
+        });
+
+        Ok(Self { addr, latest })
+    }
+
+    /// Replaces the snapshot served to clients.
+    pub fn publish(&self, snapshot: Snapshot) {
+        *self.latest.lock().unwrap_or_else(PoisonError::into_inner) = Some(snapshot);
+    }
+}
+
+/// A response about to be written.
+struct Reply {
+    status: &'static str,
+    content_type: &'static str,
+    body: String,
+}
+
+impl Reply {
+    /// A JSON `{"error": …}` reply with `status`.
+    fn error(status: &'static str, msg: &str) -> Self {
+        Self {
+            status,
+            content_type: "application/json",
+            body: Object::new().str("error", msg).finish(),
+        }
+    }
+}
+
+/// Answers the one request on a connection, then closes it.
+fn serve(stream: TcpStream, latest: &Shared, page: &str) -> io::Result<()> {
+    stream.set_read_timeout(Some(CLIENT_TIMEOUT))?;
+    stream.set_write_timeout(Some(CLIENT_TIMEOUT))?;
+    let mut reader = BufReader::new((&stream).take(MAX_REQUEST as u64));
+    let mut request = String::new();
+    reader.read_line(&mut request)?;
+    // Read the headers too: closing with them unread resets the connection
+    // before some clients see the reply.
+    let mut header = String::new();
+    loop {
+        header.clear();
+        if reader.read_line(&mut header)? == 0 || header.trim_end().is_empty() {
+            break;
+        }
+    }
+
+    let mut words = request.split_whitespace();
+    let (method, target) = (words.next(), words.next());
+    let reply = match (method, target) {
+        (Some("GET" | "HEAD"), Some(target)) => route(target, latest, page),
+        (Some(_), Some(_)) => Reply::error("405 Method Not Allowed", "only GET is supported"),
+        _ => Reply::error("400 Bad Request", "malformed request line"),
+    };
+
+    let mut writer = &stream;
+    write!(
+        writer,
+        "HTTP/1.1 {}\r\nContent-Type: {}; charset=utf-8\r\nContent-Length: {}\r\n\
+         Cache-Control: no-store\r\nConnection: close\r\n\r\n",
+        reply.status,
+        reply.content_type,
+        reply.body.len()
+    )?;
+    if method != Some("HEAD") {
+        writer.write_all(reply.body.as_bytes())?;
+    }
+    writer.flush()
+}
+
+/// Builds the reply for a `GET` of `target`.
+fn route(target: &str, latest: &Shared, page: &str) -> Reply {
+    let path = target.split_once('?').map_or(target, |(path, _)| path);
+    match path {
+        "/" | "/index.html" => Reply {
+            status: "200 OK",
+            content_type: "text/html",
+            body: page.to_string(),
+        },
+        "/snapshot.json" => {
+            let guard = latest.lock().unwrap_or_else(PoisonError::into_inner);
+            match guard.as_ref() {
+                Some(snapshot) => Reply {
+                    status: "200 OK",
+                    content_type: "application/json",
+                    body: snapshot.to_json(),
+                },
+                None => Reply::error("503 Service Unavailable", "no sample collected yet"),
+            }
+        }
+        _ => Reply::error("404 Not Found", &format!("no such page `{path}`")),
+    }
+}
+
+/// `text` with the characters HTML treats specially replaced by entities.
+fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            c => out.push(c),
+        }
+    }
+    out
+}
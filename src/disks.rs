@@ -0,0 +1,179 @@
+//! Per-device disk throughput for the disk popup.
+//!
+//! Each tick the block-storage counters are read through
+//! [`collector::block_devices`] and turned into read/write rates per
+//! driver instance, rather than summed into one system-wide figure. A
+//! device is tracked by its driver's registry ID, so an external disk
+//! that disappears just drops out of the list — with its history — and
+//! one that reappears starts fresh. Volume names come from the mount
+//! table, re-read at most every [`MOUNT_POLL`] and mapped to whichever
+//! driver each mount's device sits on; the mapping is only rebuilt when
+//! the table or the set of devices changes.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::time::Instant;
+
+use sysinfo::{DiskRefreshKind, Disks};
+
+use crate::collector::{self, BlockDevice};
+use crate::series::Series;
+use crate::volumes::MOUNT_POLL;
+
+/// Seconds of rate history each device keeps for its chart.
+pub const DEVICE_HISTORY: usize = 300;
+
+/// One disk's current throughput and recent history.
+#[derive(Debug)]
+pub struct Device {
+    /// Latest counters.
+    counters: BlockDevice,
+    /// Names of the volumes mounted from the disk, sorted.
+    pub volumes: Vec<String>,
+    /// Bytes read per second over the last tick; `None` on its first.
+    pub read_rate: Option<f64>,
+    /// Bytes written per second over the last tick; `None` on its first.
+    pub write_rate: Option<f64>,
+    /// Per-second read rate.
+    pub read_history: Series,
+    /// Per-second write rate.
+    pub write_history: Series,
+}
+
+impl Device {
+    /// BSD name of the whole disk, e.g. `disk0`.
+    pub fn name(&self) -> &str {
+        &self.counters.name
+    }
+}
+
+/// Every block device's rates, plus the mount table they are named from.
+#[derive(Debug, Default)]
+pub struct DiskIo {
+    /// Devices sorted by BSD name.
+    pub devices: Vec<Device>,
+    /// Why the counters can't be read, as the collector's error code.
+    pub error: Option<i32>,
+    /// When the counters were last read.
+    read_at: Option<Instant>,
+    /// `(mount point, volume name)` pairs of the last mount-table read.
+    mounts: Vec<(PathBuf, String)>,
+    /// When the mount table was last read.
+    mounts_polled: Option<Instant>,
+    /// Volume names per driver ID, built from `mounts`.
+    names: HashMap<u64, Vec<String>>,
+}
+
+impl DiskIo {
+    /// Reads the counters and updates every device's rates, appending
+    /// them to the history at chart position `x`.
+    pub fn update(&mut self, x: f64) {
+        let now = Instant::now();
+        let secs = self
+            .read_at
+            .map_or(0.0, |at| now.duration_since(at).as_secs_f64());
+        self.read_at = Some(now);
+        let counters = match collector::block_devices() {
+            Ok(counters) => {
+                self.error = None;
+                counters
+            }
+            Err(err) => {
+                self.error = Some(err);
+                self.devices.clear();
+                return;
+            }
+        };
+
+        let mut previous: HashMap<u64, Device> =
+            self.devices.drain(..).map(|d| (d.counters.id, d)).collect();
+        let added = counters.iter().any(|c| !previous.contains_key(&c.id));
+        for now in counters {
+            let device = match previous.remove(&now.id) {
+                Some(mut device) => {
+                    let rates = (secs > 0.0).then(|| now.rates_since(&device.counters, secs));
+                    device.read_rate = rates.map(|(read, _)| read);
+                    device.write_rate = rates.map(|(_, write)| write);
+                    if let Some((read, write)) = rates {
+                        device.read_history.push_at(x, read);
+                        device.write_history.push_at(x, write);
+                    }
+                    device.counters = now;
+                    device
+                }
+                None => Device {
+                    counters: now,
+                    volumes: Vec::new(),
+                    read_rate: None,
+                    write_rate: None,
+                    read_history: Series::new(x, 1.0, DEVICE_HISTORY),
+                    write_history: Series::new(x, 1.0, DEVICE_HISTORY),
+                },
+            };
+            self.devices.push(device);
+        }
+        self.devices.sort_by(|a, b| a.name().cmp(b.name()));
+
+        // A new disk's volumes mount right after it attaches, so look now.
+        let removed = !previous.is_empty();
+        if self.poll_mounts(added || removed) {
+            for device in &mut self.devices {
+                device.volumes = self
+                    .names
+                    .get(&device.counters.id)
+                    .cloned()
+                    .unwrap_or_default();
+            }
+        }
+    }
+
+    /// Re-reads the mount table if [`MOUNT_POLL`] has passed or `force`
+    /// is set, rebuilding the name mapping when the table changed.
+    ///
+    /// Returns whether the mapping may have changed.
+    fn poll_mounts(&mut self, force: bool) -> bool {
+        let due = self
+            .mounts_polled
+            .is_none_or(|at| at.elapsed() >= MOUNT_POLL);
+        if !due && !force {
+            return false;
+        }
+        self.mounts_polled = Some(Instant::now());
+        let disks = Disks::new_with_refreshed_list_specifics(DiskRefreshKind::nothing());
+        let mut mounts: Vec<(PathBuf, String)> = disks
+            .list()
+            .iter()
+            .map(|disk| {
+                let path = disk.mount_point().to_path_buf();
+                let name = disk.name().to_string_lossy();
+                let name = if name.is_empty() {
+                    path.display().to_string()
+                } else {
+                    name.into_owned()
+                };
+                (path, name)
+            })
+            .collect();
+        mounts.sort();
+        if mounts == self.mounts && !force {
+            return false;
+        }
+
+        self.names.clear();
+        for (path, name) in &mounts {
+            let Some(driver) = collector::mount_source(path)
+                .as_deref()
+                .and_then(collector::device_driver)
+            else {
+                continue;
+            };
+            let names = self.names.entry(driver).or_default();
+            if !names.contains(name) {
+                names.push(name.clone());
+                names.sort();
+            }
+        }
+        self.mounts = mounts;
+        true
+    }
+}
@@ -0,0 +1,180 @@
+//! Kill rules for known repeat offenders.
+//!
+//! Each rule is one config string, `<condition> [&& <condition>…] =>
+//! <mode> [signal]`, e.g. `name == vendord && mem > 8GB => confirm`.
+//! Conditions use the highlight-rule syntax from [`rules`](crate::rules).
+//! Rules are edge-triggered like alerts: a process fires a rule once
+//! when it starts matching and again only after it stopped matching.
+
+use std::collections::HashSet;
+use std::mem;
+
+use crate::app::SIGNALS;
+use crate::monitor::{Pid, ProcessSample};
+use crate::rules::Condition;
+
+/// Why `pid` must never be signalled from syswatch, or `None` if it may be.
+///
+/// `kill(0, …)` signals the caller's own process group, so kernel_task
+/// (PID 0) would take down syswatch and the shell job it runs in; PID 1
+/// is launchd.
+pub fn protected(pid: Pid) -> Option<&'static str> {
+    match pid {
+        0 => Some("kernel_task; kill(0) would signal syswatch's own process group"),
+        1 => Some("launchd"),
+        _ if pid == std::process::id() => Some("syswatch itself"),
+        _ => None,
+    }
+}
+
+/// What happens when a kill rule matches.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum KillMode {
+    /// Log what would have been done; send nothing.
+    #[default]
+    AlertOnly,
+    /// Open the kill popup pre-filled with the rule's signal.
+    Confirm,
+    /// Send the signal, if `--enable-auto-kill` was given.
+    Auto,
+}
+
+impl KillMode {
+    /// Parses a mode name as written in config.
+    fn parse(text: &str) -> Option<Self> {
+        Some(match text {
+            "alert-only" => Self::AlertOnly,
+            "confirm" => Self::Confirm,
+            "auto" => Self::Auto,
+            _ => return None,
+        })
+    }
+}
+
+/// A compiled kill rule.
+#[derive(Debug, Clone)]
+pub struct KillRule {
+    /// Source text, for alert messages.
+    pub source: String,
+    /// Conditions that must all hold.
+    conditions: Vec<Condition>,
+    /// Enforcement mode.
+    pub mode: KillMode,
+    /// Index into [`SIGNALS`] of the signal to send.
+    pub signal: usize,
+}
+
+impl KillRule {
+    /// Parses `<condition> [&& <condition>…] => <mode> [signal]`.
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let (conditions, action) = text
+            .rsplit_once("=>")
+            .ok_or("expected `<condition> => <mode>`")?;
+        let conditions = conditions
+            .split("&&")
+            .map(Condition::parse)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut words = action.split_whitespace();
+        let mode = words.next().ok_or("missing mode after `=>`")?;
+        let mode = KillMode::parse(mode).ok_or_else(|| {
+            format!("unknown mode `{mode}` (expected alert-only, confirm, or auto)")
+        })?;
+        let signal = match words.next() {
+            Some(name) => {
+                let name = name.trim_start_matches("SIG").to_ascii_uppercase();
+                SIGNALS
+                    .iter()
+                    .position(|&(n, _)| n == name)
+                    .ok_or_else(|| format!("unknown signal `{name}`"))?
+            }
+            None => 0,
+        };
+        if let Some(extra) = words.next() {
+            return Err(format!("unexpected `{extra}` after the signal"));
+        }
+
+        Ok(Self {
+            source: text.trim().to_string(),
+            conditions,
+            mode,
+            signal,
+        })
+    }
+
+    /// Whether every condition holds for `p`.
+    fn matches(&self, p: &ProcessSample) -> bool {
+        self.conditions.iter().all(|c| c.matches(p))
+    }
+}
+
+/// A rule that started matching a process this tick.
+#[derive(Debug)]
+pub struct Hit<'a> {
+    /// The rule that matched.
+    pub rule: &'a KillRule,
+    /// The process it matched.
+    pub process: &'a ProcessSample,
+}
+
+/// Evaluates kill rules across ticks.
+#[derive(Debug, Default)]
+pub struct Enforcer {
+    rules: Vec<KillRule>,
+    /// `(rule index, PID)` pairs that have fired and still match.
+    fired: HashSet<(usize, Pid)>,
+}
+
+impl Enforcer {
+    /// Creates an enforcer for `rules`.
+    pub fn new(rules: Vec<KillRule>) -> Self {
+        Self {
+            rules,
+            fired: HashSet::new(),
+        }
+    }
+
+    /// Replaces the rules. A rule whose text is unchanged keeps its fired
+    /// PIDs, so a reload never acts twice on the same process.
+    pub fn configure(&mut self, rules: Vec<KillRule>) {
+        let fired = mem::take(&mut self.fired);
+        self.fired = fired
+            .into_iter()
+            .filter_map(|(i, pid)| {
+                let source = &self.rules[i].source;
+                let j = rules.iter().position(|r| r.source == *source)?;
+                Some((j, pid))
+            })
+            .collect();
+        self.rules = rules;
+    }
+
+    /// Returns the rules that started matching since the last call.
+    pub fn evaluate<'a>(&'a mut self, processes: &'a [ProcessSample]) -> Vec<Hit<'a>> {
+        let mut matching = HashSet::new();
+        let mut hits = Vec::new();
+        for (i, rule) in self.rules.iter().enumerate() {
+            for p in processes.iter().filter(|p| rule.matches(p)) {
+                matching.insert((i, p.pid));
+                if !self.fired.contains(&(i, p.pid)) {
+                    hits.push(Hit { rule, process: p });
+                }
+            }
+        }
+        self.fired = matching;
+        hits
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn kernel_task_launchd_and_self_are_protected() {
+        assert!(protected(0).is_some());
+        assert!(protected(1).is_some());
+        assert!(protected(std::process::id()).is_some());
+        assert_eq!(protected(std::process::id() + 1), None);
+    }
+}
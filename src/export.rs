@@ -0,0 +1,139 @@
+//! Exporting the chart history.
+//!
+//! An export covers either the chart's visible window or everything
+//! retained this session ([`Scope`]). It is written as CSV or JSON to the
+//! working directory, or copied to the clipboard as CSV through
+//! `pbcopy`. Only the per-second tier is exported; a window reaching
+//! further back than it is retained yields the samples that remain.
+
+use std::io::{self, Write};
+use std::process::{Command, Stdio};
+use std::time::SystemTime;
+
+use crate::json::{self, Object};
+
+/// CSV header row, matching [`Sample::csv_row`].
+const CSV_HEADER: &str = "timestamp,cpu_system,cpu_user,max_core,memory";
+
+/// Which part of the chart history an export covers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Scope {
+    /// The window the chart currently shows.
+    #[default]
+    Window,
+    /// Every retained sample.
+    Session,
+}
+
+impl Scope {
+    /// The other scope.
+    pub fn toggle(self) -> Self {
+        match self {
+            Self::Window => Self::Session,
+            Self::Session => Self::Window,
+        }
+    }
+
+    /// Display name, also written into JSON exports.
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Window => "visible window",
+            Self::Session => "entire session",
+        }
+    }
+}
+
+/// Where an export goes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Target {
+    /// `syswatch-history.csv` in the working directory.
+    Csv,
+    /// `syswatch-history.json` in the working directory.
+    Json,
+    /// CSV on the clipboard.
+    Clipboard,
+}
+
+/// Targets offered by the export popup, in menu order.
+pub const TARGETS: [(Target, &str); 3] = [
+    (Target::Csv, "CSV file (syswatch-history.csv)"),
+    (Target::Json, "JSON file (syswatch-history.json)"),
+    (Target::Clipboard, "copy CSV to the clipboard"),
+];
+
+/// One slot of chart history.
+#[derive(Debug, Clone, Copy)]
+pub struct Sample {
+    /// Wall-clock time of the sample.
+    pub time: SystemTime,
+    /// System CPU percentage.
+    pub cpu_system: f64,
+    /// User CPU percentage.
+    pub cpu_user: f64,
+    /// Busiest core's percentage, when `[cpu] per_core` is on.
+    pub max_core: Option<f64>,
+    /// Used-memory percentage.
+    pub memory: Option<f64>,
+}
+
+impl Sample {
+    /// Formats the sample as one CSV line without a trailing newline.
+    fn csv_row(&self) -> String {
+        let opt = |v: Option<f64>| v.map(|v| format!("{v:.1}")).unwrap_or_default();
+        format!(
+            "{},{:.1},{:.1},{},{}",
+            json::timestamp(self.time),
+            self.cpu_system,
+            self.cpu_user,
+            opt(self.max_core),
+            opt(self.memory)
+        )
+    }
+}
+
+/// Encodes `samples` as CSV with a header row.
+pub fn csv(samples: &[Sample]) -> String {
+    let mut out = String::from(CSV_HEADER);
+    out.push('\n');
+    for s in samples {
+        out.push_str(&s.csv_row());
+        out.push('\n');
+    }
+    out
+}
+
+/// Encodes `samples` as one JSON object tagged with `scope`.
+pub fn json(samples: &[Sample], scope: Scope) -> String {
+    let rows = samples.iter().map(|s| {
+        Object::new()
+            .str("timestamp", &json::timestamp(s.time))
+            .num("cpu_system", s.cpu_system)
+            .num("cpu_user", s.cpu_user)
+            .opt("max_core", s.max_core.map(json::number))
+            .opt("memory", s.memory.map(json::number))
+            .finish()
+    });
+    Object::new()
+        .str("scope", scope.label())
+        .int("samples", samples.len() as u64)
+        .raw("history", &json::array(rows))
+        .finish()
+}
+
+/// Puts `text` on the clipboard with `pbcopy`.
+pub fn copy(text: &str) -> io::Result<()> {
+    let mut child = Command::new("pbcopy")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()?;
+    if let Some(stdin) = child.stdin.as_mut() {
+        stdin.write_all(text.as_bytes())?;
+    }
+    let status = child.wait()?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(io::Error::other(format!("pbcopy exited with {status}")))
+    }
+}
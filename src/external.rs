@@ -0,0 +1,182 @@
+//! Running user-defined external commands against a process.
+//!
+//! The TUI is suspended (raw mode off, alternate screen left) while the
+//! command runs in the foreground, then restored with a full redraw.
+//! Any local user can pick a process name, so the name is handed to the
+//! shell in an environment variable rather than pasted into the script.
+
+use std::io::{self, BufRead, Write};
+use std::process::Command;
+
+use crossterm::execute;
+use crossterm::terminal::{EnterAlternateScreen, enable_raw_mode};
+use ratatui::Terminal;
+use ratatui::backend::Backend;
+
+/// Environment variable the process name is passed to the command in.
+pub const NAME_VAR: &str = "SYSWATCH_NAME";
+
+/// A command template with its placeholders filled in for one process.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Expanded {
+    /// Script run with `sh -c`; it reads the name from [`NAME_VAR`].
+    pub script: String,
+    /// Process name exported as [`NAME_VAR`].
+    pub name: String,
+    /// The template with the name written in, for showing only.
+    pub shown: String,
+}
+
+impl Expanded {
+    /// The `sh -c` command, with the name in its environment.
+    pub fn command(&self) -> Command {
+        let mut cmd = Command::new("sh");
+        cmd.arg("-c").arg(&self.script).env(NAME_VAR, &self.name);
+        cmd
+    }
+}
+
+/// Expands `{pid}` and `{name}` placeholders in a command template.
+///
+/// The name never becomes part of the script: `{name}` turns into a
+/// reference to [`NAME_VAR`], quoted to suit where it stands in the
+/// template, so a process named `$(…)` is only ever text to the shell.
+///
+/// ```
+/// use syswatch::external::expand;
+///
+/// let expanded = expand("open -a \"{name}\" --args {pid}", 42, "$(reboot)");
+/// assert_eq!(expanded.script, "open -a \"${SYSWATCH_NAME}\" --args 42");
+/// assert_eq!(expanded.shown, "open -a \"$(reboot)\" --args 42");
+/// ```
+pub fn expand(template: &str, pid: u32, name: &str) -> Expanded {
+    let pid = pid.to_string();
+    let mut script = String::new();
+    // The quote the scan is inside of, if any.
+    let mut quote = None;
+    let mut rest = template;
+    while let Some(c) = rest.chars().next() {
+        if let Some(after) = rest.strip_prefix("{name}") {
+            script.push_str(match quote {
+                None => "\"$SYSWATCH_NAME\"",
+                Some('"') => "${SYSWATCH_NAME}",
+                _ => "'\"$SYSWATCH_NAME\"'",
+            });
+            rest = after;
+            continue;
+        }
+        if let Some(after) = rest.strip_prefix("{pid}") {
+            script.push_str(&pid);
+            rest = after;
+            continue;
+        }
+        script.push(c);
+        rest = &rest[c.len_utf8()..];
+        match (quote, c) {
+            // An escaped character is copied as is, whatever it is.
+            (None | Some('"'), '\\') => {
+                if let Some(next) = rest.chars().next() {
+                    script.push(next);
+                    rest = &rest[next.len_utf8()..];
+                }
+            }
+            (None, '\'' | '"') => quote = Some(c),
+            (Some(open), _) if open == c => quote = None,
+            _ => {}
+        }
+    }
+    Expanded {
+        script,
+        name: name.to_string(),
+        shown: template.replace("{pid}", &pid).replace("{name}", name),
+    }
+}
+
+/// Suspends the TUI, runs `command` through `sh -c`, and restores the TUI.
+///
+/// The terminal is always restored, even when the command fails to
+/// start; the command's own failure is reported on the plain screen
+/// before the user presses Enter to return.
+pub fn run<B: Backend>(terminal: &mut Terminal<B>, command: &Expanded) -> io::Result<()> {
+    ratatui::try_restore()?;
+    terminal.show_cursor()?;
+
+    let outcome = run_foreground(command);
+
+    enable_raw_mode()?;
+    execute!(io::stdout(), EnterAlternateScreen)?;
+    terminal.hide_cursor()?;
+    terminal.clear()?;
+    outcome
+}
+
+/// Runs the command on the cooked terminal and waits for Enter.
+fn run_foreground(command: &Expanded) -> io::Result<()> {
+    let mut out = io::stdout();
+    writeln!(out, "$ {}", command.shown)?;
+
+    // Ctrl-C should stop the child, not syswatch. A handler (unlike
+    // SIG_IGN) is reset to the default in the child on exec.
+    let handler: extern "C" fn(libc::c_int) = on_sigint;
+    let previous = unsafe { libc::signal(libc::SIGINT, handler as libc::sighandler_t) };
+    let status = command.command().status();
+    unsafe { libc::signal(libc::SIGINT, previous) };
+
+    match status {
+        Ok(s) if s.success() => {}
+        Ok(s) => writeln!(out, "\n[command exited with {s}]")?,
+        Err(e) => writeln!(out, "\n[failed to run command: {e}]")?,
+    }
+
+    write!(out, "\nPress Enter to return to syswatch… ")?;
+    out.flush()?;
+    let mut line = String::new();
+    io::stdin().lock().read_line(&mut line)?;
+    Ok(())
+}
+
+/// No-op SIGINT handler installed while a child command runs.
+extern "C" fn on_sigint(_: libc::c_int) {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn names_are_never_run_as_shell_code() {
+        let dir = std::env::temp_dir().join(format!("syswatch-external-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let planted = dir.join("x");
+        let name = format!(
+            "it's $(touch {}) `touch {}` \\",
+            planted.display(),
+            planted.display()
+        );
+        let out = dir.join("out");
+        for template in [
+            "printf %s {name} > OUT",
+            "printf %s \"{name}\" > OUT",
+            "printf %s '{name}' > OUT",
+            "printf %s \"x{name}x\" > OUT",
+            "printf %s \"\\\"{name}\" > OUT",
+        ] {
+            let template = template.replace("OUT", &out.display().to_string());
+            let expanded = expand(&template, 1, &name);
+            assert!(expanded.command().status().unwrap().success(), "{template}");
+            assert!(!planted.exists(), "{template} ran the name");
+            let printed = fs::read_to_string(&out).unwrap();
+            assert!(printed.contains(&name), "{template}: {printed:?}");
+        }
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn pid_and_shown_text_are_substituted() {
+        let expanded = expand("kill -INFO {pid}; echo {name}", 7, "Safari");
+        assert_eq!(expanded.script, "kill -INFO 7; echo \"$SYSWATCH_NAME\"");
+        assert_eq!(expanded.shown, "kill -INFO 7; echo Safari");
+        assert_eq!(expanded.name, "Safari");
+    }
+}
@@ -0,0 +1,409 @@
+//! Deterministic fake snapshots for tests, benchmarks, and replay work.
+//!
+//! A [`Fixture`] yields an endless sequence of [`Snapshot`]s that look
+//! like a busy Mac: plausible process names, CPU that follows a Zipf
+//! curve (a few hot processes, a long idle tail), log-normal resident
+//! memory, a handful of zombies, and PIDs that come and go between
+//! snapshots at [`FixtureOptions::churn`]. Everything is drawn from a
+//! small seeded generator, so the same options always produce the same
+//! sequence. Compiled only with the `fixtures` feature and in syswatch's
+//! own unit tests.
+//!
+//! ```
+//! use syswatch::fixtures::{Fixture, FixtureOptions};
+//!
+//! let mut fixture = Fixture::new(FixtureOptions {
+//!     processes: 200,
+//!     ..FixtureOptions::default()
+//! });
+//! let first = fixture.next().unwrap();
+//! let second = fixture.next().unwrap();
+//! assert_eq!(first.processes.len(), 200);
+//! assert_ne!(first.time, second.time);
+//! ```
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use sysinfo::ProcessStatus;
+
+use crate::collector::MemoryPressure;
+use crate::config::MemoryMode;
+use crate::monitor::{Pid, ProcessSample};
+use crate::snapshot::Snapshot;
+
+/// Names processes are drawn from; duplicates in a list are expected.
+const NAMES: [&str; 32] = [
+    "WindowServer",
+    "Safari",
+    "com.apple.WebKit.WebContent",
+    "com.apple.WebKit.Networking",
+    "Google Chrome Helper (Renderer)",
+    "Google Chrome Helper (GPU)",
+    "Slack Helper (Renderer)",
+    "Code Helper (Plugin)",
+    "Finder",
+    "Dock",
+    "SystemUIServer",
+    "Spotlight",
+    "mds_stores",
+    "mdworker_shared",
+    "coreaudiod",
+    "bluetoothd",
+    "cfprefsd",
+    "distnoted",
+    "trustd",
+    "sharingd",
+    "photoanalysisd",
+    "softwareupdated",
+    "syslogd",
+    "logd",
+    "zsh",
+    "node",
+    "cargo",
+    "rustc",
+    "rust-analyzer",
+    "python3",
+    "Xcode",
+    "Terminal",
+];
+
+/// PIDs of the processes that exist in every snapshot and never churn.
+const KERNEL_TASK_PID: Pid = 0;
+const LAUNCHD_PID: Pid = 1;
+
+/// First PID handed to generated processes.
+const FIRST_PID: Pid = 100;
+
+/// Zipf exponent of the CPU curve; higher concentrates load in fewer processes.
+const CPU_ZIPF: f64 = 1.2;
+
+/// CPU percentage of the busiest generated process before jitter.
+const CPU_PEAK: f64 = 90.0;
+
+/// Median resident memory of a generated process, in bytes.
+const MEMORY_MEDIAN: f64 = 8.0 * 1024.0 * 1024.0;
+
+/// Spread of the log-normal memory distribution (σ of its logarithm).
+const MEMORY_SIGMA: f64 = 1.5;
+
+/// Wall-clock time of the first snapshot, so timestamps are reproducible too.
+const EPOCH_SECS: u64 = 1_700_000_000;
+
+/// Shape of the generated sequence.
+#[derive(Debug, Clone, Copy)]
+pub struct FixtureOptions {
+    /// Processes in each snapshot, including `kernel_task` and `launchd`.
+    pub processes: usize,
+    /// Zombies among them.
+    pub zombies: usize,
+    /// Fraction of the processes replaced by new PIDs between snapshots.
+    pub churn: f64,
+    /// Logical cores, for the system-wide CPU split.
+    pub cores: u32,
+    /// Total memory in bytes.
+    pub mem_total: u64,
+    /// Time between snapshots.
+    pub interval: Duration,
+    /// Seed of the generator; equal seeds give equal sequences.
+    pub seed: u64,
+}
+
+impl Default for FixtureOptions {
+    fn default() -> Self {
+        Self {
+            processes: 500,
+            zombies: 3,
+            churn: 0.02,
+            cores: 10,
+            mem_total: 16 * 1024 * 1024 * 1024,
+            interval: Duration::from_secs(1),
+            seed: 1,
+        }
+    }
+}
+
+/// SplitMix64: tiny, fast, and good enough for fake data.
+#[derive(Debug, Clone)]
+pub struct Rng(u64);
+
+impl Rng {
+    /// Creates a generator from `seed`.
+    pub fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    /// The next 64 random bits.
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// A uniform value in `[0, 1)`.
+    pub fn unit(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// A uniform index below `n`, which must be non-zero.
+    pub fn below(&mut self, n: usize) -> usize {
+        (self.next_u64() % n as u64) as usize
+    }
+
+    /// A standard normal value (Box–Muller).
+    pub fn normal(&mut self) -> f64 {
+        let u = 1.0 - self.unit();
+        let v = self.unit();
+        (-2.0 * u.ln()).sqrt() * (std::f64::consts::TAU * v).cos()
+    }
+}
+
+/// One generated process and what stays fixed over its life.
+#[derive(Debug, Clone)]
+struct Fake {
+    sample: ProcessSample,
+    /// CPU percentage the process hovers around.
+    load: f64,
+}
+
+/// Endless, reproducible sequence of fake snapshots.
+#[derive(Debug, Clone)]
+pub struct Fixture {
+    options: FixtureOptions,
+    rng: Rng,
+    processes: Vec<Fake>,
+    next_pid: Pid,
+    time: SystemTime,
+}
+
+impl Fixture {
+    /// Creates the first process list; [`Iterator::next`] returns it.
+    pub fn new(options: FixtureOptions) -> Self {
+        let mut fixture = Self {
+            options,
+            rng: Rng::new(options.seed),
+            processes: Vec::with_capacity(options.processes),
+            next_pid: FIRST_PID,
+            time: UNIX_EPOCH + Duration::from_secs(EPOCH_SECS),
+        };
+        fixture
+            .processes
+            .push(fixture.fixed(KERNEL_TASK_PID, "kernel_task"));
+        fixture
+            .processes
+            .push(fixture.fixed(LAUNCHD_PID, "launchd"));
+        let generated = options.processes.saturating_sub(fixture.processes.len());
+        let zombies = options.zombies.min(generated);
+        // Shuffled, so CPU rank doesn't follow PID order.
+        let mut ranks: Vec<usize> = (0..generated).collect();
+        for i in (1..generated).rev() {
+            ranks.swap(i, fixture.rng.below(i + 1));
+        }
+        for (i, rank) in ranks.into_iter().enumerate() {
+            let mut fake = fixture.spawn(rank, true);
+            if i >= generated - zombies {
+                fake.load = 0.0;
+                fake.sample.status = ProcessStatus::Zombie;
+                fake.sample.memory = 0;
+                fake.sample.threads = None;
+                fake.sample.running = None;
+            }
+            fixture.processes.push(fake);
+        }
+        fixture
+    }
+
+    /// One of the processes present from the start to the end.
+    fn fixed(&self, pid: Pid, name: &str) -> Fake {
+        let started = EPOCH_SECS - 86_400;
+        Fake {
+            sample: self.blank(pid, name, (pid != KERNEL_TASK_PID).then_some(0), started),
+            load: if pid == KERNEL_TASK_PID { 8.0 } else { 0.2 },
+        }
+    }
+
+    /// A new process at CPU rank `rank`; `initial` ones started before the session.
+    fn spawn(&mut self, rank: usize, initial: bool) -> Fake {
+        let pid = self.next_pid;
+        self.next_pid += 1 + self.rng.below(4) as Pid;
+        let name = NAMES[self.rng.below(NAMES.len())];
+        let parent = match self.processes.len() {
+            0..=2 => LAUNCHD_PID,
+            // Mostly children of launchd, a few of other processes.
+            n if self.rng.unit() < 0.2 => self.processes[2 + self.rng.below(n - 2)].sample.pid,
+            _ => LAUNCHD_PID,
+        };
+        let now = self
+            .time
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        let started = if initial {
+            now - self.rng.below(86_400) as u64
+        } else {
+            now
+        };
+
+        let mut sample = self.blank(pid, name, Some(parent), started);
+        sample.pgid = Some(if parent == LAUNCHD_PID { pid } else { parent });
+        sample.uid = Some(if self.rng.unit() < 0.3 { 0 } else { 501 });
+        let memory = (MEMORY_MEDIAN.ln() + MEMORY_SIGMA * self.rng.normal()).exp();
+        sample.memory = (memory as u64).min(self.options.mem_total / 4);
+        let threads = 1 + (self.rng.unit().powi(3) * 120.0) as u32;
+        sample.threads = Some(threads);
+        // A process seen for the first time has no CPU delta yet.
+        sample.cpu_measured = initial;
+
+        Fake {
+            sample,
+            load: CPU_PEAK / ((rank + 1) as f64).powf(CPU_ZIPF),
+        }
+    }
+
+    /// A sleeping, inspectable process with no usage yet.
+    fn blank(&self, pid: Pid, name: &str, parent: Option<Pid>, started: u64) -> ProcessSample {
+        ProcessSample {
+            parent,
+            started,
+            ..ProcessSample::new(pid, name)
+        }
+    }
+
+    /// Replaces `churn` of the generated processes with new PIDs.
+    fn churn(&mut self) {
+        let generated = self.processes.len().saturating_sub(2);
+        if generated == 0 {
+            return;
+        }
+        let replaced = (generated as f64 * self.options.churn).round() as usize;
+        for _ in 0..replaced {
+            let index = 2 + self.rng.below(generated);
+            let rank = self.rng.below(self.processes.len());
+            self.processes[index] = self.spawn(rank, false);
+        }
+    }
+
+    /// Draws this tick's CPU and derived counters for every process.
+    fn jitter(&mut self) {
+        let secs = self.options.interval.as_secs_f64();
+        for fake in &mut self.processes {
+            let p = &mut fake.sample;
+            if p.status == ProcessStatus::Zombie {
+                continue;
+            }
+            let cpu = if p.cpu_measured {
+                fake.load * (0.5 + self.rng.unit())
+            } else {
+                0.0
+            };
+            p.cpu_usage = cpu as f32;
+            p.cpu_time += cpu / 100.0 * secs;
+            let threads = p.threads.unwrap_or(1);
+            p.running = Some(((cpu / 100.0).ceil() as u32).min(threads));
+            p.status = if cpu >= 1.0 {
+                ProcessStatus::Run
+            } else {
+                ProcessStatus::Sleep
+            };
+        }
+    }
+
+    /// The snapshot of the current process list.
+    fn snapshot(&self) -> Snapshot {
+        let processes: Vec<ProcessSample> =
+            self.processes.iter().map(|f| f.sample.clone()).collect();
+        let busy: f64 = processes.iter().map(|p| f64::from(p.cpu_usage)).sum();
+        let total = (busy / f64::from(self.options.cores.max(1))).min(100.0);
+        let mem_used: u64 = processes.iter().map(|p| p.memory).sum();
+        Snapshot {
+            time: self.time,
+            cpu_user: total * 0.7,
+            cpu_system: total * 0.3,
+            cpu_idle: 100.0 - total,
+            max_core: None,
+            mem_used: mem_used.min(self.options.mem_total),
+            mem_total: self.options.mem_total,
+            memory_mode: MemoryMode::default(),
+            swap_used: 0,
+            swap_total: 0,
+            wired: None,
+            compressed: None,
+            cached: None,
+            compressor: None,
+            memory_pressure: Some(MemoryPressure::Normal),
+            threads: processes.iter().filter_map(|p| p.threads).sum::<u32>() as usize,
+            processes,
+            processes_skipped: false,
+            top_offenders: Vec::new(),
+        }
+    }
+}
+
+impl Iterator for Fixture {
+    type Item = Snapshot;
+
+    /// Returns the current snapshot, then moves the sequence one interval on.
+    fn next(&mut self) -> Option<Snapshot> {
+        self.jitter();
+        let snapshot = self.snapshot();
+        self.time += self.options.interval;
+        for fake in &mut self.processes {
+            fake.sample.cpu_measured = true;
+        }
+        self.churn();
+        Some(snapshot)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The first `n` snapshots of a fixture, in full.
+    fn run(options: FixtureOptions, n: usize) -> Vec<String> {
+        Fixture::new(options)
+            .take(n)
+            .map(|s| format!("{s:?}"))
+            .collect()
+    }
+
+    #[test]
+    fn equal_seeds_give_equal_sequences() {
+        let options = FixtureOptions {
+            processes: 120,
+            churn: 0.1,
+            ..FixtureOptions::default()
+        };
+        assert_eq!(run(options, 20), run(options, 20));
+        let reseeded = FixtureOptions { seed: 2, ..options };
+        assert_ne!(run(options, 1), run(reseeded, 1));
+    }
+
+    #[test]
+    fn snapshots_follow_the_options() {
+        let options = FixtureOptions {
+            processes: 50,
+            zombies: 4,
+            churn: 0.0,
+            ..FixtureOptions::default()
+        };
+        let snapshots: Vec<Snapshot> = Fixture::new(options).take(3).collect();
+        let pids = |s: &Snapshot| s.processes.iter().map(|p| p.pid).collect::<Vec<_>>();
+        for s in &snapshots {
+            assert_eq!(s.processes.len(), 50);
+            let zombies = s
+                .processes
+                .iter()
+                .filter(|p| p.status == ProcessStatus::Zombie);
+            assert_eq!(zombies.count(), 4);
+            // Without churn the same processes stay.
+            assert_eq!(pids(s), pids(&snapshots[0]));
+        }
+        assert_eq!(snapshots[0].processes[0].name, "kernel_task");
+        assert_eq!(
+            snapshots[1].time.duration_since(snapshots[0].time).unwrap(),
+            options.interval
+        );
+    }
+}
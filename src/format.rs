@@ -0,0 +1,280 @@
+//! Human-readable byte counts, rates, CPU percentages, and text fitted
+//! to a column.
+//!
+//! Sizes are binary (1 KB = 1024 B) throughout, matching Activity
+//! Monitor. Rates follow `[ui] rate_base` instead — decimal by default,
+//! as network and disk speeds are usually quoted — and network rates
+//! can be shown in bits with `[ui] rate_bits`. Rates are always three
+//! significant figures padded to one width, so a column or status line
+//! holding one doesn't change width from tick to tick.
+//!
+//! Column text is measured in terminal cells rather than chars, so
+//! emoji (two cells) and combining marks (none) line up like ASCII.
+
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+use crate::config::{MemoryUnit, RateBase};
+
+/// Byte-size thresholds for [`fmt_bytes`].
+pub const KB: u64 = 1024;
+pub const MB: u64 = 1024 * 1024;
+pub const GB: u64 = 1024 * 1024 * 1024;
+
+/// Rate unit prefixes from 1 upwards.
+const SI_PREFIXES: [&str; 7] = ["", "k", "M", "G", "T", "P", "E"];
+const IEC_PREFIXES: [&str; 7] = ["", "Ki", "Mi", "Gi", "Ti", "Pi", "Ei"];
+
+/// Formats a byte count into a human-readable string (B / KB / MB / GB).
+pub fn fmt_bytes(bytes: u64) -> String {
+    fmt_bytes_in(bytes, MemoryUnit::Auto)
+}
+
+/// Formats bytes under a unit policy: auto-scaled, or fixed MB / GB.
+///
+/// Fixed units keep a column comparable at a glance; GB uses two
+/// decimals so small processes don't all read `0.0 GB`.
+pub fn fmt_bytes_in(bytes: u64, unit: MemoryUnit) -> String {
+    match unit {
+        MemoryUnit::Mb => return format!("{:.1} MB", bytes as f64 / MB as f64),
+        MemoryUnit::Gb => return format!("{:.2} GB", bytes as f64 / GB as f64),
+        MemoryUnit::Auto => {}
+    }
+    if bytes >= GB {
+        format!("{:.1} GB", bytes as f64 / GB as f64)
+    } else if bytes >= MB {
+        format!("{:.1} MB", bytes as f64 / MB as f64)
+    } else if bytes >= KB {
+        format!("{:.1} KB", bytes as f64 / KB as f64)
+    } else {
+        format!("{bytes} B")
+    }
+}
+
+/// Formats a CPU percentage with as many decimals as its size allows.
+///
+/// Two decimals below 10 (`0.04`), one below 100 (`42.5`), and whole
+/// numbers from there (`1250`), so a busy process on a many-core machine
+/// stays as narrow as an idle one: at most five characters, the width of
+/// the `CPU %` header, up to 99 999 %. Negative and non-finite values
+/// read as zero.
+pub fn fmt_percent(pct: f64) -> String {
+    let pct = if pct.is_finite() { pct.max(0.0) } else { 0.0 };
+    // Compare the rounded figure, so 9.996 reads `10.0` rather than `10.00`.
+    if (pct * 100.0).round() < 1000.0 {
+        format!("{pct:.2}")
+    } else if (pct * 10.0).round() < 1000.0 {
+        format!("{pct:.1}")
+    } else {
+        format!("{pct:.0}")
+    }
+}
+
+/// Formats a bytes-per-second rate, e.g. `988 B/s`, `4.2 MB/s`, or in
+/// bits `34 Mb/s`.
+///
+/// The value keeps three significant figures at most (`4.2`, `42`,
+/// `988`) and the result is right-aligned to [`rate_width`], so it
+/// never changes width. Negative and non-finite rates read as zero, and
+/// rates past `999 EB/s` saturate there.
+pub fn fmt_rate(bytes_per_sec: f64, base: RateBase, bits: bool) -> String {
+    let (step, prefixes) = match base {
+        RateBase::Si => (1000.0, SI_PREFIXES),
+        RateBase::Iec => (1024.0, IEC_PREFIXES),
+    };
+    let raw = if bytes_per_sec.is_finite() {
+        bytes_per_sec.max(0.0)
+    } else {
+        0.0
+    };
+    let mut value = if bits { raw * 8.0 } else { raw };
+    let mut prefix = 0;
+    // Compare the rounded figure, so 999.7 moves up to `1.0 k` rather
+    // than printing as a four-digit `1000`.
+    while prefix + 1 < prefixes.len() && (value * 10.0).round() / 10.0 >= 999.5 {
+        value /= step;
+        prefix += 1;
+    }
+    // Past the largest prefix the figure stops growing instead of widening.
+    let value = value.min(999.0);
+    let number = if value == 0.0 {
+        "0".to_string()
+    } else if value < 9.95 {
+        format!("{value:.1}")
+    } else {
+        format!("{value:.0}")
+    };
+    let unit = if bits { "b/s" } else { "B/s" };
+    let text = format!("{number} {}{unit}", prefixes[prefix]);
+    format!("{text:>width$}", width = rate_width(base))
+}
+
+/// Width of every [`fmt_rate`] result under `base`.
+pub fn rate_width(base: RateBase) -> usize {
+    let prefix = match base {
+        RateBase::Si => 1,
+        RateBase::Iec => 2,
+    };
+    // Three digits or `d.d`, a space, the prefix, and `B/s`.
+    3 + 1 + prefix + 3
+}
+
+/// Width of `text` in terminal cells.
+pub fn display_width(text: &str) -> usize {
+    text.width()
+}
+
+/// `text` padded with spaces to `width` cells, on the left when `right`.
+///
+/// Text already wider than `width` is returned as is.
+pub fn pad(text: &str, width: usize, right: bool) -> String {
+    let fill = " ".repeat(width.saturating_sub(display_width(text)));
+    if right {
+        format!("{fill}{text}")
+    } else {
+        format!("{text}{fill}")
+    }
+}
+
+/// `text` cut and left-aligned to exactly `width` cells.
+///
+/// Cuts fall between grapheme clusters, so a base character keeps its
+/// combining marks and a multi-codepoint emoji stays whole; a cut text
+/// ends in `…`, and a wide character that would straddle the edge is
+/// replaced by padding.
+///
+/// ```
+/// use syswatch::format::{display_width, fit};
+///
+/// assert_eq!(fit("Chrome", 8), "Chrome  ");
+/// assert_eq!(fit("Google Chrome Helper", 8), "Google …");
+/// // Two cells per emoji: the column still ends where ASCII ones do.
+/// assert_eq!(fit("🚀 Launcher", 6), "🚀 La…");
+/// assert_eq!(display_width(&fit("🚀🚀🚀", 4)), 4);
+/// // A combining acute stays with its `e`.
+/// assert_eq!(fit("Cafe\u{301} Helper", 5), "Cafe\u{301}…");
+/// assert_eq!(fit("日本語アプリ", 7), "日本語…");
+/// ```
+pub fn fit(text: &str, width: usize) -> String {
+    if display_width(text) <= width {
+        return pad(text, width, false);
+    }
+    let room = width.saturating_sub(1);
+    let mut out = String::new();
+    let mut used = 0;
+    for grapheme in text.graphemes(true) {
+        let w = display_width(grapheme);
+        if used + w > room {
+            break;
+        }
+        out.push_str(grapheme);
+        used += w;
+    }
+    if width > 0 {
+        out.push('…');
+        used += 1;
+    }
+    out.push_str(&" ".repeat(width - used));
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn auto_bytes_scale_to_the_largest_whole_unit() {
+        let auto = |bytes| fmt_bytes_in(bytes, MemoryUnit::Auto);
+        assert_eq!(auto(0), "0 B");
+        assert_eq!(auto(1023), "1023 B");
+        assert_eq!(auto(KB), "1.0 KB");
+        assert_eq!(auto(MB + MB / 2), "1.5 MB");
+        assert_eq!(auto(GB), "1.0 GB");
+        assert_eq!(auto(64 * 1024 * GB), "65536.0 GB");
+        assert_eq!(auto(u64::MAX), "17179869184.0 GB");
+        assert_eq!(fmt_bytes(MB + MB / 2), auto(MB + MB / 2));
+    }
+
+    #[test]
+    fn fixed_byte_units_never_rescale() {
+        let mb = |bytes| fmt_bytes_in(bytes, MemoryUnit::Mb);
+        assert_eq!(mb(0), "0.0 MB");
+        assert_eq!(mb(512 * KB), "0.5 MB");
+        assert_eq!(mb(GB), "1024.0 MB");
+        assert_eq!(mb(u64::MAX), "17592186044416.0 MB");
+
+        let gb = |bytes| fmt_bytes_in(bytes, MemoryUnit::Gb);
+        assert_eq!(gb(0), "0.00 GB");
+        assert_eq!(gb(10 * MB), "0.01 GB");
+        assert_eq!(gb(16 * GB), "16.00 GB");
+        assert_eq!(gb(u64::MAX), "17179869184.00 GB");
+    }
+
+    #[test]
+    fn percent_decimals_follow_the_rounded_size() {
+        assert_eq!(fmt_percent(0.0), "0.00");
+        assert_eq!(fmt_percent(0.04), "0.04");
+        assert_eq!(fmt_percent(9.994), "9.99");
+        assert_eq!(fmt_percent(9.996), "10.0");
+        assert_eq!(fmt_percent(42.5), "42.5");
+        assert_eq!(fmt_percent(99.94), "99.9");
+        assert_eq!(fmt_percent(99.96), "100");
+        assert_eq!(fmt_percent(250.4), "250");
+        assert_eq!(fmt_percent(1250.0), "1250");
+        assert_eq!(fmt_percent(99_999.4), "99999");
+        assert_eq!(fmt_percent(-3.0), "0.00");
+        assert_eq!(fmt_percent(f64::NAN), "0.00");
+        assert_eq!(fmt_percent(f64::INFINITY), "0.00");
+    }
+
+    #[test]
+    fn percent_fits_the_header_up_to_99_999() {
+        let mut pct = 0.001;
+        while pct < 99_999.4 {
+            assert!(fmt_percent(pct).len() <= "CPU %".len(), "{pct}");
+            pct *= 1.07;
+        }
+    }
+
+    #[test]
+    fn rates_keep_three_significant_figures() {
+        let si = |bps| fmt_rate(bps, RateBase::Si, false);
+        assert_eq!(si(0.0), "   0 B/s");
+        assert_eq!(si(0.4), " 0.4 B/s");
+        assert_eq!(si(0.04), " 0.0 B/s");
+        assert_eq!(si(988.0), " 988 B/s");
+        assert_eq!(si(999.7), "1.0 kB/s");
+        assert_eq!(si(4.2e6), "4.2 MB/s");
+        assert_eq!(si(1.3e9), "1.3 GB/s");
+        assert_eq!(si(12.4e9), " 12 GB/s");
+        assert_eq!(si(640e9), "640 GB/s");
+        assert_eq!(si(-5.0), "   0 B/s");
+        assert_eq!(si(f64::NAN), "   0 B/s");
+        assert_eq!(si(f64::INFINITY), "   0 B/s");
+    }
+
+    #[test]
+    fn rates_in_iec_units_and_bits() {
+        assert_eq!(fmt_rate(1.5 * GB as f64, RateBase::Iec, false), "1.5 GiB/s");
+        assert_eq!(fmt_rate(1023.0, RateBase::Iec, false), "1.0 KiB/s");
+        assert_eq!(fmt_rate(4.25e6, RateBase::Si, true), " 34 Mb/s");
+        assert_eq!(fmt_rate(0.1, RateBase::Si, true), " 0.8 b/s");
+    }
+
+    #[test]
+    fn rate_width_holds_from_zero_to_saturation() {
+        assert_eq!(fmt_rate(999.6e15, RateBase::Si, false), "1.0 EB/s");
+        assert_eq!(fmt_rate(1e24, RateBase::Si, false), "999 EB/s");
+        assert_eq!(fmt_rate(f64::MAX, RateBase::Iec, true), "999 Eib/s");
+        for base in [RateBase::Si, RateBase::Iec] {
+            for bits in [false, true] {
+                for exp in -3..=30 {
+                    for mantissa in [1.0, 9.96, 99.94, 999.4, 999.6] {
+                        let text = fmt_rate(mantissa * 10f64.powi(exp), base, bits);
+                        assert_eq!(display_width(&text), rate_width(base), "{text:?}");
+                    }
+                }
+            }
+        }
+    }
+}
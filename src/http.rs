@@ -0,0 +1,154 @@
+//! Minimal blocking HTTP/1.1 client for pushing metrics.
+//!
+//! syswatch only needs to POST small bodies to local or LAN endpoints
+//! (InfluxDB, webhook relays), so this speaks plain `http://` over a
+//! `TcpStream` with `Connection: close` instead of pulling in an HTTP
+//! and TLS stack. Only the response status line is read.
+
+use std::fmt;
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::time::Duration;
+
+/// Default port for `http://` URLs without one.
+const DEFAULT_PORT: u16 = 80;
+
+/// A parsed `http://host[:port]/path?query` URL.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Url {
+    /// Host name or address, without brackets for IPv6.
+    pub host: String,
+    /// TCP port.
+    pub port: u16,
+    /// Path and query, always starting with `/`.
+    pub path: String,
+}
+
+impl fmt::Display for Url {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "http://{}{}", self.authority(), self.path)
+    }
+}
+
+impl Url {
+    /// Parses an `http://` URL, describing what is wrong with it.
+    pub fn parse(s: &str) -> Result<Self, String> {
+        let rest = match s.strip_prefix("http://") {
+            Some(rest) => rest,
+            None if s.starts_with("https://") => {
+                return Err("https is not supported; use an http:// endpoint".into());
+            }
+            None => return Err(format!("`{s}` is not an http:// URL")),
+        };
+
+        let (authority, path) = match rest.find(['/', '?']) {
+            Some(i) if rest[i..].starts_with('?') => (&rest[..i], format!("/{}", &rest[i..])),
+            Some(i) => (&rest[..i], rest[i..].to_string()),
+            None => (rest, "/".to_string()),
+        };
+
+        let (host, port) = if let Some(v6) = authority.strip_prefix('[') {
+            let (host, tail) = v6
+                .split_once(']')
+                .ok_or_else(|| format!("unclosed `[` in `{s}`"))?;
+            (host, tail.strip_prefix(':'))
+        } else {
+            match authority.rsplit_once(':') {
+                Some((host, port)) => (host, Some(port)),
+                None => (authority, None),
+            }
+        };
+        if host.is_empty() {
+            return Err(format!("`{s}` has no host"));
+        }
+        let port = match port {
+            Some(p) => p.parse().map_err(|_| format!("invalid port `{p}`"))?,
+            None => DEFAULT_PORT,
+        };
+
+        Ok(Self {
+            host: host.to_string(),
+            port,
+            path,
+        })
+    }
+
+    /// `host[:port]` as sent in the `Host` header.
+    fn authority(&self) -> String {
+        let host = if self.host.contains(':') {
+            format!("[{}]", self.host)
+        } else {
+            self.host.clone()
+        };
+        if self.port == DEFAULT_PORT {
+            host
+        } else {
+            format!("{host}:{}", self.port)
+        }
+    }
+}
+
+/// POSTs `body` to `url` and returns the response status code.
+///
+/// `timeout` bounds the connect and each read / write separately.
+pub fn post(
+    url: &Url,
+    content_type: &str,
+    headers: &[(&str, &str)],
+    body: &[u8],
+    timeout: Duration,
+) -> io::Result<u16> {
+    let mut stream = connect(url, timeout)?;
+    stream.set_read_timeout(Some(timeout))?;
+    stream.set_write_timeout(Some(timeout))?;
+
+    let mut request = format!(
+        "POST {} HTTP/1.1\r\nHost: {}\r\nUser-Agent: syswatch/{}\r\n\
+         Content-Type: {content_type}\r\nContent-Length: {}\r\nConnection: close\r\n",
+        url.path,
+        url.authority(),
+        env!("CARGO_PKG_VERSION"),
+        body.len()
+    );
+    for (name, value) in headers {
+        request.push_str(&format!("{name}: {value}\r\n"));
+    }
+    request.push_str("\r\n");
+
+    stream.write_all(request.as_bytes())?;
+    stream.write_all(body)?;
+    stream.flush()?;
+
+    let mut status_line = String::new();
+    BufReader::new(stream).read_line(&mut status_line)?;
+    parse_status(&status_line).ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("malformed HTTP status line `{}`", status_line.trim_end()),
+        )
+    })
+}
+
+/// Connects to the first address of `url` that accepts within `timeout`.
+fn connect(url: &Url, timeout: Duration) -> io::Result<TcpStream> {
+    let mut last_err = None;
+    for addr in (url.host.as_str(), url.port).to_socket_addrs()? {
+        match TcpStream::connect_timeout(&addr, timeout) {
+            Ok(stream) => return Ok(stream),
+            Err(err) => last_err = Some(err),
+        }
+    }
+    Err(last_err.unwrap_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("{} did not resolve", url.host),
+        )
+    }))
+}
+
+/// Extracts the code from `HTTP/1.1 204 No Content`.
+fn parse_status(line: &str) -> Option<u16> {
+    let mut parts = line.split_whitespace();
+    parts.next().filter(|v| v.starts_with("HTTP/"))?;
+    parts.next()?.parse().ok()
+}
@@ -0,0 +1,191 @@
+//! Terminal input read on a dedicated thread.
+//!
+//! The event loop waits on a channel instead of polling the terminal
+//! itself, so a key press wakes it immediately whatever the tick cadence,
+//! and other producers can be added to the wait later without touching
+//! crossterm.
+//!
+//! The loop only sees an [`EventSource`]. Besides [`Input`], [`Script`]
+//! replays a fixed list of keys and waits, so the whole loop can be
+//! driven without a terminal.
+
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::io;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::thread;
+use std::time::Duration;
+
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyModifiers};
+
+/// How long the reader blocks in `poll` before checking for a pause request.
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Requests from the event loop to the reader thread.
+enum Control {
+    /// Stop reading and acknowledge on the sender.
+    Pause(Sender<()>),
+    /// Continue reading after a pause.
+    Resume,
+}
+
+/// Handle to the reader thread; dropping it stops the thread.
+pub struct Input {
+    events: Receiver<io::Result<Event>>,
+    control: Sender<Control>,
+}
+
+impl Input {
+    /// Starts the reader thread.
+    pub fn spawn() -> Self {
+        let (events_tx, events) = mpsc::channel();
+        let (control, control_rx) = mpsc::channel();
+        thread::spawn(move || read_loop(&events_tx, &control_rx));
+        Self { events, control }
+    }
+
+    /// Waits up to `timeout` for the next terminal event.
+    pub fn next(&self, timeout: Duration) -> io::Result<Option<Event>> {
+        match self.events.recv_timeout(timeout) {
+            Ok(event) => event.map(Some),
+            Err(RecvTimeoutError::Timeout) => Ok(None),
+            Err(RecvTimeoutError::Disconnected) => Err(io::Error::new(
+                io::ErrorKind::BrokenPipe,
+                "input thread stopped",
+            )),
+        }
+    }
+
+    /// Stops reading so a foreground child can own the terminal.
+    ///
+    /// Blocks until the reader has left `poll`, so no keystroke meant for
+    /// the child is consumed.
+    pub fn pause(&self) {
+        let (ack, acked) = mpsc::channel();
+        if self.control.send(Control::Pause(ack)).is_ok() {
+            let _ = acked.recv();
+        }
+    }
+
+    /// Resumes reading after [`Input::pause`].
+    pub fn resume(&self) {
+        let _ = self.control.send(Control::Resume);
+    }
+}
+
+/// Where the event loop's input comes from.
+pub trait EventSource {
+    /// Waits up to `timeout` for the next event; `None` when none came.
+    fn next(&self, timeout: Duration) -> io::Result<Option<Event>>;
+
+    /// Stops reading so a foreground child can own the terminal.
+    fn pause(&self) {}
+
+    /// Resumes reading after [`EventSource::pause`].
+    fn resume(&self) {}
+}
+
+impl EventSource for Input {
+    fn next(&self, timeout: Duration) -> io::Result<Option<Event>> {
+        Input::next(self, timeout)
+    }
+
+    fn pause(&self) {
+        Input::pause(self);
+    }
+
+    fn resume(&self) {
+        Input::resume(self);
+    }
+}
+
+/// One step of a [`Script`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Step {
+    /// Presses a key.
+    Key(KeyCode),
+    /// Presses each character of the text in turn.
+    Text(String),
+    /// Lets this many ticks pass with no input.
+    Ticks(u32),
+}
+
+/// An [`EventSource`] that plays back fixed steps, then reports the end.
+///
+/// A wait sleeps out the loop's timeout, so ticks happen at the real
+/// tick interval; a short `[tick] interval` keeps scripts fast. Once
+/// every step is played, reading fails, so a script that never quits
+/// still ends the loop.
+#[derive(Debug, Default)]
+pub struct Script {
+    steps: RefCell<VecDeque<Step>>,
+}
+
+impl Script {
+    /// A script playing `steps` in order.
+    pub fn new(steps: impl IntoIterator<Item = Step>) -> Self {
+        Self {
+            steps: RefCell::new(steps.into_iter().collect()),
+        }
+    }
+}
+
+impl EventSource for Script {
+    fn next(&self, timeout: Duration) -> io::Result<Option<Event>> {
+        let mut steps = self.steps.borrow_mut();
+        let press = |code| Ok(Some(Event::Key(KeyEvent::new(code, KeyModifiers::NONE))));
+        match steps.pop_front() {
+            Some(Step::Key(code)) => press(code),
+            Some(Step::Text(text)) => {
+                let mut chars = text.chars();
+                let Some(c) = chars.next() else {
+                    return Ok(None);
+                };
+                let rest = chars.as_str();
+                if !rest.is_empty() {
+                    steps.push_front(Step::Text(rest.to_string()));
+                }
+                press(KeyCode::Char(c))
+            }
+            Some(Step::Ticks(n)) => {
+                // The loop asks for the time left until the next tick.
+                thread::sleep(timeout);
+                if n > 1 {
+                    steps.push_front(Step::Ticks(n - 1));
+                }
+                Ok(None)
+            }
+            None => Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "script ended before syswatch quit",
+            )),
+        }
+    }
+}
+
+/// Forwards terminal events until the handle is dropped.
+fn read_loop(events: &Sender<io::Result<Event>>, control: &Receiver<Control>) {
+    loop {
+        match control.try_recv() {
+            Ok(Control::Pause(ack)) => {
+                let _ = ack.send(());
+                // Anything but a resume means the handle is gone.
+                if !matches!(control.recv(), Ok(Control::Resume)) {
+                    return;
+                }
+            }
+            Ok(Control::Resume) | Err(mpsc::TryRecvError::Empty) => {}
+            Err(mpsc::TryRecvError::Disconnected) => return,
+        }
+
+        let event = match event::poll(POLL_INTERVAL) {
+            Ok(false) => continue,
+            Ok(true) => event::read(),
+            Err(err) => Err(err),
+        };
+        let failed = event.is_err();
+        if events.send(event).is_err() || failed {
+            return;
+        }
+    }
+}
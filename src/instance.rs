@@ -0,0 +1,215 @@
+//! Detecting other running syswatch instances.
+//!
+//! Every live-monitoring instance writes a lock file named after its PID
+//! to `~/Library/Application Support/syswatch/instances/`, listing the
+//! `--socket` and `--record` paths it was explicitly given; the default
+//! socket path is never claimed. At startup the other lock files are
+//! read: one whose PID is gone, or now belongs to a process not named
+//! syswatch, is stale and deleted. A live instance holding the socket or
+//! recording path this one asked for is a [`Conflict`], which refuses
+//! startup unless `--force` is given; any other live instance is only
+//! reported, since it just doubles the collection overhead. The lock
+//! file is removed on drop and from the panic hook.
+
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use sysinfo::{ProcessRefreshKind, ProcessesToUpdate, System};
+
+use crate::cleanup::remove_on_panic;
+use crate::monitor::Pid;
+
+/// Process name a lock file's PID must have to count as live.
+const PROCESS_NAME: &str = "syswatch";
+
+/// Directory of the per-instance lock files.
+pub fn lock_dir() -> Option<PathBuf> {
+    let home = std::env::var_os("HOME")?;
+    Some(
+        PathBuf::from(home)
+            .join("Library")
+            .join("Application Support")
+            .join("syswatch")
+            .join("instances"),
+    )
+}
+
+/// Paths a running instance holds exclusively.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Claims {
+    /// Query socket path.
+    pub socket: Option<PathBuf>,
+    /// `--record` file path.
+    pub record: Option<PathBuf>,
+}
+
+impl Claims {
+    /// Encodes the claims as lock-file lines: `socket <path>`, `record <path>`.
+    fn encode(&self) -> String {
+        let mut text = String::new();
+        for (key, path) in [("socket", &self.socket), ("record", &self.record)] {
+            if let Some(path) = path {
+                text.push_str(&format!("{key} {}\n", path.display()));
+            }
+        }
+        text
+    }
+
+    /// Reads lock-file lines back, skipping any it doesn't recognise.
+    fn decode(text: &str) -> Self {
+        let mut claims = Self::default();
+        for line in text.lines() {
+            match line.split_once(' ') {
+                Some(("socket", path)) => claims.socket = Some(PathBuf::from(path)),
+                Some(("record", path)) => claims.record = Some(PathBuf::from(path)),
+                _ => {}
+            }
+        }
+        claims
+    }
+}
+
+/// A path another live instance already holds.
+#[derive(Debug)]
+pub struct Conflict {
+    /// The other instance.
+    pub pid: Pid,
+    /// Which option's path it holds: `--socket` or `--record`.
+    pub option: &'static str,
+    /// The contested path.
+    pub path: PathBuf,
+}
+
+impl fmt::Display for Conflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "syswatch (PID {}) is already using {} as its {} path; stop it or pass --force",
+            self.pid,
+            self.path.display(),
+            self.option
+        )
+    }
+}
+
+/// This instance's lock file; removed on drop.
+#[derive(Debug)]
+pub struct InstanceLock {
+    path: PathBuf,
+    /// Other live instances found at startup.
+    pub others: Vec<Pid>,
+}
+
+impl InstanceLock {
+    /// Clears stale lock files, checks `claims` against the live ones,
+    /// and writes this instance's lock.
+    ///
+    /// With `force` conflicts are ignored. Lock files that can't be
+    /// written are an error; the caller decides whether that matters.
+    pub fn acquire(claims: &Claims, force: bool) -> Result<Self, LockError> {
+        let dir = lock_dir().ok_or_else(|| {
+            LockError::Io(io::Error::new(io::ErrorKind::NotFound, "$HOME is not set"))
+        })?;
+        fs::create_dir_all(&dir).map_err(LockError::Io)?;
+        let claims = Claims {
+            socket: claims.socket.as_deref().map(absolute),
+            record: claims.record.as_deref().map(absolute),
+        };
+
+        let own = std::process::id();
+        let mut others = Vec::new();
+        for (pid, held) in read_locks(&dir).map_err(LockError::Io)? {
+            if pid == own {
+                continue;
+            }
+            if !is_syswatch(pid) {
+                let _ = fs::remove_file(dir.join(pid.to_string()));
+                continue;
+            }
+            let contested = [
+                ("--socket", &claims.socket, &held.socket),
+                ("--record", &claims.record, &held.record),
+            ];
+            for (option, wanted, taken) in contested {
+                if !force
+                    && let Some(path) = wanted
+                    && taken.as_ref() == Some(path)
+                {
+                    return Err(LockError::Conflict(Conflict {
+                        pid,
+                        option,
+                        path: path.clone(),
+                    }));
+                }
+            }
+            others.push(pid);
+        }
+        others.sort_unstable();
+
+        let path = dir.join(own.to_string());
+        fs::write(&path, claims.encode()).map_err(LockError::Io)?;
+        remove_on_panic(path.clone());
+        Ok(Self { path, others })
+    }
+}
+
+impl Drop for InstanceLock {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+/// Why [`InstanceLock::acquire`] failed.
+#[derive(Debug)]
+pub enum LockError {
+    /// Another instance holds a requested path.
+    Conflict(Conflict),
+    /// The lock directory couldn't be read or written.
+    Io(io::Error),
+}
+
+impl fmt::Display for LockError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Conflict(conflict) => conflict.fmt(f),
+            Self::Io(err) => write!(f, "instance lock: {err}"),
+        }
+    }
+}
+
+/// Lists the lock files in `dir` as `(pid, claims)`, ignoring other files.
+fn read_locks(dir: &Path) -> io::Result<Vec<(Pid, Claims)>> {
+    let mut locks = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let Some(pid) = entry.file_name().to_str().and_then(|n| n.parse().ok()) else {
+            continue;
+        };
+        // A lock removed between listing and reading belongs to an exited instance.
+        if let Ok(text) = fs::read_to_string(entry.path()) {
+            locks.push((pid, Claims::decode(&text)));
+        }
+    }
+    Ok(locks)
+}
+
+/// Whether `pid` is alive and named syswatch, so its lock isn't stale.
+fn is_syswatch(pid: Pid) -> bool {
+    let sys_pid = sysinfo::Pid::from_u32(pid);
+    let mut sys = System::new();
+    sys.refresh_processes_specifics(
+        ProcessesToUpdate::Some(&[sys_pid]),
+        false,
+        ProcessRefreshKind::nothing(),
+    );
+    sys.process(sys_pid)
+        .is_some_and(|p| p.name().to_string_lossy() == PROCESS_NAME)
+}
+
+/// `path` made absolute against the working directory, so two instances
+/// started from different directories compare the same file equal.
+fn absolute(path: &Path) -> PathBuf {
+    std::path::absolute(path).unwrap_or_else(|_| path.to_path_buf())
+}
@@ -0,0 +1,255 @@
+//! Core Foundation and IOKit registry access shared by the macOS readers.
+//!
+//! Only what the battery and block-storage readers need is declared.
+//! Values fetched from a dictionary are borrowed from it; properties
+//! copied out of the registry come back as [`Owned`] and are released
+//! on drop.
+
+use std::ffi::{CStr, c_char, c_void};
+
+/// Any Core Foundation object.
+pub type CFTypeRef = *const c_void;
+
+/// An `io_object_t` handle to a registry entry or iterator.
+pub type IoObject = u32;
+
+/// `kIOMainPortDefault`: the default main port.
+const MAIN_PORT: u32 = 0;
+
+/// Registry plane for walking drivers and their media.
+const SERVICE_PLANE: &CStr = c"IOService";
+
+/// `kCFStringEncodingUTF8`.
+const UTF8: u32 = 0x0800_0100;
+
+/// `kCFNumberSInt64Type`.
+const SINT64: isize = 4;
+
+/// `kCFCompareEqualTo`.
+const EQUAL: isize = 0;
+
+/// Longest string property read, including the terminator.
+const MAX_STRING: usize = 256;
+
+#[link(name = "IOKit", kind = "framework")]
+unsafe extern "C" {
+    pub fn IOPSCopyPowerSourcesInfo() -> CFTypeRef;
+    pub fn IOPSCopyPowerSourcesList(blob: CFTypeRef) -> CFTypeRef;
+    pub fn IOPSGetPowerSourceDescription(blob: CFTypeRef, source: CFTypeRef) -> CFTypeRef;
+    fn IOServiceMatching(class: *const c_char) -> CFTypeRef;
+    fn IOBSDNameMatching(main: u32, options: u32, name: *const c_char) -> CFTypeRef;
+    fn IOServiceGetMatchingServices(main: u32, matching: CFTypeRef, iter: *mut IoObject) -> i32;
+    fn IOServiceGetMatchingService(main: u32, matching: CFTypeRef) -> IoObject;
+    fn IOIteratorNext(iter: IoObject) -> IoObject;
+    fn IOObjectRelease(object: IoObject) -> i32;
+    fn IOObjectConformsTo(object: IoObject, class: *const c_char) -> u32;
+    fn IORegistryEntryCreateCFProperty(
+        entry: IoObject,
+        key: CFTypeRef,
+        alloc: CFTypeRef,
+        options: u32,
+    ) -> CFTypeRef;
+    fn IORegistryEntryGetChildEntry(
+        entry: IoObject,
+        plane: *const c_char,
+        child: *mut IoObject,
+    ) -> i32;
+    fn IORegistryEntryGetParentEntry(
+        entry: IoObject,
+        plane: *const c_char,
+        parent: *mut IoObject,
+    ) -> i32;
+    fn IORegistryEntryGetRegistryEntryID(entry: IoObject, id: *mut u64) -> i32;
+}
+
+#[link(name = "CoreFoundation", kind = "framework")]
+unsafe extern "C" {
+    pub fn CFArrayGetCount(array: CFTypeRef) -> isize;
+    pub fn CFArrayGetValueAtIndex(array: CFTypeRef, index: isize) -> CFTypeRef;
+    fn CFDictionaryGetValue(dict: CFTypeRef, key: CFTypeRef) -> CFTypeRef;
+    fn CFStringCreateWithCString(alloc: CFTypeRef, text: *const c_char, encoding: u32)
+    -> CFTypeRef;
+    fn CFStringGetCString(text: CFTypeRef, buf: *mut c_char, len: isize, encoding: u32) -> u8;
+    fn CFStringCompare(a: CFTypeRef, b: CFTypeRef, options: usize) -> isize;
+    fn CFNumberGetValue(number: CFTypeRef, kind: isize, value: *mut c_void) -> u8;
+    fn CFBooleanGetValue(boolean: CFTypeRef) -> u8;
+    pub fn CFRelease(object: CFTypeRef);
+}
+
+/// A Core Foundation object this code holds a reference to.
+pub struct Owned(CFTypeRef);
+
+impl Owned {
+    /// Takes ownership of `object`, or `None` for null.
+    fn new(object: CFTypeRef) -> Option<Self> {
+        (!object.is_null()).then_some(Self(object))
+    }
+
+    /// The object, borrowed for as long as `self` lives.
+    pub fn get(&self) -> CFTypeRef {
+        self.0
+    }
+}
+
+impl Drop for Owned {
+    fn drop(&mut self) {
+        unsafe { CFRelease(self.0) };
+    }
+}
+
+/// A registry entry or iterator handle, released on drop.
+pub struct Entry(IoObject);
+
+impl Drop for Entry {
+    fn drop(&mut self) {
+        unsafe { IOObjectRelease(self.0) };
+    }
+}
+
+impl Entry {
+    /// The registry entry ID, unique for the entry's lifetime.
+    pub fn id(&self) -> Option<u64> {
+        let mut id = 0;
+        (unsafe { IORegistryEntryGetRegistryEntryID(self.0, &mut id) } == 0).then_some(id)
+    }
+
+    /// Whether the entry is an instance of IOKit class `class`.
+    pub fn conforms_to(&self, class: &CStr) -> bool {
+        unsafe { IOObjectConformsTo(self.0, class.as_ptr()) != 0 }
+    }
+
+    /// The first child in the service plane.
+    pub fn child(&self) -> Option<Entry> {
+        let mut child = 0;
+        let ret =
+            unsafe { IORegistryEntryGetChildEntry(self.0, SERVICE_PLANE.as_ptr(), &mut child) };
+        (ret == 0).then_some(Entry(child))
+    }
+
+    /// The parent in the service plane.
+    pub fn parent(&self) -> Option<Entry> {
+        let mut parent = 0;
+        let ret =
+            unsafe { IORegistryEntryGetParentEntry(self.0, SERVICE_PLANE.as_ptr(), &mut parent) };
+        (ret == 0).then_some(Entry(parent))
+    }
+
+    /// A copy of the property `key`.
+    pub fn property(&self, key: &CStr) -> Option<Owned> {
+        let key = string(key)?;
+        Owned::new(unsafe {
+            IORegistryEntryCreateCFProperty(self.0, key.get(), std::ptr::null(), 0)
+        })
+    }
+}
+
+/// Every registered instance of IOKit class `class`, or the
+/// `kern_return_t` on failure.
+pub fn services(class: &CStr) -> Result<Vec<Entry>, i32> {
+    let mut iter = 0;
+    unsafe {
+        // `IOServiceGetMatchingServices` consumes the matching dictionary.
+        let matching = IOServiceMatching(class.as_ptr());
+        let ret = IOServiceGetMatchingServices(MAIN_PORT, matching, &mut iter);
+        if ret != 0 {
+            return Err(ret);
+        }
+        let iter = Entry(iter);
+        let mut entries = Vec::new();
+        loop {
+            let next = IOIteratorNext(iter.0);
+            if next == 0 {
+                break;
+            }
+            entries.push(Entry(next));
+        }
+        Ok(entries)
+    }
+}
+
+/// The media entry of BSD device `name` (e.g. `disk3s1`).
+pub fn bsd_media(name: &str) -> Option<Entry> {
+    let name = std::ffi::CString::new(name).ok()?;
+    unsafe {
+        // `IOServiceGetMatchingService` consumes the matching dictionary.
+        let matching = IOBSDNameMatching(MAIN_PORT, 0, name.as_ptr());
+        if matching.is_null() {
+            return None;
+        }
+        let entry = IOServiceGetMatchingService(MAIN_PORT, matching);
+        (entry != 0).then_some(Entry(entry))
+    }
+}
+
+/// A Core Foundation string for `text`.
+fn string(text: &CStr) -> Option<Owned> {
+    Owned::new(unsafe { CFStringCreateWithCString(std::ptr::null(), text.as_ptr(), UTF8) })
+}
+
+/// The value under `key` in `dict`, borrowed from it; null if absent.
+///
+/// # Safety
+/// `dict` must be a live `CFDictionary`.
+pub unsafe fn value(dict: CFTypeRef, key: &CStr) -> CFTypeRef {
+    match string(key) {
+        Some(key) => unsafe { CFDictionaryGetValue(dict, key.get()) },
+        None => std::ptr::null(),
+    }
+}
+
+/// The integer `number`, or `None` for null.
+///
+/// # Safety
+/// `number` must be null or a live `CFNumber`.
+pub unsafe fn number(number: CFTypeRef) -> Option<i64> {
+    let mut out = 0i64;
+    (!number.is_null() && unsafe { CFNumberGetValue(number, SINT64, (&raw mut out).cast()) } != 0)
+        .then_some(out)
+}
+
+/// The integer under `key` in `dict`.
+///
+/// # Safety
+/// `dict` must be a live `CFDictionary`.
+pub unsafe fn int(dict: CFTypeRef, key: &CStr) -> Option<i64> {
+    unsafe { number(value(dict, key)) }
+}
+
+/// The boolean under `key` in `dict`.
+///
+/// # Safety
+/// `dict` must be a live `CFDictionary`.
+pub unsafe fn boolean(dict: CFTypeRef, key: &CStr) -> Option<bool> {
+    let flag = unsafe { value(dict, key) };
+    (!flag.is_null()).then(|| unsafe { CFBooleanGetValue(flag) } != 0)
+}
+
+/// The string `text` as UTF-8, or `None` for null or overlong text.
+///
+/// # Safety
+/// `text` must be null or a live `CFString`.
+pub unsafe fn to_string(text: CFTypeRef) -> Option<String> {
+    if text.is_null() {
+        return None;
+    }
+    let mut buf = [0 as c_char; MAX_STRING];
+    let ok = unsafe { CFStringGetCString(text, buf.as_mut_ptr(), MAX_STRING as isize, UTF8) };
+    if ok == 0 {
+        return None;
+    }
+    let text = unsafe { CStr::from_ptr(buf.as_ptr()) };
+    Some(text.to_string_lossy().into_owned())
+}
+
+/// Whether the string under `key` in `dict` equals `expected`.
+///
+/// # Safety
+/// `dict` must be a live `CFDictionary`.
+pub unsafe fn string_is(dict: CFTypeRef, key: &CStr, expected: &CStr) -> bool {
+    let actual = unsafe { value(dict, key) };
+    if actual.is_null() {
+        return false;
+    }
+    string(expected)
+        .is_some_and(|expected| unsafe { CFStringCompare(actual, expected.get(), 0) == EQUAL })
+}
@@ -0,0 +1,122 @@
+//! Minimal JSON encoding for machine-readable output.
+//!
+//! syswatch only ever writes flat-ish JSON (webhook payloads, query
+//! replies), so an [`Object`] builder over a `String` is enough and
+//! avoids a serialization dependency.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Encodes `s` as a quoted JSON string.
+pub fn string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if c < ' ' => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// Encodes a float; non-finite values become `null`.
+pub fn number(v: f64) -> String {
+    if v.is_finite() {
+        v.to_string()
+    } else {
+        "null".to_string()
+    }
+}
+
+/// Joins already-encoded values into a JSON array.
+pub fn array(items: impl IntoIterator<Item = String>) -> String {
+    let items: Vec<String> = items.into_iter().collect();
+    format!("[{}]", items.join(","))
+}
+
+/// Formats `time` as an RFC 3339 UTC timestamp with millisecond precision.
+pub fn timestamp(time: SystemTime) -> String {
+    let since = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+    let secs = since.as_secs();
+    let (year, month, day) = civil_from_days((secs / 86_400) as i64);
+    let rem = secs % 86_400;
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}.{:03}Z",
+        rem / 3600,
+        rem / 60 % 60,
+        rem % 60,
+        since.subsec_millis()
+    )
+}
+
+/// Converts days since 1970-01-01 into a proleptic Gregorian date.
+///
+/// Howard Hinnant's `civil_from_days` algorithm.
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+/// Builder for a JSON object, written in insertion order.
+#[derive(Debug, Default)]
+pub struct Object {
+    buf: String,
+}
+
+impl Object {
+    /// Starts an empty object.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds `key` with an already-encoded JSON value.
+    pub fn raw(mut self, key: &str, value: &str) -> Self {
+        if !self.buf.is_empty() {
+            self.buf.push(',');
+        }
+        self.buf.push_str(&string(key));
+        self.buf.push(':');
+        self.buf.push_str(value);
+        self
+    }
+
+    /// Adds a string member.
+    pub fn str(self, key: &str, value: &str) -> Self {
+        self.raw(key, &string(value))
+    }
+
+    /// Adds a float member.
+    pub fn num(self, key: &str, value: f64) -> Self {
+        self.raw(key, &number(value))
+    }
+
+    /// Adds an integer member.
+    pub fn int(self, key: &str, value: impl Into<i128>) -> Self {
+        self.raw(key, &value.into().to_string())
+    }
+
+    /// Adds an optional member, `null` when absent.
+    pub fn opt(self, key: &str, value: Option<String>) -> Self {
+        let value = value.unwrap_or_else(|| "null".to_string());
+        self.raw(key, &value)
+    }
+
+    /// Returns the encoded object.
+    pub fn finish(self) -> String {
+        format!("{{{}}}", self.buf)
+    }
+}
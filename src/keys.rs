@@ -0,0 +1,433 @@
+//! Key bindings of the main view.
+//!
+//! [`BINDINGS`] is the single table of actions and the keys that trigger
+//! them: `handle_key` dispatches through [`action_for`], and the help
+//! overlay, the function-key bar, and `--keys` are generated from it.
+
+use crossterm::event::KeyCode;
+
+/// An action reachable from the main view.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    /// Quit syswatch.
+    Quit,
+    /// Move the selection down.
+    Down,
+    /// Move the selection up.
+    Up,
+    /// Move the selection down a page.
+    PageDown,
+    /// Move the selection up a page.
+    PageUp,
+    /// Start editing the name filter.
+    Filter,
+    /// Cycle through the `[filters]` presets.
+    CyclePreset,
+    /// Open the filter preset picker.
+    Presets,
+    /// Toggle the process tree.
+    Tree,
+    /// Toggle showing only processes run from external volumes.
+    ExternalOnly,
+    /// Open the sort menu.
+    SortMenu,
+    /// Open the column order and visibility settings.
+    Columns,
+    /// Cycle the sort column.
+    CycleSort,
+    /// Reverse the sort direction.
+    Reverse,
+    /// Toggle CPU band separators.
+    Bands,
+    /// Open the detail popup.
+    Details,
+    /// Toggle the docked detail pane.
+    Dock,
+    /// Open the live activity view of the selected process.
+    Activity,
+    /// Open the signal picker.
+    Kill,
+    /// Open the children timeline of the selected process.
+    Children,
+    /// Open the memory breakdown.
+    Memory,
+    /// Open the per-device disk throughput.
+    Disks,
+    /// Export the chart history.
+    Export,
+    /// Open the "open with" picker.
+    OpenWith,
+    /// Sample call stacks of the selected process.
+    Sample,
+    /// Toggle the compact layout.
+    Compact,
+    /// Toggle coloring relative to the table's largest value.
+    RelativeColors,
+    /// Cycle through the `[profile.<name>]` bundles.
+    CycleProfile,
+    /// Toggle the System CPU series.
+    ToggleSystem,
+    /// Toggle the User CPU series.
+    ToggleUser,
+    /// Toggle the Max core CPU series.
+    ToggleMaxCore,
+    /// Zoom the CPU chart out.
+    ZoomOut,
+    /// Zoom the CPU chart in.
+    ZoomIn,
+    /// Open the notification snooze picker.
+    Snooze,
+    /// Toggle the self-monitoring overlay.
+    Debug,
+    /// Show the help overlay.
+    Help,
+}
+
+/// One action with its keys and descriptions.
+#[derive(Debug, Clone, Copy)]
+pub struct Binding {
+    /// What the keys do.
+    pub action: Action,
+    /// Keys that trigger the action; the first is the primary one.
+    pub keys: &'static [KeyCode],
+    /// Description shown in the help overlay and `--keys`.
+    pub help: &'static str,
+    /// Label in the function-key bar, for actions bound to an F-key.
+    pub bar: Option<&'static str>,
+}
+
+/// Every main-view binding, in help order.
+pub const BINDINGS: [Binding; 36] = [
+    Binding {
+        action: Action::Quit,
+        keys: &[KeyCode::Char('q'), KeyCode::Esc, KeyCode::F(10)],
+        help: "quit (Esc clears an active filter first)",
+        bar: Some("Quit"),
+    },
+    Binding {
+        action: Action::Down,
+        keys: &[KeyCode::Char('j'), KeyCode::Down],
+        help: "move the selection down",
+        bar: None,
+    },
+    Binding {
+        action: Action::Up,
+        keys: &[KeyCode::Char('k'), KeyCode::Up],
+        help: "move the selection up",
+        bar: None,
+    },
+    Binding {
+        action: Action::PageDown,
+        keys: &[KeyCode::PageDown],
+        help: "move the selection down a page",
+        bar: None,
+    },
+    Binding {
+        action: Action::PageUp,
+        keys: &[KeyCode::PageUp],
+        help: "move the selection up a page",
+        bar: None,
+    },
+    Binding {
+        action: Action::Filter,
+        keys: &[KeyCode::Char('/'), KeyCode::F(3)],
+        help: "filter by name pattern (Enter keeps, Esc clears)",
+        bar: Some("Search"),
+    },
+    Binding {
+        action: Action::CyclePreset,
+        keys: &[KeyCode::Char('f')],
+        help: "cycle the [filters] presets",
+        bar: None,
+    },
+    Binding {
+        action: Action::Presets,
+        keys: &[KeyCode::Char('F'), KeyCode::F(4)],
+        help: "pick a filter preset",
+        bar: None,
+    },
+    Binding {
+        action: Action::Tree,
+        keys: &[KeyCode::Char('t'), KeyCode::F(5)],
+        help: "toggle the process tree",
+        bar: Some("Tree"),
+    },
+    Binding {
+        action: Action::ExternalOnly,
+        keys: &[KeyCode::Char('V')],
+        help: "only processes run from external volumes or disk images (⏏)",
+        bar: None,
+    },
+    Binding {
+        action: Action::SortMenu,
+        keys: &[KeyCode::Char('o'), KeyCode::F(6)],
+        help: "sort menu (r reverses inside it, c opens the column settings)",
+        bar: Some("Sort"),
+    },
+    Binding {
+        action: Action::Columns,
+        keys: &[KeyCode::F(2)],
+        help: "column settings: J/K reorder, Space shows or hides, Enter applies",
+        bar: Some("Columns"),
+    },
+    Binding {
+        action: Action::CycleSort,
+        keys: &[KeyCode::Char('s')],
+        help: "cycle the sort column",
+        bar: None,
+    },
+    Binding {
+        action: Action::Reverse,
+        keys: &[KeyCode::Char('r')],
+        help: "reverse the sort direction",
+        bar: None,
+    },
+    Binding {
+        action: Action::Bands,
+        keys: &[KeyCode::Char('b')],
+        help: "CPU band separators (CPU sort, flat list)",
+        bar: None,
+    },
+    Binding {
+        action: Action::Details,
+        keys: &[KeyCode::Enter],
+        help: "process details",
+        bar: None,
+    },
+    Binding {
+        action: Action::Dock,
+        keys: &[KeyCode::Char('d')],
+        help: "dock details beside the table (wide terminals)",
+        bar: None,
+    },
+    Binding {
+        action: Action::Activity,
+        keys: &[KeyCode::Char('a')],
+        help: "live activity of the process (j k follow the selection)",
+        bar: None,
+    },
+    Binding {
+        action: Action::Kill,
+        keys: &[KeyCode::Char('K'), KeyCode::F(9)],
+        help: "send a signal to the process",
+        bar: Some("Kill"),
+    },
+    Binding {
+        action: Action::Children,
+        keys: &[KeyCode::Char('C')],
+        help: "timeline of the process's children (e exports JSON)",
+        bar: None,
+    },
+    Binding {
+        action: Action::Memory,
+        keys: &[KeyCode::Char('M')],
+        help: "memory breakdown and compressor activity",
+        bar: None,
+    },
+    Binding {
+        action: Action::Disks,
+        keys: &[KeyCode::Char('I')],
+        help: "disk I/O per device (j k: pick the charted one)",
+        bar: None,
+    },
+    Binding {
+        action: Action::Export,
+        keys: &[KeyCode::Char('e')],
+        help: "export the chart history (w: visible window / entire session)",
+        bar: None,
+    },
+    Binding {
+        action: Action::OpenWith,
+        keys: &[KeyCode::Char('x')],
+        help: "open the process with a command",
+        bar: None,
+    },
+    Binding {
+        action: Action::Sample,
+        keys: &[KeyCode::Char('!')],
+        help: "sample call stacks",
+        bar: None,
+    },
+    Binding {
+        action: Action::Compact,
+        keys: &[KeyCode::Char('c')],
+        help: "toggle compact mode",
+        bar: None,
+    },
+    Binding {
+        action: Action::RelativeColors,
+        keys: &[KeyCode::Char('m')],
+        help: "color memory (and [table] relative_cpu CPU) relative to the largest",
+        bar: None,
+    },
+    Binding {
+        action: Action::CycleProfile,
+        keys: &[KeyCode::Char('p')],
+        help: "cycle the [profile.*] bundles",
+        bar: None,
+    },
+    Binding {
+        action: Action::ToggleSystem,
+        keys: &[KeyCode::Char('1')],
+        help: "toggle the System series",
+        bar: None,
+    },
+    Binding {
+        action: Action::ToggleUser,
+        keys: &[KeyCode::Char('2')],
+        help: "toggle the User series",
+        bar: None,
+    },
+    Binding {
+        action: Action::ToggleMaxCore,
+        keys: &[KeyCode::Char('3')],
+        help: "toggle the Max core series ([cpu] per_core)",
+        bar: None,
+    },
+    Binding {
+        action: Action::ZoomOut,
+        keys: &[KeyCode::Char('z')],
+        help: "zoom the CPU chart out",
+        bar: None,
+    },
+    Binding {
+        action: Action::ZoomIn,
+        keys: &[KeyCode::Char('Z')],
+        help: "zoom the CPU chart in",
+        bar: None,
+    },
+    Binding {
+        action: Action::Snooze,
+        keys: &[KeyCode::Char('n')],
+        help: "snooze notifications (alerts are still logged)",
+        bar: None,
+    },
+    Binding {
+        action: Action::Debug,
+        keys: &[KeyCode::Char('D')],
+        help: "self-monitoring overlay",
+        bar: None,
+    },
+    Binding {
+        action: Action::Help,
+        keys: &[KeyCode::Char('?'), KeyCode::F(1)],
+        help: "this help",
+        bar: Some("Help"),
+    },
+];
+
+/// Keys that work inside popups, as `(keys, description)`.
+pub const POPUP_KEYS: [(&str, &str); 3] = [
+    ("j k  Enter", "select and confirm"),
+    ("/", "filter this help"),
+    ("Esc  q", "close"),
+];
+
+/// Returns the action bound to `code`, if any.
+pub fn action_for(code: KeyCode) -> Option<Action> {
+    BINDINGS
+        .iter()
+        .find(|b| b.keys.contains(&code))
+        .map(|b| b.action)
+}
+
+/// Display name of one key.
+pub fn key_name(code: KeyCode) -> String {
+    match code {
+        KeyCode::Char(c) => c.to_string(),
+        KeyCode::F(n) => format!("F{n}"),
+        KeyCode::Up => "↑".to_string(),
+        KeyCode::Down => "↓".to_string(),
+        KeyCode::PageUp => "PgUp".to_string(),
+        KeyCode::PageDown => "PgDn".to_string(),
+        KeyCode::Enter => "Enter".to_string(),
+        KeyCode::Esc => "Esc".to_string(),
+        other => format!("{other:?}"),
+    }
+}
+
+impl Binding {
+    /// All of the binding's keys, space-separated.
+    pub fn key_names(&self) -> String {
+        let names: Vec<String> = self.keys.iter().map(|&k| key_name(k)).collect();
+        names.join("  ")
+    }
+
+    /// The binding's function key number, if it has one.
+    fn function_key(&self) -> Option<u8> {
+        self.keys.iter().find_map(|k| match k {
+            KeyCode::F(n) => Some(*n),
+            _ => None,
+        })
+    }
+
+    /// Whether `query` (lowercase) occurs in the keys or description.
+    pub fn matches(&self, query: &str) -> bool {
+        self.help.to_lowercase().contains(query) || self.key_names().to_lowercase().contains(query)
+    }
+}
+
+/// Function-bar segments as `(key, label)`, ordered by F-key number.
+pub fn function_bar() -> Vec<(String, &'static str)> {
+    let mut segments: Vec<(u8, &'static str)> = BINDINGS
+        .iter()
+        .filter_map(|b| Some((b.function_key()?, b.bar?)))
+        .collect();
+    segments.sort_unstable_by_key(|&(n, _)| n);
+    segments
+        .into_iter()
+        .map(|(n, label)| (format!("F{n}"), label))
+        .collect()
+}
+
+/// Format for `--keys`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum KeysFormat {
+    /// Aligned plain text.
+    #[default]
+    Text,
+    /// A Markdown table.
+    Markdown,
+}
+
+impl KeysFormat {
+    /// Parses a format name as given on the command line.
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "text" => Some(Self::Text),
+            "markdown" | "md" => Some(Self::Markdown),
+            _ => None,
+        }
+    }
+}
+
+/// Renders the full binding table for `--keys`.
+pub fn cheatsheet(format: KeysFormat) -> String {
+    let main = BINDINGS.iter().map(|b| (b.key_names(), b.help));
+    let popup = POPUP_KEYS.iter().map(|&(k, h)| (k.to_string(), h));
+    let mut out = String::new();
+    match format {
+        KeysFormat::Text => {
+            out.push_str("Main view\n");
+            for (keys, help) in main {
+                out.push_str(&format!("  {keys:<14}{help}\n"));
+            }
+            out.push_str("\nIn popups\n");
+            for (keys, help) in popup {
+                out.push_str(&format!("  {keys:<14}{help}\n"));
+            }
+        }
+        KeysFormat::Markdown => {
+            out.push_str("| Key | Action |\n|-----|--------|\n");
+            for (keys, help) in main {
+                let keys: Vec<String> = keys.split("  ").map(|k| format!("`{k}`")).collect();
+                out.push_str(&format!("| {} | {help} |\n", keys.join(" / ")));
+            }
+            out.push_str("\n| In popups | Action |\n|-----|--------|\n");
+            for (keys, help) in popup {
+                out.push_str(&format!("| `{keys}` | {help} |\n"));
+            }
+        }
+    }
+    out
+}
@@ -0,0 +1,70 @@
+//! Syswatch's monitoring core, shared by the `syswatch` TUI and embedders.
+//!
+//! [`monitor::Monitor`] samples the system into plain
+//! [`snapshot::Snapshot`] values with no terminal state attached; the
+//! TUI's [`app::App`] is one consumer of it. The remaining modules are the
+//! TUI itself and are public so the binary can use them.
+//!
+//! ```no_run
+//! use std::time::Duration;
+//! use syswatch::monitor::{Monitor, MonitorOptions};
+//!
+//! for snapshot in Monitor::new(MonitorOptions::default()).every(Duration::from_secs(1)) {
+//!     if let Some(top) = snapshot.top_cpu() {
+//!         println!("{} {:.1}%", top.name, top.cpu_usage);
+//!     }
+//! }
+//! ```
+
+pub mod alerts;
+pub mod app;
+pub mod battery;
+pub mod children;
+pub mod cleanup;
+pub mod cli;
+pub mod collector;
+pub mod config;
+pub mod dashboard;
+pub mod disks;
+pub mod enforce;
+pub mod export;
+pub mod external;
+#[cfg(any(test, feature = "fixtures"))]
+pub mod fixtures;
+pub mod format;
+pub mod http;
+pub mod input;
+pub mod instance;
+#[cfg(target_os = "macos")]
+pub mod iokit;
+pub mod json;
+pub mod keys;
+pub mod monitor;
+#[cfg(feature = "mqtt")]
+pub mod mqtt;
+pub mod notify;
+pub mod pattern;
+pub mod plain;
+pub mod profile;
+pub mod record;
+pub mod reload;
+pub mod replay;
+pub mod rotate;
+pub mod rules;
+pub mod sample;
+pub mod series;
+pub mod session;
+pub mod snapshot;
+pub mod socket;
+pub mod state;
+pub mod stream;
+pub mod summary;
+pub mod terminal;
+pub mod thermal;
+pub mod tick;
+pub mod title;
+pub mod tui;
+pub mod ui;
+pub mod view;
+pub mod volumes;
+pub mod webhook;
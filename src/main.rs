@@ -3,61 +3,237 @@
 //! Renders live CPU, memory, thread, and per-process statistics
 //! inside a ratatui TUI refreshed once per second.
 
-mod app;
-mod ui;
-
 use std::io;
-use std::time::{Duration, Instant};
+use std::net::SocketAddr;
 
-use crossterm::event::{self, Event, KeyCode, KeyEventKind};
-use ratatui::DefaultTerminal;
+use syswatch::app::App;
+use syswatch::cleanup::chain_panic_cleanup;
+use syswatch::cli::{CliError, Options};
+use syswatch::config::Config;
+use syswatch::dashboard::Dashboard;
+use syswatch::input::Input;
+use syswatch::instance::{Claims, InstanceLock, LockError};
+use syswatch::keys;
+#[cfg(feature = "mqtt")]
+use syswatch::mqtt;
+use syswatch::record::Recorder;
+use syswatch::socket::{self, Server};
+use syswatch::title;
+use syswatch::{collector, plain, reload, replay, stream, summary, terminal, tui, webhook};
 
-use app::App;
+fn main() -> io::Result<()> {
+    let opts = match Options::from_env() {
+        Ok(opts) => opts,
+        Err(CliError::Help) => {
+            println!("{}", CliError::Help);
+            return Ok(());
+        }
+        Err(err) => {
+            eprintln!("syswatch: {err}");
+            std::process::exit(2);
+        }
+    };
 
-/// Refresh interval for the main event loop.
-const TICK_RATE: Duration = Duration::from_secs(1);
+    if let Some(format) = opts.keys {
+        print!("{}", keys::cheatsheet(format));
+        return Ok(());
+    }
 
-fn main() -> io::Result<()> {
-    let mut terminal = ratatui::init();
-    let result = run(&mut terminal);
-    ratatui::restore();
-    result
-}
+    if let Some(path) = &opts.view {
+        let recording = match replay::Recording::open(path) {
+            Ok(recording) => recording,
+            Err(err) => {
+                eprintln!("syswatch: --view {}: {err}", path.display());
+                std::process::exit(2);
+            }
+        };
+        let mut terminal = match terminal::init() {
+            Ok(terminal) => terminal,
+            Err(reason) => {
+                eprintln!("syswatch: --view needs a full-screen terminal: {reason}");
+                std::process::exit(2);
+            }
+        };
+        let result = replay::run(&mut terminal, recording);
+        ratatui::restore();
+        return result;
+    }
 
-/// Drives the event loop: draws the UI, polls for input, and ticks state.
-fn run(terminal: &mut DefaultTerminal) -> io::Result<()> {
-    let mut app = App::new();
-    std::thread::sleep(sysinfo::MINIMUM_CPU_UPDATE_INTERVAL);
-    app.tick();
+    if opts.check {
+        let healthy = collector::check(&mut io::stdout())?;
+        std::process::exit(if healthy { 0 } else { 1 });
+    }
 
-    let mut last_tick = Instant::now();
+    let config = match Config::load(opts.config.as_deref()) {
+        Ok(config) => config,
+        Err(err) => {
+            eprintln!("syswatch: {err}");
+            std::process::exit(2);
+        }
+    };
+    if opts.test_webhook {
+        std::process::exit(test_webhook(&config));
+    }
+    if let Some(name) = &opts.profile
+        && !config.profiles.iter().any(|p| p.name == *name)
+    {
+        let names: Vec<&str> = config.profiles.iter().map(|p| p.name.as_str()).collect();
+        let known = if names.is_empty() {
+            "none are configured".to_string()
+        } else {
+            format!("configured: {}", names.join(", "))
+        };
+        eprintln!("syswatch: unknown profile `{name}` ({known})");
+        std::process::exit(2);
+    }
 
-    while app.running {
-        terminal.draw(|f| ui::draw(f, &mut app))?;
+    // Left on the main screen, so they are visible again after quitting.
+    for warning in &config.warnings {
+        eprintln!("syswatch: config: {warning}");
+    }
 
-        let timeout = TICK_RATE.saturating_sub(last_tick.elapsed());
-        if event::poll(timeout)?
-            && let Event::Key(key) = event::read()?
-            && key.kind == KeyEventKind::Press
-        {
-            handle_key(&mut app, key.code);
+    let lock = lock_instance(&opts);
+    let recorder = match Recorder::open(&opts) {
+        Ok(recorder) => recorder,
+        Err(err) => {
+            eprintln!("syswatch: --record: {err}");
+            drop(lock);
+            std::process::exit(2);
         }
+    };
 
-        if last_tick.elapsed() >= TICK_RATE {
-            app.tick();
-            last_tick = Instant::now();
+    let server = start_socket(&opts);
+    reload::install();
+    let mut app = start_app(&opts, config, recorder, server);
+    app.other_instances = lock.as_ref().map(|l| l.others.clone()).unwrap_or_default();
+
+    let app = if opts.plain {
+        plain::run(&mut app, &mut io::stdout())?;
+        app
+    } else if opts.stream {
+        stream::run(&mut app, opts.stream_processes, &mut io::stdout())?;
+        app
+    } else {
+        match terminal::init() {
+            Ok(mut terminal) => {
+                chain_panic_cleanup(title::restore);
+                let result = tui::run(&mut terminal, app, &Input::spawn(), &mut io::stdout());
+                ratatui::restore();
+                title::restore();
+                result?
+            }
+            Err(reason) => {
+                eprintln!("syswatch: {reason}; printing --plain reports instead");
+                plain::run(&mut app, &mut io::stdout())?;
+                app
+            }
         }
+    };
+    // The summary would corrupt a JSON stream, and the reader may be gone.
+    if app.config.exit_summary && !opts.stream {
+        summary::write(&app, &mut io::stdout())?;
     }
-
     Ok(())
 }
 
-/// Dispatches a key press to the appropriate application action.
-fn handle_key(app: &mut App, code: KeyCode) {
-    match code {
-        KeyCode::Char('q') | KeyCode::Esc => app.running = false,
-        KeyCode::Down | KeyCode::Char('j') => app.select_process(1),
-        KeyCode::Up | KeyCode::Char('k') => app.select_process(-1),
-        _ => {}
+/// Sends a sample alert for `--test-webhook`, returning the exit code.
+fn test_webhook(config: &Config) -> i32 {
+    let Some(url) = &config.alerts.webhook_url else {
+        eprintln!("syswatch: no [alerts] webhook_url is configured");
+        return 2;
+    };
+    match webhook::test(url, &collector::hostname()) {
+        Ok(status) => {
+            println!("{url}: HTTP {status}");
+            0
+        }
+        Err(msg) => {
+            eprintln!("syswatch: {msg}");
+            1
+        }
     }
 }
+
+/// Writes this instance's lock, exiting if another instance holds the
+/// requested socket or recording path. Lock-file failures only warn.
+///
+/// Only an explicit `--socket` is claimed: a second instance on the
+/// default path starts anyway and goes without a socket.
+fn lock_instance(opts: &Options) -> Option<InstanceLock> {
+    let claims = Claims {
+        socket: opts.socket.clone(),
+        record: opts.record.clone(),
+    };
+    match InstanceLock::acquire(&claims, opts.force) {
+        Ok(lock) => Some(lock),
+        Err(LockError::Conflict(conflict)) => {
+            eprintln!("syswatch: {conflict}");
+            std::process::exit(2);
+        }
+        Err(err) => {
+            eprintln!("syswatch: {err}");
+            None
+        }
+    }
+}
+
+/// Opens the query socket unless disabled; failures are reported but not fatal.
+///
+/// The default path being in use means another instance already answers
+/// there, so this one just runs without a socket.
+fn start_socket(opts: &Options) -> Option<Server> {
+    if opts.no_socket {
+        return None;
+    }
+    let path = opts.socket.clone().or_else(socket::default_path)?;
+    match Server::start(path.clone()) {
+        Ok(server) => Some(server),
+        Err(err) if err.kind() == io::ErrorKind::AddrInUse && opts.socket.is_none() => {
+            eprintln!("syswatch: {err}; running without a query socket");
+            None
+        }
+        Err(err) => {
+            eprintln!("syswatch: query socket {}: {err}", path.display());
+            None
+        }
+    }
+}
+
+/// Opens the `--http` status page; failures are reported but not fatal.
+fn start_dashboard(addr: SocketAddr) -> Option<Dashboard> {
+    match Dashboard::start(addr, &collector::hostname()) {
+        Ok(dashboard) => Some(dashboard),
+        Err(err) => {
+            eprintln!("syswatch: --http {addr}: {err}");
+            None
+        }
+    }
+}
+
+/// Creates the app with its outputs attached and takes the first tick.
+fn start_app(
+    opts: &Options,
+    config: Config,
+    recorder: Option<Recorder>,
+    server: Option<Server>,
+) -> App {
+    let mut app = App::new(opts, config);
+    app.recorder = recorder;
+    app.server = server;
+    app.dashboard = opts.http.and_then(start_dashboard);
+    #[cfg(feature = "mqtt")]
+    if let Some(broker) = &opts.mqtt {
+        let topic = opts
+            .mqtt_topic
+            .clone()
+            .unwrap_or_else(|| format!("syswatch/{}", collector::hostname()));
+        app.mqtt = Some(mqtt::Publisher::spawn(
+            broker.clone(),
+            topic,
+            opts.mqtt_every,
+        ));
+    }
+    std::thread::sleep(sysinfo::MINIMUM_CPU_UPDATE_INTERVAL);
+    app.tick();
+    app
+}
@@ -0,0 +1,825 @@
+//! Sampling the system into plain [`Snapshot`]s.
+//!
+//! [`Monitor`] owns the `sysinfo` handle, the previous Mach CPU ticks,
+//! and the per-PID bookkeeping a sample depends on: which processes have
+//! been seen before, which refuse inspection, and their session CPU
+//! totals. Each [`Monitor::sample`] refreshes everything and returns the
+//! result as data. The TUI's `App` is one consumer, adding history,
+//! alerts and view state on top.
+//!
+//! [`Monitor::from_snapshots`] makes a monitor that hands out prepared
+//! snapshots instead, such as the `fixtures` feature's, so an `App` can
+//! be driven by known data in tests.
+//!
+//! Above [`MonitorOptions::high_count`] processes the monitor switches to
+//! a cheaper high-count mode: the process list is refreshed every other
+//! sample and the per-PID libproc queries (threads, fds, App Nap) are
+//! skipped, so syswatch stays off the top of its own table.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt;
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant, SystemTime};
+
+use sysinfo::{ProcessRefreshKind, ProcessStatus, ProcessesToUpdate, System, UpdateKind};
+
+use crate::collector::{
+    NapState, app_memory_bytes, bsd_flags, cached_bytes, compressed_bytes, compressor, cpu_ticks,
+    fd_counts, memory_pressure, page_size, task_counts, vm_statistics, wired_bytes,
+};
+use crate::config::MemoryMode;
+use crate::session::{CpuAccumulator, TOP_OFFENDERS};
+use crate::snapshot::Snapshot;
+use crate::volumes::Volumes;
+
+/// Type alias for a macOS process identifier.
+pub type Pid = u32;
+
+/// Share of [`MonitorOptions::high_count`] the process count must drop
+/// below before high-count mode disengages, so it doesn't flap.
+const HIGH_COUNT_EXIT: f64 = 0.9;
+
+/// Span of thread-count samples behind [`ProcessSample::thread_growth`].
+const GROWTH_WINDOW: Duration = Duration::from_secs(60);
+
+/// Shortest span a growth rate is reported over; shorter ones are noise.
+const GROWTH_MIN_SPAN: Duration = Duration::from_secs(5);
+
+// ── Monitor ─────────────────────────────────────────────────
+
+/// What a [`Monitor`] collects.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MonitorOptions {
+    /// How the used-memory figure is measured.
+    pub memory_mode: MemoryMode,
+    /// Whether per-core usage is sampled for [`Snapshot::max_core`].
+    pub per_core: bool,
+    /// Whether syswatch runs as root, so fd and socket counts are
+    /// collected; other users' processes need it.
+    pub privileged: bool,
+    /// Process count above which high-count mode engages; 0 never engages it.
+    pub high_count: usize,
+    /// Whether the per-PID libproc queries are skipped regardless of the
+    /// process count, as in high-count mode (e.g. in low-power mode).
+    pub lean: bool,
+}
+
+/// Source of [`Snapshot`]s.
+#[derive(Debug)]
+pub struct Monitor {
+    options: MonitorOptions,
+    sys: System,
+    /// Mach CPU ticks at the previous sample.
+    prev_ticks: Option<[u64; 4]>,
+    /// User, system and idle CPU of the previous sample, kept when no ticks elapsed.
+    cpu: [f64; 3],
+    /// When the process list was last refreshed, for CPU-second integration.
+    last_sample: Instant,
+    first_seen: FirstSeen,
+    inspect: InspectCache,
+    /// Session CPU-seconds per process.
+    session_cpu: CpuAccumulator,
+    /// Recent thread counts per process.
+    thread_growth: ThreadGrowth,
+    /// Sort keys for process names.
+    name_keys: NameKeys,
+    /// Mount table for classifying executables by volume.
+    volumes: Volumes,
+    /// Whether high-count mode is engaged.
+    high_count: bool,
+    /// Whether the next sample skips the process list (high-count mode).
+    skip_processes: bool,
+    /// Thread total of the last refreshed process list.
+    threads: usize,
+    /// Prepared snapshots handed out instead of sampling, if any.
+    feed: Option<Feed>,
+}
+
+impl Monitor {
+    /// Creates a monitor, performing an initial full system refresh.
+    pub fn new(options: MonitorOptions) -> Self {
+        let mut sys = System::new_all();
+        sys.refresh_all();
+        // Two refreshes are in: everything alive now already has a CPU delta.
+        let mut first_seen = FirstSeen::default();
+        for p in sys.processes().values() {
+            first_seen.observe(p.pid().as_u32(), p.start_time());
+        }
+
+        Self {
+            options,
+            sys,
+            prev_ticks: cpu_ticks().ok(),
+            cpu: [0.0; 3],
+            last_sample: Instant::now(),
+            first_seen,
+            inspect: InspectCache::default(),
+            session_cpu: CpuAccumulator::default(),
+            thread_growth: ThreadGrowth::default(),
+            name_keys: NameKeys::default(),
+            volumes: Volumes::default(),
+            high_count: false,
+            skip_processes: false,
+            threads: 0,
+            feed: None,
+        }
+    }
+
+    /// Creates a monitor whose samples are `snapshots`, in order, without
+    /// reading the system.
+    ///
+    /// Once `snapshots` runs out its last snapshot repeats; one that
+    /// yields nothing leaves the monitor sampling the live system.
+    /// Per-process details are still read from the live system.
+    pub fn from_snapshots(
+        options: MonitorOptions,
+        snapshots: impl Iterator<Item = Snapshot> + Send + 'static,
+    ) -> Self {
+        Self {
+            options,
+            sys: System::new(),
+            prev_ticks: None,
+            cpu: [0.0; 3],
+            last_sample: Instant::now(),
+            first_seen: FirstSeen::default(),
+            inspect: InspectCache::default(),
+            session_cpu: CpuAccumulator::default(),
+            thread_growth: ThreadGrowth::default(),
+            name_keys: NameKeys::default(),
+            volumes: Volumes::default(),
+            high_count: false,
+            skip_processes: false,
+            threads: 0,
+            feed: Some(Feed {
+                snapshots: Box::new(snapshots),
+                last: None,
+            }),
+        }
+    }
+
+    /// Refreshes CPU, memory and processes, returning the result.
+    ///
+    /// CPU figures are deltas since the previous call (or [`Monitor::new`]),
+    /// so calls should be at least [`sysinfo::MINIMUM_CPU_UPDATE_INTERVAL`]
+    /// apart.
+    ///
+    /// ```
+    /// use syswatch::monitor::{Monitor, MonitorOptions};
+    ///
+    /// let mut monitor = Monitor::new(MonitorOptions::default());
+    /// std::thread::sleep(sysinfo::MINIMUM_CPU_UPDATE_INTERVAL);
+    /// let snapshot = monitor.sample();
+    /// assert!(snapshot.mem_total > 0);
+    /// assert!(!snapshot.processes.is_empty());
+    /// ```
+    pub fn sample(&mut self) -> Snapshot {
+        if let Some(snapshot) = self.feed.as_mut().and_then(Feed::next) {
+            self.update_high_count(snapshot.processes.len());
+            return snapshot;
+        }
+        let max_core = self.sample_cpu();
+        let [cpu_user, cpu_system, cpu_idle] = self.cpu;
+
+        self.sys.refresh_memory();
+        let vm = vm_statistics().ok();
+        let (mem_used, memory_mode) = match (self.options.memory_mode, &vm) {
+            (MemoryMode::App, Some(vm)) => (app_memory_bytes(vm, page_size()), MemoryMode::App),
+            _ => (self.sys.used_memory(), MemoryMode::Sysinfo),
+        };
+
+        let processes_skipped = self.high_count && self.skip_processes;
+        self.skip_processes = !processes_skipped;
+        let processes = if processes_skipped {
+            Vec::new()
+        } else {
+            let processes = self.sample_processes();
+            self.threads = processes.iter().filter_map(|p| p.threads).sum::<u32>() as usize;
+            processes
+        };
+        Snapshot {
+            time: SystemTime::now(),
+            cpu_user,
+            cpu_system,
+            cpu_idle,
+            max_core,
+            mem_used,
+            mem_total: self.sys.total_memory(),
+            memory_mode,
+            swap_used: self.sys.used_swap(),
+            swap_total: self.sys.total_swap(),
+            wired: vm.as_ref().map(|vm| wired_bytes(vm, page_size())),
+            compressed: vm.as_ref().map(|vm| compressed_bytes(vm, page_size())),
+            cached: vm.as_ref().map(|vm| cached_bytes(vm, page_size())),
+            compressor: vm.as_ref().map(|vm| compressor(vm, page_size())),
+            memory_pressure: memory_pressure(),
+            threads: self.threads,
+            processes,
+            processes_skipped,
+            top_offenders: self.session_cpu.top(TOP_OFFENDERS),
+        }
+    }
+
+    /// Turns the monitor into an endless iterator of samples `interval` apart.
+    pub fn every(self, interval: Duration) -> Samples {
+        Samples {
+            monitor: self,
+            interval,
+            last: Instant::now(),
+        }
+    }
+
+    /// Whether high-count mode is engaged.
+    pub fn high_count(&self) -> bool {
+        self.high_count
+    }
+
+    /// Turns the per-PID libproc queries off (`true`) or back on.
+    pub fn set_lean(&mut self, lean: bool) {
+        self.options.lean = lean;
+    }
+
+    /// Whether the per-PID libproc queries are turned off.
+    pub fn lean(&self) -> bool {
+        self.options.lean
+    }
+
+    /// Whether the monitor runs as root.
+    pub fn privileged(&self) -> bool {
+        self.options.privileged
+    }
+
+    /// Session CPU-seconds per process, live and exited.
+    pub fn session(&self) -> &CpuAccumulator {
+        &self.session_cpu
+    }
+
+    /// Reads `pid`'s working directory and environment.
+    pub fn details(&mut self, pid: Pid) -> DetailExtras {
+        let sys_pid = sysinfo::Pid::from_u32(pid);
+        self.sys.refresh_processes_specifics(
+            ProcessesToUpdate::Some(&[sys_pid]),
+            false,
+            ProcessRefreshKind::nothing()
+                .with_cwd(UpdateKind::Always)
+                .with_environ(UpdateKind::Always),
+        );
+
+        let proc = self.sys.process(sys_pid);
+        DetailExtras {
+            pid,
+            session: {
+                let sid = unsafe { libc::getsid(pid as libc::pid_t) };
+                (sid >= 0).then_some(sid as Pid)
+            },
+            cwd: proc.and_then(|p| p.cwd()).map(|c| c.display().to_string()),
+            environ: proc
+                .map(|p| p.environ())
+                .filter(|env| !env.is_empty())
+                .map(|env| {
+                    env.iter()
+                        .map(|e| e.to_string_lossy().into_owned())
+                        .collect()
+                }),
+        }
+    }
+
+    /// Updates the user / system / idle split from Mach tick deltas,
+    /// returning the busiest core's utilization if per-core sampling is on.
+    ///
+    /// Without Mach ticks (e.g. off macOS) sysinfo's total load is
+    /// reported as user CPU, since it has no user/system split.
+    fn sample_cpu(&mut self) -> Option<f64> {
+        let ticks = cpu_ticks();
+        // One refresh serves both users: sysinfo rates are deltas between refreshes.
+        if self.options.per_core || ticks.is_err() {
+            self.sys.refresh_cpu_usage();
+        }
+        let max_core = self.options.per_core.then(|| {
+            self.sys
+                .cpus()
+                .iter()
+                .map(|c| f64::from(c.cpu_usage()))
+                .fold(0.0, f64::max)
+        });
+
+        let Ok(now) = ticks else {
+            let user = f64::from(self.sys.global_cpu_usage());
+            self.cpu = [user, 0.0, 100.0 - user];
+            return max_core;
+        };
+
+        if let Some(prev) = self.prev_ticks {
+            let d_user = now[0].saturating_sub(prev[0]);
+            let d_system = now[1].saturating_sub(prev[1]);
+            let d_idle = now[2].saturating_sub(prev[2]);
+            let d_nice = now[3].saturating_sub(prev[3]);
+            let total = d_user + d_system + d_idle + d_nice;
+
+            if total > 0 {
+                let pct = |ticks: u64| ticks as f64 / total as f64 * 100.0;
+                self.cpu = [pct(d_user + d_nice), pct(d_system), pct(d_idle)];
+            }
+        }
+        self.prev_ticks = Some(now);
+        max_core
+    }
+
+    /// Refreshes the process list, in `sysinfo`'s (unspecified) order.
+    fn sample_processes(&mut self) -> Vec<ProcessSample> {
+        self.sys.refresh_processes_specifics(
+            ProcessesToUpdate::All,
+            true,
+            ProcessRefreshKind::nothing()
+                .with_cpu()
+                .with_memory()
+                .with_exe(UpdateKind::OnlyIfNotSet)
+                .with_user(UpdateKind::OnlyIfNotSet),
+        );
+        self.volumes.poll();
+
+        let elapsed = self.last_sample.elapsed().as_secs_f64();
+        self.last_sample = Instant::now();
+
+        self.first_seen.advance();
+        let inspect = &mut self.inspect;
+        let session_cpu = &mut self.session_cpu;
+        let first_seen = &mut self.first_seen;
+        let volumes = &mut self.volumes;
+        let thread_growth = &mut self.thread_growth;
+        let name_keys = &mut self.name_keys;
+        // Each libproc query is a syscall per PID; at high counts they dominate the tick.
+        let extras = !self.high_count && !self.options.lean;
+        let fds = self.options.privileged && extras;
+        let procs: Vec<ProcessSample> = self
+            .sys
+            .processes()
+            .values()
+            .map(|p| {
+                let pid = p.pid().as_u32();
+                let counts = if extras {
+                    inspect.query(pid, task_counts)
+                } else {
+                    None
+                };
+                let fds = if fds {
+                    inspect.query(pid, fd_counts)
+                } else {
+                    None
+                };
+                let name = p.name().to_string_lossy().into_owned();
+                // A first reading is noise: keep it out of sorting, alerts, and totals.
+                let cpu_measured = first_seen.observe(pid, p.start_time());
+                let cpu_usage = if cpu_measured { p.cpu_usage() } else { 0.0 };
+                let cpu_time = session_cpu.add(pid, p.start_time(), &name, cpu_usage, elapsed);
+                ProcessSample {
+                    pid,
+                    sort_name: name_keys.key(pid, &name),
+                    name,
+                    parent: p.parent().map(|ppid| ppid.as_u32()),
+                    pgid: process_group(pid),
+                    started: p.start_time(),
+                    uid: p.user_id().map(|uid| **uid),
+                    status: p.status(),
+                    cpu_usage,
+                    cpu_measured,
+                    cpu_time,
+                    memory: p.memory(),
+                    threads: counts.map(|c| c.threads),
+                    thread_growth: thread_growth.observe(
+                        pid,
+                        p.start_time(),
+                        counts.map(|c| c.threads),
+                    ),
+                    running: counts.map(|c| c.running),
+                    fds: fds.map(|(n, _)| n),
+                    sockets: fds.map(|(_, n)| n),
+                    inspectable: !inspect.is_denied(pid),
+                    volume: volumes.classify(pid, p.start_time(), p.exe()),
+                    nap: if extras {
+                        bsd_flags(pid).ok().and_then(NapState::from_flags)
+                    } else {
+                        None
+                    },
+                }
+            })
+            .collect();
+
+        let alive: HashSet<Pid> = procs.iter().map(|p| p.pid).collect();
+        self.inspect.prune(&alive);
+        self.session_cpu.prune(&alive);
+        self.first_seen.prune(&alive);
+        self.volumes.prune(&alive);
+        self.thread_growth.prune(&alive);
+        self.name_keys.prune(&alive);
+
+        self.update_high_count(procs.len());
+        procs
+    }
+
+    /// Engages or leaves high-count mode for a list of `count` processes.
+    fn update_high_count(&mut self, count: usize) {
+        let limit = self.options.high_count;
+        self.high_count = limit > 0
+            && if self.high_count {
+                count as f64 >= limit as f64 * HIGH_COUNT_EXIT
+            } else {
+                count > limit
+            };
+    }
+}
+
+/// Snapshots a [`Monitor::from_snapshots`] monitor hands out.
+struct Feed {
+    snapshots: Box<dyn Iterator<Item = Snapshot> + Send>,
+    /// The snapshot handed out last, repeated once `snapshots` runs out.
+    last: Option<Snapshot>,
+}
+
+impl Feed {
+    /// The next snapshot, or the last one again.
+    fn next(&mut self) -> Option<Snapshot> {
+        if let Some(snapshot) = self.snapshots.next() {
+            self.last = Some(snapshot);
+        }
+        self.last.clone()
+    }
+}
+
+impl fmt::Debug for Feed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Feed")
+            .field("last", &self.last)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Endless iterator of samples, from [`Monitor::every`].
+#[derive(Debug)]
+pub struct Samples {
+    monitor: Monitor,
+    interval: Duration,
+    /// When the previous sample was due.
+    last: Instant,
+}
+
+impl Iterator for Samples {
+    type Item = Snapshot;
+
+    /// Waits until the next sample is due and takes it.
+    fn next(&mut self) -> Option<Snapshot> {
+        thread::sleep(self.interval.saturating_sub(self.last.elapsed()));
+        self.last = Instant::now();
+        Some(self.monitor.sample())
+    }
+}
+
+// ── Per-process bookkeeping ─────────────────────────────────
+
+/// Remembers which PIDs refuse libproc inspection so they aren't retried.
+///
+/// Other users' and system processes answer `EPERM` on every call; once a
+/// PID has been denied it stays denied for its lifetime, so the flag is
+/// cached until the PID disappears from the process list.
+#[derive(Debug, Default)]
+pub struct InspectCache {
+    denied: HashSet<Pid>,
+}
+
+impl InspectCache {
+    /// Runs `query` for `pid` unless it was denied before.
+    ///
+    /// `EPERM` / `EACCES` mark the PID as denied; other errors (e.g. the
+    /// process exiting mid-tick) are treated as transient.
+    pub fn query<T>(&mut self, pid: Pid, query: impl FnOnce(Pid) -> Result<T, i32>) -> Option<T> {
+        if self.denied.contains(&pid) {
+            return None;
+        }
+        match query(pid) {
+            Ok(value) => Some(value),
+            Err(errno) => {
+                if errno == libc::EPERM || errno == libc::EACCES {
+                    self.denied.insert(pid);
+                }
+                None
+            }
+        }
+    }
+
+    /// Returns whether `pid` has been denied inspection.
+    pub fn is_denied(&self, pid: Pid) -> bool {
+        self.denied.contains(&pid)
+    }
+
+    /// Forgets PIDs that are no longer alive so reused PIDs are retried.
+    pub fn prune(&mut self, alive: &HashSet<Pid>) {
+        self.denied.retain(|pid| alive.contains(pid));
+    }
+}
+
+/// Tick on which each live process was first seen.
+///
+/// sysinfo computes CPU% as a delta against the previous refresh, so a
+/// process's first reading after it appears is meaningless. Entries are
+/// keyed by PID and start time, so a reused PID counts as new.
+#[derive(Debug, Default)]
+pub struct FirstSeen {
+    /// Ticks completed so far.
+    tick: u64,
+    /// Start time and first tick per PID.
+    seen: HashMap<Pid, (u64, u64)>,
+}
+
+impl FirstSeen {
+    /// Starts the next tick.
+    pub fn advance(&mut self) {
+        self.tick += 1;
+    }
+
+    /// Records `pid` if it is new and returns whether it has two samples yet.
+    pub fn observe(&mut self, pid: Pid, start_time: u64) -> bool {
+        let tick = self.tick;
+        let entry = self.seen.entry(pid).or_insert((start_time, tick));
+        if entry.0 != start_time {
+            *entry = (start_time, tick);
+        }
+        entry.1 < tick
+    }
+
+    /// Forgets PIDs that are no longer alive.
+    pub fn prune(&mut self, alive: &HashSet<Pid>) {
+        self.seen.retain(|pid, _| alive.contains(pid));
+    }
+}
+
+/// Thread counts per process over the last [`GROWTH_WINDOW`].
+///
+/// Entries are keyed by PID and start time, so a reused PID starts over.
+#[derive(Debug, Default)]
+pub struct ThreadGrowth {
+    samples: HashMap<Pid, (u64, VecDeque<(Instant, u32)>)>,
+}
+
+impl ThreadGrowth {
+    /// Records `pid`'s thread count and returns its growth in threads per
+    /// minute, or `None` when the count is unreadable or the samples span
+    /// less than [`GROWTH_MIN_SPAN`].
+    pub fn observe(&mut self, pid: Pid, start_time: u64, threads: Option<u32>) -> Option<f64> {
+        let threads = threads?;
+        let now = Instant::now();
+        let (started, samples) = self.samples.entry(pid).or_default();
+        if *started != start_time {
+            *started = start_time;
+            samples.clear();
+        }
+        while samples
+            .front()
+            .is_some_and(|&(at, _)| now.duration_since(at) > GROWTH_WINDOW)
+        {
+            samples.pop_front();
+        }
+        samples.push_back((now, threads));
+
+        let &(oldest, before) = samples.front()?;
+        let span = now.duration_since(oldest);
+        (span >= GROWTH_MIN_SPAN)
+            .then(|| (f64::from(threads) - f64::from(before)) / span.as_secs_f64() * 60.0)
+    }
+
+    /// Forgets PIDs that are no longer alive.
+    pub fn prune(&mut self, alive: &HashSet<Pid>) {
+        self.samples.retain(|pid, _| alive.contains(pid));
+    }
+}
+
+/// Case-folded sort key for `name`.
+///
+/// Names compare lower-cased, so `zsh` sorts after `Xcode`, and leading
+/// emoji, symbols and spaces are skipped so a decorated name sorts by its
+/// first word. ASCII punctuation is kept: `_hidd` and `(null)` stay where
+/// they sort today. Order is by code point after folding; there is no
+/// locale collation.
+///
+/// ```
+/// use syswatch::monitor::name_key;
+///
+/// assert_eq!(name_key("Xcode"), "xcode");
+/// assert_eq!(name_key("🚀 Rocket"), "rocket");
+/// assert_eq!(name_key("Ünïcødé Helper"), "ünïcødé helper");
+/// assert_eq!(name_key("ΟΔΟΣ-Sync"), "οδος-sync");
+/// assert_eq!(name_key("_hidd"), "_hidd");
+/// // Nothing left to skip to: the whole name is the key.
+/// assert_eq!(name_key("🔥"), "🔥");
+/// ```
+pub fn name_key(name: &str) -> String {
+    let word = name
+        .trim_start_matches(|c: char| c.is_whitespace() || !(c.is_ascii() || c.is_alphanumeric()));
+    if word.is_empty() { name } else { word }.to_lowercase()
+}
+
+/// Sort keys per process, recomputed only when a name changes.
+#[derive(Debug, Default)]
+pub struct NameKeys {
+    keys: HashMap<Pid, (String, Arc<str>)>,
+}
+
+impl NameKeys {
+    /// Returns the [`name_key`] for `pid` named `name`.
+    pub fn key(&mut self, pid: Pid, name: &str) -> Arc<str> {
+        let (cached, key) = self
+            .keys
+            .entry(pid)
+            .or_insert_with(|| (name.to_string(), name_key(name).into()));
+        if cached != name {
+            *cached = name.to_string();
+            *key = name_key(name).into();
+        }
+        Arc::clone(key)
+    }
+
+    /// Forgets PIDs that are no longer alive.
+    pub fn prune(&mut self, alive: &HashSet<Pid>) {
+        self.keys.retain(|pid, _| alive.contains(pid));
+    }
+}
+
+// ── Data ────────────────────────────────────────────────────
+
+/// One process as of a sample.
+#[derive(Debug, Clone)]
+pub struct ProcessSample {
+    /// macOS process identifier.
+    pub pid: Pid,
+    /// Display name of the process.
+    pub name: String,
+    /// [`name_key`] of `name`, which the table sorts by.
+    pub sort_name: Arc<str>,
+    /// Parent process identifier, if known.
+    pub parent: Option<Pid>,
+    /// Process group, from `getpgid`; `None` once the process has exited.
+    pub pgid: Option<Pid>,
+    /// Start time in seconds since the Unix epoch.
+    pub started: u64,
+    /// Owning user ID, if known.
+    pub uid: Option<u32>,
+    /// Scheduler state.
+    pub status: ProcessStatus,
+    /// Instantaneous CPU usage percentage; 0 until `cpu_measured`.
+    pub cpu_usage: f32,
+    /// Whether the process has two samples, so `cpu_usage` is a real delta.
+    pub cpu_measured: bool,
+    /// CPU-seconds accumulated over this session.
+    pub cpu_time: f64,
+    /// Resident memory in bytes.
+    pub memory: u64,
+    /// Thread count, or `None` when libproc inspection is denied.
+    pub threads: Option<u32>,
+    /// Change in `threads` per minute over the last minute; `None` while
+    /// unreadable or sampled for less than a few seconds.
+    pub thread_growth: Option<f64>,
+    /// Runnable threads, from the same task info as `threads`.
+    pub running: Option<u32>,
+    /// Open file descriptors; collected only when privileged.
+    pub fds: Option<u32>,
+    /// Open sockets; collected only when privileged.
+    pub sockets: Option<u32>,
+    /// Whether libproc calls for this PID are permitted.
+    pub inspectable: bool,
+    /// Whether the OS has put the process in App Nap or suspended it.
+    pub nap: Option<NapState>,
+    /// Mount point of the external disk or disk image its executable is on.
+    pub volume: Option<Arc<str>>,
+}
+
+impl ProcessSample {
+    /// A sleeping, inspectable root process with no usage yet, as
+    /// fixtures and tests build on.
+    pub fn new(pid: Pid, name: &str) -> Self {
+        Self {
+            pid,
+            name: name.to_string(),
+            sort_name: name_key(name).into(),
+            parent: None,
+            pgid: Some(pid),
+            started: 0,
+            uid: Some(0),
+            status: ProcessStatus::Sleep,
+            cpu_usage: 0.0,
+            cpu_measured: true,
+            cpu_time: 0.0,
+            memory: 0,
+            threads: Some(1),
+            thread_growth: None,
+            running: Some(0),
+            fds: None,
+            sockets: None,
+            inspectable: true,
+            nap: None,
+            volume: None,
+        }
+    }
+}
+
+/// Process group of `pid`, or `None` if it has exited.
+fn process_group(pid: Pid) -> Option<Pid> {
+    let pgid = unsafe { libc::getpgid(pid as libc::pid_t) };
+    (pgid >= 0).then_some(pgid as Pid)
+}
+
+/// Expensive per-process extras, gathered for one PID on request.
+#[derive(Debug, Default)]
+pub struct DetailExtras {
+    /// Process the extras belong to.
+    pub pid: Pid,
+    /// Session ID, from `getsid`.
+    pub session: Option<Pid>,
+    /// Current working directory, if readable.
+    pub cwd: Option<String>,
+    /// Environment as `KEY=value` strings, if readable.
+    pub environ: Option<Vec<String>>,
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+
+    use super::*;
+    use crate::fixtures::{Fixture, FixtureOptions};
+
+    #[test]
+    fn denied_pids_are_not_queried_again() {
+        let mut cache = InspectCache::default();
+        let calls = Cell::new(0);
+        let denied = |_| {
+            calls.set(calls.get() + 1);
+            Err::<u32, _>(libc::EPERM)
+        };
+        assert_eq!(cache.query(88, denied), None);
+        assert_eq!(cache.query(88, denied), None);
+        assert_eq!(calls.get(), 1);
+        assert!(cache.is_denied(88));
+
+        assert_eq!(cache.query(89, |_| Err::<u32, _>(libc::EACCES)), None);
+        assert!(cache.is_denied(89));
+        assert_eq!(cache.query(90, |pid| Ok(pid * 2)), Some(180));
+    }
+
+    #[test]
+    fn transient_errors_are_retried_and_pruned_pids_forgotten() {
+        let mut cache = InspectCache::default();
+        assert_eq!(cache.query(88, |_| Err::<u32, _>(libc::ESRCH)), None);
+        assert!(!cache.is_denied(88));
+        assert_eq!(cache.query(88, |_| Ok(7)), Some(7));
+
+        cache.query(89, |_| Err::<u32, _>(libc::EPERM));
+        cache.prune(&HashSet::from([88]));
+        // A reused PID gets asked again.
+        assert!(!cache.is_denied(89));
+        assert_eq!(cache.query(89, |_| Ok(3)), Some(3));
+    }
+
+    #[test]
+    fn cpu_counts_from_the_second_tick_a_process_is_seen() {
+        let mut seen = FirstSeen::default();
+        assert!(!seen.observe(10, 1000));
+        // Seeing it again within the same tick changes nothing.
+        assert!(!seen.observe(10, 1000));
+        seen.advance();
+        assert!(seen.observe(10, 1000));
+        assert!(!seen.observe(11, 1005));
+        seen.advance();
+        assert!(seen.observe(10, 1000));
+        assert!(seen.observe(11, 1005));
+    }
+
+    #[test]
+    fn reused_and_pruned_pids_start_over() {
+        let mut seen = FirstSeen::default();
+        seen.observe(10, 1000);
+        seen.observe(12, 1000);
+        seen.advance();
+        // Same PID, different start time: a new process.
+        assert!(!seen.observe(10, 2000));
+        seen.prune(&HashSet::from([10]));
+        assert!(!seen.observe(12, 1000));
+        seen.advance();
+        assert!(seen.observe(10, 2000));
+        assert!(seen.observe(12, 1000));
+    }
+
+    #[test]
+    fn fed_monitor_hands_out_its_snapshots_then_repeats_the_last() {
+        let options = FixtureOptions {
+            processes: 20,
+            ..FixtureOptions::default()
+        };
+        let expected: Vec<Snapshot> = Fixture::new(options).take(2).collect();
+        let mut monitor =
+            Monitor::from_snapshots(MonitorOptions::default(), Fixture::new(options).take(2));
+        let times: Vec<SystemTime> = (0..3).map(|_| monitor.sample().time).collect();
+        assert_eq!(
+            times,
+            [expected[0].time, expected[1].time, expected[1].time]
+        );
+    }
+}
@@ -0,0 +1,272 @@
+//! MQTT publishing of a compact summary, behind the `mqtt` feature.
+//!
+//! Only what a QoS 0 publisher needs of MQTT 3.1.1 is implemented:
+//! CONNECT, PUBLISH, PINGREQ, and DISCONNECT. A worker thread owns the
+//! connection and reconnects with exponential backoff; the event loop
+//! hands it payloads through a bounded channel and never waits on it.
+
+use std::fmt;
+use std::io::{self, Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, SyncSender, TryRecvError};
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+use sysinfo::System;
+
+use crate::app::App;
+use crate::json::{self, Object};
+
+/// Port used when `--mqtt` names only a host.
+pub const DEFAULT_PORT: u16 = 1883;
+
+/// Keepalive announced in CONNECT, in seconds.
+const KEEPALIVE_SECS: u16 = 60;
+
+/// Idle time after which a PINGREQ is sent; half the keepalive.
+const PING_INTERVAL: Duration = Duration::from_secs(KEEPALIVE_SECS as u64 / 2);
+
+/// Connect and acknowledgement timeout.
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+/// First reconnect delay; doubles per failure up to [`MAX_BACKOFF`].
+const MIN_BACKOFF: Duration = Duration::from_secs(1);
+
+/// Longest reconnect delay.
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+
+/// Payloads buffered for the worker; further ones are dropped while it is busy.
+const QUEUE_LEN: usize = 4;
+
+/// `PINGREQ` packet.
+const PINGREQ: [u8; 2] = [0xC0, 0x00];
+
+/// `DISCONNECT` packet.
+const DISCONNECT: [u8; 2] = [0xE0, 0x00];
+
+/// Connection state reported by the worker.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum State {
+    /// Connecting to the broker.
+    Connecting,
+    /// Connected; publishes go out.
+    Connected,
+    /// The last attempt or connection failed; retrying after a backoff.
+    Down(String),
+}
+
+impl fmt::Display for State {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Connecting => f.write_str("connecting"),
+            Self::Connected => f.write_str("connected"),
+            Self::Down(err) => write!(f, "down ({err})"),
+        }
+    }
+}
+
+/// Background publisher for one broker and topic.
+#[derive(Debug)]
+pub struct Publisher {
+    payloads: SyncSender<String>,
+    states: Receiver<State>,
+    /// Latest state reported by the worker.
+    state: State,
+    /// Publish every this many ticks.
+    every: u32,
+    /// Ticks since the last publish.
+    ticks: u32,
+}
+
+impl Publisher {
+    /// Starts a worker that connects to `broker` (`host:port`) and publishes to `topic`.
+    pub fn spawn(broker: String, topic: String, every: u32) -> Self {
+        let (payloads, rx) = mpsc::sync_channel(QUEUE_LEN);
+        let (state_tx, states) = mpsc::channel();
+        thread::spawn(move || run(&broker, &topic, &rx, &state_tx));
+        Self {
+            payloads,
+            states,
+            state: State::Connecting,
+            every: every.max(1),
+            ticks: 0,
+        }
+    }
+
+    /// Counts a tick and, every N ticks, queues the payload `build` returns.
+    pub fn on_tick(&mut self, build: impl FnOnce() -> String) {
+        if let Some(state) = self.states.try_iter().last() {
+            self.state = state;
+        }
+        self.ticks += 1;
+        if self.ticks >= self.every {
+            self.ticks = 0;
+            // QoS 0: a payload the worker can't take right now is dropped.
+            let _ = self.payloads.try_send(build());
+        }
+    }
+
+    /// Latest connection state.
+    pub fn state(&self) -> &State {
+        &self.state
+    }
+}
+
+/// Compact JSON summary: CPU split, memory, load averages, and the top process.
+pub fn summary(app: &App, host: &str) -> String {
+    let load = System::load_average();
+    let top = app
+        .processes
+        .iter()
+        .max_by(|a, b| a.cpu_usage.total_cmp(&b.cpu_usage))
+        .map(|p| {
+            Object::new()
+                .int("pid", p.pid)
+                .str("name", &p.name)
+                .num("cpu", f64::from(p.cpu_usage))
+                .finish()
+        });
+    Object::new()
+        .str("host", host)
+        .str("timestamp", &json::timestamp(SystemTime::now()))
+        .num("cpu_user", app.user_pct)
+        .num("cpu_system", app.system_pct)
+        .num("cpu_idle", app.idle_pct)
+        .int("mem_used", app.used_memory)
+        .int("mem_total", app.total_memory)
+        .raw(
+            "load",
+            &json::array([load.one, load.five, load.fifteen].map(json::number)),
+        )
+        .opt("top", top)
+        .finish()
+}
+
+/// Worker loop: connect, publish until the connection breaks, back off, repeat.
+fn run(broker: &str, topic: &str, payloads: &Receiver<String>, states: &mpsc::Sender<State>) {
+    let client_id = format!("syswatch-{}", std::process::id());
+    let mut backoff = MIN_BACKOFF;
+    loop {
+        let _ = states.send(State::Connecting);
+        let failure = match connect(broker, &client_id) {
+            Ok(mut stream) => {
+                backoff = MIN_BACKOFF;
+                let _ = states.send(State::Connected);
+                match pump(&mut stream, topic, payloads) {
+                    Ok(()) => {
+                        // The publisher was dropped: leave cleanly.
+                        let _ = stream.write_all(&DISCONNECT);
+                        return;
+                    }
+                    Err(err) => err,
+                }
+            }
+            Err(err) => err,
+        };
+        let _ = states.send(State::Down(failure.to_string()));
+
+        thread::sleep(backoff);
+        backoff = (backoff * 2).min(MAX_BACKOFF);
+        // Samples queued while down are stale; drop them.
+        loop {
+            match payloads.try_recv() {
+                Ok(_) => {}
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => return,
+            }
+        }
+    }
+}
+
+/// Publishes queued payloads, pinging the broker while idle.
+///
+/// Returns `Ok` when the publisher is dropped, or the I/O error that
+/// ended the connection.
+fn pump(stream: &mut TcpStream, topic: &str, payloads: &Receiver<String>) -> io::Result<()> {
+    loop {
+        match payloads.recv_timeout(PING_INTERVAL) {
+            Ok(payload) => stream.write_all(&publish_packet(topic, payload.as_bytes()))?,
+            Err(RecvTimeoutError::Timeout) => {
+                stream.write_all(&PINGREQ)?;
+                let mut reply = [0; 2];
+                stream.read_exact(&mut reply)?;
+                if reply != [0xD0, 0x00] {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        "unexpected reply to PINGREQ",
+                    ));
+                }
+            }
+            Err(RecvTimeoutError::Disconnected) => return Ok(()),
+        }
+    }
+}
+
+/// Opens a TCP connection and completes the CONNECT / CONNACK handshake.
+fn connect(broker: &str, client_id: &str) -> io::Result<TcpStream> {
+    let addr = broker
+        .to_socket_addrs()?
+        .next()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, format!("{broker}: no address")))?;
+    let mut stream = TcpStream::connect_timeout(&addr, TIMEOUT)?;
+    stream.set_read_timeout(Some(TIMEOUT))?;
+    stream.set_write_timeout(Some(TIMEOUT))?;
+
+    let mut body = Vec::new();
+    put_str(&mut body, "MQTT");
+    body.push(4); // protocol level 3.1.1
+    body.push(0x02); // clean session
+    body.extend_from_slice(&KEEPALIVE_SECS.to_be_bytes());
+    put_str(&mut body, client_id);
+    stream.write_all(&packet(0x10, &body))?;
+
+    let mut connack = [0; 4];
+    stream.read_exact(&mut connack)?;
+    if connack[0] != 0x20 {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "broker did not answer CONNACK",
+        ));
+    }
+    if connack[3] != 0 {
+        return Err(io::Error::new(
+            io::ErrorKind::ConnectionRefused,
+            format!("broker refused the connection (code {})", connack[3]),
+        ));
+    }
+    Ok(stream)
+}
+
+/// A QoS 0 PUBLISH packet.
+fn publish_packet(topic: &str, payload: &[u8]) -> Vec<u8> {
+    let mut body = Vec::with_capacity(topic.len() + payload.len() + 2);
+    put_str(&mut body, topic);
+    body.extend_from_slice(payload);
+    packet(0x30, &body)
+}
+
+/// Frames `body` behind a fixed header with the variable-length remaining length.
+fn packet(header: u8, body: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(body.len() + 5);
+    out.push(header);
+    let mut len = body.len();
+    loop {
+        let mut byte = (len % 128) as u8;
+        len /= 128;
+        if len > 0 {
+            byte |= 0x80;
+        }
+        out.push(byte);
+        if len == 0 {
+            break;
+        }
+    }
+    out.extend_from_slice(body);
+    out
+}
+
+/// Appends a length-prefixed UTF-8 string.
+fn put_str(buf: &mut Vec<u8>, s: &str) {
+    buf.extend_from_slice(&(s.len() as u16).to_be_bytes());
+    buf.extend_from_slice(s.as_bytes());
+}
@@ -0,0 +1,556 @@
+//! Routing fired alerts to notification channels.
+//!
+//! Each alert kind has its own set of [`Channel`]s from the `[notify]`
+//! config section. [`Dispatcher::dispatch`] is the single entry point the
+//! app calls per alert; it applies the global rate limit and hands the
+//! alert to a [`Sink`], so the routing can be exercised without a
+//! terminal or network.
+//!
+//! Notifications can be held back two ways: `[quiet_hours]` ranges of
+//! local time, for every kind or per kind, and a [`Snooze`] picked at
+//! runtime. Either way every channel stays silent, the webhook included;
+//! the alert is still logged, recorded, and shown in the status bar.
+
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use crate::alerts::{Alert, AlertKind};
+
+/// Default minimum time between two cues on the same channel.
+pub const DEFAULT_RATE_LIMIT: Duration = Duration::from_secs(10);
+
+/// Seconds in a day, the period of [`TimeRange`] clocks.
+const DAY: u32 = 86_400;
+
+/// Local time a [`Snooze::Morning`] runs until, in seconds after midnight.
+pub const MORNING: u32 = 8 * 3600;
+
+/// Where an alert can be delivered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Channel {
+    /// Terminal bell (`\a`).
+    Bell,
+    /// Status bar inverted for one frame.
+    Visual,
+    /// macOS Notification Center banner.
+    Notify,
+    /// JSON POST to `[alerts] webhook_url`.
+    Webhook,
+}
+
+impl Channel {
+    /// Every channel, in config-name order.
+    const ALL: [Self; 4] = [Self::Bell, Self::Visual, Self::Notify, Self::Webhook];
+
+    /// Parses a channel name as written in config.
+    pub fn parse(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|c| c.id() == name)
+    }
+
+    /// Name used in config.
+    pub fn id(self) -> &'static str {
+        match self {
+            Self::Bell => "bell",
+            Self::Visual => "visual",
+            Self::Notify => "notify",
+            Self::Webhook => "webhook",
+        }
+    }
+
+    /// Whether the rate limit applies; webhook deliveries are never limited.
+    fn is_cue(self) -> bool {
+        self != Self::Webhook
+    }
+}
+
+/// Channel selection per alert kind, from the `[notify]` config section.
+#[derive(Debug, Clone)]
+pub struct NotifyConfig {
+    /// Channels for [`AlertKind::ProcessCpu`].
+    pub process_cpu: Vec<Channel>,
+    /// Channels for [`AlertKind::Memory`].
+    pub memory: Vec<Channel>,
+    /// Channels for [`AlertKind::ProcessStart`].
+    pub process_start: Vec<Channel>,
+    /// Channels for [`AlertKind::KillRule`].
+    pub kill_rule: Vec<Channel>,
+    /// Channels for [`AlertKind::KernelTask`].
+    pub kernel_task: Vec<Channel>,
+    /// Channels for [`AlertKind::ThreadGrowth`].
+    pub thread_growth: Vec<Channel>,
+    /// Channels for [`AlertKind::SingleThread`].
+    pub single_thread: Vec<Channel>,
+    /// Minimum time between two cues on the same channel.
+    pub rate_limit: Duration,
+    /// `[quiet_hours]` ranges; `None` applies to every kind.
+    pub quiet_hours: Vec<(Option<AlertKind>, TimeRange)>,
+}
+
+impl Default for NotifyConfig {
+    fn default() -> Self {
+        Self {
+            process_cpu: vec![Channel::Webhook],
+            memory: vec![Channel::Webhook],
+            process_start: vec![Channel::Webhook],
+            kill_rule: vec![Channel::Webhook],
+            kernel_task: vec![Channel::Webhook],
+            thread_growth: vec![Channel::Webhook],
+            single_thread: vec![Channel::Webhook],
+            rate_limit: DEFAULT_RATE_LIMIT,
+            quiet_hours: Vec::new(),
+        }
+    }
+}
+
+impl NotifyConfig {
+    /// Channels configured for `kind`.
+    fn channels(&self, kind: AlertKind) -> &[Channel] {
+        match kind {
+            AlertKind::ProcessCpu => &self.process_cpu,
+            AlertKind::Memory => &self.memory,
+            AlertKind::ProcessStart => &self.process_start,
+            AlertKind::KillRule => &self.kill_rule,
+            AlertKind::KernelTask => &self.kernel_task,
+            AlertKind::ThreadGrowth => &self.thread_growth,
+            AlertKind::SingleThread => &self.single_thread,
+        }
+    }
+
+    /// Mutable channel list for the config key `key`, if it names an alert kind.
+    pub fn channels_mut(&mut self, key: &str) -> Option<&mut Vec<Channel>> {
+        Some(match key {
+            "process_cpu" => &mut self.process_cpu,
+            "memory" => &mut self.memory,
+            "process_start" => &mut self.process_start,
+            "kill_rule" => &mut self.kill_rule,
+            "kernel_task" => &mut self.kernel_task,
+            "thread_growth" => &mut self.thread_growth,
+            "single_thread" => &mut self.single_thread,
+            _ => return None,
+        })
+    }
+
+    /// Whether `kind` is inside one of its quiet ranges at local time `clock`.
+    fn is_quiet(&self, kind: AlertKind, clock: u32) -> bool {
+        self.quiet_hours
+            .iter()
+            .any(|(only, range)| only.is_none_or(|k| k == kind) && range.contains(clock))
+    }
+}
+
+/// A daily range of local time, `start` inclusive and `end` exclusive.
+///
+/// A range whose end is earlier than its start crosses midnight:
+/// `22:00-07:00` holds from 22:00 up to 07:00 the next morning.
+///
+/// ```
+/// use syswatch::notify::TimeRange;
+///
+/// let night = TimeRange::parse("22:00-07:00").unwrap();
+/// assert!(night.contains(23 * 3600));
+/// assert!(night.contains(0));
+/// assert!(night.contains(6 * 3600 + 59 * 60));
+/// assert!(!night.contains(7 * 3600));
+/// assert!(!night.contains(12 * 3600));
+///
+/// let lunch = TimeRange::parse("12:00-13:30").unwrap();
+/// assert!(lunch.contains(12 * 3600));
+/// assert!(!lunch.contains(13 * 3600 + 30 * 60));
+/// assert!(!lunch.contains(23 * 3600));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimeRange {
+    /// Seconds after midnight the range opens.
+    pub start: u32,
+    /// Seconds after midnight the range closes.
+    pub end: u32,
+}
+
+impl TimeRange {
+    /// Parses `HH:MM-HH:MM`.
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let (start, end) = text.split_once('-').ok_or("expected `HH:MM-HH:MM`")?;
+        let range = Self {
+            start: parse_clock(start.trim())?,
+            end: parse_clock(end.trim())?,
+        };
+        if range.start == range.end {
+            return Err("start and end are the same time".to_string());
+        }
+        Ok(range)
+    }
+
+    /// Whether `clock` (seconds after local midnight) falls in the range.
+    pub fn contains(self, clock: u32) -> bool {
+        let clock = clock % DAY;
+        if self.start < self.end {
+            (self.start..self.end).contains(&clock)
+        } else {
+            clock >= self.start || clock < self.end
+        }
+    }
+}
+
+/// Parses `HH:MM` into seconds after midnight.
+fn parse_clock(text: &str) -> Result<u32, String> {
+    let invalid = || format!("`{text}` is not a time of day (HH:MM)");
+    let (hours, minutes) = text.split_once(':').ok_or_else(invalid)?;
+    let hours: u32 = hours.parse().map_err(|_| invalid())?;
+    let minutes: u32 = minutes.parse().map_err(|_| invalid())?;
+    if hours > 23 || minutes > 59 || text.len() < 4 || text.len() > 5 {
+        return Err(invalid());
+    }
+    Ok(hours * 3600 + minutes * 60)
+}
+
+/// Seconds after local midnight at `time`, or after UTC midnight if the
+/// local offset is unknown.
+pub fn local_clock(time: SystemTime) -> u32 {
+    let secs = time.duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs()) as libc::time_t;
+    let mut tm: libc::tm = unsafe { std::mem::zeroed() };
+    if unsafe { libc::localtime_r(&secs, &mut tm) }.is_null() {
+        return secs.rem_euclid(86_400) as u32;
+    }
+    (tm.tm_hour * 3600 + tm.tm_min * 60 + tm.tm_sec) as u32
+}
+
+/// How long cues are held back by the snooze picker.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Snooze {
+    /// Fifteen minutes.
+    Quarter,
+    /// One hour.
+    Hour,
+    /// Until the next [`MORNING`] in local time.
+    Morning,
+}
+
+impl Snooze {
+    /// Every length, in picker order.
+    pub const ALL: [Self; 3] = [Self::Quarter, Self::Hour, Self::Morning];
+
+    /// Label in the picker.
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Quarter => "15 minutes",
+            Self::Hour => "1 hour",
+            Self::Morning => "until morning (08:00)",
+        }
+    }
+
+    /// How long the snooze lasts when picked at local time `clock`.
+    pub fn duration(self, clock: u32) -> Duration {
+        match self {
+            Self::Quarter => Duration::from_secs(15 * 60),
+            Self::Hour => Duration::from_secs(3600),
+            Self::Morning => {
+                let left = (MORNING + DAY - clock % DAY) % DAY;
+                // Picked at exactly 08:00, it means the next morning.
+                Duration::from_secs(u64::from(if left == 0 { DAY } else { left }))
+            }
+        }
+    }
+}
+
+/// Receiver of dispatched alerts.
+pub trait Sink {
+    /// Delivers `alert` on `channel`.
+    fn deliver(&mut self, channel: Channel, alert: &Alert);
+}
+
+/// Applies the per-kind channel selection and the rate limit.
+#[derive(Debug)]
+pub struct Dispatcher {
+    config: NotifyConfig,
+    /// When each cue channel last fired, indexed like [`Channel::ALL`].
+    last: [Option<Instant>; 4],
+    /// End of the current snooze, if one was picked.
+    snoozed_until: Option<Instant>,
+}
+
+impl Dispatcher {
+    /// Creates a dispatcher that has fired nothing yet.
+    pub fn new(config: NotifyConfig) -> Self {
+        Self {
+            config,
+            last: [None; 4],
+            snoozed_until: None,
+        }
+    }
+
+    /// Replaces the channels and rate limit, keeping when each cue last
+    /// fired and any running snooze.
+    pub fn configure(&mut self, config: NotifyConfig) {
+        self.config = config;
+    }
+
+    /// Holds back notifications until `until`; `None` ends a running snooze.
+    pub fn snooze(&mut self, until: Option<Instant>) {
+        self.snoozed_until = until;
+    }
+
+    /// Time left on the snooze at `now`, if one is running.
+    pub fn snoozed(&self, now: Instant) -> Option<Duration> {
+        self.snoozed_until
+            .map(|until| until.saturating_duration_since(now))
+            .filter(|left| !left.is_zero())
+    }
+
+    /// Routes `alert` to its channels, returning the ones that fired.
+    ///
+    /// Alerts with `notify` unset (e.g. watch rules in `alert` mode)
+    /// stay in syswatch and reach no channel. Neither does any alert
+    /// during a snooze, or when `clock` (seconds after local midnight) is
+    /// in one of the kind's quiet ranges.
+    pub fn dispatch(
+        &mut self,
+        alert: &Alert,
+        now: Instant,
+        clock: u32,
+        sink: &mut impl Sink,
+    ) -> Vec<Channel> {
+        if !alert.notify || self.snoozed(now).is_some() || self.config.is_quiet(alert.kind, clock) {
+            return Vec::new();
+        }
+        let mut fired = Vec::new();
+        for &channel in self.config.channels(alert.kind) {
+            if channel.is_cue() {
+                let slot = &mut self.last[channel as usize];
+                if slot.is_some_and(|t| now.duration_since(t) < self.config.rate_limit) {
+                    continue;
+                }
+                *slot = Some(now);
+            }
+            sink.deliver(channel, alert);
+            fired.push(channel);
+        }
+        fired
+    }
+}
+
+/// Shows a Notification Center banner via `osascript`, without waiting for it.
+pub fn banner(title: &str, body: &str) {
+    let script = format!(
+        "display notification {} with title {}",
+        applescript_string(body),
+        applescript_string(title)
+    );
+    let child = Command::new("osascript")
+        .arg("-e")
+        .arg(script)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn();
+    if let Ok(mut child) = child {
+        // Reaped off-thread so the banner never delays a frame.
+        thread::spawn(move || child.wait());
+    }
+}
+
+/// Quotes `s` as an AppleScript string literal.
+fn applescript_string(s: &str) -> String {
+    format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Records every delivery.
+    #[derive(Default)]
+    struct Recorder(Vec<(Channel, AlertKind)>);
+
+    impl Sink for Recorder {
+        fn deliver(&mut self, channel: Channel, alert: &Alert) {
+            self.0.push((channel, alert.kind));
+        }
+    }
+
+    /// An alert of `kind` that goes to its channels.
+    fn alert(kind: AlertKind) -> Alert {
+        Alert {
+            kind,
+            subject: None,
+            detail: None,
+            value: 95.0,
+            threshold: 90.0,
+            time: SystemTime::UNIX_EPOCH,
+            notify: true,
+        }
+    }
+
+    /// Seconds after midnight of `hours:minutes`.
+    fn at(hours: u32, minutes: u32) -> u32 {
+        hours * 3600 + minutes * 60
+    }
+
+    #[test]
+    fn quiet_hours_across_midnight_mute_every_channel() {
+        let mut config = NotifyConfig {
+            memory: vec![Channel::Webhook, Channel::Bell, Channel::Notify],
+            ..NotifyConfig::default()
+        };
+        config
+            .quiet_hours
+            .push((None, TimeRange::parse("22:00-07:00").unwrap()));
+        let mut dispatcher = Dispatcher::new(config);
+        let mut sink = Recorder::default();
+        let now = Instant::now();
+        for clock in [at(22, 0), at(23, 59), at(0, 0), at(6, 59)] {
+            assert!(
+                dispatcher
+                    .dispatch(&alert(AlertKind::Memory), now, clock, &mut sink)
+                    .is_empty()
+            );
+        }
+        assert!(sink.0.is_empty());
+        let fired = dispatcher.dispatch(&alert(AlertKind::Memory), now, at(7, 0), &mut sink);
+        assert_eq!(fired, [Channel::Webhook, Channel::Bell, Channel::Notify]);
+        assert_eq!(sink.0.len(), 3);
+    }
+
+    #[test]
+    fn quiet_hours_for_one_kind_leave_the_others() {
+        let mut config = NotifyConfig::default();
+        config.quiet_hours.push((
+            Some(AlertKind::ProcessStart),
+            TimeRange::parse("12:00-13:30").unwrap(),
+        ));
+        let mut dispatcher = Dispatcher::new(config);
+        let mut sink = Recorder::default();
+        let now = Instant::now();
+        let noon = at(12, 30);
+        assert!(
+            dispatcher
+                .dispatch(&alert(AlertKind::ProcessStart), now, noon, &mut sink)
+                .is_empty()
+        );
+        assert_eq!(
+            dispatcher.dispatch(&alert(AlertKind::Memory), now, noon, &mut sink),
+            [Channel::Webhook]
+        );
+    }
+
+    #[test]
+    fn snooze_mutes_every_channel_until_it_ends() {
+        let config = NotifyConfig {
+            memory: vec![Channel::Webhook, Channel::Visual],
+            ..NotifyConfig::default()
+        };
+        let mut dispatcher = Dispatcher::new(config);
+        let mut sink = Recorder::default();
+        let now = Instant::now();
+        let hour = Duration::from_secs(3600);
+        dispatcher.snooze(Some(now + hour));
+        assert_eq!(dispatcher.snoozed(now), Some(hour));
+        assert!(
+            dispatcher
+                .dispatch(&alert(AlertKind::Memory), now, at(12, 0), &mut sink)
+                .is_empty()
+        );
+        assert!(sink.0.is_empty());
+
+        let later = now + hour;
+        assert_eq!(dispatcher.snoozed(later), None);
+        assert_eq!(
+            dispatcher.dispatch(&alert(AlertKind::Memory), later, at(13, 0), &mut sink),
+            [Channel::Webhook, Channel::Visual]
+        );
+    }
+
+    #[test]
+    fn each_kind_goes_to_its_own_channels() {
+        let config = NotifyConfig {
+            process_cpu: vec![Channel::Notify],
+            memory: vec![Channel::Bell, Channel::Visual],
+            kill_rule: Vec::new(),
+            ..NotifyConfig::default()
+        };
+        let mut dispatcher = Dispatcher::new(config);
+        let mut sink = Recorder::default();
+        let now = Instant::now();
+        for kind in [
+            AlertKind::ProcessCpu,
+            AlertKind::Memory,
+            AlertKind::KillRule,
+            AlertKind::ThreadGrowth,
+        ] {
+            dispatcher.dispatch(&alert(kind), now, at(12, 0), &mut sink);
+        }
+        assert_eq!(
+            sink.0,
+            [
+                (Channel::Notify, AlertKind::ProcessCpu),
+                (Channel::Bell, AlertKind::Memory),
+                (Channel::Visual, AlertKind::Memory),
+                (Channel::Webhook, AlertKind::ThreadGrowth),
+            ]
+        );
+    }
+
+    #[test]
+    fn cue_channels_are_rate_limited_one_by_one() {
+        let config = NotifyConfig {
+            process_cpu: vec![Channel::Webhook, Channel::Bell],
+            memory: vec![Channel::Bell, Channel::Visual],
+            rate_limit: Duration::from_secs(10),
+            ..NotifyConfig::default()
+        };
+        let mut dispatcher = Dispatcher::new(config);
+        let mut sink = Recorder::default();
+        let now = Instant::now();
+        let clock = at(12, 0);
+        assert_eq!(
+            dispatcher.dispatch(&alert(AlertKind::ProcessCpu), now, clock, &mut sink),
+            [Channel::Webhook, Channel::Bell]
+        );
+        // The bell is shared across kinds; the visual cue hasn't fired yet.
+        let soon = now + Duration::from_secs(5);
+        assert_eq!(
+            dispatcher.dispatch(&alert(AlertKind::Memory), soon, clock, &mut sink),
+            [Channel::Visual]
+        );
+        // The webhook is never limited.
+        assert_eq!(
+            dispatcher.dispatch(&alert(AlertKind::ProcessCpu), soon, clock, &mut sink),
+            [Channel::Webhook]
+        );
+        let later = now + Duration::from_secs(10);
+        assert_eq!(
+            dispatcher.dispatch(&alert(AlertKind::Memory), later, clock, &mut sink),
+            [Channel::Bell]
+        );
+    }
+
+    #[test]
+    fn alerts_without_notify_reach_no_channel() {
+        let config = NotifyConfig {
+            kill_rule: vec![Channel::Webhook, Channel::Bell],
+            ..NotifyConfig::default()
+        };
+        let mut dispatcher = Dispatcher::new(config);
+        let mut sink = Recorder::default();
+        let quiet = Alert {
+            notify: false,
+            ..alert(AlertKind::KillRule)
+        };
+        assert!(
+            dispatcher
+                .dispatch(&quiet, Instant::now(), at(12, 0), &mut sink)
+                .is_empty()
+        );
+        assert!(sink.0.is_empty());
+        // Nor does it use up the bell's rate limit.
+        assert_eq!(
+            dispatcher.dispatch(
+                &alert(AlertKind::KillRule),
+                Instant::now(),
+                at(12, 0),
+                &mut sink
+            ),
+            [Channel::Webhook, Channel::Bell]
+        );
+    }
+}
@@ -0,0 +1,496 @@
+//! Small regular-expression matcher for process-name rules.
+//!
+//! syswatch only needs to match short process names, so this supports
+//! the common subset instead of pulling in a regex crate: literals,
+//! `.`, `[...]` classes (ranges, `^` negation), `\d \w \s` and their
+//! negations, groups `(...)`, alternation `|`, the `* + ?` quantifiers,
+//! `^` / `$` anchors, and a leading `(?i)` for case-insensitive matching.
+//! Matching is unanchored (a search). The pattern is compiled into a
+//! small automaton that is run over every thread at once, each
+//! instruction visited at most once per text position, so matching takes
+//! time linear in the text whatever the pattern: nested quantifiers such
+//! as `(a+)+b` cannot make it backtrack exponentially.
+
+use std::fmt;
+use std::mem;
+
+/// One element of a parsed pattern.
+#[derive(Debug, Clone)]
+enum Node {
+    /// A literal character.
+    Char(char),
+    /// `.`: any character.
+    Any,
+    /// `[...]` or a `\d`-style shorthand.
+    Class {
+        /// Inclusive character ranges.
+        ranges: Vec<(char, char)>,
+        /// Whether the class matches characters *outside* the ranges.
+        negated: bool,
+    },
+    /// `^`
+    Start,
+    /// `$`
+    End,
+    /// `(...)`: alternatives, each a sequence.
+    Group(Vec<Vec<Node>>),
+    /// A quantified node.
+    Repeat {
+        /// Repeated node.
+        node: Box<Node>,
+        /// Minimum repetitions.
+        min: usize,
+        /// Maximum repetitions, `None` for unbounded.
+        max: Option<usize>,
+    },
+}
+
+/// A compiled pattern.
+#[derive(Debug, Clone)]
+pub struct Pattern {
+    /// Source text, for display.
+    source: String,
+    /// Compiled automaton; its last instruction is [`Inst::Match`].
+    program: Vec<Inst>,
+    /// Whether matching ignores case.
+    ignore_case: bool,
+}
+
+impl fmt::Display for Pattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.source)
+    }
+}
+
+impl Pattern {
+    /// Compiles `source`, describing the first syntax error.
+    pub fn new(source: &str) -> Result<Self, String> {
+        let (ignore_case, body) = match source.strip_prefix("(?i)") {
+            Some(rest) => (true, rest),
+            None => (false, source),
+        };
+
+        let mut parser = Parser {
+            chars: body.chars().collect(),
+            pos: 0,
+            ignore_case,
+        };
+        let alts = parser.alternation()?;
+        if parser.pos < parser.chars.len() {
+            return Err(format!("unmatched `)` at offset {}", parser.pos));
+        }
+
+        Ok(Self {
+            source: source.to_string(),
+            program: compile(&alts),
+            ignore_case,
+        })
+    }
+
+    /// Whether the pattern matches anywhere in `text`.
+    pub fn is_match(&self, text: &str) -> bool {
+        let chars: Vec<char> = if self.ignore_case {
+            text.chars().map(fold_case).collect()
+        } else {
+            text.chars().collect()
+        };
+
+        let mut current = Threads::new(self.program.len());
+        let mut next = Threads::new(self.program.len());
+        for pos in 0..=chars.len() {
+            // Unanchored: a fresh attempt starts at every position.
+            self.follow(&mut current, 0, pos, chars.len());
+            if current.matched {
+                return true;
+            }
+            let Some(&c) = chars.get(pos) else {
+                break;
+            };
+            next.clear();
+            for &pc in &current.list {
+                if let Inst::Step(node) = &self.program[pc]
+                    && atom_matches(node, c)
+                {
+                    self.follow(&mut next, pc + 1, pos + 1, chars.len());
+                }
+            }
+            mem::swap(&mut current, &mut next);
+        }
+        false
+    }
+
+    /// Adds `pc` and everything reachable from it without consuming a
+    /// character to `threads`, for the text position `pos` of `len`.
+    fn follow(&self, threads: &mut Threads, pc: usize, pos: usize, len: usize) {
+        let mut stack = vec![pc];
+        while let Some(pc) = stack.pop() {
+            if !threads.insert(pc) {
+                continue;
+            }
+            match self.program[pc] {
+                Inst::Jump(to) => stack.push(to),
+                Inst::Split(first, second) => stack.extend([second, first]),
+                Inst::Start if pos == 0 => stack.push(pc + 1),
+                Inst::End if pos == len => stack.push(pc + 1),
+                Inst::Match => threads.matched = true,
+                Inst::Start | Inst::End | Inst::Step(_) => {}
+            }
+        }
+    }
+}
+
+/// Escapes `text` so it matches itself literally.
+pub fn escape(text: &str) -> String {
+    text.chars()
+        .flat_map(|c| {
+            let special = !c.is_alphanumeric() && c != ' ' && c != '_';
+            special.then_some('\\').into_iter().chain([c])
+        })
+        .collect()
+}
+
+/// Lower-cases a single character (first char of its lowercase form).
+///
+/// Final sigma folds to `σ`, so `ς` in a name matches either in a pattern.
+fn fold_case(c: char) -> char {
+    match c {
+        'ς' => 'σ',
+        c => c.to_lowercase().next().unwrap_or(c),
+    }
+}
+
+// ── Parsing ─────────────────────────────────────────────────
+
+/// Recursive-descent parser over the pattern's characters.
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+    ignore_case: bool,
+}
+
+impl Parser {
+    /// Returns the next character without consuming it.
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    /// Consumes and returns the next character.
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += 1;
+        Some(c)
+    }
+
+    /// Applies case folding to a literal when the pattern ignores case.
+    fn literal(&self, c: char) -> char {
+        if self.ignore_case { fold_case(c) } else { c }
+    }
+
+    /// `seq ('|' seq)*`, stopping before `)` or at the end.
+    fn alternation(&mut self) -> Result<Vec<Vec<Node>>, String> {
+        let mut alts = vec![self.sequence()?];
+        while self.peek() == Some('|') {
+            self.pos += 1;
+            alts.push(self.sequence()?);
+        }
+        Ok(alts)
+    }
+
+    /// Quantified atoms up to `|`, `)`, or the end.
+    fn sequence(&mut self) -> Result<Vec<Node>, String> {
+        let mut seq = Vec::new();
+        while let Some(c) = self.peek() {
+            if c == '|' || c == ')' {
+                break;
+            }
+            let atom = self.atom()?;
+            seq.push(self.quantifier(atom)?);
+        }
+        Ok(seq)
+    }
+
+    /// Wraps `atom` in a [`Node::Repeat`] if a quantifier follows.
+    fn quantifier(&mut self, atom: Node) -> Result<Node, String> {
+        let (min, max) = match self.peek() {
+            Some('*') => (0, None),
+            Some('+') => (1, None),
+            Some('?') => (0, Some(1)),
+            _ => return Ok(atom),
+        };
+        if matches!(atom, Node::Start | Node::End) {
+            return Err(format!("nothing to repeat at offset {}", self.pos));
+        }
+        self.pos += 1;
+        Ok(Node::Repeat {
+            node: Box::new(atom),
+            min,
+            max,
+        })
+    }
+
+    /// A single atom: literal, `.`, class, group, anchor, or escape.
+    fn atom(&mut self) -> Result<Node, String> {
+        let at = self.pos;
+        match self.bump() {
+            Some('.') => Ok(Node::Any),
+            Some('^') => Ok(Node::Start),
+            Some('$') => Ok(Node::End),
+            Some('(') => {
+                let alts = self.alternation()?;
+                if self.bump() != Some(')') {
+                    return Err(format!("unclosed `(` at offset {at}"));
+                }
+                Ok(Node::Group(alts))
+            }
+            Some('[') => self.class(at),
+            Some('\\') => self.escape(),
+            Some(c @ ('*' | '+' | '?')) => {
+                Err(format!("nothing to repeat before `{c}` at offset {at}"))
+            }
+            Some(c) => Ok(Node::Char(self.literal(c))),
+            None => Err("unexpected end of pattern".into()),
+        }
+    }
+
+    /// The character after a `\`.
+    fn escape(&mut self) -> Result<Node, String> {
+        let c = self.bump().ok_or("trailing `\\` in pattern")?;
+        Ok(shorthand(c).unwrap_or(Node::Char(self.literal(c))))
+    }
+
+    /// A `[...]` class; the opening bracket has been consumed.
+    fn class(&mut self, at: usize) -> Result<Node, String> {
+        let negated = self.peek() == Some('^');
+        if negated {
+            self.pos += 1;
+        }
+
+        let mut ranges = Vec::new();
+        let mut first = true;
+        loop {
+            let c = self
+                .bump()
+                .ok_or_else(|| format!("unclosed `[` at offset {at}"))?;
+            if c == ']' && !first {
+                break;
+            }
+            first = false;
+
+            let lo = if c == '\\' {
+                let e = self.bump().ok_or("trailing `\\` in pattern")?;
+                match shorthand(e) {
+                    Some(Node::Class {
+                        ranges: r,
+                        negated: false,
+                    }) => {
+                        ranges.extend(r);
+                        continue;
+                    }
+                    Some(_) => {
+                        return Err(format!("`\\{e}` is not supported inside `[...]`"));
+                    }
+                    None => e,
+                }
+            } else {
+                c
+            };
+
+            let hi = if self.peek() == Some('-') && self.chars.get(self.pos + 1) != Some(&']') {
+                self.pos += 1;
+                self.bump()
+                    .ok_or_else(|| format!("unclosed `[` at offset {at}"))?
+            } else {
+                lo
+            };
+            if hi < lo {
+                return Err(format!("invalid range `{lo}-{hi}` at offset {at}"));
+            }
+            ranges.push((self.literal(lo), self.literal(hi)));
+        }
+
+        Ok(Node::Class { ranges, negated })
+    }
+}
+
+/// Expands `\d \w \s` (and upper-case negations) into classes.
+fn shorthand(c: char) -> Option<Node> {
+    let ranges = match c.to_ascii_lowercase() {
+        'd' => vec![('0', '9')],
+        'w' => vec![('a', 'z'), ('A', 'Z'), ('0', '9'), ('_', '_')],
+        's' => vec![(' ', ' '), ('\t', '\t'), ('\n', '\n'), ('\r', '\r')],
+        _ => return None,
+    };
+    Some(Node::Class {
+        ranges,
+        negated: c.is_ascii_uppercase(),
+    })
+}
+
+// ── Matching ────────────────────────────────────────────────
+
+/// One instruction of the compiled automaton.
+#[derive(Debug, Clone)]
+enum Inst {
+    /// Consumes one character matching a `Char`, `Any` or `Class` node.
+    Step(Node),
+    /// Continues at both targets.
+    Split(usize, usize),
+    /// Continues at the target.
+    Jump(usize),
+    /// Continues only at the start of the text.
+    Start,
+    /// Continues only at the end of the text.
+    End,
+    /// The pattern has matched.
+    Match,
+}
+
+/// Compiles top-level alternatives into a program ending in [`Inst::Match`].
+fn compile(alts: &[Vec<Node>]) -> Vec<Inst> {
+    let mut program = Vec::new();
+    emit_alts(alts, &mut program);
+    program.push(Inst::Match);
+    program
+}
+
+/// Emits alternatives as a chain of splits that all rejoin after the last.
+fn emit_alts(alts: &[Vec<Node>], program: &mut Vec<Inst>) {
+    let Some((last, init)) = alts.split_last() else {
+        return;
+    };
+    let mut jumps = Vec::new();
+    for seq in init {
+        let split = program.len();
+        program.push(Inst::Split(split + 1, 0));
+        for node in seq {
+            emit(node, program);
+        }
+        jumps.push(program.len());
+        program.push(Inst::Jump(0));
+        program[split] = Inst::Split(split + 1, program.len());
+    }
+    for node in last {
+        emit(node, program);
+    }
+    let end = program.len();
+    for jump in jumps {
+        program[jump] = Inst::Jump(end);
+    }
+}
+
+/// Emits one node; quantifiers become splits around copies of their node.
+fn emit(node: &Node, program: &mut Vec<Inst>) {
+    match node {
+        Node::Start => program.push(Inst::Start),
+        Node::End => program.push(Inst::End),
+        Node::Group(alts) => emit_alts(alts, program),
+        Node::Repeat { node, min, max } => {
+            for _ in 0..*min {
+                emit(node, program);
+            }
+            let split = program.len();
+            program.push(Inst::Split(split + 1, 0));
+            emit(node, program);
+            if max.is_none() {
+                program.push(Inst::Jump(split));
+            }
+            program[split] = Inst::Split(split + 1, program.len());
+        }
+        atom => program.push(Inst::Step(atom.clone())),
+    }
+}
+
+/// Instructions active at one text position, each at most once.
+struct Threads {
+    /// Whether each instruction is already in `list`.
+    seen: Vec<bool>,
+    /// Active instructions, in the order they were reached.
+    list: Vec<usize>,
+    /// Whether [`Inst::Match`] was reached.
+    matched: bool,
+}
+
+impl Threads {
+    /// Empty set for a program of `len` instructions.
+    fn new(len: usize) -> Self {
+        Self {
+            seen: vec![false; len],
+            list: Vec::with_capacity(len),
+            matched: false,
+        }
+    }
+
+    /// Adds `pc`, returning whether it was new.
+    fn insert(&mut self, pc: usize) -> bool {
+        let new = !self.seen[pc];
+        if new {
+            self.seen[pc] = true;
+            self.list.push(pc);
+        }
+        new
+    }
+
+    /// Empties the set for reuse.
+    fn clear(&mut self) {
+        for pc in self.list.drain(..) {
+            self.seen[pc] = false;
+        }
+        self.matched = false;
+    }
+}
+
+/// Whether a single-character node matches `c`.
+fn atom_matches(node: &Node, c: char) -> bool {
+    match node {
+        Node::Char(want) => *want == c,
+        Node::Any => true,
+        Node::Class { ranges, negated } => {
+            ranges.iter().any(|&(lo, hi)| (lo..=hi).contains(&c)) != *negated
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn is_match(pattern: &str, text: &str) -> bool {
+        Pattern::new(pattern).unwrap().is_match(text)
+    }
+
+    #[test]
+    fn nested_quantifiers_do_not_backtrack_exponentially() {
+        // Each of these takes 2^n steps in a backtracking matcher.
+        let a = "a".repeat(64);
+        assert!(!is_match("(.*)*x", &a));
+        assert!(is_match("(.*)*x", &format!("{a}x")));
+        assert!(!is_match("(a+)+b", &a));
+        assert!(is_match("^(a+)+b$", &format!("{a}b")));
+        assert!(!is_match("(a|a)*b", &a));
+        assert!(!is_match(r"(?i)(\w+)+z", &"com_apple_webkit".repeat(4)));
+        assert!(is_match(r"(?i)(\w+)+z", "FUZZ"));
+    }
+
+    #[test]
+    fn quantifiers_and_anchors() {
+        assert!(is_match("^a?b+c*$", "bb"));
+        assert!(is_match("^a?b+c*$", "abcc"));
+        assert!(!is_match("^a?b+c*$", "aac"));
+        assert!(is_match("^(a*)*$", ""));
+        assert!(is_match("^(|x)y$", "y"));
+        assert!(is_match("^(|x)y$", "xy"));
+        assert!(!is_match("^$", "a"));
+        assert!(is_match("node$", "com.node"));
+        assert!(!is_match("node$", "nodes"));
+    }
+
+    #[test]
+    fn alternation_and_classes() {
+        assert!(is_match("^(chrome|firefox)$", "firefox"));
+        assert!(!is_match("^(chrome|firefox)$", "chromefox"));
+        assert!(is_match(r"^[^\d]+\d$", "python3"));
+        assert!(!is_match(r"^[^\d]+\d$", "3d"));
+        assert!(is_match("(?i)^SAFARI$", "Safari"));
+    }
+}
@@ -0,0 +1,63 @@
+//! The `--plain` mode: line-oriented reports for screen readers.
+//!
+//! Neither raw mode nor the alternate screen is used. Each tick appends
+//! one report from [`ui::plain_report`] to stdout, separated from the
+//! previous one by a blank line, so nothing is ever rewritten in place
+//! and a screen reader only has new lines to announce. Ctrl-C stops the
+//! loop between reports, so the exit summary still prints.
+
+use std::io::{self, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::app::App;
+use crate::ui;
+
+/// Longest stretch slept before checking for Ctrl-C again.
+const INTERRUPT_POLL: Duration = Duration::from_millis(100);
+
+/// Set by the SIGINT handler.
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+
+/// SIGINT handler recording the request to stop.
+extern "C" fn on_sigint(_: libc::c_int) {
+    INTERRUPTED.store(true, Ordering::SeqCst);
+}
+
+/// Prints a report every tick until Ctrl-C.
+///
+/// `app` must have ticked once, so the first report has CPU deltas.
+pub fn run(app: &mut App, out: &mut impl Write) -> io::Result<()> {
+    every_tick(app, |app| {
+        out.write_all(ui::plain_report(app).as_bytes())?;
+        out.write_all(b"\n")?;
+        out.flush()
+    })
+}
+
+/// Calls `report` now and after every further tick until Ctrl-C or
+/// until it fails; the terminal stays in normal mode throughout.
+pub fn every_tick(app: &mut App, mut report: impl FnMut(&App) -> io::Result<()>) -> io::Result<()> {
+    let handler: extern "C" fn(libc::c_int) = on_sigint;
+    let previous = unsafe { libc::signal(libc::SIGINT, handler as libc::sighandler_t) };
+
+    let result = loop {
+        if let Err(err) = report(app) {
+            break Err(err);
+        }
+
+        let last_tick = Instant::now();
+        while !INTERRUPTED.load(Ordering::SeqCst) && last_tick.elapsed() < app.ticker.interval() {
+            let remaining = app.ticker.interval().saturating_sub(last_tick.elapsed());
+            thread::sleep(remaining.min(INTERRUPT_POLL));
+        }
+        if INTERRUPTED.load(Ordering::SeqCst) {
+            break Ok(());
+        }
+        app.tick();
+    };
+
+    unsafe { libc::signal(libc::SIGINT, previous) };
+    result
+}
@@ -0,0 +1,256 @@
+//! Named startup profiles from `[profile.<name>]` config sections.
+//!
+//! A profile is only a bundle of existing settings. [`Settings::resolve`]
+//! layers them: built-in defaults, then the config file, then the
+//! column layout saved from the column settings, then the selected
+//! profile, then command-line flags, each overriding the one before.
+//! The same resolution runs at startup and when `p` switches profiles,
+//! so switching never leaves settings from the previous one.
+//!
+//! Low-power mode is one more layer, between the selected profile and
+//! the command line: the `[profile.low-power]` section if the config has
+//! one, else [`Profile::low_power`]. It is applied and reverted
+//! automatically on battery, so leaving it is just resolving without it.
+
+use std::time::Duration;
+
+use crate::config::Config;
+use crate::view::{COLUMNS, SortKey};
+
+/// Settings a profile may set; `None` leaves the lower layer's value.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Profile {
+    /// Name from the section header.
+    pub name: String,
+    /// Start in the compact layout.
+    pub compact: Option<bool>,
+    /// Visible columns as indices into [`COLUMNS`].
+    pub columns: Option<Vec<usize>>,
+    /// Name of the `[filters]` preset to apply.
+    pub filter: Option<String>,
+    /// Sort column.
+    pub sort: Option<SortKey>,
+    /// Sort direction; defaults to the sort column's natural one.
+    pub descending: Option<bool>,
+    /// Tick interval.
+    pub interval: Option<Duration>,
+    /// Adaptive (idle-slowdown) ticking.
+    pub adaptive: Option<bool>,
+    /// Per-process thread, fd and App Nap scans.
+    pub threads: Option<bool>,
+    /// Detail extras for the process on screen (cwd, env, Mach IPC, disk I/O).
+    pub extras: Option<bool>,
+    /// Shortest time between network and disk counter refreshes.
+    pub io_interval: Option<Duration>,
+}
+
+/// Name of the profile used as the low-power layer.
+pub const LOW_POWER_PROFILE: &str = "low-power";
+
+impl Profile {
+    /// Built-in low-power layer, used when the config has no
+    /// `[profile.low-power]` section.
+    pub fn low_power() -> Self {
+        Self {
+            name: LOW_POWER_PROFILE.to_string(),
+            interval: Some(Duration::from_secs(5)),
+            adaptive: Some(true),
+            threads: Some(false),
+            extras: Some(false),
+            io_interval: Some(Duration::from_secs(15)),
+            ..Self::default()
+        }
+    }
+}
+
+/// The effective values of every setting a profile can touch.
+#[derive(Debug, Clone)]
+pub struct Settings {
+    /// Whether the compact layout is used.
+    pub compact: bool,
+    /// Visible columns as indices into [`COLUMNS`].
+    pub columns: Vec<usize>,
+    /// Filter preset to apply, if any.
+    pub filter: Option<String>,
+    /// Sort column.
+    pub sort: SortKey,
+    /// Whether the sort is largest-first.
+    pub descending: bool,
+    /// Tick interval.
+    pub interval: Duration,
+    /// Whether ticks slow down while idle.
+    pub adaptive: bool,
+    /// Whether per-process thread, fd and App Nap scans run.
+    pub threads: bool,
+    /// Whether the process on screen gets its detail extras.
+    pub extras: bool,
+    /// Shortest time between network and disk refreshes; zero is every tick.
+    pub io_interval: Duration,
+}
+
+impl Settings {
+    /// Resolves defaults ← `config` ← `saved_columns` ← `profile` ←
+    /// `low_power` ← command line.
+    ///
+    /// `saved_columns` is the layout last applied in the column settings.
+    /// `-c` is the only command-line flag among these settings, passed as
+    /// `cli_compact`.
+    pub fn resolve(
+        config: &Config,
+        saved_columns: Option<&[usize]>,
+        profile: Option<&Profile>,
+        low_power: Option<&Profile>,
+        cli_compact: bool,
+    ) -> Self {
+        let mut settings = Self {
+            compact: false,
+            columns: saved_columns
+                .map(<[usize]>::to_vec)
+                .or_else(|| config.columns.clone())
+                .unwrap_or_else(|| {
+                    (0..COLUMNS.len())
+                        .filter(|&i| COLUMNS[i].default_visible)
+                        .collect()
+                }),
+            filter: None,
+            sort: SortKey::Cpu,
+            descending: SortKey::Cpu.default_descending(),
+            interval: config.tick_interval,
+            adaptive: config.adaptive_tick,
+            threads: true,
+            extras: true,
+            io_interval: Duration::ZERO,
+        };
+
+        for p in profile.into_iter().chain(low_power) {
+            settings.overlay(p);
+        }
+
+        if cli_compact {
+            settings.compact = true;
+        }
+        settings
+    }
+
+    /// Overrides every setting `p` sets.
+    fn overlay(&mut self, p: &Profile) {
+        if let Some(compact) = p.compact {
+            self.compact = compact;
+        }
+        if let Some(columns) = &p.columns {
+            self.columns.clone_from(columns);
+        }
+        if p.filter.is_some() {
+            self.filter.clone_from(&p.filter);
+        }
+        if let Some(sort) = p.sort {
+            self.sort = sort;
+            self.descending = sort.default_descending();
+        }
+        if let Some(descending) = p.descending {
+            self.descending = descending;
+        }
+        if let Some(interval) = p.interval {
+            self.interval = interval;
+        }
+        if let Some(adaptive) = p.adaptive {
+            self.adaptive = adaptive;
+        }
+        if let Some(threads) = p.threads {
+            self.threads = threads;
+        }
+        if let Some(extras) = p.extras {
+            self.extras = extras;
+        }
+        if let Some(io_interval) = p.io_interval {
+            self.io_interval = io_interval;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A config setting the interval, adaptive ticking and columns.
+    fn config() -> Config {
+        Config::parse(
+            "[tick]\ninterval = 2\nadaptive = true\n\n[table]\ncolumns = [\"pid\", \"name\"]\n",
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn defaults_apply_without_config_or_profiles() {
+        let settings = Settings::resolve(&Config::default(), None, None, None, false);
+        let visible: Vec<usize> = (0..COLUMNS.len())
+            .filter(|&i| COLUMNS[i].default_visible)
+            .collect();
+        assert_eq!(settings.columns, visible);
+        assert_eq!(settings.sort, SortKey::Cpu);
+        assert!(settings.descending);
+        assert_eq!(settings.interval, Config::default().tick_interval);
+        assert!(!settings.compact && settings.threads && settings.extras);
+        assert_eq!(settings.io_interval, Duration::ZERO);
+    }
+
+    #[test]
+    fn each_layer_overrides_the_one_before() {
+        let config = config();
+        let pid_name = vec![0, 1];
+        let saved = [2, 1];
+        let profile = Profile {
+            name: "work".to_string(),
+            columns: Some(vec![4, 1]),
+            sort: Some(SortKey::Name),
+            interval: Some(Duration::from_millis(500)),
+            compact: Some(false),
+            ..Profile::default()
+        };
+        let low_power = Profile::low_power();
+
+        let resolve = |saved, profile, low_power, cli| {
+            Settings::resolve(&config, saved, profile, low_power, cli)
+        };
+
+        // Config over defaults.
+        let settings = resolve(None, None, None, false);
+        assert_eq!(settings.columns, pid_name);
+        assert_eq!(settings.interval, Duration::from_secs(2));
+        assert!(settings.adaptive);
+        // Saved columns over the config's.
+        let settings = resolve(Some(&saved), None, None, false);
+        assert_eq!(settings.columns, saved);
+        assert_eq!(settings.interval, Duration::from_secs(2));
+        // The profile over saved columns and config; its sort brings its
+        // natural direction.
+        let settings = resolve(Some(&saved), Some(&profile), None, false);
+        assert_eq!(settings.columns, [4, 1]);
+        assert_eq!(settings.interval, Duration::from_millis(500));
+        assert_eq!(settings.sort, SortKey::Name);
+        assert!(!settings.descending);
+        assert!(settings.adaptive);
+        // Low power over the profile, only where it sets something.
+        let settings = resolve(Some(&saved), Some(&profile), Some(&low_power), false);
+        assert_eq!(settings.interval, Duration::from_secs(5));
+        assert!(!settings.threads && !settings.extras);
+        assert_eq!(settings.io_interval, Duration::from_secs(15));
+        assert_eq!(settings.columns, [4, 1]);
+        assert_eq!(settings.sort, SortKey::Name);
+        // `-c` over everything, even a profile asking for the full layout.
+        let settings = resolve(Some(&saved), Some(&profile), Some(&low_power), true);
+        assert!(settings.compact);
+    }
+
+    #[test]
+    fn profile_direction_overrides_its_sort_default() {
+        let profile = Profile {
+            sort: Some(SortKey::Name),
+            descending: Some(true),
+            ..Profile::default()
+        };
+        let settings = Settings::resolve(&Config::default(), None, Some(&profile), None, false);
+        assert_eq!(settings.sort, SortKey::Name);
+        assert!(settings.descending);
+    }
+}
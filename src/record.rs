@@ -0,0 +1,605 @@
+//! Metric recording for `--record` and `--influx-url`.
+//!
+//! Each tick produces one [`Sample`] of system-wide metrics, written as a
+//! CSV row or an InfluxDB line-protocol point. Line protocol goes either
+//! to a file or to an HTTP write endpoint; the latter is batched on a
+//! worker thread so a slow or unreachable server never stalls the UI.
+//! Files may be rotated with `--record-rotate` (see [`crate::rotate`]).
+//!
+//! Alerts fired during the tick are recorded too: as points of their own
+//! measurement in line protocol, and in a companion `name-events.csv`
+//! (see [`events_path`]) beside a CSV recording, created with the first
+//! alert and never rotated.
+
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use crate::alerts::Alert;
+use crate::app::App;
+use crate::cli::Options;
+use crate::collector::{MemoryPressure, hostname};
+use crate::http::{self, Url};
+use crate::rotate::RecordFile;
+
+/// Line-protocol measurement name.
+const MEASUREMENT: &str = "syswatch";
+
+/// CSV header row, matching [`Sample::csv_row`].
+///
+/// Columns are only ever appended, so scripts indexing by position keep
+/// working; memory counters that can't be read are left empty.
+const CSV_HEADER: &str = "timestamp,cpu_user,cpu_system,cpu_idle,mem_used,mem_total,threads,processes,\
+     swap_used,swap_total,wired,compressed,cached,memory_pressure";
+
+/// Header of the events companion file, matching [`event_row`].
+const EVENTS_HEADER: &str = "timestamp,type,pid,name,value,threshold,message";
+
+/// Line-protocol measurement name of alert events.
+const EVENT_MEASUREMENT: &str = "syswatch_alert";
+
+/// Environment variable holding an InfluxDB API token, sent as `Authorization: Token …`.
+const INFLUX_TOKEN_VAR: &str = "INFLUX_TOKEN";
+
+/// Points per HTTP write request.
+const BATCH_LINES: usize = 10;
+
+/// Longest a point waits in a partial batch before it is sent anyway.
+const FLUSH_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Attempts per batch before it is dropped.
+const SEND_ATTEMPTS: u32 = 3;
+
+/// Delay before the first retry; doubled for each further one.
+const RETRY_DELAY: Duration = Duration::from_secs(1);
+
+/// Connect / read / write timeout for write requests.
+const HTTP_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Output encoding selected with `--record-format`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RecordFormat {
+    /// Comma-separated values with a header row.
+    #[default]
+    Csv,
+    /// InfluxDB line protocol with nanosecond timestamps.
+    Influx,
+}
+
+impl RecordFormat {
+    /// Parses a `--record-format` value.
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "csv" => Some(Self::Csv),
+            "influx" => Some(Self::Influx),
+            _ => None,
+        }
+    }
+}
+
+/// System-wide metrics for one tick.
+#[derive(Debug, Clone)]
+pub struct Sample {
+    /// Wall-clock time the sample was taken.
+    pub time: SystemTime,
+    /// User CPU percentage.
+    pub cpu_user: f64,
+    /// System CPU percentage.
+    pub cpu_system: f64,
+    /// Idle CPU percentage.
+    pub cpu_idle: f64,
+    /// Used memory in bytes.
+    pub mem_used: u64,
+    /// Total memory in bytes.
+    pub mem_total: u64,
+    /// Threads across inspectable processes.
+    pub threads: usize,
+    /// Number of processes.
+    pub processes: usize,
+    /// Used swap in bytes.
+    pub swap_used: u64,
+    /// Total swap in bytes.
+    pub swap_total: u64,
+    /// Wired kernel memory in bytes.
+    pub wired: Option<u64>,
+    /// Compressor-held memory in bytes.
+    pub compressed: Option<u64>,
+    /// File-backed cached memory in bytes.
+    pub cached: Option<u64>,
+    /// Kernel memory-pressure level.
+    pub memory_pressure: Option<MemoryPressure>,
+}
+
+impl Sample {
+    /// Captures the metrics `app` computed on its latest tick.
+    pub fn from_app(app: &App) -> Self {
+        Self {
+            time: SystemTime::now(),
+            cpu_user: app.user_pct,
+            cpu_system: app.system_pct,
+            cpu_idle: app.idle_pct,
+            mem_used: app.used_memory,
+            mem_total: app.total_memory,
+            threads: app.thread_count,
+            processes: app.processes.len(),
+            swap_used: app.used_swap,
+            swap_total: app.total_swap,
+            wired: app.wired_memory,
+            compressed: app.compressed_memory,
+            cached: app.cached_memory,
+            memory_pressure: app.memory_pressure,
+        }
+    }
+
+    /// The sample as a CSV row (no newline), columns as in [`CSV_HEADER`].
+    fn csv_row(&self) -> String {
+        let cell = |v: Option<u64>| v.map_or_else(String::new, |v| v.to_string());
+        format!(
+            "{},{:.2},{:.2},{:.2},{},{},{},{},{},{},{},{},{},{}",
+            csv_time(self.time),
+            self.cpu_user,
+            self.cpu_system,
+            self.cpu_idle,
+            self.mem_used,
+            self.mem_total,
+            self.threads,
+            self.processes,
+            self.swap_used,
+            self.swap_total,
+            cell(self.wired),
+            cell(self.compressed),
+            cell(self.cached),
+            self.memory_pressure.map_or("", MemoryPressure::id)
+        )
+    }
+
+    /// The sample as one line-protocol point (no newline), tagged with `host`.
+    ///
+    /// Memory counters that can't be read are omitted from the field set.
+    pub fn line_protocol(&self, host: &str) -> String {
+        let mut fields = format!(
+            "cpu_user={},cpu_system={},cpu_idle={},mem_used={}i,mem_total={}i,\
+             threads={}i,processes={}i,swap_used={}i,swap_total={}i",
+            self.cpu_user,
+            self.cpu_system,
+            self.cpu_idle,
+            self.mem_used,
+            self.mem_total,
+            self.threads,
+            self.processes,
+            self.swap_used,
+            self.swap_total,
+        );
+        for (key, value) in [
+            ("wired", self.wired),
+            ("compressed", self.compressed),
+            ("cached", self.cached),
+        ] {
+            if let Some(value) = value {
+                fields.push_str(&format!(",{key}={value}i"));
+            }
+        }
+        if let Some(pressure) = self.memory_pressure {
+            fields.push_str(&format!(",memory_pressure=\"{}\"", pressure.id()));
+        }
+        format!(
+            "{MEASUREMENT},host={} {fields} {}",
+            escape_tag(host),
+            unix_nanos(self.time)
+        )
+    }
+}
+
+/// Seconds since the Unix epoch with millisecond precision, as in CSV rows.
+fn csv_time(time: SystemTime) -> String {
+    let secs = time
+        .duration_since(UNIX_EPOCH)
+        .map_or(0.0, |d| d.as_secs_f64());
+    format!("{secs:.3}")
+}
+
+/// Quotes a CSV cell if it holds a comma, quote, or line break.
+pub fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+/// Companion file receiving the alerts of the CSV recording at `record`:
+/// `name.csv` becomes `name-events.csv`.
+pub fn events_path(record: &Path) -> PathBuf {
+    let stem = record.file_stem().unwrap_or(record.as_os_str());
+    let mut name = stem.to_os_string();
+    name.push("-events.csv");
+    record.with_file_name(name)
+}
+
+/// An alert as a row of the events file (no newline), columns as in
+/// [`EVENTS_HEADER`].
+fn event_row(alert: &Alert) -> String {
+    let (pid, name) = alert
+        .subject
+        .as_ref()
+        .map_or((String::new(), ""), |(pid, name)| (pid.to_string(), name));
+    format!(
+        "{},{},{pid},{},{},{},{}",
+        csv_time(alert.time),
+        alert.kind.id(),
+        csv_field(name),
+        alert.value,
+        alert.threshold,
+        csv_field(&alert.message())
+    )
+}
+
+/// An alert as one line-protocol point (no newline), tagged with `host`
+/// and the alert type.
+fn event_line_protocol(alert: &Alert, host: &str) -> String {
+    let string = |s: &str| format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""));
+    let mut fields = format!(
+        "value={},threshold={},message={}",
+        alert.value,
+        alert.threshold,
+        string(&alert.message())
+    );
+    if let Some((pid, name)) = &alert.subject {
+        fields.push_str(&format!(",pid={pid}i,name={}", string(name)));
+    }
+    format!(
+        "{EVENT_MEASUREMENT},host={},type={} {fields} {}",
+        escape_tag(host),
+        alert.kind.id(),
+        unix_nanos(alert.time)
+    )
+}
+
+/// Escapes a line-protocol tag key or value (commas, equals signs, spaces).
+pub fn escape_tag(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        if matches!(c, ',' | '=' | ' ') {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out
+}
+
+/// Nanoseconds since the Unix epoch; times before it clamp to 0.
+pub fn unix_nanos(time: SystemTime) -> u128 {
+    time.duration_since(UNIX_EPOCH).map_or(0, |d| d.as_nanos())
+}
+
+/// Where recorded samples go.
+#[derive(Debug)]
+enum Sink {
+    /// A local file, appended to and possibly rotated.
+    File(RecordFile),
+    /// An InfluxDB write endpoint, fed through a worker thread.
+    Http(Pusher),
+}
+
+/// Writes one sample per tick in the format chosen on the command line.
+#[derive(Debug)]
+pub struct Recorder {
+    format: RecordFormat,
+    /// Value of the line-protocol `host` tag.
+    host: String,
+    sink: Sink,
+    /// Path of the CSV recording whose events file is created on demand.
+    events_base: Option<PathBuf>,
+    /// The events file, once an alert has been recorded.
+    events: Option<RecordFile>,
+}
+
+impl Recorder {
+    /// Opens the destination requested by `opts`, or returns `None` when
+    /// recording is off.
+    pub fn open(opts: &Options) -> io::Result<Option<Self>> {
+        let sink = match (&opts.record, &opts.influx_url) {
+            (Some(path), _) => {
+                let header = (opts.record_format == RecordFormat::Csv).then_some(CSV_HEADER);
+                Sink::File(RecordFile::open(
+                    path,
+                    opts.record_rotate,
+                    opts.record_keep,
+                    header,
+                )?)
+            }
+            (None, Some(url)) => Sink::Http(Pusher::spawn(url.clone())),
+            (None, None) => return Ok(None),
+        };
+        Ok(Some(Self {
+            format: opts.record_format,
+            host: hostname(),
+            sink,
+            events_base: opts
+                .record
+                .clone()
+                .filter(|_| opts.record_format == RecordFormat::Csv),
+            events: None,
+        }))
+    }
+
+    /// Writes `sample` and the tick's `events` to the destination.
+    ///
+    /// HTTP delivery is asynchronous; its failures surface through
+    /// [`Recorder::take_error`] instead.
+    pub fn record(&mut self, sample: &Sample, events: &[Alert]) -> io::Result<()> {
+        let line = match self.format {
+            RecordFormat::Csv => sample.csv_row(),
+            RecordFormat::Influx => sample.line_protocol(&self.host),
+        };
+        self.write(line)?;
+        for alert in events {
+            match self.format {
+                RecordFormat::Csv => self.write_event(alert)?,
+                RecordFormat::Influx => self.write(event_line_protocol(alert, &self.host))?,
+            }
+        }
+        Ok(())
+    }
+
+    /// Writes one line to the sink.
+    fn write(&mut self, line: String) -> io::Result<()> {
+        match &mut self.sink {
+            Sink::File(file) => file.write_line(&line),
+            Sink::Http(pusher) => {
+                pusher.push(line);
+                Ok(())
+            }
+        }
+    }
+
+    /// Appends `alert` to the events file, opening it first if needed.
+    fn write_event(&mut self, alert: &Alert) -> io::Result<()> {
+        let Some(base) = &self.events_base else {
+            return Ok(());
+        };
+        let file = match &mut self.events {
+            Some(file) => file,
+            None => self.events.insert(RecordFile::open(
+                &events_path(base),
+                None,
+                None,
+                Some(EVENTS_HEADER),
+            )?),
+        };
+        file.write_line(&event_row(alert))
+    }
+
+    /// File currently written to, when recording to a file.
+    pub fn file(&self) -> Option<&Path> {
+        match &self.sink {
+            Sink::File(file) => Some(file.path()),
+            Sink::Http(_) => None,
+        }
+    }
+
+    /// Returns the oldest undelivered error from the HTTP worker, if any.
+    pub fn take_error(&mut self) -> Option<String> {
+        match &self.sink {
+            Sink::Http(pusher) => pusher.errors.try_recv().ok(),
+            Sink::File(_) => None,
+        }
+    }
+}
+
+// ── HTTP delivery ───────────────────────────────────────────
+
+/// Handle to the worker thread that batches points and POSTs them.
+#[derive(Debug)]
+struct Pusher {
+    /// Feeds points to the worker; dropped first on shutdown.
+    lines: Option<Sender<String>>,
+    /// Delivery failures reported by the worker.
+    errors: Receiver<String>,
+    worker: Option<JoinHandle<()>>,
+}
+
+impl Pusher {
+    /// Starts a worker writing to `url`.
+    fn spawn(url: Url) -> Self {
+        let token = std::env::var(INFLUX_TOKEN_VAR).ok();
+        let (lines, rx) = mpsc::channel();
+        let (err_tx, errors) = mpsc::channel();
+        let worker = thread::spawn(move || push_loop(&url, token.as_deref(), &rx, &err_tx));
+        Self {
+            lines: Some(lines),
+            errors,
+            worker: Some(worker),
+        }
+    }
+
+    /// Queues one point for the next batch.
+    fn push(&self, line: String) {
+        if let Some(lines) = &self.lines {
+            // A send error means the worker died; its error was already reported.
+            let _ = lines.send(line);
+        }
+    }
+}
+
+impl Drop for Pusher {
+    /// Closes the queue and waits for the worker to send the final batch.
+    fn drop(&mut self) {
+        self.lines = None;
+        if let Some(worker) = self.worker.take() {
+            let _ = worker.join();
+        }
+    }
+}
+
+/// Worker body: collects points into batches and sends each one.
+///
+/// A batch is sent once it holds [`BATCH_LINES`] points, its oldest
+/// point is [`FLUSH_INTERVAL`] old, or the queue closes.
+fn push_loop(url: &Url, token: Option<&str>, rx: &Receiver<String>, errors: &Sender<String>) {
+    let mut batch = String::new();
+    let mut count = 0;
+    let mut oldest = Instant::now();
+
+    loop {
+        let timeout = if count == 0 {
+            FLUSH_INTERVAL
+        } else {
+            FLUSH_INTERVAL.saturating_sub(oldest.elapsed())
+        };
+        let closed = match rx.recv_timeout(timeout) {
+            Ok(line) => {
+                if count == 0 {
+                    oldest = Instant::now();
+                }
+                batch.push_str(&line);
+                batch.push('\n');
+                count += 1;
+                if count < BATCH_LINES {
+                    continue;
+                }
+                false
+            }
+            Err(RecvTimeoutError::Timeout) if count == 0 => continue,
+            Err(RecvTimeoutError::Timeout) => false,
+            Err(RecvTimeoutError::Disconnected) => true,
+        };
+
+        if count > 0
+            && let Err(msg) = send_batch(url, token, &batch)
+        {
+            let _ = errors.send(format!("influx: {msg}; dropped {count} points"));
+        }
+        batch.clear();
+        count = 0;
+
+        if closed {
+            return;
+        }
+    }
+}
+
+/// POSTs one batch, retrying connection failures and server errors.
+///
+/// Client errors (4xx) are not retried since resending won't fix them.
+fn send_batch(url: &Url, token: Option<&str>, body: &str) -> Result<(), String> {
+    let auth = token.map(|t| format!("Token {t}"));
+    let headers: Vec<(&str, &str)> = auth
+        .as_deref()
+        .map(|a| ("Authorization", a))
+        .into_iter()
+        .collect();
+
+    let mut delay = RETRY_DELAY;
+    let mut last_err = String::new();
+    for attempt in 1..=SEND_ATTEMPTS {
+        match http::post(
+            url,
+            "text/plain; charset=utf-8",
+            &headers,
+            body.as_bytes(),
+            HTTP_TIMEOUT,
+        ) {
+            Ok(status) if (200..300).contains(&status) => return Ok(()),
+            Ok(status) if (400..500).contains(&status) => {
+                return Err(format!("{url} answered HTTP {status}"));
+            }
+            Ok(status) => last_err = format!("{url} answered HTTP {status}"),
+            Err(err) => last_err = format!("{url}: {err}"),
+        }
+        if attempt < SEND_ATTEMPTS {
+            thread::sleep(delay);
+            delay *= 2;
+        }
+    }
+    Err(format!("{last_err} after {SEND_ATTEMPTS} attempts"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(time: SystemTime) -> Sample {
+        Sample {
+            time,
+            cpu_user: 12.5,
+            cpu_system: 3.25,
+            cpu_idle: 84.25,
+            mem_used: 8 << 30,
+            mem_total: 16 << 30,
+            threads: 2048,
+            processes: 512,
+            swap_used: 0,
+            swap_total: 1 << 30,
+            wired: Some(2 << 30),
+            compressed: None,
+            cached: None,
+            memory_pressure: Some(MemoryPressure::Warning),
+        }
+    }
+
+    #[test]
+    fn csv_rows_line_up_with_the_header() {
+        let time = UNIX_EPOCH + Duration::from_millis(1_700_000_000_250);
+        let header: Vec<&str> = CSV_HEADER.split(',').collect();
+        let row = sample(time).csv_row();
+        let cells: Vec<&str> = row.split(',').collect();
+        assert_eq!(cells.len(), header.len(), "{row}");
+        let column = |name: &str| {
+            let index = header.iter().position(|h| *h == name).unwrap();
+            cells[index]
+        };
+        assert_eq!(column("timestamp"), "1700000000.250");
+        assert_eq!(column("cpu_user"), "12.50");
+        assert_eq!(column("cpu_system"), "3.25");
+        assert_eq!(column("cpu_idle"), "84.25");
+        assert_eq!(column("mem_used"), "8589934592");
+        assert_eq!(column("mem_total"), "17179869184");
+        assert_eq!(column("threads"), "2048");
+        assert_eq!(column("processes"), "512");
+        assert_eq!(column("swap_used"), "0");
+        assert_eq!(column("swap_total"), "1073741824");
+        assert_eq!(column("wired"), "2147483648");
+        assert_eq!(column("compressed"), "");
+        assert_eq!(column("cached"), "");
+        assert_eq!(column("memory_pressure"), "warning");
+    }
+
+    #[test]
+    fn tag_values_escape_commas_equals_and_spaces() {
+        assert_eq!(escape_tag("studio"), "studio");
+        assert_eq!(escape_tag("Mac mini,lab=2"), r"Mac\ mini\,lab\=2");
+        assert_eq!(escape_tag(r"a\b"), r"a\b");
+        assert_eq!(escape_tag(""), "");
+    }
+
+    #[test]
+    fn line_protocol_point() {
+        let time = UNIX_EPOCH + Duration::new(1_700_000_000, 123_456_789);
+        assert_eq!(
+            sample(time).line_protocol("Mac mini,lab=2"),
+            concat!(
+                r"syswatch,host=Mac\ mini\,lab\=2 ",
+                "cpu_user=12.5,cpu_system=3.25,cpu_idle=84.25,",
+                "mem_used=8589934592i,mem_total=17179869184i,threads=2048i,processes=512i,",
+                "swap_used=0i,swap_total=1073741824i,wired=2147483648i,",
+                r#"memory_pressure="warning" 1700000000123456789"#,
+            )
+        );
+    }
+
+    #[test]
+    fn timestamps_are_nanoseconds_clamped_at_the_epoch() {
+        assert_eq!(unix_nanos(UNIX_EPOCH), 0);
+        assert_eq!(unix_nanos(UNIX_EPOCH + Duration::from_nanos(1)), 1);
+        assert_eq!(unix_nanos(UNIX_EPOCH - Duration::from_secs(5)), 0);
+        assert_eq!(
+            unix_nanos(UNIX_EPOCH + Duration::from_secs(4_102_444_800)),
+            4_102_444_800_000_000_000
+        );
+        let point = sample(UNIX_EPOCH - Duration::from_secs(1)).line_protocol("h");
+        assert!(point.ends_with(" 0"), "{point}");
+    }
+}
@@ -0,0 +1,71 @@
+//! When to re-read the config file.
+//!
+//! A [`ConfigWatch`] reports a reload as due after `SIGHUP`, or when the
+//! file's modification time has changed since the last look. The mtime
+//! is only checked every [`POLL_TICKS`] ticks, so an unchanged file costs
+//! one `stat` every few seconds. Applying the new config is up to
+//! `App::reload_config`.
+
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::SystemTime;
+
+/// Ticks between two checks of the config file's modification time.
+const POLL_TICKS: u32 = 3;
+
+/// Set by the SIGHUP handler.
+static HANGUP: AtomicBool = AtomicBool::new(false);
+
+/// SIGHUP handler recording the request to reload.
+extern "C" fn on_sighup(_: libc::c_int) {
+    HANGUP.store(true, Ordering::SeqCst);
+}
+
+/// Makes SIGHUP request a config reload instead of terminating syswatch.
+pub fn install() {
+    let handler: extern "C" fn(libc::c_int) = on_sighup;
+    unsafe { libc::signal(libc::SIGHUP, handler as libc::sighandler_t) };
+}
+
+/// Change detection for one config file.
+#[derive(Debug)]
+pub struct ConfigWatch {
+    /// File to watch; `None` when no home directory is known.
+    path: Option<PathBuf>,
+    /// Modification time at the last look; `None` while the file is missing.
+    mtime: Option<SystemTime>,
+    /// Ticks since the last look.
+    ticks: u32,
+}
+
+impl ConfigWatch {
+    /// Starts watching `path` from its current state.
+    pub fn new(path: Option<PathBuf>) -> Self {
+        let mut watch = Self {
+            path,
+            mtime: None,
+            ticks: 0,
+        };
+        watch.mtime = watch.modified();
+        watch
+    }
+
+    /// Called once per tick; returns whether the config should be re-read.
+    pub fn due(&mut self) -> bool {
+        let hangup = HANGUP.swap(false, Ordering::SeqCst);
+        self.ticks += 1;
+        if !hangup && self.ticks < POLL_TICKS {
+            return false;
+        }
+        self.ticks = 0;
+        let mtime = self.modified();
+        let changed = mtime != self.mtime;
+        self.mtime = mtime;
+        hangup || changed
+    }
+
+    /// Current modification time of the file, if it can be read.
+    fn modified(&self) -> Option<SystemTime> {
+        std::fs::metadata(self.path.as_ref()?).ok()?.modified().ok()
+    }
+}
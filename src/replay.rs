@@ -0,0 +1,406 @@
+//! Browsing a `--record` CSV file with `--view`.
+//!
+//! The file is parsed by header name, so recordings from older versions
+//! with fewer columns still load. It is re-read from the last complete
+//! line every [`TAIL_INTERVAL`], so a file another instance is still
+//! appending to grows on screen; while the cursor sits on the newest
+//! sample it follows new rows.
+//!
+//! Alerts recorded alongside the CSV (`name-events.csv`, see
+//! [`events_path`]) are loaded too and marked on the chart at the time
+//! they fired. A rotated `name.N.csv` shares the events file of `name.csv`.
+
+use std::fs::{self, File};
+use std::io::{self, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use crossterm::event::{Event, KeyCode, KeyEventKind};
+use ratatui::DefaultTerminal;
+
+use crate::app::ZOOM_WINDOWS;
+use crate::input::Input;
+use crate::record::events_path;
+use crate::ui;
+
+/// How often the file is checked for appended rows.
+const TAIL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Samples moved by `H` / `L` (a minute at the default tick).
+const PAGE_STEP: usize = 60;
+
+/// One recorded tick; columns missing from the file are `None`.
+#[derive(Debug, Clone)]
+pub struct Row {
+    /// Seconds since the Unix epoch.
+    pub time: f64,
+    /// User CPU percentage.
+    pub cpu_user: f64,
+    /// System CPU percentage.
+    pub cpu_system: f64,
+    /// Idle CPU percentage.
+    pub cpu_idle: Option<f64>,
+    /// Used memory in bytes.
+    pub mem_used: Option<u64>,
+    /// Total memory in bytes.
+    pub mem_total: Option<u64>,
+    /// Used swap in bytes.
+    pub swap_used: Option<u64>,
+    /// Thread count.
+    pub threads: Option<u64>,
+    /// Process count.
+    pub processes: Option<u64>,
+    /// Memory-pressure level name.
+    pub memory_pressure: Option<String>,
+}
+
+/// One alert from the events file.
+#[derive(Debug, Clone)]
+pub struct AlertMark {
+    /// Seconds since the Unix epoch.
+    pub time: f64,
+    /// Alert type id.
+    pub kind: String,
+    /// Description recorded when it fired.
+    pub message: String,
+}
+
+impl AlertMark {
+    /// Parses one data line of the events file, or `None` if it is malformed.
+    fn parse(line: &str) -> Option<Self> {
+        let cells = split_csv(line);
+        Some(Self {
+            time: cells.first()?.parse().ok()?,
+            kind: cells.get(1)?.clone(),
+            message: cells.get(6)?.clone(),
+        })
+    }
+}
+
+/// Splits a CSV line into cells, undoing quoting.
+fn split_csv(line: &str) -> Vec<String> {
+    let mut cells = vec![String::new()];
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                chars.next();
+                cells.last_mut().unwrap().push('"');
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => cells.push(String::new()),
+            c => cells.last_mut().unwrap().push(c),
+        }
+    }
+    cells
+}
+
+/// Events file belonging to the recording at `path`.
+fn companion(path: &Path) -> PathBuf {
+    let rotated = path
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .and_then(|stem| stem.rsplit_once('.'))
+        .filter(|(_, n)| n.parse::<usize>().is_ok());
+    match rotated {
+        Some((base, _)) => {
+            let mut name = base.to_string();
+            if let Some(ext) = path.extension() {
+                name.push('.');
+                name.push_str(&ext.to_string_lossy());
+            }
+            events_path(&path.with_file_name(name))
+        }
+        None => events_path(path),
+    }
+}
+
+/// Positions of the known columns in the file's header.
+#[derive(Debug)]
+struct Columns {
+    time: usize,
+    cpu_user: usize,
+    cpu_system: usize,
+    cpu_idle: Option<usize>,
+    mem_used: Option<usize>,
+    mem_total: Option<usize>,
+    swap_used: Option<usize>,
+    threads: Option<usize>,
+    processes: Option<usize>,
+    memory_pressure: Option<usize>,
+}
+
+impl Columns {
+    /// Locates the columns in `header`; the timestamp and CPU split are required.
+    fn parse(header: &str) -> Result<Self, String> {
+        let names: Vec<&str> = header.trim_end().split(',').collect();
+        let find = |name: &str| names.iter().position(|&n| n == name);
+        let require = |name: &str| find(name).ok_or(format!("no `{name}` column in the header"));
+        Ok(Self {
+            time: require("timestamp")?,
+            cpu_user: require("cpu_user")?,
+            cpu_system: require("cpu_system")?,
+            cpu_idle: find("cpu_idle"),
+            mem_used: find("mem_used"),
+            mem_total: find("mem_total"),
+            swap_used: find("swap_used"),
+            threads: find("threads"),
+            processes: find("processes"),
+            memory_pressure: find("memory_pressure"),
+        })
+    }
+
+    /// Parses one data line, or `None` if a required cell is malformed.
+    fn row(&self, line: &str) -> Option<Row> {
+        let cells: Vec<&str> = line.split(',').collect();
+        let cell = |i: Option<usize>| i.and_then(|i| cells.get(i)).filter(|c| !c.is_empty());
+        let num = |i: usize| cells.get(i)?.parse().ok();
+        Some(Row {
+            time: num(self.time)?,
+            cpu_user: num(self.cpu_user)?,
+            cpu_system: num(self.cpu_system)?,
+            cpu_idle: cell(self.cpu_idle).and_then(|c| c.parse().ok()),
+            mem_used: cell(self.mem_used).and_then(|c| c.parse().ok()),
+            mem_total: cell(self.mem_total).and_then(|c| c.parse().ok()),
+            swap_used: cell(self.swap_used).and_then(|c| c.parse().ok()),
+            threads: cell(self.threads).and_then(|c| c.parse().ok()),
+            processes: cell(self.processes).and_then(|c| c.parse().ok()),
+            memory_pressure: cell(self.memory_pressure).map(|c| c.to_string()),
+        })
+    }
+}
+
+/// A recorded CSV file, read incrementally.
+#[derive(Debug)]
+pub struct Recording {
+    /// Path shown in the title.
+    pub path: PathBuf,
+    file: File,
+    /// Bytes consumed so far, up to the end of the last complete line.
+    offset: u64,
+    /// Trailing bytes of a line still being written.
+    partial: Vec<u8>,
+    /// Header column positions, known once the first line is complete.
+    columns: Option<Columns>,
+    /// Samples read so far, in file order.
+    pub rows: Vec<Row>,
+    /// Data lines that could not be parsed.
+    pub skipped: usize,
+    /// Events file checked on every poll.
+    events_path: PathBuf,
+    /// Size of the events file when last read.
+    events_len: u64,
+    /// Recorded alerts, in file order.
+    pub events: Vec<AlertMark>,
+}
+
+impl Recording {
+    /// Opens `path` and reads everything written so far.
+    pub fn open(path: &Path) -> io::Result<Self> {
+        let mut recording = Self {
+            path: path.to_path_buf(),
+            file: File::open(path)?,
+            offset: 0,
+            partial: Vec::new(),
+            columns: None,
+            rows: Vec::new(),
+            skipped: 0,
+            events_path: companion(path),
+            events_len: 0,
+            events: Vec::new(),
+        };
+        recording.poll()?;
+        if recording.columns.is_none() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "no header row (is this a --record CSV file?)",
+            ));
+        }
+        Ok(recording)
+    }
+
+    /// Reads rows appended since the last call, returning how many were added.
+    ///
+    /// A file that shrank (truncated or replaced) is read again from the start.
+    pub fn poll(&mut self) -> io::Result<usize> {
+        self.poll_events()?;
+        if self.file.metadata()?.len() < self.offset {
+            self.file = File::open(&self.path)?;
+            self.offset = 0;
+            self.partial.clear();
+            self.columns = None;
+            self.rows.clear();
+            self.skipped = 0;
+        }
+        self.file.seek(SeekFrom::Start(self.offset))?;
+        let mut buf = Vec::new();
+        self.file.read_to_end(&mut buf)?;
+        self.offset += buf.len() as u64;
+        self.partial.extend_from_slice(&buf);
+
+        // Only complete lines are parsed; the rest waits for the writer.
+        let Some(end) = self.partial.iter().rposition(|&b| b == b'\n') else {
+            return Ok(0);
+        };
+        let complete: Vec<u8> = self.partial.drain(..=end).collect();
+        let before = self.rows.len();
+        for line in String::from_utf8_lossy(&complete).lines() {
+            match &self.columns {
+                None => {
+                    self.columns = Some(
+                        Columns::parse(line)
+                            .map_err(|msg| io::Error::new(io::ErrorKind::InvalidData, msg))?,
+                    );
+                }
+                Some(columns) => match columns.row(line) {
+                    Some(row) => self.rows.push(row),
+                    None => self.skipped += 1,
+                },
+            }
+        }
+        Ok(self.rows.len() - before)
+    }
+
+    /// Re-reads the events file if its size changed; a missing one has no events.
+    fn poll_events(&mut self) -> io::Result<()> {
+        let len = match fs::metadata(&self.events_path) {
+            Ok(meta) => meta.len(),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => 0,
+            Err(e) => return Err(e),
+        };
+        if len == self.events_len {
+            return Ok(());
+        }
+        self.events_len = len;
+        self.events = if len == 0 {
+            Vec::new()
+        } else {
+            let text = fs::read_to_string(&self.events_path)?;
+            text.lines().skip(1).filter_map(AlertMark::parse).collect()
+        };
+        Ok(())
+    }
+
+    /// Alerts recorded after row `index - 1` and up to row `index`.
+    pub fn events_at(&self, index: usize) -> impl Iterator<Item = &AlertMark> {
+        let to = self.rows.get(index).map_or(f64::NEG_INFINITY, |r| r.time);
+        let from = index
+            .checked_sub(1)
+            .and_then(|i| self.rows.get(i))
+            .map_or(f64::NEG_INFINITY, |r| r.time);
+        self.events
+            .iter()
+            .filter(move |e| e.time > from && e.time <= to)
+    }
+}
+
+/// State of the `--view` screen.
+#[derive(Debug)]
+pub struct Replay {
+    /// The file being viewed.
+    pub recording: Recording,
+    /// Index of the inspected row; `None` follows the newest one.
+    cursor: Option<usize>,
+    /// Index into [`ZOOM_WINDOWS`].
+    pub zoom: usize,
+    /// Latest error from re-reading the file.
+    pub error: Option<String>,
+    running: bool,
+}
+
+impl Replay {
+    /// Creates a viewer following the newest row.
+    pub fn new(recording: Recording) -> Self {
+        Self {
+            recording,
+            cursor: None,
+            zoom: 0,
+            error: None,
+            running: true,
+        }
+    }
+
+    /// Index of the inspected row, if the file has any.
+    pub fn cursor(&self) -> Option<usize> {
+        let len = self.recording.rows.len();
+        match self.cursor {
+            _ if len == 0 => None,
+            Some(i) => Some(i.min(len - 1)),
+            None => Some(len - 1),
+        }
+    }
+
+    /// Whether the cursor tracks the newest row.
+    pub fn following(&self) -> bool {
+        self.cursor.is_none()
+    }
+
+    /// Chart window width in seconds.
+    pub fn window(&self) -> f64 {
+        ZOOM_WINDOWS[self.zoom]
+    }
+
+    /// X-axis bounds: the window around the cursor, clamped to the recording.
+    pub fn bounds(&self) -> [f64; 2] {
+        let rows = &self.recording.rows;
+        let window = self.window();
+        let (Some(first), Some(last), Some(i)) = (rows.first(), rows.last(), self.cursor()) else {
+            return [0.0, window];
+        };
+        let right = (rows[i].time + window / 2.0)
+            .min(last.time)
+            .max(first.time + window);
+        [right - window, right]
+    }
+
+    /// Moves the cursor by `offset` rows; reaching the newest row resumes following.
+    fn step(&mut self, offset: isize) {
+        let Some(current) = self.cursor() else {
+            return;
+        };
+        let last = self.recording.rows.len() - 1;
+        let next = current.saturating_add_signed(offset).min(last);
+        self.cursor = (next < last).then_some(next);
+    }
+
+    /// Applies one key press.
+    fn handle_key(&mut self, code: KeyCode) {
+        match code {
+            KeyCode::Char('q') | KeyCode::Esc => self.running = false,
+            KeyCode::Char('h') | KeyCode::Left => self.step(-1),
+            KeyCode::Char('l') | KeyCode::Right => self.step(1),
+            KeyCode::Char('H') | KeyCode::PageUp => self.step(-(PAGE_STEP as isize)),
+            KeyCode::Char('L') | KeyCode::PageDown => self.step(PAGE_STEP as isize),
+            KeyCode::Char('g') | KeyCode::Home => self.cursor = Some(0),
+            KeyCode::Char('G') | KeyCode::End => self.cursor = None,
+            KeyCode::Char('z') => self.zoom = (self.zoom + 1).min(ZOOM_WINDOWS.len() - 1),
+            KeyCode::Char('Z') => self.zoom = self.zoom.saturating_sub(1),
+            _ => {}
+        }
+    }
+}
+
+/// Runs the viewer until the user quits.
+pub fn run(terminal: &mut DefaultTerminal, recording: Recording) -> io::Result<()> {
+    let mut replay = Replay::new(recording);
+    let input = Input::spawn();
+    let mut last_poll = Instant::now();
+
+    while replay.running {
+        terminal.draw(|f| ui::draw_replay(f, &replay))?;
+
+        let timeout = TAIL_INTERVAL.saturating_sub(last_poll.elapsed());
+        if let Some(Event::Key(key)) = input.next(timeout)?
+            && key.kind == KeyEventKind::Press
+        {
+            replay.handle_key(key.code);
+        }
+
+        if last_poll.elapsed() >= TAIL_INTERVAL {
+            replay.error = replay.recording.poll().err().map(|e| e.to_string());
+            last_poll = Instant::now();
+        }
+    }
+    Ok(())
+}
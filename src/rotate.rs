@@ -0,0 +1,363 @@
+//! Recording files for `--record`, optionally rotated.
+//!
+//! Without a [`Rotation`] samples are appended to the given path. With
+//! one they go to numbered files beside it — `name.1.csv`, `name.2.csv`,
+//! … — and a new file starts once the current one would exceed the size
+//! limit, or on the first sample of a new local day. The finished file
+//! is fsynced before the next one is opened, so a power loss can only
+//! cost the file being written. With `--record-keep N` only the newest
+//! N files are kept. A restarted session appends to the highest-numbered
+//! existing file, so numbering carries on where it stopped.
+
+use std::ffi::OsString;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::rules::parse_size;
+
+/// When a recording moves on to the next numbered file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rotation {
+    /// Once the file would grow past this many bytes.
+    Size(u64),
+    /// At local midnight.
+    Daily,
+}
+
+impl Rotation {
+    /// Parses a `--record-rotate` value: `daily` or a size like `100MB`.
+    pub fn parse(text: &str) -> Result<Self, String> {
+        if text.eq_ignore_ascii_case("daily") {
+            return Ok(Self::Daily);
+        }
+        match parse_size(text)? {
+            bytes if bytes >= 1.0 => Ok(Self::Size(bytes as u64)),
+            _ => Err(format!("`{text}` is too small a size")),
+        }
+    }
+}
+
+/// The file a recording is being written to.
+#[derive(Debug)]
+pub struct RecordFile {
+    /// Path given with `--record`; numbered files are derived from it.
+    base: PathBuf,
+    rotation: Option<Rotation>,
+    /// Number of files kept when rotating; `None` keeps them all.
+    keep: Option<usize>,
+    /// First line of every file (the CSV header), if the format has one.
+    header: Option<&'static str>,
+    /// Number of the current file; 0 when not rotating.
+    index: usize,
+    /// Path of the current file.
+    path: PathBuf,
+    file: BufWriter<File>,
+    /// Size of the current file in bytes.
+    size: u64,
+    /// Local day the current file belongs to.
+    day: i64,
+}
+
+impl RecordFile {
+    /// Opens the file to append to: `base` itself, or with a rotation the
+    /// highest-numbered existing file (`name.1.ext` if there is none).
+    ///
+    /// An existing file whose first line isn't `header` is refused, so
+    /// one file never mixes two layouts.
+    pub fn open(
+        base: &Path,
+        rotation: Option<Rotation>,
+        keep: Option<usize>,
+        header: Option<&'static str>,
+    ) -> io::Result<Self> {
+        let index = match rotation {
+            Some(_) => numbered_files(base)?.into_iter().max().unwrap_or(1),
+            None => 0,
+        };
+        let path = if index == 0 {
+            base.to_path_buf()
+        } else {
+            numbered(base, index)
+        };
+        let (file, size) = open_append(&path, header)?;
+        let modified = file.get_ref().metadata()?.modified()?;
+        let record = Self {
+            base: base.to_path_buf(),
+            rotation,
+            keep,
+            header,
+            index,
+            path,
+            file,
+            size,
+            day: local_day(modified),
+        };
+        record.prune()?;
+        Ok(record)
+    }
+
+    /// Appends one line, first moving to the next file if it is due.
+    pub fn write_line(&mut self, line: &str) -> io::Result<()> {
+        let len = line.len() as u64 + 1;
+        let due = match self.rotation {
+            Some(Rotation::Size(limit)) => self.size > 0 && self.size + len > limit,
+            Some(Rotation::Daily) => local_day(SystemTime::now()) != self.day,
+            None => false,
+        };
+        if due {
+            self.rotate()?;
+        }
+        writeln!(self.file, "{line}")?;
+        self.file.flush()?;
+        self.size += len;
+        Ok(())
+    }
+
+    /// Path of the file being written.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Syncs the current file to disk and starts the next one.
+    fn rotate(&mut self) -> io::Result<()> {
+        self.file.flush()?;
+        self.file.get_ref().sync_all()?;
+        self.index += 1;
+        self.path = numbered(&self.base, self.index);
+        (self.file, self.size) = open_append(&self.path, self.header)?;
+        self.day = local_day(SystemTime::now());
+        self.prune()
+    }
+
+    /// Deletes numbered files older than the newest `keep`.
+    fn prune(&self) -> io::Result<()> {
+        let Some(keep) = self.keep else {
+            return Ok(());
+        };
+        let oldest_kept = self.index.saturating_sub(keep);
+        for i in numbered_files(&self.base)? {
+            if i <= oldest_kept {
+                fs::remove_file(numbered(&self.base, i))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// `name.ext` with `.{index}` inserted before the extension.
+fn numbered(base: &Path, index: usize) -> PathBuf {
+    let mut name = OsString::from(base.file_stem().unwrap_or(base.as_os_str()));
+    name.push(format!(".{index}"));
+    if let Some(ext) = base.extension() {
+        name.push(".");
+        name.push(ext);
+    }
+    base.with_file_name(name)
+}
+
+/// Numbers of the existing `name.N.ext` files beside `base`, in no
+/// particular order.
+fn numbered_files(base: &Path) -> io::Result<Vec<usize>> {
+    let stem = base
+        .file_stem()
+        .unwrap_or(base.as_os_str())
+        .to_string_lossy();
+    let ext = base
+        .extension()
+        .map(|e| format!(".{}", e.to_string_lossy()));
+    let dir = match base.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e),
+    };
+    let mut found = Vec::new();
+    for entry in entries {
+        let name = entry?.file_name();
+        let name = name.to_string_lossy();
+        let index = name
+            .strip_prefix(&*stem)
+            .and_then(|rest| rest.strip_prefix('.'))
+            .and_then(|rest| match &ext {
+                Some(ext) => rest.strip_suffix(ext.as_str()),
+                None => Some(rest),
+            })
+            .filter(|n| !n.is_empty() && n.bytes().all(|b| b.is_ascii_digit()))
+            .and_then(|n| n.parse::<usize>().ok());
+        found.extend(index);
+    }
+    Ok(found)
+}
+
+/// Opens `path` for appending, writing `header` to a new file and
+/// checking it against an existing one. Returns the writer and the
+/// file's size.
+fn open_append(path: &Path, header: Option<&str>) -> io::Result<(BufWriter<File>, u64)> {
+    let file = OpenOptions::new().create(true).append(true).open(path)?;
+    let mut size = file.metadata()?.len();
+    let Some(header) = header else {
+        return Ok((BufWriter::new(file), size));
+    };
+    if size > 0 {
+        let mut first = String::new();
+        BufReader::new(File::open(path)?).read_line(&mut first)?;
+        if first.trim_end() != header {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "{} has different CSV columns; record to a new file",
+                    path.display()
+                ),
+            ));
+        }
+    }
+    let mut writer = BufWriter::new(file);
+    if size == 0 {
+        writeln!(writer, "{header}")?;
+        writer.flush()?;
+        size = header.len() as u64 + 1;
+    }
+    Ok((writer, size))
+}
+
+/// Days since the epoch in local time, for detecting a date change.
+fn local_day(time: SystemTime) -> i64 {
+    let secs = time.duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs()) as libc::time_t;
+    let mut tm: libc::tm = unsafe { std::mem::zeroed() };
+    if unsafe { libc::localtime_r(&secs, &mut tm) }.is_null() {
+        return secs / 86_400;
+    }
+    (secs + tm.tm_gmtoff) / 86_400
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// CSV header used by the tests.
+    const HEADER: &str = "timestamp,value";
+
+    /// A fresh, empty directory for one test.
+    fn scratch(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("syswatch-rotate-{}-{name}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    /// Numbers of the files beside `base`, ascending.
+    fn files(base: &Path) -> Vec<usize> {
+        let mut found = numbered_files(base).unwrap();
+        found.sort_unstable();
+        found
+    }
+
+    #[test]
+    fn size_limit_starts_the_next_file() {
+        let dir = scratch("size");
+        let base = dir.join("rec.csv");
+        // The header takes 16 bytes and each row 6, so two rows fit in 30.
+        let mut record =
+            RecordFile::open(&base, Some(Rotation::Size(30)), None, Some(HEADER)).unwrap();
+        for row in ["1,aaa", "2,bbb", "3,ccc"] {
+            record.write_line(row).unwrap();
+        }
+        assert_eq!(record.path(), dir.join("rec.2.csv").as_path());
+        assert_eq!(files(&base), [1, 2]);
+        assert_eq!(
+            fs::read_to_string(dir.join("rec.1.csv")).unwrap(),
+            "timestamp,value\n1,aaa\n2,bbb\n"
+        );
+        assert_eq!(
+            fs::read_to_string(dir.join("rec.2.csv")).unwrap(),
+            "timestamp,value\n3,ccc\n"
+        );
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn new_day_starts_the_next_file() {
+        let dir = scratch("daily");
+        let base = dir.join("rec.csv");
+        let mut record =
+            RecordFile::open(&base, Some(Rotation::Daily), None, Some(HEADER)).unwrap();
+        record.write_line("1,a").unwrap();
+        record.write_line("2,b").unwrap();
+        assert_eq!(files(&base), [1]);
+        // The file was started yesterday.
+        record.day -= 1;
+        record.write_line("3,c").unwrap();
+        assert_eq!(files(&base), [1, 2]);
+        assert_eq!(
+            fs::read_to_string(dir.join("rec.2.csv")).unwrap(),
+            "timestamp,value\n3,c\n"
+        );
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn keep_prunes_the_oldest_files() {
+        let dir = scratch("keep");
+        let base = dir.join("rec.csv");
+        let mut record =
+            RecordFile::open(&base, Some(Rotation::Size(20)), Some(2), Some(HEADER)).unwrap();
+        for i in 0..6 {
+            record.write_line(&format!("{i},x")).unwrap();
+        }
+        // One row per file, so six rows made six files.
+        assert_eq!(files(&base), [5, 6]);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn restart_after_pruning_continues_the_numbering() {
+        let dir = scratch("restart");
+        let base = dir.join("rec.csv");
+        let mut record =
+            RecordFile::open(&base, Some(Rotation::Size(20)), Some(2), Some(HEADER)).unwrap();
+        for i in 0..10 {
+            record.write_line(&format!("{i},x")).unwrap();
+        }
+        drop(record);
+        assert_eq!(files(&base), [9, 10]);
+
+        let mut record =
+            RecordFile::open(&base, Some(Rotation::Size(20)), Some(2), Some(HEADER)).unwrap();
+        assert_eq!(record.path(), dir.join("rec.10.csv").as_path());
+        record.write_line("10,x").unwrap();
+        assert_eq!(files(&base), [10, 11]);
+        // Unrelated names beside the recording are left alone.
+        fs::write(dir.join("rec.old.csv"), "").unwrap();
+        fs::write(dir.join("rec.12.txt"), "").unwrap();
+        drop(record);
+        let record =
+            RecordFile::open(&base, Some(Rotation::Size(20)), Some(2), Some(HEADER)).unwrap();
+        assert_eq!(record.path(), dir.join("rec.11.csv").as_path());
+        assert!(dir.join("rec.old.csv").exists() && dir.join("rec.12.txt").exists());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn different_header_is_refused() {
+        let dir = scratch("header");
+        let base = dir.join("rec.csv");
+        fs::write(&base, "timestamp,other\n1,2\n").unwrap();
+        let err = RecordFile::open(&base, None, None, Some(HEADER)).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        // A matching header is appended to as is.
+        fs::write(&base, "timestamp,value\n1,2\n").unwrap();
+        let mut record = RecordFile::open(&base, None, None, Some(HEADER)).unwrap();
+        record.write_line("3,4").unwrap();
+        assert_eq!(
+            fs::read_to_string(&base).unwrap(),
+            "timestamp,value\n1,2\n3,4\n"
+        );
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
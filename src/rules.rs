@@ -0,0 +1,378 @@
+//! User-defined process-table highlight rules.
+//!
+//! Each rule is one config string, `<condition> => <style>`, e.g.
+//! `name ~ (?i)backupd|mds => magenta bold` or `mem > 2GB => yellow`.
+//! Rules are compiled once at config load and evaluated per row; the
+//! first matching rule wins over the built-in CPU colouring.
+
+use ratatui::style::{Color, Modifier, Style};
+use sysinfo::ProcessStatus;
+
+use crate::monitor::ProcessSample;
+use crate::pattern::{self, Pattern};
+
+/// Comparison operators, longest first so `>=` isn't read as `>`.
+const OPERATORS: [&str; 8] = ["!~", ">=", "<=", "==", "!=", "~", ">", "<"];
+
+/// Comparison between a process field and a rule value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Op {
+    /// `~`: pattern matches.
+    Matches,
+    /// `!~`: pattern doesn't match.
+    NotMatches,
+    /// `==`
+    Eq,
+    /// `!=`
+    Ne,
+    /// `>`
+    Gt,
+    /// `>=`
+    Ge,
+    /// `<`
+    Lt,
+    /// `<=`
+    Le,
+}
+
+impl Op {
+    /// Parses an operator token.
+    fn parse(token: &str) -> Option<Self> {
+        Some(match token {
+            "~" => Self::Matches,
+            "!~" => Self::NotMatches,
+            "==" => Self::Eq,
+            "!=" => Self::Ne,
+            ">" => Self::Gt,
+            ">=" => Self::Ge,
+            "<" => Self::Lt,
+            "<=" => Self::Le,
+            _ => return None,
+        })
+    }
+
+    /// Applies a numeric comparison.
+    fn compare(self, lhs: f64, rhs: f64) -> bool {
+        match self {
+            Self::Eq => lhs == rhs,
+            Self::Ne => lhs != rhs,
+            Self::Gt => lhs > rhs,
+            Self::Ge => lhs >= rhs,
+            Self::Lt => lhs < rhs,
+            Self::Le => lhs <= rhs,
+            Self::Matches | Self::NotMatches => false,
+        }
+    }
+}
+
+/// Numeric process field a rule can compare.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Metric {
+    /// CPU usage in percent.
+    Cpu,
+    /// Session CPU-seconds.
+    CpuTime,
+    /// Resident memory in bytes.
+    Memory,
+    /// Thread count.
+    Threads,
+}
+
+impl Metric {
+    /// Reads the metric from `p`; `None` when the value is unavailable.
+    fn value(self, p: &ProcessSample) -> Option<f64> {
+        match self {
+            Self::Cpu => Some(f64::from(p.cpu_usage)),
+            Self::CpuTime => Some(p.cpu_time),
+            Self::Memory => Some(p.memory as f64),
+            Self::Threads => p.threads.map(f64::from),
+        }
+    }
+}
+
+/// Left-hand side of a rule, compiled.
+#[derive(Debug, Clone)]
+pub enum Condition {
+    /// Process name against a pattern (`~` / `!~`) or exact text (`==` / `!=`).
+    Name {
+        /// Compiled pattern; exact comparisons compile to an anchored literal.
+        pattern: Pattern,
+        /// Whether a match means the rule applies.
+        want: bool,
+    },
+    /// Owning user, resolved to a UID at load.
+    User {
+        /// UID the process must (or must not) have.
+        uid: u32,
+        /// Whether equality means the rule applies.
+        want: bool,
+    },
+    /// Numeric comparison such as `mem > 2GB`.
+    Metric {
+        /// Field compared.
+        metric: Metric,
+        /// Comparison.
+        op: Op,
+        /// Right-hand side in the metric's base unit.
+        value: f64,
+    },
+    /// Scheduler state such as `state == zombie`.
+    State {
+        /// State the process must (or must not) be in.
+        status: ProcessStatus,
+        /// Whether equality means the rule applies.
+        want: bool,
+    },
+}
+
+impl Condition {
+    /// Parses `<field> <op> <value>`.
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let text = text.trim();
+        let split = text
+            .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+            .ok_or_else(|| format!("missing operator in `{text}`"))?;
+        let field = &text[..split];
+        let rest = text[split..].trim_start();
+        let (op_text, value) = OPERATORS
+            .iter()
+            .find_map(|op| rest.strip_prefix(op).map(|v| (*op, v)))
+            .ok_or_else(|| format!("missing operator after `{field}`"))?;
+        let op = Op::parse(op_text).ok_or_else(|| format!("unknown operator `{op_text}`"))?;
+        let value = unquote(value.trim());
+        if value.is_empty() {
+            return Err(format!("missing value after `{field} {op_text}`"));
+        }
+
+        match field {
+            "name" => {
+                let (pattern, want) = match op {
+                    Op::Matches => (Pattern::new(value)?, true),
+                    Op::NotMatches => (Pattern::new(value)?, false),
+                    Op::Eq => (literal_pattern(value)?, true),
+                    Op::Ne => (literal_pattern(value)?, false),
+                    _ => return Err(format!("`name` supports ~, !~, ==, != (not `{op_text}`)")),
+                };
+                Ok(Self::Name { pattern, want })
+            }
+            "user" => Ok(Self::User {
+                uid: resolve_user(value)?,
+                want: equality(op, field, op_text)?,
+            }),
+            "state" => Ok(Self::State {
+                status: parse_state(value)?,
+                want: equality(op, field, op_text)?,
+            }),
+            _ => {
+                let metric = match field {
+                    "cpu" => Metric::Cpu,
+                    "cpu_time" => Metric::CpuTime,
+                    "mem" | "memory" => Metric::Memory,
+                    "threads" => Metric::Threads,
+                    _ => {
+                        return Err(format!(
+                            "unknown field `{field}` (expected name, user, state, cpu, cpu_time, mem, threads)"
+                        ));
+                    }
+                };
+                if matches!(op, Op::Matches | Op::NotMatches) {
+                    return Err(format!("`{field}` can't be pattern-matched"));
+                }
+                let value = if metric == Metric::Memory {
+                    parse_size(value)?
+                } else {
+                    value
+                        .parse()
+                        .map_err(|_| format!("`{value}` is not a number"))?
+                };
+                Ok(Self::Metric { metric, op, value })
+            }
+        }
+    }
+
+    /// Whether `p` satisfies the condition.
+    pub fn matches(&self, p: &ProcessSample) -> bool {
+        match self {
+            Self::Name { pattern, want } => pattern.is_match(&p.name) == *want,
+            Self::User { uid, want } => p.uid.is_some_and(|u| (u == *uid) == *want),
+            Self::Metric { metric, op, value } => {
+                metric.value(p).is_some_and(|v| op.compare(v, *value))
+            }
+            Self::State { status, want } => (p.status == *status) == *want,
+        }
+    }
+}
+
+/// A compiled highlight rule.
+#[derive(Debug, Clone)]
+pub struct ColorRule {
+    /// Which rows the rule applies to.
+    condition: Condition,
+    /// Style applied to matching rows.
+    pub style: Style,
+}
+
+impl ColorRule {
+    /// Parses `<condition> => <style>`.
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let (condition, style) = text
+            .split_once("=>")
+            .ok_or("expected `<condition> => <style>`")?;
+        Ok(Self {
+            condition: Condition::parse(condition)?,
+            style: parse_style(style)?,
+        })
+    }
+}
+
+/// Returns the style of the first rule matching `p`.
+pub fn first_match(rules: &[ColorRule], p: &ProcessSample) -> Option<Style> {
+    rules
+        .iter()
+        .find(|rule| rule.condition.matches(p))
+        .map(|rule| rule.style)
+}
+
+/// Converts `==` / `!=` into "want equal", rejecting other operators.
+fn equality(op: Op, field: &str, op_text: &str) -> Result<bool, String> {
+    match op {
+        Op::Eq => Ok(true),
+        Op::Ne => Ok(false),
+        _ => Err(format!("`{field}` supports == and != (not `{op_text}`)")),
+    }
+}
+
+/// Strips one pair of matching quotes.
+fn unquote(s: &str) -> &str {
+    for q in ['"', '\''] {
+        if let Some(inner) = s.strip_prefix(q).and_then(|r| r.strip_suffix(q)) {
+            return inner;
+        }
+    }
+    s
+}
+
+/// Compiles `text` as an exact, anchored literal.
+fn literal_pattern(text: &str) -> Result<Pattern, String> {
+    Pattern::new(&format!("^{}$", pattern::escape(text)))
+}
+
+/// Looks up a user name (or numeric UID) in the password database.
+fn resolve_user(name: &str) -> Result<u32, String> {
+    if let Ok(uid) = name.parse() {
+        return Ok(uid);
+    }
+    let c_name = std::ffi::CString::new(name).map_err(|_| format!("invalid user `{name}`"))?;
+    let entry = unsafe { libc::getpwnam(c_name.as_ptr()) };
+    if entry.is_null() {
+        return Err(format!("unknown user `{name}`"));
+    }
+    Ok(unsafe { (*entry).pw_uid })
+}
+
+/// Parses a scheduler state name.
+fn parse_state(name: &str) -> Result<ProcessStatus, String> {
+    Ok(match name.to_ascii_lowercase().as_str() {
+        "run" | "running" | "runnable" => ProcessStatus::Run,
+        "sleep" | "sleeping" => ProcessStatus::Sleep,
+        "idle" => ProcessStatus::Idle,
+        "stop" | "stopped" => ProcessStatus::Stop,
+        "zombie" => ProcessStatus::Zombie,
+        _ => {
+            return Err(format!(
+                "unknown state `{name}` (expected running, sleeping, idle, stopped, zombie)"
+            ));
+        }
+    })
+}
+
+/// Parses a byte size like `512MB`, `2GB`, or `1.5G` (binary units).
+pub fn parse_size(text: &str) -> Result<f64, String> {
+    let split = text
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(text.len());
+    let (number, unit) = text.split_at(split);
+    let number: f64 = number
+        .parse()
+        .map_err(|_| format!("`{text}` is not a size"))?;
+    let scale = match unit.trim().to_ascii_uppercase().as_str() {
+        "" | "B" => 1.0,
+        "K" | "KB" | "KIB" => 1024.0,
+        "M" | "MB" | "MIB" => 1024.0 * 1024.0,
+        "G" | "GB" | "GIB" => 1024.0 * 1024.0 * 1024.0,
+        "T" | "TB" | "TIB" => 1024.0 * 1024.0 * 1024.0 * 1024.0,
+        _ => return Err(format!("unknown size unit in `{text}`")),
+    };
+    Ok(number * scale)
+}
+
+/// Parses a style: colour names, `on <colour>` for the background, and modifiers.
+fn parse_style(text: &str) -> Result<Style, String> {
+    let mut style = Style::default();
+    let mut words = text.split_whitespace();
+    let mut any = false;
+
+    while let Some(word) = words.next() {
+        any = true;
+        let word = word.to_ascii_lowercase();
+        if word == "on" {
+            let bg = words.next().ok_or("`on` needs a background colour")?;
+            style = style.bg(parse_color(bg)?);
+        } else if let Some(modifier) = parse_modifier(&word) {
+            style = style.add_modifier(modifier);
+        } else {
+            style = style.fg(parse_color(&word)?);
+        }
+    }
+
+    if any {
+        Ok(style)
+    } else {
+        Err("missing style after `=>`".into())
+    }
+}
+
+/// Parses a text modifier name.
+fn parse_modifier(word: &str) -> Option<Modifier> {
+    Some(match word {
+        "bold" => Modifier::BOLD,
+        "dim" => Modifier::DIM,
+        "italic" => Modifier::ITALIC,
+        "underlined" | "underline" => Modifier::UNDERLINED,
+        "reversed" | "reverse" => Modifier::REVERSED,
+        "crossed_out" | "strikethrough" => Modifier::CROSSED_OUT,
+        "blink" => Modifier::SLOW_BLINK,
+        _ => return None,
+    })
+}
+
+/// Parses a colour name or `#rrggbb`.
+fn parse_color(word: &str) -> Result<Color, String> {
+    let word = word.to_ascii_lowercase();
+    if let Some(hex) = word.strip_prefix('#')
+        && hex.len() == 6
+        && let Ok(rgb) = u32::from_str_radix(hex, 16)
+    {
+        return Ok(Color::Rgb((rgb >> 16) as u8, (rgb >> 8) as u8, rgb as u8));
+    }
+
+    Ok(match word.as_str() {
+        "black" => Color::Black,
+        "red" => Color::Red,
+        "green" => Color::Green,
+        "yellow" => Color::Yellow,
+        "blue" => Color::Blue,
+        "magenta" => Color::Magenta,
+        "cyan" => Color::Cyan,
+        "gray" | "grey" => Color::Gray,
+        "darkgray" | "darkgrey" => Color::DarkGray,
+        "lightred" => Color::LightRed,
+        "lightgreen" => Color::LightGreen,
+        "lightyellow" => Color::LightYellow,
+        "lightblue" => Color::LightBlue,
+        "lightmagenta" => Color::LightMagenta,
+        "lightcyan" => Color::LightCyan,
+        "white" => Color::White,
+        _ => return Err(format!("unknown colour or modifier `{word}`")),
+    })
+}
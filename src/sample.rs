@@ -0,0 +1,263 @@
+//! Background call-stack sampling of a single process.
+//!
+//! Shells out to macOS `sample` on a worker thread, then extracts the
+//! hottest top-of-stack symbols from the report so the UI can show them
+//! in a popup. `sample` is used under root too: `spindump` reports have
+//! no top-of-stack section to parse.
+//!
+//! Reports are written into a fresh directory only the current user can
+//! open, so another account can't plant or read the file.
+
+use std::ffi::OsString;
+use std::fs::OpenOptions;
+use std::io;
+use std::os::unix::ffi::OsStringExt;
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::PathBuf;
+use std::process::Command;
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::thread;
+use std::time::Instant;
+
+/// Sampling duration in seconds passed to the sampling tool.
+const SAMPLE_SECS: &str = "1";
+
+/// Sampling interval in milliseconds passed to the sampling tool.
+const SAMPLE_INTERVAL_MS: &str = "10";
+
+/// Number of hottest symbols kept from a report.
+const TOP_SYMBOLS: usize = 8;
+
+/// Header that precedes the flattened top-of-stack section in `sample` output.
+const TOP_OF_STACK_HEADER: &str = "Sort by top of stack";
+
+/// `mkdtemp` template for the directory holding a report.
+const REPORT_DIR_TEMPLATE: &str = "syswatch-sample-XXXXXX";
+
+/// A finished sampling run.
+#[derive(Debug)]
+pub struct SampleReport {
+    /// `(symbol, sample count)` pairs, hottest first.
+    pub hottest: Vec<(String, u64)>,
+    /// Full report written by the tool.
+    pub path: PathBuf,
+}
+
+/// Progress of a sampling run as seen by the UI.
+#[derive(Debug)]
+pub enum SampleStatus {
+    /// The worker thread is still sampling.
+    Running,
+    /// Sampling finished and the report was parsed.
+    Done(SampleReport),
+    /// Sampling failed; the message is user-facing.
+    Failed(String),
+}
+
+/// A sampling run for one process, polled from the event loop.
+#[derive(Debug)]
+pub struct SampleJob {
+    /// Process being sampled.
+    pub pid: u32,
+    /// Display name of the sampled process.
+    pub name: String,
+    /// When the run was started.
+    pub started: Instant,
+    /// Latest known status.
+    pub status: SampleStatus,
+    rx: Option<Receiver<Result<SampleReport, String>>>,
+}
+
+impl SampleJob {
+    /// Starts sampling `pid` on a worker thread.
+    pub fn spawn(pid: u32, name: String) -> Self {
+        let (tx, rx) = mpsc::channel();
+        thread::spawn(move || {
+            // The receiver may be gone if the popup was closed; that's fine.
+            let _ = tx.send(run_sampler(pid));
+        });
+
+        Self {
+            pid,
+            name,
+            started: Instant::now(),
+            status: SampleStatus::Running,
+            rx: Some(rx),
+        }
+    }
+
+    /// Picks up the worker's result if it has finished.
+    pub fn poll(&mut self) {
+        let Some(rx) = &self.rx else {
+            return;
+        };
+
+        match rx.try_recv() {
+            Ok(Ok(report)) => self.status = SampleStatus::Done(report),
+            Ok(Err(msg)) => self.status = SampleStatus::Failed(msg),
+            Err(TryRecvError::Empty) => return,
+            Err(TryRecvError::Disconnected) => {
+                self.status = SampleStatus::Failed("sampling thread exited unexpectedly".into());
+            }
+        }
+        self.rx = None;
+    }
+}
+
+/// Runs `sample` to completion and parses its report.
+fn run_sampler(pid: u32) -> Result<SampleReport, String> {
+    let path = report_path(pid).map_err(|e| format!("could not create a report file: {e}"))?;
+    let pid_arg = pid.to_string();
+
+    let output = Command::new("sample")
+        .args([pid_arg.as_str(), SAMPLE_SECS, SAMPLE_INTERVAL_MS, "-mayDie"])
+        .arg("-file")
+        .arg(&path)
+        .output()
+        .map_err(|e| match e.kind() {
+            io::ErrorKind::NotFound => {
+                "`sample` not found — it ships with macOS in /usr/bin".into()
+            }
+            io::ErrorKind::PermissionDenied => "not allowed to execute `sample`".into(),
+            _ => format!("failed to run `sample`: {e}"),
+        })?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let lower = stderr.to_lowercase();
+        if lower.contains("permission") || lower.contains("not permitted") {
+            return Err(format!(
+                "permission denied sampling PID {pid} — relaunch syswatch with sudo"
+            ));
+        }
+        let detail = stderr.lines().find(|l| !l.trim().is_empty()).unwrap_or("");
+        return Err(format!("`sample` exited with {}: {detail}", output.status));
+    }
+
+    let text = std::fs::read_to_string(&path)
+        .map_err(|e| format!("could not read report {}: {e}", path.display()))?;
+
+    Ok(SampleReport {
+        hottest: hottest_symbols(&text, TOP_SYMBOLS),
+        path,
+    })
+}
+
+/// Creates an empty report file for `pid` in a new private directory.
+///
+/// The directory comes from `mkdtemp`, so its name can't be guessed and
+/// it is mode 0700; the file is then created with `create_new`, so an
+/// existing file or symlink there is an error rather than a target.
+fn report_path(pid: u32) -> io::Result<PathBuf> {
+    let mut template = std::env::temp_dir()
+        .join(REPORT_DIR_TEMPLATE)
+        .into_os_string()
+        .into_vec();
+    template.push(0);
+    if unsafe { libc::mkdtemp(template.as_mut_ptr().cast()) }.is_null() {
+        return Err(io::Error::last_os_error());
+    }
+    template.pop();
+
+    let path = PathBuf::from(OsString::from_vec(template)).join(format!("sample-{pid}.txt"));
+    OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .mode(0o600)
+        .open(&path)?;
+    Ok(path)
+}
+
+/// Extracts the `limit` hottest entries from the top-of-stack section.
+///
+/// Lines in that section look like
+/// `        __psynch_cvwait  (in libsystem_kernel.dylib)        412`;
+/// the trailing integer is the sample count.
+fn hottest_symbols(report: &str, limit: usize) -> Vec<(String, u64)> {
+    let lines = report
+        .lines()
+        .skip_while(|l| !l.trim_start().starts_with(TOP_OF_STACK_HEADER))
+        .skip(1);
+
+    let mut out = Vec::new();
+    for line in lines {
+        let line = line.trim();
+        if line.is_empty() {
+            if out.is_empty() {
+                continue;
+            }
+            break;
+        }
+
+        let Some((symbol, count)) = line.rsplit_once(char::is_whitespace) else {
+            continue;
+        };
+        let Ok(count) = count.parse::<u64>() else {
+            continue;
+        };
+        out.push((symbol.trim().to_string(), count));
+    }
+
+    out.sort_by_key(|&(_, count)| std::cmp::Reverse(count));
+    out.truncate(limit);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::os::unix::fs::PermissionsExt;
+
+    #[test]
+    fn report_files_sit_in_fresh_private_directories() {
+        let first = report_path(42).unwrap();
+        let second = report_path(42).unwrap();
+        assert_ne!(first.parent(), second.parent());
+        for path in [&first, &second] {
+            let dir = path.parent().unwrap();
+            assert_eq!(
+                fs::metadata(dir).unwrap().permissions().mode() & 0o777,
+                0o700
+            );
+            assert_eq!(
+                fs::metadata(path).unwrap().permissions().mode() & 0o777,
+                0o600
+            );
+            assert_eq!(fs::read_to_string(path).unwrap(), "");
+            fs::remove_dir_all(dir).unwrap();
+        }
+    }
+
+    #[test]
+    fn hottest_symbols_come_from_the_top_of_stack_section() {
+        let report = "\
+Call graph:
+    412 Thread_1   DispatchQueue_1
+      412 start  (in dyld) + 1942  [0x1]
+
+Total number in stack (recursive counted multiple times):
+
+Sort by top of stack, same collapsed (when >= 5):
+        __psynch_cvwait  (in libsystem_kernel.dylib)        412
+        mach_msg2_trap  (in libsystem_kernel.dylib)        1030
+        kevent_id  (in libsystem_kernel.dylib)        7
+
+Binary Images:
+";
+        assert_eq!(
+            hottest_symbols(report, 2),
+            [
+                (
+                    "mach_msg2_trap  (in libsystem_kernel.dylib)".to_string(),
+                    1030
+                ),
+                (
+                    "__psynch_cvwait  (in libsystem_kernel.dylib)".to_string(),
+                    412
+                ),
+            ]
+        );
+        assert!(hottest_symbols("no section here\n", 8).is_empty());
+    }
+}
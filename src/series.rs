@@ -0,0 +1,432 @@
+//! Compact fixed-interval time series for chart history.
+//!
+//! Values sit in fixed-interval slots, so only the first slot's
+//! timestamp and the interval are stored; each value is an `f32`. Points
+//! are reconstructed as `(x, y)` pairs on demand for the Chart API.
+//! Samples taken less often than the slot interval are held across the
+//! slots they cover, so x positions always reflect true sample times.
+//!
+//! [`Rollup`] is the coarse tier: samples evicted from a [`Series`] are
+//! aggregated into fixed-width buckets (mean + max) for long horizons.
+
+use std::collections::VecDeque;
+
+/// Bounded time series of evenly spaced samples.
+#[derive(Debug, Clone)]
+pub struct Series {
+    /// X coordinate of the oldest retained sample.
+    start: f64,
+    /// X distance between consecutive samples.
+    interval: f64,
+    /// Maximum number of retained samples.
+    capacity: usize,
+    values: VecDeque<f32>,
+}
+
+impl Series {
+    /// Creates an empty series whose first sample will sit at `start`.
+    pub fn new(start: f64, interval: f64, capacity: usize) -> Self {
+        Self {
+            start,
+            interval,
+            capacity,
+            values: VecDeque::with_capacity(capacity),
+        }
+    }
+
+    /// Records a sample taken at time `x`, returning evicted points (oldest first).
+    ///
+    /// Every slot from the last recorded one up to `x` receives `value`,
+    /// so a gap longer than one interval is filled by holding the new
+    /// sample. A sample that still falls in the last slot replaces it.
+    pub fn push_at(&mut self, x: f64, value: f64) -> Vec<(f64, f64)> {
+        let next = self.start + self.values.len() as f64 * self.interval;
+        let slots = ((x - next) / self.interval).round() as i64 + 1;
+
+        if slots <= 0 {
+            match self.values.back_mut() {
+                Some(last) => *last = value as f32,
+                None => self.values.push_back(value as f32),
+            }
+            return Vec::new();
+        }
+
+        let mut evicted = Vec::new();
+        let mut slots = slots as usize;
+        if slots > self.capacity {
+            // Everything retained rolls off; jump over fill that would be
+            // evicted straight away instead of generating it.
+            evicted.extend(self.points());
+            self.values.clear();
+            self.start = next + (slots - self.capacity) as f64 * self.interval;
+            slots = self.capacity;
+        }
+
+        for _ in 0..slots {
+            if self.values.len() >= self.capacity
+                && let Some(v) = self.values.pop_front()
+            {
+                evicted.push((self.start, f64::from(v)));
+                self.start += self.interval;
+            }
+            self.values.push_back(value as f32);
+        }
+        evicted
+    }
+
+    /// Moves the series onto slots `interval` apart, at most `capacity`
+    /// of them, returning points that no longer fit (oldest first).
+    ///
+    /// Each retained point is recorded again at its own x, so finer
+    /// slots hold a coarser sample across the ones it covers and a
+    /// coarser slot keeps the last point that falls in it. The newest
+    /// points are kept when the span shrinks.
+    pub fn resample(&mut self, interval: f64, capacity: usize) -> Vec<(f64, f64)> {
+        let old = std::mem::replace(self, Self::new(self.start, interval, capacity.max(1)));
+        old.points().flat_map(|(x, y)| self.push_at(x, y)).collect()
+    }
+
+    /// X distance between consecutive slots.
+    pub fn interval(&self) -> f64 {
+        self.interval
+    }
+
+    /// X coordinate of the most recent sample, or of the first slot when empty.
+    pub fn end(&self) -> f64 {
+        self.start + self.values.len().saturating_sub(1) as f64 * self.interval
+    }
+
+    /// Time span the series can hold, in x units.
+    pub fn span(&self) -> f64 {
+        self.capacity as f64 * self.interval
+    }
+
+    /// Number of retained samples.
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Whether no samples are retained.
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Iterates over the retained values, oldest first.
+    pub fn values(&self) -> impl DoubleEndedIterator<Item = f64> + ExactSizeIterator + '_ {
+        self.values.iter().map(|&v| f64::from(v))
+    }
+
+    /// Iterates over `(x, y)` chart points, oldest first.
+    pub fn points(&self) -> impl Iterator<Item = (f64, f64)> + '_ {
+        self.values
+            .iter()
+            .enumerate()
+            .map(|(i, &v)| (self.start + i as f64 * self.interval, f64::from(v)))
+    }
+
+    /// X coordinate of the oldest retained sample.
+    pub fn start(&self) -> f64 {
+        self.start
+    }
+
+    /// Value of the slot nearest `x`, if it is retained.
+    pub fn at(&self, x: f64) -> Option<f64> {
+        let i = ((x - self.start) / self.interval).round();
+        if i < 0.0 {
+            return None;
+        }
+        self.values.get(i as usize).map(|&v| f64::from(v))
+    }
+
+    /// Iterates over points with `from <= x <= to`, oldest first.
+    ///
+    /// The bounds are converted to slot indices, so points outside the
+    /// range are never visited; any part of the range outside the
+    /// retained history contributes no points.
+    pub fn range(&self, from: f64, to: f64) -> impl Iterator<Item = (f64, f64)> + '_ {
+        let index = |x: f64| ((x - self.start) / self.interval).max(0.0);
+        let first = (index(from).ceil() as usize).min(self.values.len());
+        let end = if to < self.start {
+            0
+        } else {
+            (index(to).floor() as usize + 1).min(self.values.len())
+        };
+        (first..end.max(first)).map(|i| {
+            (
+                self.start + i as f64 * self.interval,
+                f64::from(self.values[i]),
+            )
+        })
+    }
+
+    /// Iterates over the points of the `span` before `right`, with x
+    /// measured from the window's left edge, `right - span`.
+    ///
+    /// Offsets are counted back from the newest slot rather than
+    /// subtracted from absolute x values, so the result depends only on
+    /// the recent samples and where `right` sits past the newest one,
+    /// never on how long the series has been running.
+    pub fn window_points(&self, right: f64, span: f64) -> impl Iterator<Item = (f64, f64)> + '_ {
+        let newest = self.values.len().saturating_sub(1);
+        let lead = right - self.end();
+        let reach = ((span - lead) / self.interval).floor();
+        let first = if reach < 0.0 {
+            self.values.len()
+        } else {
+            newest.saturating_sub(reach as usize)
+        };
+        self.values
+            .iter()
+            .enumerate()
+            .skip(first)
+            .map(move |(i, &v)| {
+                let back = (newest - i) as f64 * self.interval;
+                (span - lead - back, f64::from(v))
+            })
+    }
+
+    /// Where `x`, one of the series' slots, falls in the window of
+    /// [`Series::window_points`].
+    ///
+    /// Counts whole slots back from the newest, so the result matches
+    /// the plotted points exactly.
+    pub fn window_x(&self, x: f64, right: f64, span: f64) -> f64 {
+        let back = ((self.end() - x) / self.interval).round() * self.interval;
+        span - (right - self.end()) - back
+    }
+
+    /// Iterates over points whose x coordinate is at least `from`.
+    pub fn points_since(&self, from: f64) -> impl Iterator<Item = (f64, f64)> + '_ {
+        let skip = ((from - self.start) / self.interval).ceil().max(0.0) as usize;
+        self.points().skip(skip)
+    }
+}
+
+/// Mean and maximum of the samples that fell into one bucket.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Bucket {
+    /// Arithmetic mean of the bucket's samples.
+    pub mean: f64,
+    /// Largest sample in the bucket.
+    pub max: f64,
+}
+
+/// Running sum / max / count for the bucket being filled.
+#[derive(Debug, Clone, Copy)]
+struct Accumulator {
+    key: i64,
+    sum: f64,
+    max: f64,
+    count: u32,
+}
+
+impl Accumulator {
+    /// Finalises the accumulator into `(bucket start x, bucket)`.
+    fn finish(&self, width: f64) -> (f64, Bucket) {
+        let bucket = Bucket {
+            mean: self.sum / f64::from(self.count),
+            max: self.max,
+        };
+        (self.key as f64 * width, bucket)
+    }
+}
+
+/// Bounded series of fixed-width buckets aggregated from finer samples.
+#[derive(Debug, Clone)]
+pub struct Rollup {
+    /// Bucket width in x units (e.g. 60 for minute buckets of 1 s samples).
+    width: f64,
+    capacity: usize,
+    buckets: VecDeque<(f64, Bucket)>,
+    pending: Option<Accumulator>,
+}
+
+impl Rollup {
+    /// Creates an empty rollup keeping at most `capacity` finished buckets.
+    pub fn new(width: f64, capacity: usize) -> Self {
+        Self {
+            width,
+            capacity,
+            buckets: VecDeque::with_capacity(capacity),
+            pending: None,
+        }
+    }
+
+    /// Adds a sample at `x`; crossing a bucket boundary finishes the previous bucket.
+    ///
+    /// Samples must arrive in non-decreasing `x` order.
+    pub fn push(&mut self, x: f64, y: f64) {
+        let key = (x / self.width).floor() as i64;
+
+        match &mut self.pending {
+            Some(acc) if acc.key == key => {
+                acc.sum += y;
+                acc.max = acc.max.max(y);
+                acc.count += 1;
+            }
+            _ => {
+                self.flush();
+                self.pending = Some(Accumulator {
+                    key,
+                    sum: y,
+                    max: y,
+                    count: 1,
+                });
+            }
+        }
+    }
+
+    /// Finishes the partially filled bucket, if any.
+    fn flush(&mut self) {
+        if let Some(acc) = self.pending.take() {
+            if self.buckets.len() >= self.capacity {
+                self.buckets.pop_front();
+            }
+            self.buckets.push_back(acc.finish(self.width));
+        }
+    }
+
+    /// Returns every bucket, followed by `recent` samples aggregated the same way.
+    ///
+    /// `recent` is typically the fine tier's retained points, which have
+    /// not rolled off yet; merging them gives a complete coarse view.
+    pub fn merged(&self, recent: impl Iterator<Item = (f64, f64)>) -> Vec<(f64, Bucket)> {
+        let mut all = self.clone();
+        // The merged view is transient, so it never evicts.
+        all.capacity = usize::MAX;
+        for (x, y) in recent {
+            all.push(x, y);
+        }
+        all.flush();
+        all.buckets.into()
+    }
+
+    /// [`Rollup::merged`], keeping the buckets that start in the `span`
+    /// before `right`, with x measured from the window's left edge like
+    /// [`Series::window_points`].
+    ///
+    /// Buckets are placed by whole widths back from the newest one.
+    pub fn merged_window(
+        &self,
+        recent: impl Iterator<Item = (f64, f64)>,
+        right: f64,
+        span: f64,
+    ) -> Vec<(f64, Bucket)> {
+        let all = self.merged(recent);
+        let Some(&(newest, _)) = all.last() else {
+            return all;
+        };
+        let lead = right - newest;
+        all.into_iter()
+            .map(|(x, bucket)| {
+                let back = ((newest - x) / self.width).round() * self.width;
+                (span - lead - back, bucket)
+            })
+            .filter(|&(x, _)| x >= 0.0)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn points_after_wrap_around_eviction() {
+        let mut series = Series::new(10.0, 1.0, 4);
+        let mut evicted = Vec::new();
+        for i in 0..7 {
+            evicted.extend(series.push_at(10.0 + f64::from(i), f64::from(i)));
+        }
+        assert_eq!(evicted, [(10.0, 0.0), (11.0, 1.0), (12.0, 2.0)]);
+        assert_eq!(
+            series.points().collect::<Vec<_>>(),
+            [(13.0, 3.0), (14.0, 4.0), (15.0, 5.0), (16.0, 6.0)]
+        );
+        assert_eq!(
+            (series.start(), series.end(), series.len()),
+            (13.0, 16.0, 4)
+        );
+    }
+
+    #[test]
+    fn gaps_hold_the_new_sample_and_long_gaps_evict_everything() {
+        let mut series = Series::new(0.0, 1.0, 4);
+        series.push_at(0.0, 1.0);
+        series.push_at(1.0, 2.0);
+        // Two slots late: both are filled with the new value.
+        assert_eq!(series.push_at(3.0, 5.0), []);
+        assert_eq!(series.values().collect::<Vec<_>>(), [1.0, 2.0, 5.0, 5.0]);
+        // A sample in the last slot replaces it.
+        series.push_at(3.2, 6.0);
+        assert_eq!(series.at(3.0), Some(6.0));
+
+        let evicted = series.push_at(100.0, 9.0);
+        assert_eq!(evicted, [(0.0, 1.0), (1.0, 2.0), (2.0, 5.0), (3.0, 6.0)]);
+        assert_eq!(
+            series.points().collect::<Vec<_>>(),
+            [(97.0, 9.0), (98.0, 9.0), (99.0, 9.0), (100.0, 9.0)]
+        );
+    }
+
+    fn bucket(mean: f64, max: f64) -> Bucket {
+        Bucket { mean, max }
+    }
+
+    #[test]
+    fn rollup_buckets_keep_mean_and_max() {
+        let mut rollup = Rollup::new(60.0, 2);
+        for (x, y) in [(0.0, 1.0), (30.0, 3.0), (59.0, 2.0), (60.0, 10.0)] {
+            rollup.push(x, y);
+        }
+        // The bucket being filled is only finished by `merged`.
+        assert_eq!(
+            rollup.merged(std::iter::empty()),
+            [(0.0, bucket(2.0, 3.0)), (60.0, bucket(10.0, 10.0))]
+        );
+        // Recent samples join the open bucket and start new ones.
+        assert_eq!(
+            rollup.merged([(61.0, 20.0), (130.0, 4.0)].into_iter()),
+            [
+                (0.0, bucket(2.0, 3.0)),
+                (60.0, bucket(15.0, 20.0)),
+                (120.0, bucket(4.0, 4.0)),
+            ]
+        );
+        // Merging never changes the rollup itself.
+        assert_eq!(rollup.merged(std::iter::empty()).len(), 2);
+    }
+
+    #[test]
+    fn rollup_evicts_the_oldest_finished_bucket() {
+        let mut rollup = Rollup::new(60.0, 2);
+        for x in [0.0, 60.0, 120.0, 180.0] {
+            rollup.push(x, x);
+        }
+        let starts: Vec<f64> = rollup
+            .merged(std::iter::empty())
+            .iter()
+            .map(|&(x, _)| x)
+            .collect();
+        assert_eq!(starts, [60.0, 120.0, 180.0]);
+    }
+
+    #[test]
+    fn range_clips_to_the_retained_history() {
+        let mut series = Series::new(6.0, 2.0, 5);
+        for i in 0..7 {
+            series.push_at(6.0 + f64::from(i) * 2.0, f64::from(i));
+        }
+        // Slots 6 and 8 have been evicted; 10..=18 remain.
+        let xs = |from, to| series.range(from, to).map(|(x, _)| x).collect::<Vec<_>>();
+        assert_eq!(xs(4.0, 13.0), [10.0, 12.0]);
+        assert_eq!(xs(15.0, 40.0), [16.0, 18.0]);
+        assert_eq!(xs(0.0, 100.0), [10.0, 12.0, 14.0, 16.0, 18.0]);
+        assert_eq!(xs(12.0, 12.0), [12.0]);
+        assert!(xs(0.0, 9.0).is_empty());
+        assert!(xs(19.0, 30.0).is_empty());
+        assert!(xs(13.0, 13.5).is_empty());
+        assert!(xs(14.0, 10.0).is_empty());
+        assert_eq!(series.range(13.0, 15.0).collect::<Vec<_>>(), [(14.0, 4.0)]);
+    }
+}
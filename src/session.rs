@@ -0,0 +1,151 @@
+//! Session-wide per-process CPU accounting.
+//!
+//! Instantaneous CPU% misses a process that spiked briefly and went
+//! quiet. [`CpuAccumulator`] integrates each PID's CPU usage over the
+//! ticks it was observed, so the session's heaviest consumers can be
+//! ranked afterwards. Exited PIDs are folded into a departed total to
+//! keep the map bounded, with a short leaderboard kept so heavy
+//! short-lived processes still make the report. Entries are keyed by
+//! PID and start time, so a reused PID starts from zero and the process
+//! that held it before counts as departed.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::monitor::Pid;
+
+/// Number of entries in the top-offenders report.
+pub const TOP_OFFENDERS: usize = 10;
+
+/// CPU time accumulated by one process.
+#[derive(Debug, Clone)]
+pub struct Accumulated {
+    /// Process identifier.
+    pub pid: Pid,
+    /// Name when last observed.
+    pub name: String,
+    /// CPU-seconds observed during the session.
+    pub cpu_secs: f64,
+    /// Whether the process has exited.
+    pub departed: bool,
+}
+
+/// Per-PID CPU-seconds observed during this session.
+#[derive(Debug, Default)]
+pub struct CpuAccumulator {
+    /// Start time and CPU time per live PID.
+    live: HashMap<Pid, (u64, Accumulated)>,
+    /// CPU-seconds of every process that has exited.
+    departed_secs: f64,
+    /// Heaviest exited processes, at most [`TOP_OFFENDERS`].
+    departed_top: Vec<Accumulated>,
+}
+
+impl CpuAccumulator {
+    /// Adds `cpu_pct` (percent of one core) sustained for `secs` to `pid`,
+    /// which started at `start_time`.
+    ///
+    /// Returns the PID's new session total in CPU-seconds.
+    pub fn add(&mut self, pid: Pid, start_time: u64, name: &str, cpu_pct: f32, secs: f64) -> f64 {
+        if let Some(&(started, _)) = self.live.get(&pid)
+            && started != start_time
+            && let Some((_, acc)) = self.live.remove(&pid)
+        {
+            self.depart(acc);
+        }
+        let (_, entry) = self.live.entry(pid).or_insert_with(|| {
+            let acc = Accumulated {
+                pid,
+                name: name.to_string(),
+                cpu_secs: 0.0,
+                departed: false,
+            };
+            (start_time, acc)
+        });
+        if entry.name != name {
+            entry.name = name.to_string();
+        }
+        entry.cpu_secs += f64::from(cpu_pct) / 100.0 * secs;
+        entry.cpu_secs
+    }
+
+    /// Moves PIDs that are no longer alive into the departed totals.
+    pub fn prune(&mut self, alive: &HashSet<Pid>) {
+        let gone: Vec<Pid> = self
+            .live
+            .keys()
+            .filter(|pid| !alive.contains(pid))
+            .copied()
+            .collect();
+
+        for pid in gone {
+            if let Some((_, acc)) = self.live.remove(&pid) {
+                self.depart(acc);
+            }
+        }
+    }
+
+    /// Folds an exited process into the departed totals.
+    fn depart(&mut self, mut acc: Accumulated) {
+        self.departed_secs += acc.cpu_secs;
+        acc.departed = true;
+        self.departed_top.push(acc);
+        sort_heaviest(&mut self.departed_top);
+        self.departed_top.truncate(TOP_OFFENDERS);
+    }
+
+    /// CPU-seconds of every process observed this session, live or exited.
+    pub fn total_secs(&self) -> f64 {
+        self.departed_secs + self.live.values().map(|(_, a)| a.cpu_secs).sum::<f64>()
+    }
+
+    /// CPU-seconds of processes that have exited.
+    pub fn departed_secs(&self) -> f64 {
+        self.departed_secs
+    }
+
+    /// The `n` heaviest processes of the session, live and exited.
+    pub fn top(&self, n: usize) -> Vec<Accumulated> {
+        let mut all: Vec<Accumulated> = self
+            .live
+            .values()
+            .map(|(_, a)| a)
+            .chain(&self.departed_top)
+            .cloned()
+            .collect();
+        sort_heaviest(&mut all);
+        all.truncate(n);
+        all
+    }
+}
+
+/// Sorts by descending CPU-seconds.
+fn sort_heaviest(list: &mut [Accumulated]) {
+    list.sort_by(|a, b| b.cpu_secs.total_cmp(&a.cpu_secs));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_reused_pid_starts_over_and_departs_the_old_process() {
+        let mut session = CpuAccumulator::default();
+        assert_eq!(session.add(42, 100, "make", 200.0, 3.0), 6.0);
+        assert_eq!(session.add(42, 100, "make", 100.0, 1.0), 7.0);
+        // Same PID, new start time: another process.
+        assert_eq!(session.add(42, 250, "cc", 50.0, 2.0), 1.0);
+        assert_eq!(session.departed_secs(), 7.0);
+        assert_eq!(session.total_secs(), 8.0);
+
+        let top = session.top(TOP_OFFENDERS);
+        let summary: Vec<_> = top
+            .iter()
+            .map(|a| (a.pid, a.name.as_str(), a.cpu_secs, a.departed))
+            .collect();
+        assert_eq!(summary, [(42, "make", 7.0, true), (42, "cc", 1.0, false)]);
+
+        session.prune(&HashSet::new());
+        assert_eq!(session.departed_secs(), 8.0);
+        assert_eq!(session.total_secs(), 8.0);
+    }
+}
@@ -0,0 +1,298 @@
+//! Point-in-time copy of the monitor's state for machine-readable output.
+//!
+//! A [`Snapshot`] holds plain data only (no UI state), so it can be
+//! handed to other threads such as the query socket and encoded as JSON
+//! there without touching [`App`]. [`Monitor::sample`] produces one per
+//! call for embedders; [`Snapshot::from_app`] re-captures the app's
+//! state in table order.
+//!
+//! [`Monitor::sample`]: crate::monitor::Monitor::sample
+//!
+//! Member names in the JSON encoding are part of the interface: scripts
+//! key on them, so they are never renamed, and any change to the layout
+//! bumps [`SCHEMA_VERSION`].
+
+use std::time::SystemTime;
+
+use crate::app::App;
+use crate::collector::{Compressor, MemoryPressure};
+use crate::config::MemoryMode;
+use crate::json::{self, Object};
+use crate::monitor::ProcessSample;
+use crate::session::{Accumulated, TOP_OFFENDERS};
+use crate::view::{COLUMNS, SortKey};
+
+/// Version of the snapshot JSON layout; bumped when fields change meaning.
+///
+/// 2 added `wired`, `compressed`, `cached`, and `pressure` to `memory`;
+/// 3 added `thread_growth` to processes.
+pub const SCHEMA_VERSION: u32 = 3;
+
+/// System-wide metrics and the process list from one tick.
+#[derive(Debug, Clone)]
+pub struct Snapshot {
+    /// When the tick ran.
+    pub time: SystemTime,
+    /// User CPU percentage.
+    pub cpu_user: f64,
+    /// System CPU percentage.
+    pub cpu_system: f64,
+    /// Idle CPU percentage.
+    pub cpu_idle: f64,
+    /// Utilization of the busiest core, when per-core sampling is on.
+    pub max_core: Option<f64>,
+    /// Used memory in bytes, per `memory_mode`.
+    pub mem_used: u64,
+    /// Total memory in bytes.
+    pub mem_total: u64,
+    /// How `mem_used` was measured.
+    pub memory_mode: MemoryMode,
+    /// Used swap in bytes.
+    pub swap_used: u64,
+    /// Total swap in bytes.
+    pub swap_total: u64,
+    /// Wired kernel memory in bytes.
+    pub wired: Option<u64>,
+    /// Compressor-held memory in bytes.
+    pub compressed: Option<u64>,
+    /// File-backed cached memory in bytes.
+    pub cached: Option<u64>,
+    /// Compressor sizes and activity counters.
+    pub compressor: Option<Compressor>,
+    /// Kernel memory-pressure level.
+    pub memory_pressure: Option<MemoryPressure>,
+    /// Threads across inspectable processes.
+    pub threads: usize,
+    /// Every process; in the table's order when taken from the app.
+    pub processes: Vec<ProcessSample>,
+    /// Whether the process list was skipped this sample in high-count
+    /// mode; `processes` is then empty and the previous list still holds.
+    pub processes_skipped: bool,
+    /// Heaviest CPU consumers of the session, live and exited.
+    pub top_offenders: Vec<Accumulated>,
+}
+
+impl Snapshot {
+    /// Captures `app`'s state after its latest tick.
+    pub fn from_app(app: &App) -> Self {
+        Self {
+            time: SystemTime::now(),
+            cpu_user: app.user_pct,
+            cpu_system: app.system_pct,
+            cpu_idle: app.idle_pct,
+            max_core: app.max_core_pct,
+            mem_used: app.used_memory,
+            mem_total: app.total_memory,
+            memory_mode: app.memory_mode,
+            swap_used: app.used_swap,
+            swap_total: app.total_swap,
+            wired: app.wired_memory,
+            compressed: app.compressed_memory,
+            cached: app.cached_memory,
+            compressor: app.compressor,
+            memory_pressure: app.memory_pressure,
+            threads: app.thread_count,
+            processes: app.processes.clone(),
+            processes_skipped: false,
+            top_offenders: app.monitor.session().top(TOP_OFFENDERS),
+        }
+    }
+
+    /// The process using the most CPU, if any.
+    pub fn top_cpu(&self) -> Option<&ProcessSample> {
+        self.processes
+            .iter()
+            .max_by(|a, b| a.cpu_usage.total_cmp(&b.cpu_usage))
+    }
+
+    /// Encodes the full snapshot as one JSON object.
+    pub fn to_json(&self) -> String {
+        let processes = json::array(self.processes.iter().map(process_json));
+        self.header()
+            .raw("processes", &processes)
+            .raw(
+                "top_offenders",
+                &json::array(self.top_offenders.iter().map(offender_json)),
+            )
+            .finish()
+    }
+
+    /// Encodes the system metrics plus the `n` largest processes by `key`.
+    pub fn top_json(&self, n: usize, key: SortKey) -> String {
+        self.top(n, key).finish()
+    }
+
+    /// Encodes one `--stream` line: the `top` layout for the `n` busiest
+    /// processes, with `"type": "snapshot"` to tell it from other lines.
+    pub fn stream_json(&self, n: usize) -> String {
+        self.top(n, SortKey::Cpu).str("type", "snapshot").finish()
+    }
+
+    /// The [`Snapshot::top_json`] members, unfinished.
+    fn top(&self, n: usize, key: SortKey) -> Object {
+        let mut procs: Vec<&ProcessSample> = self.processes.iter().collect();
+        procs.sort_by(|a, b| {
+            let ord = key.compare(a, b);
+            if key.default_descending() {
+                ord.reverse()
+            } else {
+                ord
+            }
+        });
+        procs.truncate(n);
+
+        self.header()
+            .str("key", COLUMNS[key.column_index()].id)
+            .raw(
+                "processes",
+                &json::array(procs.into_iter().map(process_json)),
+            )
+    }
+
+    /// Members shared by every snapshot encoding.
+    fn header(&self) -> Object {
+        Object::new()
+            .int("schema", SCHEMA_VERSION)
+            .str("timestamp", &json::timestamp(self.time))
+            .raw(
+                "cpu",
+                &Object::new()
+                    .num("user", self.cpu_user)
+                    .num("system", self.cpu_system)
+                    .num("idle", self.cpu_idle)
+                    .finish(),
+            )
+            .raw(
+                "memory",
+                &Object::new()
+                    .int("used", self.mem_used)
+                    .int("total", self.mem_total)
+                    .int("swap_used", self.swap_used)
+                    .int("swap_total", self.swap_total)
+                    .opt("wired", self.wired.map(|b| b.to_string()))
+                    .opt("compressed", self.compressed.map(|b| b.to_string()))
+                    .opt("cached", self.cached.map(|b| b.to_string()))
+                    .opt(
+                        "pressure",
+                        self.memory_pressure.map(|p| json::string(p.id())),
+                    )
+                    .finish(),
+            )
+            .int("threads", self.threads as u64)
+            .int("process_count", self.processes.len() as u64)
+    }
+}
+
+/// Encodes one process.
+fn process_json(p: &ProcessSample) -> String {
+    Object::new()
+        .int("pid", p.pid)
+        .opt("parent", p.parent.map(|pid| pid.to_string()))
+        .str("name", &p.name)
+        .str("status", &p.status.to_string().to_lowercase())
+        .num("cpu", f64::from(p.cpu_usage))
+        .num("cpu_time", p.cpu_time)
+        .int("memory", p.memory)
+        .opt("threads", p.threads.map(|t| t.to_string()))
+        .opt("running", p.running.map(|n| n.to_string()))
+        .opt("thread_growth", p.thread_growth.map(json::number))
+        .finish()
+}
+
+/// Encodes one session top-offender entry.
+fn offender_json(a: &Accumulated) -> String {
+    Object::new()
+        .int("pid", a.pid)
+        .str("name", &a.name)
+        .num("cpu_secs", a.cpu_secs)
+        .raw("exited", if a.departed { "true" } else { "false" })
+        .finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, UNIX_EPOCH};
+
+    use super::*;
+
+    fn snapshot() -> Snapshot {
+        let mut shell = ProcessSample::new(310, "zsh");
+        shell.parent = Some(1);
+        shell.cpu_usage = 1.5;
+        shell.memory = 4096;
+        let mut build = ProcessSample::new(420, "cargo \"build\"");
+        build.cpu_usage = 97.25;
+        build.cpu_time = 12.5;
+        build.threads = None;
+        build.running = None;
+        build.thread_growth = Some(-0.5);
+        Snapshot {
+            time: UNIX_EPOCH + Duration::from_millis(1_700_000_000_250),
+            cpu_user: 40.5,
+            cpu_system: 9.5,
+            cpu_idle: 50.0,
+            max_core: None,
+            mem_used: 6 << 30,
+            mem_total: 16 << 30,
+            memory_mode: MemoryMode::Sysinfo,
+            swap_used: 0,
+            swap_total: 2 << 30,
+            wired: Some(1 << 30),
+            compressed: None,
+            cached: None,
+            compressor: None,
+            memory_pressure: Some(MemoryPressure::Normal),
+            threads: 3,
+            processes: vec![shell, build],
+            processes_skipped: false,
+            top_offenders: vec![Accumulated {
+                pid: 99,
+                name: "make".to_string(),
+                cpu_secs: 30.0,
+                departed: true,
+            }],
+        }
+    }
+
+    /// `to_json`'s header, shared by every encoding.
+    const HEADER: &str = concat!(
+        r#"{"schema":3,"timestamp":"2023-11-14T22:13:20.250Z","#,
+        r#""cpu":{"user":40.5,"system":9.5,"idle":50},"#,
+        r#""memory":{"used":6442450944,"total":17179869184,"swap_used":0,"swap_total":2147483648,"#,
+        r#""wired":1073741824,"compressed":null,"cached":null,"pressure":"normal"},"#,
+        r#""threads":3,"process_count":2,"#,
+    );
+
+    #[test]
+    fn full_snapshot_layout() {
+        let expected = format!(
+            "{HEADER}{}",
+            concat!(
+                r#""processes":["#,
+                r#"{"pid":310,"parent":1,"name":"zsh","status":"sleeping","cpu":1.5,"cpu_time":0,"#,
+                r#""memory":4096,"threads":1,"running":0,"thread_growth":null},"#,
+                r#"{"pid":420,"parent":null,"name":"cargo \"build\"","status":"sleeping","cpu":97.25,"#,
+                r#""cpu_time":12.5,"memory":0,"threads":null,"running":null,"thread_growth":-0.5}],"#,
+                r#""top_offenders":[{"pid":99,"name":"make","cpu_secs":30,"exited":true}]}"#,
+            )
+        );
+        assert_eq!(snapshot().to_json(), expected);
+    }
+
+    #[test]
+    fn top_and_stream_layouts() {
+        let busiest = concat!(
+            r#""key":"cpu","processes":[{"pid":420,"parent":null,"name":"cargo \"build\"","#,
+            r#""status":"sleeping","cpu":97.25,"cpu_time":12.5,"memory":0,"threads":null,"#,
+            r#""running":null,"thread_growth":-0.5}]"#,
+        );
+        assert_eq!(
+            snapshot().top_json(1, SortKey::Cpu),
+            format!("{HEADER}{busiest}}}")
+        );
+        assert_eq!(
+            snapshot().stream_json(1),
+            format!(r#"{HEADER}{busiest},"type":"snapshot"}}"#)
+        );
+    }
+}
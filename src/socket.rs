@@ -0,0 +1,221 @@
+//! Local query socket for scripting against a running syswatch.
+//!
+//! A Unix domain socket accepts one connection at a time; each request
+//! is a single line and each reply a single line of JSON:
+//!
+//! - `snapshot` — the latest [`Snapshot`]
+//! - `top [n=10] [key=cpu]` — system metrics plus the top `n` processes
+//! - `health` — uptime, sample age, and the collector probes
+//!
+//! The event loop publishes a fresh snapshot each tick, so queries never
+//! touch the collectors themselves (except `health`'s probes).
+
+use std::fs;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::os::unix::fs::PermissionsExt;
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime};
+
+use crate::cleanup::remove_on_panic;
+use crate::collector::collectors;
+use crate::json::{self, Object};
+use crate::snapshot::Snapshot;
+use crate::view::{COLUMNS, SortKey, column_by_id};
+
+/// Longest accepted request line in bytes.
+const MAX_REQUEST: usize = 1024;
+
+/// How long a connection may sit idle before it is dropped.
+const CLIENT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Processes returned by `top` when `n` is not given.
+const DEFAULT_TOP: usize = 10;
+
+/// Default socket location: `~/Library/Application Support/syswatch/sock`.
+pub fn default_path() -> Option<PathBuf> {
+    let home = std::env::var_os("HOME")?;
+    Some(
+        PathBuf::from(home)
+            .join("Library")
+            .join("Application Support")
+            .join("syswatch")
+            .join("sock"),
+    )
+}
+
+/// Latest snapshot shared between the event loop and the listener.
+type Shared = Arc<Mutex<Option<Snapshot>>>;
+
+/// A listening query socket; the socket file is removed on drop.
+#[derive(Debug)]
+pub struct Server {
+    path: PathBuf,
+    latest: Shared,
+}
+
+impl Server {
+    /// Binds `path` and starts the listener thread.
+    ///
+    /// A stale socket file left by a crashed instance is replaced; one a
+    /// live instance is still answering on is an `AddrInUse` error.
+    pub fn start(path: PathBuf) -> io::Result<Self> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        if path.exists() {
+            if UnixStream::connect(&path).is_ok() {
+                return Err(io::Error::new(
+                    io::ErrorKind::AddrInUse,
+                    format!("another syswatch is listening on {}", path.display()),
+                ));
+            }
+            fs::remove_file(&path)?;
+        }
+
+        let listener = UnixListener::bind(&path)?;
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o600))?;
+        remove_on_panic(path.clone());
+
+        let latest = Shared::default();
+        let shared = Arc::clone(&latest);
+        let started = Instant::now();
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                // A misbehaving client only loses its own connection.
+                let _ = serve(stream, &shared, started);
+            }
+        });
+
+        Ok(Self { path, latest })
+    }
+
+    /// Replaces the snapshot served to clients.
+    pub fn publish(&self, snapshot: Snapshot) {
+        *self.latest.lock().unwrap_or_else(PoisonError::into_inner) = Some(snapshot);
+    }
+}
+
+impl Drop for Server {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+/// Answers requests on one connection until it closes.
+fn serve(stream: UnixStream, latest: &Shared, started: Instant) -> io::Result<()> {
+    stream.set_read_timeout(Some(CLIENT_TIMEOUT))?;
+    stream.set_write_timeout(Some(CLIENT_TIMEOUT))?;
+    let mut reader = BufReader::new(&stream);
+    let mut writer = &stream;
+    let mut line = Vec::new();
+
+    loop {
+        line.clear();
+        let n = (&mut reader)
+            .take(MAX_REQUEST as u64 + 1)
+            .read_until(b'\n', &mut line)?;
+        if n == 0 {
+            return Ok(());
+        }
+        if line.last() != Some(&b'\n') && n > MAX_REQUEST {
+            let reply = error(&format!("request exceeds {MAX_REQUEST} bytes"));
+            return writeln!(writer, "{reply}");
+        }
+
+        let request = String::from_utf8_lossy(&line);
+        let reply = respond(request.trim(), latest, started);
+        writeln!(writer, "{reply}")?;
+    }
+}
+
+/// Builds the JSON reply for one request line.
+fn respond(request: &str, latest: &Shared, started: Instant) -> String {
+    let mut words = request.split_whitespace();
+    let Some(command) = words.next() else {
+        return error("empty request");
+    };
+
+    let guard = latest.lock().unwrap_or_else(PoisonError::into_inner);
+    match command {
+        "snapshot" => match guard.as_ref() {
+            Some(snapshot) => snapshot.to_json(),
+            None => error("no sample collected yet"),
+        },
+        "top" => {
+            let (n, key) = match parse_top(words) {
+                Ok(args) => args,
+                Err(msg) => return error(&msg),
+            };
+            match guard.as_ref() {
+                Some(snapshot) => snapshot.top_json(n, key),
+                None => error("no sample collected yet"),
+            }
+        }
+        "health" => {
+            let age = guard
+                .as_ref()
+                .and_then(|s| SystemTime::now().duration_since(s.time).ok())
+                .map(|d| json::number(d.as_secs_f64()));
+            drop(guard);
+            health(started, age)
+        }
+        other => error(&format!(
+            "unknown command `{other}` (expected snapshot, top, or health)"
+        )),
+    }
+}
+
+/// Parses `n=<count>` and `key=<column>` arguments of `top`.
+fn parse_top<'a>(args: impl Iterator<Item = &'a str>) -> Result<(usize, SortKey), String> {
+    let mut n = DEFAULT_TOP;
+    let mut key = SortKey::Cpu;
+    for arg in args {
+        match arg.split_once('=') {
+            Some(("n", value)) => {
+                n = value
+                    .parse()
+                    .map_err(|_| format!("invalid count `{value}`"))?;
+            }
+            Some(("key", value)) => {
+                let id = if value == "mem" { "memory" } else { value };
+                key = column_by_id(id).map(|i| COLUMNS[i].sort).ok_or_else(|| {
+                    let known: Vec<_> = COLUMNS.iter().map(|c| c.id).collect();
+                    format!(
+                        "unknown key `{value}` (expected one of {})",
+                        known.join(", ")
+                    )
+                })?;
+            }
+            _ => return Err(format!("unknown argument `{arg}` (expected n=… or key=…)")),
+        }
+    }
+    Ok((n, key))
+}
+
+/// Uptime, snapshot age, and a fresh probe of every collector.
+fn health(started: Instant, sample_age: Option<String>) -> String {
+    let probes = collectors().into_iter().map(|c| {
+        let probe = c.probe();
+        Object::new()
+            .str("name", c.name())
+            .str("health", &probe.health.label().to_lowercase())
+            .str("detail", &probe.detail)
+            .finish()
+    });
+    Object::new()
+        .str("status", "ok")
+        .str("version", env!("CARGO_PKG_VERSION"))
+        .int("pid", std::process::id())
+        .num("uptime_secs", started.elapsed().as_secs_f64())
+        .opt("sample_age_secs", sample_age)
+        .raw("collectors", &json::array(probes))
+        .finish()
+}
+
+/// An `{"error": …}` reply.
+fn error(msg: &str) -> String {
+    Object::new().str("error", msg).finish()
+}
@@ -0,0 +1,88 @@
+//! Interface choices remembered between sessions.
+//!
+//! Settings changed from inside the TUI, rather than in the config file,
+//! are kept in `~/Library/Application Support/syswatch/ui-state`, one
+//! `key value` line each. Today that is only the column layout picked in
+//! the column settings. The file is rewritten whenever such a choice is
+//! applied; a missing or unreadable file is an empty state, and lines or
+//! column ids it doesn't recognise are skipped, so the file never stops
+//! syswatch from starting.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::view::{COLUMNS, column_by_id};
+
+/// Default state file location.
+pub fn default_path() -> Option<PathBuf> {
+    let home = std::env::var_os("HOME")?;
+    Some(
+        PathBuf::from(home)
+            .join("Library")
+            .join("Application Support")
+            .join("syswatch")
+            .join("ui-state"),
+    )
+}
+
+/// Everything the state file records.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct UiState {
+    /// Visible columns, in display order, as indices into [`COLUMNS`].
+    pub columns: Option<Vec<usize>>,
+}
+
+impl UiState {
+    /// Reads the state at `path`; anything unreadable yields no state.
+    pub fn load(path: &Path) -> Self {
+        fs::read_to_string(path)
+            .map(|text| Self::decode(&text))
+            .unwrap_or_default()
+    }
+
+    /// Writes the state to `path`, creating its directory.
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(path, self.encode())
+    }
+
+    /// Encodes the state as lines: `columns <id>,<id>,…`.
+    ///
+    /// ```
+    /// use syswatch::state::UiState;
+    ///
+    /// let state = UiState { columns: Some(vec![2, 0, 1]) };
+    /// assert_eq!(state.encode(), "columns cpu,pid,name\n");
+    /// assert_eq!(UiState::decode(&state.encode()), state);
+    /// // Unknown ids are dropped, not fatal.
+    /// assert_eq!(UiState::decode("columns cpu,gpu\n").columns, Some(vec![2]));
+    /// ```
+    pub fn encode(&self) -> String {
+        let mut text = String::new();
+        if let Some(columns) = &self.columns {
+            let ids: Vec<&str> = columns.iter().map(|&c| COLUMNS[c].id).collect();
+            text.push_str(&format!("columns {}\n", ids.join(",")));
+        }
+        text
+    }
+
+    /// Reads state lines back, skipping any it doesn't recognise.
+    pub fn decode(text: &str) -> Self {
+        let mut state = Self::default();
+        for line in text.lines() {
+            if let Some(("columns", ids)) = line.split_once(' ') {
+                let mut columns: Vec<usize> = Vec::new();
+                for c in ids.split(',').filter_map(|id| column_by_id(id.trim())) {
+                    if !columns.contains(&c) {
+                        columns.push(c);
+                    }
+                }
+                state.columns = (!columns.is_empty()).then_some(columns);
+            }
+        }
+        state
+    }
+}
@@ -0,0 +1,35 @@
+//! The `--stream` mode: JSON lines on stdout.
+//!
+//! Each tick writes one line per alert it fired ([`Alert::stream_json`],
+//! `"type": "alert"`), then a snapshot in the `top` layout of the query
+//! socket ([`Snapshot::stream_json`], `"type": "snapshot"`), so readers
+//! tell the two apart by `type`. Lines are flushed as they are
+//! written, for consumers like `jq` or a log shipper. A reader that goes
+//! away ends the stream quietly, as does Ctrl-C.
+//!
+//! [`Alert::stream_json`]: crate::alerts::Alert::stream_json
+
+use std::io::{self, Write};
+
+use crate::app::App;
+use crate::plain;
+use crate::snapshot::Snapshot;
+
+/// Writes each tick's alerts and snapshot, embedding the `processes`
+/// busiest processes, until Ctrl-C or until the reader closes the pipe.
+///
+/// `app` must have ticked once, so the first line has CPU deltas.
+pub fn run(app: &mut App, processes: usize, out: &mut impl Write) -> io::Result<()> {
+    let result = plain::every_tick(app, |app| {
+        for alert in &app.events {
+            writeln!(out, "{}", alert.stream_json())?;
+        }
+        let line = Snapshot::from_app(app).stream_json(processes);
+        writeln!(out, "{line}")?;
+        out.flush()
+    });
+    match result {
+        Err(err) if err.kind() == io::ErrorKind::BrokenPipe => Ok(()),
+        other => other,
+    }
+}
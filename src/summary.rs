@@ -0,0 +1,68 @@
+//! Session summary printed after the TUI exits.
+
+use std::io::{self, Write};
+
+use crate::app::App;
+use crate::battery::fmt_remaining;
+use crate::session::TOP_OFFENDERS;
+
+/// Writes the session length, CPU totals, battery use, and top CPU consumers to `out`.
+pub fn write(app: &App, out: &mut impl Write) -> io::Result<()> {
+    let elapsed = app.started.elapsed().as_secs();
+    let cpu = app.monitor.session();
+
+    writeln!(
+        out,
+        "syswatch session: {}h {:02}m {:02}s, {:.1} CPU-seconds observed ({:.1} from exited processes)",
+        elapsed / 3600,
+        elapsed / 60 % 60,
+        elapsed % 60,
+        cpu.total_secs(),
+        cpu.departed_secs()
+    )?;
+
+    let late = app.ticker.late_ticks();
+    if late > 0 {
+        writeln!(
+            out,
+            "{late} late ticks ({} samples skipped), longest interval {:.1}s",
+            app.ticker.skipped_ticks(),
+            app.ticker.longest_interval().as_secs_f64()
+        )?;
+    }
+
+    let battery = &app.battery;
+    if battery.consumed > 0.0 {
+        let mut line = format!("battery: {:.1}% used on battery", battery.consumed);
+        if let Some(rate) = battery.drain_rate() {
+            line.push_str(&format!(", draining {rate:.1}%/h"));
+        }
+        if let Some(remaining) = battery.time_to_empty() {
+            line.push_str(&format!(", about {} left", fmt_remaining(remaining)));
+        }
+        writeln!(out, "{line}")?;
+    }
+
+    let hidden = app.view.hidden;
+    let top: Vec<_> = cpu
+        .top(TOP_OFFENDERS + 1)
+        .into_iter()
+        .filter(|acc| Some(acc.pid) != hidden)
+        .take(TOP_OFFENDERS)
+        .collect();
+    if top.is_empty() {
+        return Ok(());
+    }
+
+    writeln!(out, "\nTop CPU consumers this session:")?;
+    writeln!(out, "  {:>8}  {:>10}  NAME", "PID", "CPU-SEC")?;
+    for acc in top {
+        let exited = if acc.departed { "  (exited)" } else { "" };
+        writeln!(
+            out,
+            "  {:>8}  {:>10.1}  {}{exited}",
+            acc.pid, acc.cpu_secs, acc.name
+        )?;
+    }
+    Ok(())
+}
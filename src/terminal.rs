@@ -0,0 +1,189 @@
+//! Deciding whether the full-screen TUI can run at all.
+//!
+//! The TUI needs a terminal on both stdin and stdout, a `TERM` with
+//! cursor addressing, and raw mode plus the alternate screen. Where any
+//! of these is missing — output piped to a file, `TERM=dumb`, some IDE
+//! consoles — syswatch falls back to `--plain` reports with a one-line
+//! reason instead of failing half-way into raw mode. The environment is
+//! read into a [`Probe`] and the terminal set up through a closure, so
+//! the decision doesn't depend on what the process is attached to.
+
+use std::fmt;
+use std::io::{self, IsTerminal};
+
+use ratatui::DefaultTerminal;
+
+/// `TERM` values known to lack cursor addressing.
+const DUMB_TERMS: [&str; 2] = ["dumb", "unknown"];
+
+/// What the process is attached to, as seen at startup.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Probe {
+    /// Whether stdin is a terminal.
+    pub stdin_tty: bool,
+    /// Whether stdout is a terminal.
+    pub stdout_tty: bool,
+    /// The `TERM` variable, if set.
+    pub term: Option<String>,
+}
+
+impl Probe {
+    /// Reads the probe from the real stdin, stdout, and environment.
+    pub fn detect() -> Self {
+        Self {
+            stdin_tty: io::stdin().is_terminal(),
+            stdout_tty: io::stdout().is_terminal(),
+            term: std::env::var("TERM").ok(),
+        }
+    }
+
+    /// Why the TUI can't run here, or `None` if nothing rules it out
+    /// before raw mode is tried.
+    pub fn unsupported(&self) -> Option<Unsupported> {
+        if !self.stdout_tty {
+            return Some(Unsupported::NotATerminal("stdout"));
+        }
+        if !self.stdin_tty {
+            return Some(Unsupported::NotATerminal("stdin"));
+        }
+        match self.term.as_deref().map(str::trim) {
+            None | Some("") => Some(Unsupported::NoTerm),
+            Some(term) if DUMB_TERMS.contains(&term) => {
+                Some(Unsupported::DumbTerm(term.to_string()))
+            }
+            Some(_) => None,
+        }
+    }
+}
+
+/// Why the full-screen TUI can't run.
+#[derive(Debug)]
+pub enum Unsupported {
+    /// The named stream isn't a terminal.
+    NotATerminal(&'static str),
+    /// `TERM` isn't set.
+    NoTerm,
+    /// `TERM` names a terminal without cursor addressing.
+    DumbTerm(String),
+    /// Raw mode or the alternate screen couldn't be entered.
+    Setup(io::Error),
+}
+
+impl fmt::Display for Unsupported {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotATerminal(stream) => write!(f, "{stream} is not a terminal"),
+            Self::NoTerm => f.write_str("TERM is not set"),
+            Self::DumbTerm(term) => write!(f, "TERM={term} can't draw a full-screen display"),
+            Self::Setup(err) => write!(f, "the terminal refused raw mode: {err}"),
+        }
+    }
+}
+
+/// Checks `probe`, then sets the terminal up with `enter`.
+///
+/// When `enter` fails, `leave` runs to undo whatever it managed before
+/// failing, so the console isn't left in raw mode.
+pub fn open<T>(
+    probe: &Probe,
+    enter: impl FnOnce() -> io::Result<T>,
+    leave: impl FnOnce(),
+) -> Result<T, Unsupported> {
+    if let Some(reason) = probe.unsupported() {
+        return Err(reason);
+    }
+    enter().map_err(|err| {
+        leave();
+        Unsupported::Setup(err)
+    })
+}
+
+/// Enters raw mode and the alternate screen on the real terminal.
+pub fn init() -> Result<DefaultTerminal, Unsupported> {
+    open(&Probe::detect(), ratatui::try_init, ratatui::restore)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    /// A probe of a real terminal with `TERM` set to `term`.
+    fn tty(term: Option<&str>) -> Probe {
+        Probe {
+            stdin_tty: true,
+            stdout_tty: true,
+            term: term.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn probe_rules_out_pipes_and_dumb_terms() {
+        let piped_out = Probe {
+            stdout_tty: false,
+            ..tty(Some("xterm-256color"))
+        };
+        assert_eq!(
+            piped_out.unsupported().unwrap().to_string(),
+            "stdout is not a terminal"
+        );
+        let piped_in = Probe {
+            stdin_tty: false,
+            ..tty(Some("xterm-256color"))
+        };
+        assert_eq!(
+            piped_in.unsupported().unwrap().to_string(),
+            "stdin is not a terminal"
+        );
+        for term in [None, Some(""), Some("  ")] {
+            assert!(
+                matches!(tty(term).unsupported(), Some(Unsupported::NoTerm)),
+                "{term:?}"
+            );
+        }
+        for term in ["dumb", "unknown", " dumb "] {
+            assert_eq!(
+                tty(Some(term)).unsupported().unwrap().to_string(),
+                format!("TERM={} can't draw a full-screen display", term.trim())
+            );
+        }
+        assert!(tty(Some("xterm-256color")).unsupported().is_none());
+    }
+
+    #[test]
+    fn open_skips_setup_when_the_probe_fails() {
+        let entered = Cell::new(false);
+        let result = open(
+            &tty(Some("dumb")),
+            || {
+                entered.set(true);
+                Ok(())
+            },
+            || panic!("nothing to undo"),
+        );
+        assert!(matches!(result, Err(Unsupported::DumbTerm(_))));
+        assert!(!entered.get());
+    }
+
+    #[test]
+    fn failed_setup_is_undone() {
+        let left = Cell::new(false);
+        let result: Result<(), _> = open(
+            &tty(Some("xterm")),
+            || Err(io::Error::other("no raw mode")),
+            || left.set(true),
+        );
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "the terminal refused raw mode: no raw mode"
+        );
+        assert!(left.get());
+
+        let left = Cell::new(false);
+        assert_eq!(
+            open(&tty(Some("xterm")), || Ok(7), || left.set(true)).unwrap(),
+            7
+        );
+        assert!(!left.get());
+    }
+}
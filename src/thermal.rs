@@ -0,0 +1,110 @@
+//! Thermal pressure readings from the Darwin notification center.
+//!
+//! macOS publishes the system thermal pressure level as the state of the
+//! `com.apple.system.thermalpressurelevel` notification. Registering a
+//! check token once and polling its state is cheap enough to do per tick.
+//! Other platforms have no such source; [`ThermalMonitor::new`] returns
+//! `None` there and the throttling strip stays hidden.
+
+#[cfg(target_os = "macos")]
+use std::ffi::CStr;
+
+/// Notification whose state is the current thermal pressure level.
+#[cfg(target_os = "macos")]
+const THERMAL_PRESSURE_NOTIFICATION: &CStr = c"com.apple.system.thermalpressurelevel";
+
+/// `NOTIFY_STATUS_OK` from `<notify.h>`.
+#[cfg(target_os = "macos")]
+const NOTIFY_STATUS_OK: u32 = 0;
+
+#[cfg(target_os = "macos")]
+unsafe extern "C" {
+    unsafe fn notify_register_check(name: *const libc::c_char, out_token: *mut libc::c_int) -> u32;
+    unsafe fn notify_get_state(token: libc::c_int, state: *mut u64) -> u32;
+    unsafe fn notify_cancel(token: libc::c_int) -> u32;
+}
+
+/// Thermal pressure level, from no throttling to forced sleep.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ThermalLevel {
+    /// No thermal limits in effect.
+    Nominal,
+    /// Mild throttling.
+    Moderate,
+    /// Significant throttling.
+    Heavy,
+    /// CPU performance is being severely capped.
+    Trapping,
+    /// The machine is about to sleep to cool down.
+    Sleeping,
+}
+
+impl ThermalLevel {
+    /// Converts a notification state value; unknown values map to the nearest level.
+    #[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+    fn from_state(state: u64) -> Self {
+        match state {
+            0 => Self::Nominal,
+            1 => Self::Moderate,
+            2 => Self::Heavy,
+            3 => Self::Trapping,
+            _ => Self::Sleeping,
+        }
+    }
+
+    /// Converts a stored chart value back into a level.
+    pub fn from_value(value: f64) -> Self {
+        Self::from_state(value.round().max(0.0) as u64)
+    }
+
+    /// Numeric value stored in the chart history.
+    pub fn value(self) -> f64 {
+        self as u8 as f64
+    }
+}
+
+/// Registered check token for the thermal pressure notification.
+#[derive(Debug)]
+pub struct ThermalMonitor {
+    /// Token from `notify_register_check`.
+    #[cfg(target_os = "macos")]
+    token: libc::c_int,
+}
+
+#[cfg(target_os = "macos")]
+impl ThermalMonitor {
+    /// Registers for thermal pressure updates, or `None` if the OS doesn't publish them.
+    pub fn new() -> Option<Self> {
+        let mut token = 0;
+        let status =
+            unsafe { notify_register_check(THERMAL_PRESSURE_NOTIFICATION.as_ptr(), &mut token) };
+        (status == NOTIFY_STATUS_OK).then_some(Self { token })
+    }
+
+    /// Reads the current thermal pressure level.
+    pub fn level(&self) -> Option<ThermalLevel> {
+        let mut state = 0;
+        let status = unsafe { notify_get_state(self.token, &mut state) };
+        (status == NOTIFY_STATUS_OK).then(|| ThermalLevel::from_state(state))
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+impl ThermalMonitor {
+    /// Always `None`: there is no thermal pressure source on this platform.
+    pub fn new() -> Option<Self> {
+        None
+    }
+
+    /// Unreachable, since no monitor is ever created.
+    pub fn level(&self) -> Option<ThermalLevel> {
+        None
+    }
+}
+
+#[cfg(target_os = "macos")]
+impl Drop for ThermalMonitor {
+    fn drop(&mut self) {
+        unsafe { notify_cancel(self.token) };
+    }
+}
@@ -0,0 +1,180 @@
+//! Tick scheduling with an optional battery-saving idle rate.
+//!
+//! In adaptive mode the [`Ticker`] drops to [`IDLE_TICK`] once total CPU
+//! has stayed below [`IDLE_CPU_THRESHOLD`] and no key has been pressed
+//! for [`IDLE_AFTER`]. A keypress or a busy sample returns it to the
+//! normal rate on the spot.
+//!
+//! The ticker also measures the interval between applied samples. One
+//! longer than [`LATE_FACTOR`] times the expected interval is a late
+//! tick: collection and drawing could not keep up, and the samples that
+//! should have fallen in between were skipped.
+
+use std::time::{Duration, Instant};
+
+/// Collection interval while idle; a longer normal interval is kept as is.
+const IDLE_TICK: Duration = Duration::from_secs(5);
+
+/// Total CPU percentage below which the system counts as calm.
+const IDLE_CPU_THRESHOLD: f64 = 10.0;
+
+/// How long the system must be calm and untouched before going idle.
+const IDLE_AFTER: Duration = Duration::from_secs(60);
+
+/// Multiple of the expected interval beyond which a tick counts as late.
+const LATE_FACTOR: f64 = 1.5;
+
+/// How long [`Ticker::lag`] keeps reporting the last late interval.
+const LAG_HOLD: Duration = Duration::from_secs(5);
+
+/// Tick scheduler state.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    /// Ticking at the configured interval.
+    Normal,
+    /// Ticking at [`IDLE_TICK`].
+    Idle,
+}
+
+/// Chooses the interval between ticks.
+#[derive(Debug)]
+pub struct Ticker {
+    /// Interval at the normal rate.
+    rate: Duration,
+    /// Whether the idle rate may be used at all.
+    adaptive: bool,
+    state: State,
+    /// When a key was last pressed.
+    last_input: Instant,
+    /// Start of the current run of calm CPU samples, if the last one was calm.
+    calm_since: Option<Instant>,
+    /// When the last sample was applied.
+    last_applied: Option<Instant>,
+    /// Longest interval in effect since the last applied sample.
+    expected: Duration,
+    /// Last late interval and when it was measured.
+    last_late: Option<(Instant, Duration)>,
+    /// Late ticks over the session.
+    late: u64,
+    /// Samples skipped by late ticks over the session.
+    skipped: u64,
+    /// Longest measured interval over the session.
+    longest: Duration,
+}
+
+impl Ticker {
+    /// Creates a ticker at the normal rate of one tick per `rate`.
+    pub fn new(rate: Duration, adaptive: bool) -> Self {
+        Self {
+            rate,
+            adaptive,
+            state: State::Normal,
+            last_input: Instant::now(),
+            calm_since: None,
+            last_applied: None,
+            expected: rate,
+            last_late: None,
+            late: 0,
+            skipped: 0,
+            longest: Duration::ZERO,
+        }
+    }
+
+    /// Interval until the next tick.
+    pub fn interval(&self) -> Duration {
+        match self.state {
+            State::Normal => self.rate,
+            State::Idle => IDLE_TICK.max(self.rate),
+        }
+    }
+
+    /// Changes the normal rate and adaptive mode, returning to the normal rate.
+    pub fn configure(&mut self, rate: Duration, adaptive: bool) {
+        self.rate = rate;
+        self.adaptive = adaptive;
+        self.state = State::Normal;
+        self.calm_since = None;
+        // The next interval is measured against the new rate only.
+        self.last_applied = None;
+    }
+
+    /// Whether the idle rate is in effect.
+    pub fn is_idle(&self) -> bool {
+        self.state == State::Idle
+    }
+
+    /// Whether adaptive mode is enabled.
+    pub fn is_adaptive(&self) -> bool {
+        self.adaptive
+    }
+
+    /// Records a keypress; returns to the normal rate.
+    pub fn on_input(&mut self) {
+        self.last_input = Instant::now();
+        self.state = State::Normal;
+    }
+
+    /// Records a total CPU sample and moves between normal and idle.
+    pub fn on_sample(&mut self, total_cpu: f64) {
+        if !self.adaptive {
+            return;
+        }
+        if total_cpu >= IDLE_CPU_THRESHOLD {
+            self.calm_since = None;
+            self.state = State::Normal;
+            return;
+        }
+
+        let now = Instant::now();
+        let calm_since = *self.calm_since.get_or_insert(now);
+        if now.duration_since(calm_since) >= IDLE_AFTER
+            && now.duration_since(self.last_input) >= IDLE_AFTER
+        {
+            self.state = State::Idle;
+            self.expected = self.expected.max(self.interval());
+        }
+    }
+
+    /// Records that a sample is being applied; returns the interval since
+    /// the previous one if it was late.
+    pub fn on_applied(&mut self) -> Option<Duration> {
+        let now = Instant::now();
+        let measured = self.last_applied.map(|at| now.duration_since(at));
+        let expected = self.expected;
+        self.last_applied = Some(now);
+        self.expected = self.interval();
+
+        let measured = measured?;
+        self.longest = self.longest.max(measured);
+        let ratio = measured.as_secs_f64() / expected.as_secs_f64();
+        if ratio <= LATE_FACTOR {
+            return None;
+        }
+        self.late += 1;
+        self.skipped += (ratio.round() as u64).saturating_sub(1);
+        self.last_late = Some((now, measured));
+        Some(measured)
+    }
+
+    /// The last late interval, while it is under [`LAG_HOLD`] old.
+    pub fn lag(&self) -> Option<Duration> {
+        self.last_late
+            .filter(|(at, _)| at.elapsed() < LAG_HOLD)
+            .map(|(_, measured)| measured)
+    }
+
+    /// Late ticks over the session.
+    pub fn late_ticks(&self) -> u64 {
+        self.late
+    }
+
+    /// Samples skipped by late ticks over the session.
+    pub fn skipped_ticks(&self) -> u64 {
+        self.skipped
+    }
+
+    /// Longest interval measured between two samples this session.
+    pub fn longest_interval(&self) -> Duration {
+        self.longest
+    }
+}
@@ -0,0 +1,115 @@
+//! Live summary in the terminal's window or tab title (`[ui] title`).
+//!
+//! Each tick the title is set with OSC 0, which sets both the window
+//! title and the icon name that most terminals and tmux show on tabs.
+//! The title is only written when the text changes. Before the first
+//! write the original title is pushed onto the terminal's title stack
+//! (XTWINOPS 22), and on exit — including exit by panic — it is popped
+//! back (XTWINOPS 23). A terminal without a title stack keeps
+//! syswatch's last title. Off by default, since some terminals handle
+//! titles oddly.
+
+use std::io::{self, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::app::App;
+
+/// Title template used when `[ui] title_template` is not set.
+pub const DEFAULT_TEMPLATE: &str = "syswatch ▏cpu {cpu}% ▏mem {mem}";
+
+/// Placeholders a template may use.
+const PLACEHOLDERS: [&str; 3] = ["cpu", "mem", "procs"];
+
+/// Bytes per gibibyte for the `{mem}` placeholder.
+const BYTES_PER_GIB: f64 = 1_073_741_824.0;
+
+/// Whether the original title is saved on the terminal's stack.
+static SAVED: AtomicBool = AtomicBool::new(false);
+
+/// What the title currently shows.
+#[derive(Debug, Default)]
+pub struct Title {
+    /// Text last written; `None` while syswatch isn't setting the title.
+    shown: Option<String>,
+}
+
+impl Title {
+    /// Sets the title from `app`'s latest tick if `[ui] title` is on, or
+    /// restores the original one if it has just been turned off.
+    pub fn update(&mut self, app: &App, out: &mut impl Write) -> io::Result<()> {
+        if !app.config.title {
+            if self.shown.take().is_some() {
+                restore_to(out)?;
+            }
+            return Ok(());
+        }
+        let text = render(&app.config.title_template, app);
+        if self.shown.as_ref() == Some(&text) {
+            return Ok(());
+        }
+        if !SAVED.swap(true, Ordering::SeqCst) {
+            out.write_all(b"\x1b[22;0t")?;
+        }
+        write!(out, "\x1b]0;{text}\x07")?;
+        out.flush()?;
+        self.shown = Some(text);
+        Ok(())
+    }
+}
+
+/// Pops the original title if syswatch saved it; safe to call from the
+/// panic hook and more than once.
+pub fn restore() {
+    // Nothing more can be done about a title that can't be restored.
+    let _ = restore_to(&mut io::stdout());
+}
+
+/// Writes the pop sequence to `out` if the title was saved.
+fn restore_to(out: &mut impl Write) -> io::Result<()> {
+    if SAVED.swap(false, Ordering::SeqCst) {
+        out.write_all(b"\x1b[23;0t")?;
+        out.flush()?;
+    }
+    Ok(())
+}
+
+/// Fills `template`'s placeholders from `app`.
+///
+/// `{cpu}` is the total CPU percentage, `{mem}` used/total memory like
+/// `14/32G`, and `{procs}` the process count. Control characters are
+/// dropped, so the text can't end the escape sequence early.
+pub fn render(template: &str, app: &App) -> String {
+    let cpu = format!("{:.0}", app.user_pct + app.system_pct);
+    let mem = format!(
+        "{:.0}/{:.0}G",
+        app.used_memory as f64 / BYTES_PER_GIB,
+        app.total_memory as f64 / BYTES_PER_GIB
+    );
+    let procs = app.processes.len().to_string();
+    template
+        .replace("{cpu}", &cpu)
+        .replace("{mem}", &mem)
+        .replace("{procs}", &procs)
+        .chars()
+        .filter(|c| !c.is_control())
+        .collect()
+}
+
+/// Checks that every `{…}` in `template` is a known placeholder.
+pub fn check_template(template: &str) -> Result<(), String> {
+    let mut rest = template;
+    while let Some(open) = rest.find('{') {
+        let after = &rest[open + 1..];
+        let Some(close) = after.find('}') else {
+            return Err(format!("unclosed `{{` in title template `{template}`"));
+        };
+        let name = &after[..close];
+        if !PLACEHOLDERS.contains(&name) {
+            return Err(format!(
+                "unknown title placeholder `{{{name}}}` (expected {{cpu}}, {{mem}}, or {{procs}})"
+            ));
+        }
+        rest = &after[close + 1..];
+    }
+    Ok(())
+}
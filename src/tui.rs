@@ -0,0 +1,313 @@
+//! The live TUI: its event loop and key dispatch.
+//!
+//! [`run`] is the loop behind the live view. It reads events from any
+//! [`EventSource`]: the terminal's [`Input`](crate::input::Input) thread in
+//! the binary, or a [`Script`](crate::input::Script) that feeds fixed keys
+//! to drive it against a `TestBackend`. [`handle_key`] routes each key to
+//! the open popup, the filter editor, or the main view's [`BINDINGS`](keys::BINDINGS).
+
+use std::io::{self, Write};
+use std::mem;
+use std::time::Instant;
+
+use crossterm::event::{Event, KeyCode, KeyEventKind};
+use ratatui::Terminal;
+use ratatui::backend::Backend;
+
+use crate::app::{App, Popup, SIGNALS};
+use crate::export::TARGETS;
+use crate::external;
+use crate::input::EventSource;
+use crate::keys::{self, Action};
+use crate::notify::Snooze;
+use crate::title::Title;
+use crate::ui;
+use crate::view::COLUMNS;
+
+/// Drives the event loop: draws the UI, waits for input, and ticks state.
+///
+/// The loop redraws after every key press and every tick; between the
+/// two it waits on `input` until the next tick is due. It ends when a
+/// key clears [`App::running`], or with the error `input` reports.
+///
+/// Title updates (`[ui] title`) are written to `title_out`, which is
+/// the terminal's stdout in the binary.
+///
+/// Returns the final application state for the exit summary.
+pub fn run<B: Backend>(
+    terminal: &mut Terminal<B>,
+    mut app: App,
+    input: &impl EventSource,
+    title_out: &mut impl Write,
+) -> io::Result<App> {
+    let mut last_tick = Instant::now();
+    let mut title = Title::default();
+
+    while app.running {
+        title.update(&app, title_out)?;
+        let frame_start = Instant::now();
+        terminal.draw(|f| ui::draw(f, &mut app))?;
+        app.debug.frame_time = frame_start.elapsed();
+        app.debug.frames += 1;
+
+        let timeout = app.ticker.interval().saturating_sub(last_tick.elapsed());
+        match input.next(timeout)? {
+            Some(Event::Key(key)) if key.kind == KeyEventKind::Press => {
+                app.ticker.on_input();
+                handle_key(&mut app, key.code);
+            }
+            // Redrawn at the top of the loop, at the new size, without waiting for the tick.
+            Some(Event::Resize(..)) => {}
+            _ => {}
+        }
+
+        app.poll_jobs();
+
+        if let Some(command) = app.pending_command.take() {
+            input.pause();
+            let outcome = external::run(terminal, &command);
+            input.resume();
+            outcome?;
+        }
+
+        if last_tick.elapsed() >= app.ticker.interval() {
+            app.tick();
+            last_tick = Instant::now();
+        }
+    }
+
+    Ok(app)
+}
+
+/// Dispatches a key press to the appropriate application action.
+pub fn handle_key(app: &mut App, code: KeyCode) {
+    if app.popup.is_some() {
+        handle_popup_key(app, code);
+        return;
+    }
+    if app.filter_editing {
+        handle_filter_key(app, code);
+        return;
+    }
+
+    if code == KeyCode::Esc && !app.view.filter.is_empty() {
+        app.clear_filter();
+        return;
+    }
+    let Some(action) = keys::action_for(code) else {
+        return;
+    };
+    match action {
+        Action::Quit => app.running = false,
+        Action::Down => app.navigate(1),
+        Action::Up => app.navigate(-1),
+        Action::PageDown => app.page(1),
+        Action::PageUp => app.page(-1),
+        Action::Compact => app.compact = !app.compact,
+        Action::RelativeColors => app.relative_colors = !app.relative_colors,
+        Action::CycleProfile => app.cycle_profile(),
+        Action::Debug => app.debug.visible = !app.debug.visible,
+        Action::ToggleSystem => app.series_visible[0] = !app.series_visible[0],
+        Action::ToggleUser => app.series_visible[1] = !app.series_visible[1],
+        Action::ToggleMaxCore => app.series_visible[2] = !app.series_visible[2],
+        Action::ZoomOut => app.zoom_by(1),
+        Action::ZoomIn => app.zoom_by(-1),
+        Action::Details => app.open_detail(),
+        Action::Children => app.open_children(),
+        Action::Memory => app.popup = Some(Popup::Memory),
+        Action::Disks => app.popup = Some(Popup::Disks { selected: 0 }),
+        Action::Export => app.open_export(),
+        Action::Dock => app.toggle_dock(),
+        Action::Activity => app.open_activity(),
+        Action::Sample => app.start_sample(),
+        Action::OpenWith => app.open_with_picker(),
+        Action::Snooze => app.open_snooze(),
+        Action::Filter => app.start_filter(),
+        Action::CyclePreset => app.cycle_preset(),
+        Action::Presets => app.open_presets(),
+        Action::Tree => app.toggle_tree(),
+        Action::ExternalOnly => app.toggle_external_only(),
+        Action::CycleSort => app.cycle_sort(),
+        Action::SortMenu => app.open_sort_menu(),
+        Action::Columns => app.open_columns(),
+        Action::Reverse => app.reverse_sort(),
+        Action::Bands => app.toggle_bands(),
+        Action::Kill => app.open_kill(),
+        Action::Help => app.popup = Some(Popup::help()),
+    }
+}
+
+/// Edits the name filter while it has keyboard focus.
+fn handle_filter_key(app: &mut App, code: KeyCode) {
+    match code {
+        KeyCode::Enter => app.filter_editing = false,
+        KeyCode::Esc => app.clear_filter(),
+        KeyCode::Backspace => app.pop_filter(),
+        KeyCode::Char(c) => app.push_filter(c),
+        _ => {}
+    }
+}
+
+/// Routes a key press to the open popup instead of the main view.
+fn handle_popup_key(app: &mut App, code: KeyCode) {
+    let close = matches!(code, KeyCode::Char('q') | KeyCode::Esc);
+
+    match &mut app.popup {
+        Some(Popup::Help {
+            query,
+            editing: true,
+        }) => match code {
+            KeyCode::Enter => {
+                app.popup = Some(Popup::Help {
+                    query: mem::take(query),
+                    editing: false,
+                });
+            }
+            KeyCode::Esc => app.popup = Some(Popup::help()),
+            KeyCode::Backspace => {
+                query.pop();
+            }
+            KeyCode::Char(c) => query.push(c),
+            _ => {}
+        },
+        Some(Popup::Help { editing, .. }) if code == KeyCode::Char('/') => *editing = true,
+        Some(Popup::Sample(_) | Popup::Detail { .. } | Popup::Memory | Popup::Help { .. })
+            if close || code == KeyCode::Enter =>
+        {
+            app.popup = None;
+        }
+        Some(Popup::Activity) => match code {
+            KeyCode::Down | KeyCode::Char('j') => app.navigate(1),
+            KeyCode::Up | KeyCode::Char('k') => app.navigate(-1),
+            _ if close || code == KeyCode::Enter => app.popup = None,
+            _ => {}
+        },
+        Some(Popup::Disks { selected }) => match code {
+            KeyCode::Down | KeyCode::Char('j') => {
+                *selected = (*selected + 1).min(app.disks.devices.len().saturating_sub(1))
+            }
+            KeyCode::Up | KeyCode::Char('k') => *selected = selected.saturating_sub(1),
+            _ if close || code == KeyCode::Enter => app.popup = None,
+            _ => {}
+        },
+        Some(Popup::Children { scroll }) => match code {
+            KeyCode::Down | KeyCode::Char('j') => *scroll += 1,
+            KeyCode::Up | KeyCode::Char('k') => *scroll = scroll.saturating_sub(1),
+            KeyCode::Char('e') => app.export_children(),
+            _ if close => app.popup = None,
+            _ => {}
+        },
+        Some(Popup::Export { selected, scope }) => match code {
+            KeyCode::Down | KeyCode::Char('j') => {
+                *selected = (*selected + 1).min(TARGETS.len() - 1)
+            }
+            KeyCode::Up | KeyCode::Char('k') => *selected = selected.saturating_sub(1),
+            KeyCode::Char('w') => *scope = scope.toggle(),
+            KeyCode::Enter => {
+                let (target, scope) = (TARGETS[*selected].0, *scope);
+                app.export_history(target, scope);
+            }
+            _ if close => app.popup = None,
+            _ => {}
+        },
+        Some(Popup::OpenWith { selected }) => match code {
+            KeyCode::Down | KeyCode::Char('j') => {
+                *selected = (*selected + 1).min(app.config.open_with.len().saturating_sub(1));
+            }
+            KeyCode::Up | KeyCode::Char('k') => *selected = selected.saturating_sub(1),
+            KeyCode::Enter => {
+                let index = *selected;
+                app.choose_open_with(index);
+            }
+            _ if close => app.popup = None,
+            _ => {}
+        },
+        Some(Popup::Kill {
+            pid,
+            selected,
+            group,
+            ..
+        }) => match code {
+            KeyCode::Down | KeyCode::Char('j') => {
+                *selected = (*selected + 1).min(SIGNALS.len() - 1);
+            }
+            KeyCode::Up | KeyCode::Char('k') => *selected = selected.saturating_sub(1),
+            KeyCode::Char('g') => app.toggle_kill_group(),
+            KeyCode::Enter | KeyCode::Char('y') => {
+                let (pid, index) = (*pid, *selected);
+                match *group {
+                    Some(pgid) => app.confirm_group_kill(pgid, index),
+                    None => app.send_signal(pid, index),
+                }
+            }
+            _ if close => app.popup = None,
+            _ => {}
+        },
+        // Only a capital Y sends: the group may hold more than was looked at.
+        Some(Popup::GroupKill {
+            pgid,
+            signal,
+            members,
+        }) => match code {
+            KeyCode::Char('Y') => {
+                let (pgid, signal, count) = (*pgid, *signal, members.len());
+                app.signal_group(pgid, signal, count);
+            }
+            _ if close || code == KeyCode::Enter => app.popup = None,
+            _ => {}
+        },
+        Some(Popup::Snooze { selected }) => match code {
+            KeyCode::Down | KeyCode::Char('j') => {
+                *selected = (*selected + 1).min(Snooze::ALL.len());
+            }
+            KeyCode::Up | KeyCode::Char('k') => *selected = selected.saturating_sub(1),
+            KeyCode::Enter => {
+                let index = *selected;
+                app.choose_snooze(index);
+            }
+            _ if close => app.popup = None,
+            _ => {}
+        },
+        Some(Popup::Sort { selected }) => match code {
+            KeyCode::Down | KeyCode::Char('j') => {
+                *selected = (*selected + 1).min(COLUMNS.len() - 1)
+            }
+            KeyCode::Up | KeyCode::Char('k') => *selected = selected.saturating_sub(1),
+            KeyCode::Char('r') => app.reverse_sort(),
+            KeyCode::Char('c') => app.open_columns(),
+            KeyCode::Enter => {
+                let index = *selected;
+                app.choose_sort(index);
+            }
+            _ if close => app.popup = None,
+            _ => {}
+        },
+        Some(Popup::Columns(editor)) => match code {
+            KeyCode::Down | KeyCode::Char('j') => editor.select(1),
+            KeyCode::Up | KeyCode::Char('k') => editor.select(-1),
+            KeyCode::Char('J') => editor.shift(1),
+            KeyCode::Char('K') => editor.shift(-1),
+            KeyCode::Char(' ') => editor.toggle(),
+            KeyCode::Enter => {
+                let visible = editor.visible();
+                app.apply_columns(visible);
+            }
+            _ if close => app.popup = None,
+            _ => {}
+        },
+        Some(Popup::Filters { selected }) => match code {
+            KeyCode::Down | KeyCode::Char('j') => {
+                *selected = (*selected + 1).min(app.config.filters.len())
+            }
+            KeyCode::Up | KeyCode::Char('k') => *selected = selected.saturating_sub(1),
+            KeyCode::Enter => {
+                let row = *selected;
+                app.choose_preset(row);
+            }
+            _ if close => app.popup = None,
+            _ => {}
+        },
+        Some(Popup::Sample(_) | Popup::Detail { .. } | Popup::Memory | Popup::Help { .. })
+        | None => {}
+    }
+}
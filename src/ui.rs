@@ -1,7 +1,10 @@
 //! Terminal UI rendering for syswatch.
 //!
 //! All drawing functions receive an [`App`] reference and render
-//! widgets into a ratatui [`Frame`].
+//! widgets into a ratatui [`Frame`]; [`draw_replay`] renders the
+//! `--view` screen from a [`Replay`] instead.
+
+use std::time::{Duration, SystemTime};
 
 use ratatui::Frame;
 use ratatui::layout::{Alignment, Constraint, Direction, Layout, Rect};
@@ -9,34 +12,162 @@ use ratatui::style::{Color, Modifier, Style};
 use ratatui::symbols;
 use ratatui::text::{Line, Span};
 use ratatui::widgets::{
-    Axis, Block, Borders, Chart, Dataset, GraphType, Paragraph, Row, Table,
+    Axis, Block, Borders, Cell, Chart, Clear, Dataset, GraphType, HighlightSpacing, Paragraph, Row,
+    Sparkline, Table, Wrap,
 };
 
-use crate::app::App;
+use crate::app::{App, ColumnEditor, Popup, SIGNALS};
+use crate::battery::{Reading, fmt_remaining};
+use crate::collector::MemoryPressure;
+use crate::config::{MemoryUnit, TableFooter};
+use crate::disks::{DEVICE_HISTORY, Device};
+use crate::export::{Scope, TARGETS};
+use crate::external;
+use crate::format::{
+    MB, display_width, fit, fmt_bytes, fmt_bytes_in, fmt_percent, fmt_rate, pad, rate_width,
+};
+use crate::json;
+use crate::keys::{self, BINDINGS, POPUP_KEYS};
+use crate::monitor::{Pid, ProcessSample};
+use crate::notify::Snooze;
+use crate::record::Recorder;
+use crate::replay::Replay;
+use crate::rules;
+use crate::sample::{SampleJob, SampleStatus};
+use crate::thermal::ThermalLevel;
+use crate::view::{
+    COLUMN_SPACING, COLUMNS, PARTIAL_SORT_ROWS, SortKey, ViewRow, cpu_band, relative_band,
+};
 
 /// Bytes per gibibyte for memory display.
 const BYTES_PER_GIB: f64 = 1_073_741_824.0;
 
-/// Byte-size thresholds for [`fmt_bytes`].
-const KB: u64 = 1024;
-const MB: u64 = 1024 * 1024;
-const GB: u64 = 1024 * 1024 * 1024;
+/// Marker drawn before the selected process-table row.
+const HIGHLIGHT_SYMBOL: &str = ">> ";
+
+/// One plotted chart series: name, colour, current value, and points.
+type ChartSeries = (&'static str, Color, f64, Vec<(f64, f64)>);
+
+/// Y-axis labels of the CPU chart; their width sets the plot area's left edge.
+const CPU_Y_LABELS: [&str; 3] = ["0%", "50%", "100%"];
+
+/// Environment entries listed in the detail view.
+const DETAIL_ENV_LINES: usize = 6;
+
+/// Placeholder for values the OS would not let us read.
+const UNAVAILABLE: &str = "·";
+
+/// Key hints shown in the process table's bottom border.
+const TABLE_HINTS: &str =
+    " q: quit  j/k: scroll  /: filter  t: tree  o: sort  Enter: details  K: kill  ?: help ";
+
+/// Cells given to a name in the children timeline.
+const CHILD_NAME_WIDTH: usize = 24;
+
+/// Length of the rule drawn in separator cells; the table truncates it to fit.
+const SEPARATOR_RULE_WIDTH: usize = 64;
+
+/// Terminal heights below this switch to the compact layout automatically.
+const COMPACT_MAX_HEIGHT: u16 = 16;
+
+/// Terminal widths below this switch to the compact layout automatically.
+const COMPACT_MIN_WIDTH: u16 = 60;
+
+/// Smallest chart plot area (inside the border) worth drawing axes into.
+const CHART_MIN_SIZE: (u16, u16) = (8, 3);
+
+/// Row style of processes flagged by an `[alerts] watch` rule.
+const WATCH_STYLE: Style = Style::new().fg(Color::Green).add_modifier(Modifier::BOLD);
+
+/// Row style of syswatch's own process.
+const SELF_STYLE: Style = Style::new()
+    .add_modifier(Modifier::ITALIC)
+    .add_modifier(Modifier::DIM);
+
+/// Below this many visible rows the table shows a notice of the rows cut off.
+const SMALL_TABLE_ROWS: usize = 10;
+
+/// Name-cell marker of processes in App Nap or suspended by the OS.
+const NAP_MARKER: &str = "zZ";
+
+/// Name-cell marker of processes flagged as single-thread bottlenecks.
+const SINGLE_THREAD_MARKER: &str = "①";
+
+/// Name-cell marker of processes run from an external volume or disk image.
+const VOLUME_MARKER: &str = "⏏";
+
+/// Width of the docked detail pane.
+const DOCK_WIDTH: u16 = 44;
+
+/// Narrowest table area that still fits the docked pane; below it the pane hides.
+const DOCK_MIN_WIDTH: u16 = 110;
+
+/// Rows given to the docked pane's CPU sparkline.
+const DOCK_SPARK_HEIGHT: u16 = 4;
+
+/// Rows per tile of the activity view, borders included.
+const ACTIVITY_TILE_HEIGHT: u16 = 5;
+
+/// Rows given to the detail view's memory chart, borders included.
+const MEMORY_CHART_HEIGHT: u16 = 9;
+
+/// Rows given to the disk popup's rate chart, borders included.
+const DISK_CHART_HEIGHT: u16 = 9;
 
 // ── Main layout ─────────────────────────────────────────────
 
 /// Draws the complete UI: top metrics panel and process table.
+///
+/// The layout is recomputed from `f.area()` every frame; panels whose
+/// slot comes out empty (as while a tiling WM snaps the window) are
+/// skipped rather than drawn into a zero-sized area.
 pub fn draw(f: &mut Frame, app: &mut App) {
-    let chunks = Layout::default()
-        .direction(Direction::Vertical)
-        .margin(1)
-        .constraints([
-            Constraint::Length(12), // top panel
-            Constraint::Min(8),    // process table
-        ])
-        .split(f.area());
+    let full = f.area();
+    if full.is_empty() {
+        return;
+    }
+    if app.compact || full.height < COMPACT_MAX_HEIGHT || full.width < COMPACT_MIN_WIDTH {
+        draw_compact(f, app, full);
+    } else {
+        let bar_height = u16::from(app.config.function_bar);
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .margin(1)
+            .constraints([
+                Constraint::Length(12),         // top panel
+                Constraint::Min(8),             // process table
+                Constraint::Length(1),          // status bar
+                Constraint::Length(bar_height), // function-key bar
+            ])
+            .split(full);
 
-    draw_top_panel(f, app, chunks[0]);
-    draw_process_table(f, app, chunks[1]);
+        if !chunks[0].is_empty() {
+            draw_top_panel(f, app, chunks[0]);
+        }
+        if app.dock && chunks[1].width >= DOCK_MIN_WIDTH {
+            let cols = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([Constraint::Min(1), Constraint::Length(DOCK_WIDTH)])
+                .split(chunks[1]);
+            draw_process_table(f, app, cols[0]);
+            draw_detail_dock(f, app, cols[1]);
+        } else if !chunks[1].is_empty() {
+            draw_process_table(f, app, chunks[1]);
+        }
+        if !chunks[2].is_empty() {
+            draw_status_bar(f, app, chunks[2]);
+        }
+        if app.config.function_bar && !chunks[3].is_empty() {
+            draw_function_bar(f, chunks[3]);
+        }
+    }
+
+    if app.debug.visible {
+        draw_debug_overlay(f, app);
+    }
+    draw_popup(f, app);
+    // The visual bell lasts one frame.
+    app.flash = false;
 }
 
 // ── Top panel: stats | chart | counts ───────────────────────
@@ -84,6 +215,23 @@ fn draw_cpu_stats(f: &mut Frame, app: &App, area: Rect) {
                 Style::default().fg(Color::DarkGray),
             ),
         ]),
+        Line::from("  ─────────────────"),
+        Line::from(vec![
+            Span::raw("  Kernel:  "),
+            Span::styled(
+                app.kernel_cpu
+                    .map_or_else(|| format!("{UNAVAILABLE:>7}"), |c| format!("{c:>6.2}%")),
+                Style::default().fg(Color::Yellow),
+            ),
+        ]),
+        Line::from(Span::styled(
+            format!(
+                "  ({} wired)",
+                app.wired_memory
+                    .map_or_else(|| UNAVAILABLE.to_string(), fmt_bytes)
+            ),
+            Style::default().fg(Color::DarkGray),
+        )),
     ];
 
     let widget = Paragraph::new(text).block(bordered(""));
@@ -91,42 +239,173 @@ fn draw_cpu_stats(f: &mut Frame, app: &App, area: Rect) {
 }
 
 /// Renders the live CPU-load chart with system and user datasets.
+///
+/// Windows longer than the fine tier switch to minute buckets, plotting
+/// the mean and max of total CPU instead of the system/user split.
 fn draw_cpu_chart(f: &mut Frame, app: &App, area: Rect) {
-    let sys_data: Vec<(f64, f64)> = app.system_history.iter().copied().collect();
-    let usr_data: Vec<(f64, f64)> = app.user_history.iter().copied().collect();
+    // History keeps session-relative x; the chart plots over [0, window].
+    let bounds = app.history_bounds();
+    let window = app.window();
 
-    let datasets = vec![
-        Dataset::default()
-            .name("System")
-            .marker(symbols::Marker::Braille)
-            .graph_type(GraphType::Line)
-            .style(Style::default().fg(Color::Red))
-            .data(&sys_data),
-        Dataset::default()
-            .name("User")
-            .marker(symbols::Marker::Braille)
-            .graph_type(GraphType::Line)
-            .style(Style::default().fg(Color::Cyan))
-            .data(&usr_data),
-    ];
+    let mut series: Vec<ChartSeries> = if app.coarse_zoom() {
+        let buckets = app.cpu_minute_buckets();
+        let last = buckets.last().map(|&(_, b)| b);
+        vec![
+            (
+                "Max",
+                Color::Red,
+                last.map_or(0.0, |b| b.max),
+                buckets.iter().map(|&(x, b)| (x, b.max)).collect(),
+            ),
+            (
+                "Mean",
+                Color::Cyan,
+                last.map_or(0.0, |b| b.mean),
+                buckets.iter().map(|&(x, b)| (x, b.mean)).collect(),
+            ),
+        ]
+    } else {
+        vec![
+            (
+                "System",
+                Color::Red,
+                app.system_pct,
+                app.system_history
+                    .window_points(bounds[1], window)
+                    .collect(),
+            ),
+            (
+                "User",
+                Color::Cyan,
+                app.user_pct,
+                app.user_history.window_points(bounds[1], window).collect(),
+            ),
+        ]
+    };
+    if let Some(max_core) = app.max_core_pct.filter(|_| !app.coarse_zoom()) {
+        series.push((
+            "Max core",
+            Color::Yellow,
+            max_core,
+            app.max_core_history
+                .window_points(bounds[1], window)
+                .collect(),
+        ));
+    }
 
-    let bounds = app.history_bounds();
+    let mut datasets: Vec<Dataset> = series
+        .iter()
+        .zip(app.series_visible)
+        .filter(|&(_, visible)| visible)
+        .map(|((_, color, _, data), _)| {
+            Dataset::default()
+                .marker(symbols::Marker::Braille)
+                .graph_type(GraphType::Line)
+                .style(Style::default().fg(*color))
+                .data(data)
+        })
+        .collect();
+
+    // Late ticks: redraw the segment leading to each late sample dimmed,
+    // over the series' own colour.
+    if !app.coarse_zoom() {
+        for (from, to) in app.late_segments() {
+            for ((_, _, _, data), _) in series
+                .iter()
+                .zip(app.series_visible)
+                .filter(|&(_, visible)| visible)
+            {
+                let first = data.partition_point(|&(x, _)| x < from);
+                let end = data.partition_point(|&(x, _)| x <= to);
+                datasets.push(
+                    Dataset::default()
+                        .marker(symbols::Marker::Braille)
+                        .graph_type(GraphType::Line)
+                        .style(Style::default().fg(Color::DarkGray))
+                        .data(&data[first.min(end)..end]),
+                );
+            }
+        }
+    }
+
+    let legend: Vec<_> = series
+        .iter()
+        .zip(app.series_visible)
+        .map(|(&(name, color, value, _), visible)| (name, color, value, visible))
+        .collect();
 
     let x_axis = Axis::default()
         .style(Style::default().fg(Color::DarkGray))
-        .bounds(bounds);
+        .bounds([0.0, window]);
 
     let y_axis = Axis::default()
         .style(Style::default().fg(Color::DarkGray))
         .bounds([0.0, 100.0])
-        .labels(["0%", "50%", "100%"]);
+        .labels(CPU_Y_LABELS);
 
-    let chart = Chart::new(datasets)
-        .block(bordered(" CPU LOAD ").title_alignment(Alignment::Center))
-        .x_axis(x_axis)
-        .y_axis(y_axis);
+    let mut title = format!(" CPU LOAD · {} ", fmt_duration(app.window()));
+    if let Some(level) = app.thermal_level.filter(|&l| l > ThermalLevel::Nominal) {
+        title.push_str(&format!("· {level:?} throttling "));
+    }
+    let block = bordered(&title)
+        .title_alignment(Alignment::Center)
+        .title(legend_line(&legend));
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+    if inner.width < CHART_MIN_SIZE.0 || inner.height < CHART_MIN_SIZE.1 {
+        return;
+    }
+
+    let chart = Chart::new(datasets).x_axis(x_axis).y_axis(y_axis);
+
+    match app.thermal_points() {
+        Some(levels) if inner.height > 2 => {
+            let rows = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Min(1), Constraint::Length(1)])
+                .split(inner);
+            f.render_widget(chart, rows[0]);
+            draw_thermal_strip(f, &levels, window, rows[1]);
+        }
+        _ => f.render_widget(chart, inner),
+    }
+}
+
+/// Renders a one-row strip under the chart marking throttled periods.
+///
+/// Columns line up with the chart's plot area (right of the y-axis
+/// labels and axis line); each shows the worst level among the samples
+/// that fall into it. Level x values run over `[0, window]`.
+fn draw_thermal_strip(f: &mut Frame, levels: &[(f64, ThermalLevel)], window: f64, area: Rect) {
+    let offset = CPU_Y_LABELS.iter().map(|l| l.len()).max().unwrap_or(0) + 1;
+    let width = (area.width as usize).saturating_sub(offset);
+    if width == 0 || window <= 0.0 {
+        return;
+    }
 
-    f.render_widget(chart, area);
+    let mut columns = vec![None; width];
+    for &(x, level) in levels {
+        let col = (x / window * width as f64) as usize;
+        if let Some(slot) = columns.get_mut(col) {
+            *slot = (*slot).max(Some(level));
+        }
+    }
+
+    let mut spans = vec![Span::styled(
+        format!("{:>w$} ", "thm", w = offset - 1),
+        Style::default().fg(Color::DarkGray),
+    )];
+    spans.extend(columns.into_iter().map(|level| match level {
+        None => Span::raw(" "),
+        Some(ThermalLevel::Nominal) => Span::styled("─", Style::default().fg(Color::DarkGray)),
+        Some(ThermalLevel::Moderate) => Span::styled("▀", Style::default().fg(Color::Yellow)),
+        Some(ThermalLevel::Heavy) => Span::styled("▀", Style::default().fg(Color::LightRed)),
+        Some(ThermalLevel::Trapping | ThermalLevel::Sleeping) => {
+            Span::styled("█", Style::default().fg(Color::Red))
+        }
+    }));
+
+    f.render_widget(Paragraph::new(Line::from(spans)), area);
 }
 
 /// Renders the Threads / Processes / Memory column.
@@ -134,7 +413,7 @@ fn draw_system_counts(f: &mut Frame, app: &App, area: Rect) {
     let used_gb = app.used_memory as f64 / BYTES_PER_GIB;
     let total_gb = app.total_memory as f64 / BYTES_PER_GIB;
 
-    let text = vec![
+    let mut text = vec![
         Line::from(""),
         Line::from(vec![
             Span::raw("  Threads:    "),
@@ -151,116 +430,2773 @@ fn draw_system_counts(f: &mut Frame, app: &App, area: Rect) {
                 Style::default().fg(Color::Green),
             ),
         ]),
+        Line::from(vec![
+            Span::raw(format!("  {NAP_MARKER} napping: ")),
+            Span::styled(
+                format!("{:>6}", fmt_thousands(app.napping)),
+                Style::default().fg(Color::Blue),
+            ),
+        ]),
         Line::from("  ─────────────────"),
         Line::from(vec![
             Span::raw("  Memory:     "),
             Span::styled(
                 format!("{used_gb:.1}/{total_gb:.0}G"),
-                Style::default().fg(mem_color(used_gb, total_gb)),
+                Style::default().fg(mem_color(used_gb, total_gb, app.memory_pressure)),
             ),
         ]),
+        Line::from(Span::styled(
+            format!("  ({} memory)", app.memory_mode.label()),
+            Style::default().fg(Color::DarkGray),
+        )),
     ];
+    if let Some(reading) = app.battery.reading {
+        text.push(Line::from("  ─────────────────"));
+        let room = (area.height.saturating_sub(2) as usize).saturating_sub(text.len());
+        text.extend(battery_lines(app, reading, room));
+    }
 
     let widget = Paragraph::new(text).block(bordered(""));
     f.render_widget(widget, area);
 }
 
+// ── Status bar ──────────────────────────────────────────────
+
+/// Renders the one-line status bar with mode badges.
+fn draw_status_bar(f: &mut Frame, app: &App, area: Rect) {
+    let mut spans = Vec::new();
+
+    if app.privileged {
+        spans.push(Span::styled(
+            " ELEVATED ",
+            Style::default()
+                .fg(Color::White)
+                .bg(Color::Red)
+                .add_modifier(Modifier::BOLD),
+        ));
+        spans.push(Span::styled(
+            " fd, socket, cwd and env collectors enabled",
+            Style::default().fg(Color::DarkGray),
+        ));
+    } else {
+        spans.push(Span::styled(
+            " user mode",
+            Style::default().fg(Color::DarkGray),
+        ));
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    {
+        spans.push(Span::styled(
+            format!(" {} ", std::env::consts::OS.to_uppercase()),
+            Style::default()
+                .fg(Color::Black)
+                .bg(Color::Yellow)
+                .add_modifier(Modifier::BOLD),
+        ));
+        spans.push(Span::styled(
+            format!(" unavailable: {}", crate::collector::MISSING.join(", ")),
+            Style::default().fg(Color::DarkGray),
+        ));
+    }
+
+    if app.ticker.is_adaptive() {
+        let rate = app.ticker.interval().as_secs_f64();
+        let label = if app.ticker.is_idle() {
+            format!("  tick {rate}s (idle)")
+        } else {
+            format!("  tick {rate}s")
+        };
+        spans.push(Span::styled(label, Style::default().fg(Color::DarkGray)));
+    }
+
+    if let Some(file) = app.recorder.as_ref().and_then(Recorder::file) {
+        let name = file.file_name().unwrap_or(file.as_os_str());
+        spans.push(Span::styled(
+            format!("  rec {}", name.to_string_lossy()),
+            Style::default().fg(Color::DarkGray),
+        ));
+    }
+
+    if let Some(dashboard) = &app.dashboard {
+        spans.push(Span::styled(
+            format!("  http {}", dashboard.addr),
+            Style::default().fg(Color::DarkGray),
+        ));
+    }
+
+    if let Some(left) = app.snoozed() {
+        // Rounded up, so the last minute does not read `0m`.
+        spans.push(Span::styled(
+            format!(
+                "  snoozed {}",
+                fmt_remaining(left + Duration::from_secs(59))
+            ),
+            Style::default().fg(Color::Yellow),
+        ));
+    }
+
+    if let Some(lag) = app.ticker.lag() {
+        spans.push(Span::raw("  "));
+        spans.push(Span::styled(
+            " LAG ",
+            Style::default()
+                .fg(Color::Black)
+                .bg(Color::LightRed)
+                .add_modifier(Modifier::BOLD),
+        ));
+        spans.push(Span::styled(
+            format!(
+                " {:.1}s between samples (tick {}s)",
+                lag.as_secs_f64(),
+                app.ticker.interval().as_secs_f64()
+            ),
+            Style::default().fg(Color::DarkGray),
+        ));
+    }
+
+    if !app.other_instances.is_empty() {
+        let pids: Vec<String> = app
+            .other_instances
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        spans.push(Span::styled(
+            format!("  also running: syswatch PID {}", pids.join(", ")),
+            Style::default().fg(Color::Yellow),
+        ));
+    }
+
+    if app.monitor.high_count() {
+        spans.push(Span::raw("  "));
+        spans.push(Span::styled(
+            " HIGH COUNT ",
+            Style::default()
+                .fg(Color::Black)
+                .bg(Color::Yellow)
+                .add_modifier(Modifier::BOLD),
+        ));
+        spans.push(Span::styled(
+            format!(
+                " {} processes: top {PARTIAL_SORT_ROWS} sorted, list every other tick, no per-process extras",
+                fmt_thousands(app.processes.len())
+            ),
+            Style::default().fg(Color::DarkGray),
+        ));
+    }
+
+    if app.low_power {
+        spans.push(Span::raw("  "));
+        spans.push(Span::styled(
+            " LOW POWER ",
+            Style::default()
+                .fg(Color::Black)
+                .bg(Color::Green)
+                .add_modifier(Modifier::BOLD),
+        ));
+        let charge = app
+            .battery
+            .reading
+            .map_or_else(String::new, |r| format!("battery {:.0}%: ", r.percent));
+        spans.push(Span::styled(
+            format!(
+                " {charge}tick {}s, expensive collectors paused",
+                app.ticker.interval().as_secs_f64()
+            ),
+            Style::default().fg(Color::DarkGray),
+        ));
+    }
+
+    #[cfg(feature = "mqtt")]
+    if let Some(mqtt) = &app.mqtt {
+        let color = match mqtt.state() {
+            crate::mqtt::State::Connected => Color::Green,
+            crate::mqtt::State::Connecting => Color::DarkGray,
+            crate::mqtt::State::Down(_) => Color::Red,
+        };
+        spans.push(Span::styled(
+            format!("  mqtt {}", mqtt.state()),
+            Style::default().fg(color),
+        ));
+    }
+
+    if let Some(msg) = app.status() {
+        spans.push(Span::raw("  "));
+        spans.push(Span::styled(
+            msg.to_string(),
+            Style::default().fg(Color::Yellow),
+        ));
+    }
+
+    let mut bar = Paragraph::new(Line::from(spans));
+    if app.flash {
+        bar = bar.style(Style::default().add_modifier(Modifier::REVERSED));
+    }
+    f.render_widget(bar, area);
+}
+
+/// Renders the `F1Help F3Search …` bar in htop's key/label colours.
+fn draw_function_bar(f: &mut Frame, area: Rect) {
+    let spans: Vec<Span> = keys::function_bar()
+        .into_iter()
+        .flat_map(|(key, label)| {
+            [
+                Span::raw(key),
+                Span::styled(
+                    format!("{label:<7}"),
+                    Style::default().fg(Color::Black).bg(Color::Cyan),
+                ),
+            ]
+        })
+        .collect();
+
+    f.render_widget(Paragraph::new(Line::from(spans)), area);
+}
+
+// ── Compact layout ──────────────────────────────────────────
+
+/// Renders the dense single-line summary plus an optional CPU sparkline.
+fn draw_compact(f: &mut Frame, app: &App, area: Rect) {
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(1), Constraint::Length(1)])
+        .split(area);
+
+    f.render_widget(Paragraph::new(compact_line(app)), rows[0]);
+
+    if rows[1].height > 0 {
+        let width = rows[1].width as usize;
+        let skip = app.system_history.len().saturating_sub(width);
+        let data: Vec<u64> = app
+            .system_history
+            .values()
+            .zip(app.user_history.values())
+            .skip(skip)
+            .map(|(sys, usr)| (sys + usr).round() as u64)
+            .collect();
+
+        let spark = Sparkline::default()
+            .data(&data)
+            .max(100)
+            .style(Style::default().fg(Color::Cyan));
+        f.render_widget(spark, rows[1]);
+    }
+}
+
+/// Builds the `CPU … │ Mem … │ Swap … │ proc / thr │ net` summary line.
+fn compact_line(app: &App) -> Line<'static> {
+    let used_gb = app.used_memory as f64 / BYTES_PER_GIB;
+    let total_gb = app.total_memory as f64 / BYTES_PER_GIB;
+    let swap_gb = app.used_swap as f64 / BYTES_PER_GIB;
+    let sep = || Span::styled(" │ ", Style::default().fg(Color::DarkGray));
+
+    Line::from(vec![
+        Span::raw("CPU "),
+        Span::styled(
+            format!("{:.0}%", app.user_pct + app.system_pct),
+            Style::default().add_modifier(Modifier::BOLD),
+        ),
+        Span::raw(" (u "),
+        Span::styled(
+            format!("{:.0}", app.user_pct),
+            Style::default().fg(Color::Cyan),
+        ),
+        Span::raw("/s "),
+        Span::styled(
+            format!("{:.0}", app.system_pct),
+            Style::default().fg(Color::Red),
+        ),
+        Span::raw(")"),
+        sep(),
+        Span::raw("Mem "),
+        Span::styled(
+            format!("{used_gb:.1}/{total_gb:.0}G"),
+            Style::default().fg(mem_color(used_gb, total_gb, app.memory_pressure)),
+        ),
+        sep(),
+        Span::raw(format!("Swap {swap_gb:.1}G")),
+        sep(),
+        Span::styled(
+            format!("{} proc", fmt_thousands(app.processes.len())),
+            Style::default().fg(Color::Green),
+        ),
+        Span::raw(" / "),
+        Span::styled(
+            format!("{} thr", fmt_thousands(app.thread_count)),
+            Style::default().fg(Color::Magenta),
+        ),
+        sep(),
+        Span::raw(format!(
+            "↓{} ↑{}",
+            fmt_rate(app.net_rx_rate, app.config.rate_base, app.config.rate_bits),
+            fmt_rate(app.net_tx_rate, app.config.rate_base, app.config.rate_bits)
+        )),
+    ])
+}
+
 // ── Process table ───────────────────────────────────────────
 
 /// Renders the scrollable, sortable process table.
 fn draw_process_table(f: &mut Frame, app: &mut App, area: Rect) {
-    let header = Row::new(["PID", "Process", "CPU %", "Memory"])
-        .style(
-            Style::default()
-                .fg(Color::Yellow)
-                .add_modifier(Modifier::BOLD),
-        )
-        .bottom_margin(1);
+    let header = Row::new(
+        app.view
+            .columns
+            .iter()
+            .map(|&c| aligned_cell(COLUMNS[c].title, c)),
+    )
+    .style(
+        Style::default()
+            .fg(Color::Yellow)
+            .add_modifier(Modifier::BOLD),
+    )
+    .bottom_margin(1);
 
+    refresh_row_cache(app);
+
+    let relative_cpu = app.relative_colors && app.config.relative_cpu;
     let rows: Vec<Row> = app
-        .processes
+        .view
+        .rows
         .iter()
-        .map(|p| {
-            let cpu_style = if p.cpu_usage > 50.0 {
-                Style::default().fg(Color::Red)
-            } else if p.cpu_usage > 10.0 {
-                Style::default().fg(Color::Yellow)
-            } else {
-                Style::default()
+        .zip(&app.row_cache.rows)
+        .map(|(row, cells)| {
+            let proc = row.index().map(|i| &app.processes[i]);
+            let style = match proc {
+                Some(p) if p.pid == std::process::id() => SELF_STYLE,
+                Some(p) if app.alerts.watched.contains(&p.pid) => WATCH_STYLE,
+                Some(p) => rules::first_match(&app.config.color_rules, p).unwrap_or_else(|| {
+                    if relative_cpu {
+                        Style::default()
+                    } else {
+                        band_style(cpu_band(p.cpu_usage))
+                    }
+                }),
+                None => Style::default().fg(Color::DarkGray),
             };
 
-            Row::new([
-                p.pid.to_string(),
-                p.name.clone(),
-                format!("{:.1}", p.cpu_usage),
-                fmt_bytes(p.memory),
-            ])
-            .style(cpu_style)
+            let cells = cells.iter().zip(&app.view.columns).map(|(text, &c)| {
+                let cell = aligned_cell(text, c);
+                let band = match (proc, COLUMNS[c].sort) {
+                    (Some(p), SortKey::Memory) if app.relative_colors => {
+                        relative_band(p.memory as f64, app.view.peak_memory as f64)
+                    }
+                    (Some(p), SortKey::Cpu) if relative_cpu => {
+                        relative_band(f64::from(p.cpu_usage), f64::from(app.view.peak_cpu))
+                    }
+                    _ => return cell,
+                };
+                cell.style(band_style(band))
+            });
+            Row::new(cells).style(style)
         })
         .collect();
 
-    let widths = [
-        Constraint::Length(8),
-        Constraint::Min(20),
-        Constraint::Length(10),
-        Constraint::Length(12),
-    ];
+    let title = table_title(app);
+    let block = bordered(&title).title_bottom(Line::from(TABLE_HINTS).right_aligned());
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    // The footer takes one line. Header and its margin take two more, and
+    // a short table also gives one to the notice, so rows cut off below
+    // are never silently hidden.
+    let footer = app.config.table_footer != TableFooter::Off;
+    let (inner, footer_area) = if footer {
+        let [table, footer] =
+            Layout::vertical([Constraint::Min(0), Constraint::Length(1)]).areas(inner);
+        (table, Some(footer))
+    } else {
+        (inner, None)
+    };
+    let total = app.view.rows.len();
+    let fits = usize::from(inner.height.saturating_sub(2));
+    let notice = fits < SMALL_TABLE_ROWS && total > fits;
+    let (table_area, notice_area) = if notice {
+        let [table, notice] =
+            Layout::vertical([Constraint::Min(0), Constraint::Length(1)]).areas(inner);
+        (table, Some(notice))
+    } else {
+        (inner, None)
+    };
+    app.page_rows = usize::from(table_area.height.saturating_sub(2)).max(1);
 
-    let table = Table::new(rows, widths)
+    let table = Table::new(rows, app.view.widths())
         .header(header)
-        .block(
-            bordered(" Processes ")
-                .title_bottom(Line::from(" q: quit  j/k up/dn: scroll ").right_aligned()),
-        )
+        .column_spacing(COLUMN_SPACING)
         .row_highlight_style(Style::default().bg(Color::DarkGray))
-        .highlight_symbol(">> ");
+        .highlight_symbol(HIGHLIGHT_SYMBOL)
+        .highlight_spacing(HighlightSpacing::Always);
+    f.render_stateful_widget(table, table_area, &mut app.table_state);
 
-    f.render_stateful_widget(table, area, &mut app.table_state);
+    if let Some(notice_area) = notice_area {
+        let below = total.saturating_sub(app.table_state.offset() + app.page_rows);
+        let text = if below > 0 {
+            format!("… {below} more — PgDn / resize")
+        } else {
+            format!("… {} above — PgUp / resize", app.table_state.offset())
+        };
+        f.render_widget(
+            Paragraph::new(text).style(Style::default().fg(Color::DarkGray)),
+            notice_area,
+        );
+    }
+    if let Some(footer_area) = footer_area {
+        draw_table_footer(f, app, footer_area);
+    }
 }
 
-// ── Helpers ─────────────────────────────────────────────────
+/// Renders the frozen row below the process table, cell by cell in the
+/// table's own column layout.
+fn draw_table_footer(f: &mut Frame, app: &App, area: Rect) {
+    let selection = Line::from(HIGHLIGHT_SYMBOL).width() as u16;
+    let style = Style::default()
+        .fg(Color::Yellow)
+        .add_modifier(Modifier::BOLD);
+    let cells = footer_cells(app);
+    for ((text, &c), cell) in cells
+        .iter()
+        .zip(&app.view.columns)
+        .zip(app.view.column_areas(area, selection))
+    {
+        let line = Line::from(text.as_str()).style(style);
+        let line = if COLUMNS[c].right_aligned {
+            line.right_aligned()
+        } else {
+            line
+        };
+        f.render_widget(Paragraph::new(line), cell);
+    }
+}
 
-/// Creates a bordered block with an optional title.
-fn bordered(title: &str) -> Block<'_> {
-    Block::default()
-        .title(title)
-        .borders(Borders::ALL)
-        .border_style(Style::default().fg(Color::DarkGray))
+/// Footer text of each visible column, per `[table] footer`.
+///
+/// The selected process's name is shown without its tree prefix; the
+/// totals sum over the rows that pass the filter.
+fn footer_cells(app: &App) -> Vec<String> {
+    let keys = app.view.columns.iter().map(|&c| COLUMNS[c].sort);
+    if app.config.table_footer == TableFooter::Selected {
+        return match app.selected_process() {
+            Some(p) => {
+                let pinned = app.alerts.saturated.contains(&p.pid);
+                keys.map(|key| cell_text(key, p, "", pinned, app.config.memory_unit))
+                    .collect()
+            }
+            None => keys
+                .map(|key| match key {
+                    SortKey::Name => "no selection".to_string(),
+                    _ => String::new(),
+                })
+                .collect(),
+        };
+    }
+
+    let procs: Vec<&ProcessSample> = app
+        .view
+        .rows
+        .iter()
+        .filter_map(ViewRow::index)
+        .map(|i| &app.processes[i])
+        .collect();
+    let sum = |value: fn(&ProcessSample) -> Option<u32>| -> u32 {
+        procs.iter().filter_map(|p| value(p)).sum()
+    };
+    keys.map(|key| match key {
+        SortKey::Pid => String::new(),
+        SortKey::Name => match procs.len() {
+            1 => "Σ 1 process".to_string(),
+            n => format!("Σ {} processes", fmt_thousands(n)),
+        },
+        SortKey::Cpu => fmt_percent(procs.iter().map(|p| f64::from(p.cpu_usage)).sum()),
+        SortKey::CpuTime => fmt_cpu_time(procs.iter().map(|p| p.cpu_time).sum()),
+        SortKey::Memory => {
+            fmt_bytes_in(procs.iter().map(|p| p.memory).sum(), app.config.memory_unit)
+        }
+        SortKey::Threads => sum(|p| p.threads).to_string(),
+        SortKey::Running => fmt_running(Some(sum(|p| p.running)), Some(sum(|p| p.threads))),
+        SortKey::ThreadGrowth => format!(
+            "{:+.0}",
+            procs.iter().filter_map(|p| p.thread_growth).sum::<f64>()
+        ),
+    })
+    .collect()
+}
+
+/// Text color of band 0 (red), band 1 (yellow), or any later band (default).
+fn band_style(band: usize) -> Style {
+    match band {
+        0 => Style::default().fg(Color::Red),
+        1 => Style::default().fg(Color::Yellow),
+        _ => Style::default(),
+    }
 }
 
-/// Formats a byte count into a human-readable string (B / KB / MB / GB).
-fn fmt_bytes(bytes: u64) -> String {
-    if bytes >= GB {
-        format!("{:.1} GB", bytes as f64 / GB as f64)
-    } else if bytes >= MB {
-        format!("{:.1} MB", bytes as f64 / MB as f64)
-    } else if bytes >= KB {
-        format!("{:.1} KB", bytes as f64 / KB as f64)
+/// Wraps `text` in a cell aligned per column `c` of [`COLUMNS`].
+fn aligned_cell(text: &str, c: usize) -> Cell<'_> {
+    let line = Line::from(text);
+    Cell::from(if COLUMNS[c].right_aligned {
+        line.right_aligned()
     } else {
-        format!("{bytes} B")
+        line
+    })
+}
+
+/// Builds the table title: sort order, tree mode, and the name filter.
+fn table_title(app: &App) -> String {
+    let view = &app.view;
+    let arrow = if view.descending { "↓" } else { "↑" };
+    let mut title = format!(" Processes · {} {arrow}", view.sort.label());
+
+    if view.tree {
+        title.push_str(" · tree");
+    }
+    if view.shows_bands() {
+        title.push_str(" · bands");
     }
+    if view.external_only {
+        title.push_str(&format!(" · {VOLUME_MARKER} external volumes"));
+    }
+    if let Some(name) = &view.preset {
+        title.push_str(&format!(" · [{name}] ({} shown)", view.process_count()));
+    } else if app.filter_editing || !view.filter.is_empty() {
+        let cursor = if app.filter_editing { "▏" } else { "" };
+        title.push_str(&format!(
+            " · /{}{cursor} ({} shown)",
+            view.filter,
+            view.process_count()
+        ));
+    }
+    title.push(' ');
+    title
 }
 
-/// Formats a count with K / M suffixes for thousands / millions.
-fn fmt_thousands(n: usize) -> String {
-    if n >= 1_000_000 {
-        format!("{:.1}M", n as f64 / 1_000_000.0)
-    } else if n >= 1_000 {
-        format!("{:.1}K", n as f64 / 1_000.0)
+/// Re-formats the table cells if the cache was invalidated since last frame.
+fn refresh_row_cache(app: &mut App) {
+    if app.row_cache.valid {
+        app.debug.rows_formatted = 0;
+        return;
+    }
+
+    app.row_cache.rows = app
+        .view
+        .rows
+        .iter()
+        .map(|row| {
+            let columns = app.view.columns.iter().map(|&c| COLUMNS[c].sort);
+            match row {
+                ViewRow::Process { index, prefix } => {
+                    let p = &app.processes[*index];
+                    let pinned = app.alerts.saturated.contains(&p.pid);
+                    columns
+                        .map(|key| cell_text(key, p, prefix, pinned, app.config.memory_unit))
+                        .collect()
+                }
+                ViewRow::Separator(label) => {
+                    columns.map(|key| separator_cell(key, label)).collect()
+                }
+            }
+        })
+        .collect();
+    app.row_cache.valid = true;
+
+    app.debug.rows_formatted = app.row_cache.rows.len();
+    app.debug.rows_formatted_total += app.row_cache.rows.len() as u64;
+}
+
+/// Formats the cell of column `key` for process `p`; `pinned` marks a
+/// single-thread bottleneck.
+fn cell_text(
+    key: SortKey,
+    p: &ProcessSample,
+    prefix: &str,
+    pinned: bool,
+    unit: MemoryUnit,
+) -> String {
+    match key {
+        SortKey::Pid => p.pid.to_string(),
+        SortKey::Name => {
+            let mut text = format!("{prefix}{}", p.name);
+            if p.pid == std::process::id() {
+                text.push_str(" (self)");
+            } else if p.nap.is_some() {
+                text.push_str(&format!(" {NAP_MARKER}"));
+            }
+            if pinned {
+                text.push_str(&format!(" {SINGLE_THREAD_MARKER}"));
+            }
+            if p.volume.is_some() {
+                text.push_str(&format!(" {VOLUME_MARKER}"));
+            }
+            text
+        }
+        SortKey::Cpu => fmt_cpu(p),
+        SortKey::CpuTime => fmt_cpu_time(p.cpu_time),
+        SortKey::Memory => fmt_bytes_in(p.memory, unit),
+        SortKey::Threads => fmt_opt(p.threads),
+        SortKey::Running => fmt_running(p.running, p.threads),
+        SortKey::ThreadGrowth => p
+            .thread_growth
+            .map_or_else(|| UNAVAILABLE.to_string(), |g| format!("{g:+.0}")),
+    }
+}
+
+/// The detail view's process-group line: group ID, whether `p` leads
+/// it, its member count in the snapshot, and the session ID.
+fn group_text(app: &App, p: &ProcessSample) -> String {
+    let Some(pgid) = p.pgid else {
+        return UNAVAILABLE.to_string();
+    };
+    let members = app
+        .processes
+        .iter()
+        .filter(|q| q.pgid == Some(pgid))
+        .count();
+    let leader = if pgid == p.pid { " (leader)" } else { "" };
+    let session = app
+        .detail
+        .as_ref()
+        .filter(|d| d.pid == p.pid)
+        .and_then(|d| d.session)
+        .map_or_else(|| UNAVAILABLE.to_string(), |sid| sid.to_string());
+    format!("{pgid}{leader} · {members} processes · session {session}")
+}
+
+/// Detail lines for the process's Mach message rates and port count,
+/// saying why a value is missing.
+fn ipc_lines(app: &App, pid: Pid, label: impl Fn(&str) -> Span<'static>) -> Vec<Line<'static>> {
+    let ipc = app.ipc.as_ref().filter(|ipc| ipc.pid == pid);
+    let missing = |why: &str| format!("{UNAVAILABLE} ({why})");
+    let platform = !cfg!(target_os = "macos");
+
+    let messages = match ipc {
+        None if !app.extras => missing(extras_off(app)),
+        None => UNAVAILABLE.to_string(),
+        Some(_) if platform => missing("macOS only"),
+        Some(ipc) if ipc.messages_error.is_some() => missing("task info not permitted"),
+        Some(ipc) => ipc.rates.map_or_else(
+            || "measuring…".to_string(),
+            |(sent, received)| {
+                format!(
+                    "{}/s sent · {}/s received",
+                    fmt_thousands(sent.round() as usize),
+                    fmt_thousands(received.round() as usize)
+                )
+            },
+        ),
+    };
+    let ports = match ipc.map(|ipc| ipc.ports) {
+        None if !app.extras => missing(extras_off(app)),
+        None => UNAVAILABLE.to_string(),
+        Some(Ok(count)) => fmt_thousands(count as usize),
+        Some(Err(_)) if platform => missing("macOS only"),
+        Some(Err(_)) if !app.privileged => missing("needs root"),
+        Some(Err(_)) => missing("refused by the system"),
+    };
+    vec![
+        Line::from(vec![label("Mach msgs"), Span::raw(messages)]),
+        Line::from(vec![label("Ports"), Span::raw(ports)]),
+    ]
+}
+
+/// Formats runnable / total threads as `3/57`.
+fn fmt_running(running: Option<u32>, threads: Option<u32>) -> String {
+    match (running, threads) {
+        (Some(running), Some(threads)) => format!("{running}/{threads}"),
+        _ => UNAVAILABLE.to_string(),
+    }
+}
+
+/// CPU% cell, or `·` until the process has been sampled twice.
+fn fmt_cpu(p: &ProcessSample) -> String {
+    if p.cpu_measured {
+        fmt_percent(f64::from(p.cpu_usage))
     } else {
-        format!("{n}")
+        UNAVAILABLE.to_string()
     }
 }
 
-/// Picks a colour for the memory reading based on usage percentage.
-fn mem_color(used: f64, total: f64) -> Color {
-    if total <= 0.0 {
-        return Color::White;
+/// Cell of a CPU band separator: a dim rule, labelled in the name column.
+fn separator_cell(key: SortKey, label: &str) -> String {
+    let rule = "─".repeat(SEPARATOR_RULE_WIDTH);
+    match key {
+        SortKey::Name => format!("── {label} {rule}"),
+        _ => rule,
     }
-    let pct = used / total * 100.0;
-    match pct as u32 {
-        0..=60 => Color::Green,
-        61..=85 => Color::Yellow,
-        _ => Color::Red,
+}
+
+// ── Self-monitoring overlay ─────────────────────────────────
+
+/// Renders syswatch's own resource usage and draw-path counters.
+fn draw_debug_overlay(f: &mut Frame, app: &App) {
+    let (cpu, rss) = app
+        .own_process()
+        .map_or((0.0, 0), |p| (p.cpu_usage, p.memory));
+    let stats = &app.debug;
+
+    let text = vec![
+        Line::from(format!(" CPU        {cpu:>9.1}%")),
+        Line::from(format!(" RSS        {:>10}", fmt_bytes(rss))),
+        Line::from(format!(
+            " Frame      {:>7.2} ms",
+            stats.frame_time.as_secs_f64() * 1000.0
+        )),
+        Line::from(format!(
+            " Frames     {:>10}",
+            fmt_thousands(stats.frames as usize)
+        )),
+        Line::from(format!(" Rows fmt   {:>10}", stats.rows_formatted)),
+        Line::from(format!(
+            " Rows total {:>10}",
+            fmt_thousands(stats.rows_formatted_total as usize)
+        )),
+        Line::from(format!(
+            " Late ticks {:>10}",
+            fmt_thousands(app.ticker.late_ticks() as usize)
+        )),
+        Line::from(format!(
+            " Skipped    {:>10}",
+            fmt_thousands(app.ticker.skipped_ticks() as usize)
+        )),
+        Line::from(format!(
+            " Alerts     {:>10}",
+            fmt_thousands(app.alerts.history.len())
+        )),
+        Line::from(format!(
+            " Hook fails {:>10}",
+            app.webhook.as_ref().map_or(0, |w| w.failures)
+        )),
+    ];
+
+    let full = f.area();
+    let width = 26.min(full.width);
+    let height = (text.len() as u16 + 2).min(full.height);
+    let area = Rect {
+        x: full.x + full.width - width,
+        y: full.y + full.height - height,
+        width,
+        height,
+    };
+
+    f.render_widget(Clear, area);
+    f.render_widget(Paragraph::new(text).block(bordered(" syswatch ")), area);
+}
+
+// ── Popups ──────────────────────────────────────────────────
+
+/// Renders the active modal overlay, if any, centred over the UI.
+fn draw_popup(f: &mut Frame, app: &App) {
+    match &app.popup {
+        Some(Popup::Sample(job)) => draw_sample_popup(f, job),
+        Some(Popup::Detail { pid }) => draw_detail_popup(f, app, *pid),
+        Some(Popup::Children { scroll }) => draw_children_popup(f, app, *scroll),
+        Some(Popup::Memory) => draw_memory_popup(f, app),
+        Some(Popup::Activity) => draw_activity_popup(f, app),
+        Some(Popup::Disks { selected }) => draw_disks_popup(f, app, *selected),
+        Some(Popup::Export { selected, scope }) => draw_export_popup(f, app, *selected, *scope),
+        Some(Popup::OpenWith { selected }) => draw_open_with_popup(f, app, *selected),
+        Some(Popup::Kill {
+            pid,
+            name,
+            selected,
+            group,
+        }) => draw_kill_popup(f, *pid, name, *selected, *group),
+        Some(Popup::GroupKill {
+            pgid,
+            signal,
+            members,
+        }) => draw_group_kill_popup(f, *pgid, *signal, members),
+        Some(Popup::Snooze { selected }) => draw_snooze_popup(f, app, *selected),
+        Some(Popup::Sort { selected }) => draw_sort_popup(f, app, *selected),
+        Some(Popup::Columns(editor)) => draw_columns_popup(f, app, editor),
+        Some(Popup::Filters { selected }) => draw_filters_popup(f, app, *selected),
+        Some(Popup::Help { query, editing }) => draw_help_popup(f, app, query, *editing),
+        None => {}
+    }
+}
+
+/// Renders every known field of one process, noting unavailable ones,
+/// above its memory history.
+fn draw_detail_popup(f: &mut Frame, app: &App, pid: Pid) {
+    let text = detail_lines(app, pid);
+    let name = app.process(pid).map_or("?", |p| p.name.as_str());
+    let title = format!(" {name} ({pid}) ");
+    let block = bordered(&title)
+        .title_alignment(Alignment::Center)
+        .title_bottom(Line::from(" Esc: close ").right_aligned());
+    let height = text.len() as u16 + 4 + MEMORY_CHART_HEIGHT;
+    let area = centered(f.area(), 72, height);
+
+    f.render_widget(Clear, area);
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+    let [lines, chart] =
+        Layout::vertical([Constraint::Min(1), Constraint::Length(MEMORY_CHART_HEIGHT)])
+            .areas(inner);
+    f.render_widget(Paragraph::new(text).wrap(Wrap { trim: false }), lines);
+    draw_memory_trail(f, app, pid, chart);
+}
+
+/// Charts `pid`'s resident memory since the detail view opened on it.
+///
+/// The y-axis is scaled to the samples' range, so slow growth is visible;
+/// the title states how much time that covers.
+fn draw_memory_trail(f: &mut Frame, app: &App, pid: Pid, area: Rect) {
+    let Some(trail) = app.memory_trail.as_ref().filter(|t| t.pid == pid) else {
+        f.render_widget(bordered(" Memory · collecting… "), area);
+        return;
+    };
+    let history = &trail.history;
+    let covered = history.end() - history.start().max(trail.since);
+    let mut title = format!(
+        " Memory · last {}:{:02} ",
+        covered as u64 / 60,
+        covered as u64 % 60
+    );
+    if history.start() > trail.since {
+        title.push_str("(oldest dropped) ");
+    }
+    let block = bordered(&title).title_alignment(Alignment::Center);
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+    if inner.width < CHART_MIN_SIZE.0 || inner.height < CHART_MIN_SIZE.1 {
+        return;
+    }
+
+    let right = history.end().max(history.start() + 1.0);
+    let span = right - history.start();
+    let data: Vec<(f64, f64)> = history.window_points(right, span).collect();
+    let (low, high) = data.iter().fold((f64::MAX, 0.0_f64), |(lo, hi), &(_, y)| {
+        (lo.min(y), hi.max(y))
+    });
+    // A flat line still gets a band around it rather than a zero-height axis.
+    let pad = ((high - low) * 0.1).max(MB as f64);
+    let bounds = [(low - pad).max(0.0), high + pad];
+    let labels: Vec<String> = [bounds[0], (bounds[0] + bounds[1]) / 2.0, bounds[1]]
+        .iter()
+        .map(|&y| fmt_bytes(y as u64))
+        .collect();
+
+    let dataset = Dataset::default()
+        .marker(symbols::Marker::Braille)
+        .graph_type(GraphType::Line)
+        .style(Style::default().fg(Color::Magenta))
+        .data(&data);
+    let chart = Chart::new(vec![dataset])
+        .x_axis(
+            Axis::default()
+                .style(Style::default().fg(Color::DarkGray))
+                .bounds([0.0, span]),
+        )
+        .y_axis(
+            Axis::default()
+                .style(Style::default().fg(Color::DarkGray))
+                .bounds(bounds)
+                .labels(labels),
+        );
+    f.render_widget(chart, inner);
+}
+
+/// Renders the docked detail pane for the selected process.
+fn draw_detail_dock(f: &mut Frame, app: &App, area: Rect) {
+    let Some(proc) = app.selected_process() else {
+        f.render_widget(bordered(" Details "), area);
+        return;
+    };
+    let pid = proc.pid;
+    let title = format!(" {} ({pid}) ", proc.name);
+    let block = bordered(&title);
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Min(1),
+            Constraint::Length(MEMORY_CHART_HEIGHT),
+            Constraint::Length(DOCK_SPARK_HEIGHT),
+        ])
+        .split(inner);
+    f.render_widget(
+        Paragraph::new(detail_lines(app, pid)).wrap(Wrap { trim: false }),
+        rows[0],
+    );
+    draw_memory_trail(f, app, pid, rows[1]);
+
+    if let Some(trail) = app.cpu_trails.get(pid) {
+        let width = rows[2].width as usize;
+        let data: Vec<u64> = trail
+            .iter()
+            .skip(trail.len().saturating_sub(width))
+            .copied()
+            .collect();
+        // Multi-threaded processes exceed 100%; scale to the busiest sample.
+        let max = data.iter().copied().max().unwrap_or(0).max(100);
+        let spark = Sparkline::default()
+            .data(&data)
+            .max(max)
+            .style(Style::default().fg(Color::Cyan));
+        f.render_widget(spark, rows[2]);
+    }
+}
+
+/// Renders the activity view: live tiles for the selected process.
+///
+/// Every tile reads one collector; a reading the platform, permissions,
+/// or high-count mode withholds shows [`UNAVAILABLE`] with the reason.
+fn draw_activity_popup(f: &mut Frame, app: &App) {
+    let area = centered(f.area(), 76, ACTIVITY_TILE_HEIGHT * 3 + 3);
+    f.render_widget(Clear, area);
+    let Some(p) = app.selected_process() else {
+        f.render_widget(bordered(" Activity "), area);
+        return;
+    };
+
+    let title = format!(" Activity · {} ({}) ", p.name, p.pid);
+    let block = bordered(&title)
+        .title_alignment(Alignment::Center)
+        .title_bottom(Line::from(" j/k: follow the selection  Esc: close ").right_aligned());
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let rows = Layout::vertical([Constraint::Length(ACTIVITY_TILE_HEIGHT); 3]).split(inner);
+    let tiles: Vec<[Rect; 2]> = rows
+        .iter()
+        .map(|&row| Layout::horizontal([Constraint::Percentage(50); 2]).areas(row))
+        .collect();
+
+    // CPU: the per-process trail every process keeps.
+    let cpu = if p.cpu_measured {
+        format!("{}%", fmt_percent(f64::from(p.cpu_usage)))
+    } else {
+        "measuring…".to_string()
+    };
+    let trail: Vec<u64> = app
+        .cpu_trails
+        .get(p.pid)
+        .map(|t| t.iter().copied().collect())
+        .unwrap_or_default();
+    // Multi-threaded processes exceed 100%; scale to the busiest sample.
+    let max = trail.iter().copied().max().unwrap_or(0).max(100);
+    draw_spark_tile(
+        f,
+        tiles[0][0],
+        &format!(" CPU · {cpu} "),
+        &trail,
+        max,
+        Color::Cyan,
+    );
+
+    // Memory: the trail kept for the process on screen.
+    let memory: Vec<u64> = app
+        .memory_trail
+        .as_ref()
+        .filter(|t| t.pid == p.pid)
+        .map(|t| t.history.values().map(|v| v as u64).collect())
+        .unwrap_or_default();
+    // Scaled to the samples' range like the detail chart, so growth shows.
+    let (low, high) = memory
+        .iter()
+        .fold((u64::MAX, 0), |(lo, hi), &v| (lo.min(v), hi.max(v)));
+    let pad = ((high.saturating_sub(low)) / 10).max(MB);
+    let floor = low.saturating_sub(pad);
+    let memory: Vec<u64> = memory.iter().map(|v| v - floor).collect();
+    let max = (high + pad).saturating_sub(floor);
+    let title = format!(" Memory · {} ", fmt_bytes(p.memory));
+    draw_spark_tile(f, tiles[0][1], &title, &memory, max, Color::Magenta);
+
+    let rate = |bps: f64| disk_rate(app, bps);
+    let disk = match app.disk_activity.as_ref().filter(|d| d.pid == p.pid) {
+        Some(_) if !cfg!(target_os = "macos") => vec![missing_reason("macOS only")],
+        Some(d) if d.error.is_some() && !app.privileged => vec![missing_reason("needs root")],
+        Some(d) if d.error.is_some() => vec![missing_reason("refused by the system")],
+        Some(d) => match d.rates {
+            Some((read, written)) => vec![
+                format!("read   {}", rate(read)),
+                format!("write  {}", rate(written)),
+            ],
+            None => vec!["measuring…".to_string()],
+        },
+        None if !app.extras => vec![missing_reason(extras_off(app))],
+        None => vec!["measuring…".to_string()],
+    };
+    draw_text_tile(f, tiles[1][0], " Disk ", disk);
+    draw_text_tile(
+        f,
+        tiles[1][1],
+        " Network ",
+        vec![missing_reason("no per-process collector")],
+    );
+
+    let withheld = libproc_reason(app, p);
+    let files = match (p.fds, p.sockets) {
+        (Some(fds), sockets) => vec![
+            format!("open   {}", fmt_thousands(fds as usize)),
+            format!("socket {}", fmt_opt(sockets)),
+        ],
+        (None, _) if !app.privileged => vec![missing_reason("needs root")],
+        (None, _) => vec![missing_reason(withheld)],
+    };
+    draw_text_tile(f, tiles[2][0], " File descriptors ", files);
+    let threads = match p.threads {
+        Some(_) => {
+            let mut lines = vec![format!("running {}", fmt_running(p.running, p.threads))];
+            if let Some(growth) = p.thread_growth {
+                lines.push(format!("growth  {growth:+.1}/min"));
+            }
+            lines
+        }
+        None => vec![missing_reason(withheld)],
+    };
+    draw_text_tile(f, tiles[2][1], " Threads ", threads);
+}
+
+/// Why libproc counts of `p` are missing, for the activity view.
+fn libproc_reason(app: &App, p: &ProcessSample) -> &'static str {
+    if !cfg!(target_os = "macos") {
+        "macOS only"
+    } else if app.monitor.high_count() {
+        "skipped in high-count mode"
+    } else if app.monitor.lean() && app.low_power {
+        "paused in low-power mode"
+    } else if app.monitor.lean() {
+        "thread scans off in this profile"
+    } else if !p.inspectable {
+        "protected by the system"
+    } else {
+        "refused by the system"
+    }
+}
+
+/// Why detail extras are missing while a profile turns them off.
+fn extras_off(app: &App) -> &'static str {
+    if app.low_power {
+        "paused in low-power mode"
+    } else {
+        "extras off in this profile"
+    }
+}
+
+/// A disk rate under `[ui] rate_base`; always in bytes, as `rate_bits`
+/// is for network throughput.
+fn disk_rate(app: &App, bytes_per_sec: f64) -> String {
+    fmt_rate(bytes_per_sec, app.config.rate_base, false)
+}
+
+/// [`UNAVAILABLE`] followed by why.
+fn missing_reason(why: &str) -> String {
+    format!("{UNAVAILABLE} ({why})")
+}
+
+/// Draws one activity tile holding a sparkline of `data` scaled to `max`.
+fn draw_spark_tile(f: &mut Frame, area: Rect, title: &str, data: &[u64], max: u64, color: Color) {
+    let block = bordered(title);
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+    if data.is_empty() {
+        f.render_widget(Paragraph::new(" collecting…"), inner);
+        return;
+    }
+    let width = inner.width as usize;
+    let data = &data[data.len().saturating_sub(width)..];
+    let spark = Sparkline::default()
+        .data(data)
+        .max(max.max(1))
+        .style(Style::default().fg(color));
+    f.render_widget(spark, inner);
+}
+
+/// Draws one activity tile holding `lines` of text.
+fn draw_text_tile(f: &mut Frame, area: Rect, title: &str, lines: Vec<String>) {
+    let text: Vec<Line> = lines
+        .into_iter()
+        .map(|l| Line::from(format!(" {l}")))
+        .collect();
+    f.render_widget(Paragraph::new(text).block(bordered(title)), area);
+}
+
+/// Builds the detail view's field lines for `pid`.
+fn detail_lines(app: &App, pid: Pid) -> Vec<Line<'static>> {
+    let mut text = vec![Line::from("")];
+    let label =
+        |name: &str| Span::styled(format!("  {name:<10}"), Style::default().fg(Color::Yellow));
+
+    match app.process(pid) {
+        None => text.push(Line::from("  Process has exited.")),
+        Some(p) => {
+            text.push(Line::from(vec![label("PID"), Span::raw(p.pid.to_string())]));
+            text.push(Line::from(vec![label("Name"), Span::raw(p.name.clone())]));
+            let mut state = p.status.to_string().to_lowercase();
+            if let Some(nap) = p.nap {
+                state.push_str(&format!(" ({})", nap.label()));
+            }
+            text.push(Line::from(vec![label("State"), Span::raw(state)]));
+            text.push(Line::from(vec![
+                label("Group"),
+                Span::raw(group_text(app, p)),
+            ]));
+            if let Some(volume) = &p.volume {
+                text.push(Line::from(vec![
+                    label("Volume"),
+                    Span::raw(format!("{volume} (external) {VOLUME_MARKER}")),
+                ]));
+            }
+            text.push(Line::from(vec![
+                label("CPU"),
+                Span::raw(if p.cpu_measured {
+                    format!("{}%", fmt_percent(f64::from(p.cpu_usage)))
+                } else {
+                    UNAVAILABLE.to_string()
+                }),
+            ]));
+            text.push(Line::from(vec![
+                label("Memory"),
+                Span::raw(fmt_bytes(p.memory)),
+            ]));
+            text.push(Line::from(vec![
+                label("Threads"),
+                Span::raw(fmt_opt(p.threads)),
+            ]));
+            text.push(Line::from(vec![label("FDs"), Span::raw(fmt_opt(p.fds))]));
+            text.push(Line::from(vec![
+                label("Sockets"),
+                Span::raw(fmt_opt(p.sockets)),
+            ]));
+            text.extend(ipc_lines(app, pid, label));
+
+            let extras = app.detail.as_ref().filter(|d| d.pid == pid);
+            let cwd = extras.and_then(|d| d.cwd.clone());
+            text.push(Line::from(vec![
+                label("CWD"),
+                Span::raw(cwd.unwrap_or_else(|| UNAVAILABLE.to_string())),
+            ]));
+            match extras.and_then(|d| d.environ.as_ref()) {
+                Some(env) => {
+                    text.push(Line::from(vec![
+                        label("Env"),
+                        Span::raw(format!("{} variables", env.len())),
+                    ]));
+                    text.extend(
+                        env.iter()
+                            .take(DETAIL_ENV_LINES)
+                            .map(|e| Line::from(format!("  {:10}{e}", ""))),
+                    );
+                }
+                None => text.push(Line::from(vec![label("Env"), Span::raw(UNAVAILABLE)])),
+            }
+
+            let note = if !app.privileged {
+                Some(
+                    "FDs, sockets, CWD and Env are only collected when elevated \
+                     — relaunch with `sudo syswatch` to see them.",
+                )
+            } else if !p.inspectable {
+                Some("This process is protected by the system and refuses inspection even as root.")
+            } else {
+                None
+            };
+            if let Some(note) = note {
+                text.push(Line::from(""));
+                text.push(Line::from(Span::styled(
+                    format!("  {UNAVAILABLE} {note}"),
+                    Style::default().fg(Color::DarkGray),
+                )));
+            }
+        }
+    }
+    text
+}
+
+/// Renders the "open with" command picker for the selected process.
+fn draw_export_popup(f: &mut Frame, app: &App, selected: usize, scope: Scope) {
+    let [from, to] = app.scope_bounds(scope);
+    let samples = app.system_history.range(from, to).count();
+    let mut text = vec![
+        Line::from(vec![
+            Span::raw("   Scope: "),
+            Span::styled(scope.label(), Style::default().fg(Color::Cyan)),
+            Span::raw(format!(" ({} samples)", fmt_thousands(samples))),
+        ]),
+        Line::from(""),
+    ];
+    text.extend(TARGETS.iter().enumerate().map(|(i, (_, label))| {
+        if i == selected {
+            Line::from(Span::styled(
+                format!(">> {label}"),
+                Style::default().bg(Color::DarkGray),
+            ))
+        } else {
+            Line::from(format!("   {label}"))
+        }
+    }));
+
+    let block = bordered(" Export chart history ")
+        .title_alignment(Alignment::Center)
+        .title_bottom(
+            Line::from(" j/k: select  w: scope  Enter: export  Esc: close ").right_aligned(),
+        );
+    let area = centered(f.area(), 60, TARGETS.len() as u16 + 4);
+
+    f.render_widget(Clear, area);
+    f.render_widget(Paragraph::new(text).block(block), area);
+}
+
+fn draw_open_with_popup(f: &mut Frame, app: &App, selected: usize) {
+    let Some(proc) = app.selected_process() else {
+        return;
+    };
+
+    let mut text = vec![Line::from("")];
+    text.extend(
+        app.config
+            .open_with
+            .iter()
+            .enumerate()
+            .map(|(i, template)| {
+                if i == selected {
+                    Line::from(Span::styled(
+                        format!(">> {template}"),
+                        Style::default().bg(Color::DarkGray),
+                    ))
+                } else {
+                    Line::from(format!("   {template}"))
+                }
+            }),
+    );
+
+    if let Some(template) = app.config.open_with.get(selected) {
+        text.push(Line::from(""));
+        text.push(Line::from(vec![
+            Span::raw("   $ "),
+            Span::styled(
+                external::expand(template, proc.pid, &proc.name).shown,
+                Style::default().fg(Color::Cyan),
+            ),
+        ]));
+    }
+
+    let title = format!(" Open {} ({}) with ", proc.name, proc.pid);
+    let block = bordered(&title)
+        .title_alignment(Alignment::Center)
+        .title_bottom(Line::from(" j/k: select  Enter: run  Esc: close ").right_aligned());
+    let height = app.config.open_with.len() as u16 + 6;
+    let area = centered(f.area(), 80, height);
+
+    f.render_widget(Clear, area);
+    f.render_widget(
+        Paragraph::new(text).block(block).wrap(Wrap { trim: false }),
+        area,
+    );
+}
+
+/// Renders the signal picker for the kill dialog.
+fn draw_kill_popup(f: &mut Frame, pid: Pid, name: &str, selected: usize, group: Option<Pid>) {
+    let target = match group {
+        Some(pgid) => Span::styled(
+            format!("  → process group {pgid}"),
+            Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+        ),
+        None => Span::raw(format!("  → process {pid}")),
+    };
+    let mut text = vec![Line::from(target), Line::from("")];
+    text.extend(SIGNALS.iter().enumerate().map(|(i, (sig, no))| {
+        let label = format!(" SIG{sig:<5} ({no})");
+        if i == selected {
+            Line::from(Span::styled(
+                format!(">>{label}"),
+                Style::default().bg(Color::DarkGray),
+            ))
+        } else {
+            Line::from(format!("  {label}"))
+        }
+    }));
+
+    let title = format!(" Signal {name} ({pid}) ");
+    let block = bordered(&title)
+        .title_alignment(Alignment::Center)
+        .title_bottom(
+            Line::from(" j/k: select  g: whole group  Enter: send  Esc: cancel ").right_aligned(),
+        );
+    let area = centered(f.area(), 58, SIGNALS.len() as u16 + 4);
+
+    f.render_widget(Clear, area);
+    f.render_widget(Paragraph::new(text).block(block), area);
+}
+
+/// Renders the last confirmation before signalling process group `pgid`,
+/// listing every member that will receive the signal.
+fn draw_group_kill_popup(f: &mut Frame, pgid: Pid, signal: usize, members: &[(Pid, String)]) {
+    let sig = SIGNALS.get(signal).map_or("?", |(sig, _)| sig);
+    let warning = Style::default().fg(Color::Red).add_modifier(Modifier::BOLD);
+    // Room for the member list: the screen less borders and the fixed lines.
+    let room = usize::from(f.area().height.saturating_sub(10)).max(1);
+
+    let mut text = vec![
+        Line::from(""),
+        Line::from(Span::styled(
+            format!(
+                "  SIG{sig} goes to every process in group {pgid} — {} in this snapshot:",
+                members.len()
+            ),
+            warning,
+        )),
+        Line::from(""),
+    ];
+    if members.is_empty() {
+        text.push(Line::from(Span::styled(
+            "    (none visible; the group may hold processes syswatch can't see)",
+            Style::default().fg(Color::DarkGray),
+        )));
+    }
+    let shown = if members.len() > room {
+        room - 1
+    } else {
+        members.len()
+    };
+    text.extend(
+        members[..shown.min(members.len())]
+            .iter()
+            .map(|(pid, name)| Line::from(format!("    {pid:>7}  {name}"))),
+    );
+    if shown < members.len() {
+        text.push(Line::from(format!(
+            "    … and {} more",
+            members.len() - shown
+        )));
+    }
+    text.push(Line::from(""));
+    text.push(Line::from(Span::styled(
+        "  Press Y (capital) to send it to all of them.",
+        warning,
+    )));
+
+    let title = format!(" Signal process group {pgid}? ");
+    let block = bordered(&title)
+        .border_style(Style::default().fg(Color::Red))
+        .title_alignment(Alignment::Center)
+        .title_bottom(Line::from(" Y: send to group  Esc: cancel ").right_aligned());
+    let area = centered(f.area(), 72, text.len() as u16 + 2);
+
+    f.render_widget(Clear, area);
+    f.render_widget(Paragraph::new(text).block(block), area);
+}
+
+/// Renders the sort menu, marking the active column and direction.
+fn draw_sort_popup(f: &mut Frame, app: &App, selected: usize) {
+    let arrow = if app.view.descending { "↓" } else { "↑" };
+    let mut text = vec![Line::from("")];
+    text.extend(COLUMNS.iter().enumerate().map(|(i, column)| {
+        let mark = if column.sort == app.view.sort {
+            arrow
+        } else {
+            " "
+        };
+        let label = format!(" {mark} {}", column.title);
+        if i == selected {
+            Line::from(Span::styled(
+                format!(">>{label}"),
+                Style::default().bg(Color::DarkGray),
+            ))
+        } else {
+            Line::from(format!("  {label}"))
+        }
+    }));
+
+    let block = bordered(" Sort by ")
+        .title_alignment(Alignment::Center)
+        .title_bottom(Line::from(" Enter: apply  r: reverse  c: columns ").right_aligned());
+    let area = centered(f.area(), 40, COLUMNS.len() as u16 + 3);
+
+    f.render_widget(Clear, area);
+    f.render_widget(Paragraph::new(text).block(block), area);
+}
+
+/// Renders the column settings draft, with the sort column marked.
+fn draw_columns_popup(f: &mut Frame, app: &App, editor: &ColumnEditor) {
+    let mut text = vec![Line::from("")];
+    text.extend(editor.order.iter().enumerate().map(|(i, &(c, visible))| {
+        let column = &COLUMNS[c];
+        let check = if visible { "[x]" } else { "[ ]" };
+        let sorted = if column.sort == app.view.sort {
+            "  (sort)"
+        } else {
+            ""
+        };
+        let label = format!(" {check} {}{sorted}", column.title);
+        let style = if visible {
+            Style::default()
+        } else {
+            Style::default().fg(Color::DarkGray)
+        };
+        if i == editor.selected {
+            Line::from(Span::styled(
+                format!(">>{label}"),
+                style.bg(Color::DarkGray).fg(Color::Reset),
+            ))
+        } else {
+            Line::from(Span::styled(format!("  {label}"), style))
+        }
+    }));
+
+    let block = bordered(" Columns ")
+        .title_alignment(Alignment::Center)
+        .title_bottom(Line::from(" J/K: move  Space: show/hide  Enter: apply ").right_aligned());
+    let area = centered(f.area(), 48, editor.order.len() as u16 + 3);
+
+    f.render_widget(Clear, area);
+    f.render_widget(Paragraph::new(text).block(block), area);
+}
+
+/// Renders the snooze length picker, with "resume" as the last row.
+fn draw_snooze_popup(f: &mut Frame, app: &App, selected: usize) {
+    let rows = Snooze::ALL
+        .iter()
+        .map(|s| s.label())
+        .chain(std::iter::once("resume now"));
+
+    let mut text = vec![Line::from("")];
+    text.extend(rows.enumerate().map(|(i, label)| {
+        if i == selected {
+            Line::from(Span::styled(
+                format!(">> {label}"),
+                Style::default().bg(Color::DarkGray),
+            ))
+        } else {
+            Line::from(format!("   {label}"))
+        }
+    }));
+    text.push(Line::from(""));
+    text.push(Line::from(Span::styled(
+        match app.snoozed() {
+            Some(left) => format!(
+                "   snoozed, {} left",
+                fmt_remaining(left + Duration::from_secs(59))
+            ),
+            None => "   alerts are logged either way".to_string(),
+        },
+        Style::default().fg(Color::DarkGray),
+    )));
+
+    let block = bordered(" Snooze notifications ")
+        .title_alignment(Alignment::Center)
+        .title_bottom(Line::from(" Enter: apply  Esc: close ").right_aligned());
+    let area = centered(f.area(), 40, Snooze::ALL.len() as u16 + 6);
+
+    f.render_widget(Clear, area);
+    f.render_widget(Paragraph::new(text).block(block), area);
+}
+
+/// Renders the filter preset list with the active preset marked.
+fn draw_filters_popup(f: &mut Frame, app: &App, selected: usize) {
+    let active = app.view.preset.as_deref();
+    let rows = std::iter::once(("(none)", "", active.is_none() && app.view.filter.is_empty()))
+        .chain(
+            app.config
+                .filters
+                .iter()
+                .map(|(name, pattern)| (name.as_str(), pattern.as_str(), active == Some(name))),
+        );
+
+    let mut text = vec![Line::from("")];
+    text.extend(rows.enumerate().map(|(i, (name, pattern, is_active))| {
+        let mark = if is_active { "●" } else { " " };
+        let label = format!(" {mark} {name:<12} {pattern}");
+        if i == selected {
+            Line::from(Span::styled(
+                format!(">>{label}"),
+                Style::default().bg(Color::DarkGray),
+            ))
+        } else {
+            Line::from(format!("  {label}"))
+        }
+    }));
+
+    let block = bordered(" Filter presets ")
+        .title_alignment(Alignment::Center)
+        .title_bottom(Line::from(" Enter: apply ").right_aligned());
+    let height = app.config.filters.len() as u16 + 4;
+    let area = centered(f.area(), 48, height);
+
+    f.render_widget(Clear, area);
+    f.render_widget(Paragraph::new(text).block(block), area);
+}
+
+/// Renders the key binding reference.
+fn draw_help_popup(f: &mut Frame, app: &App, query: &str, editing: bool) {
+    let key_style = Style::default()
+        .fg(Color::Yellow)
+        .add_modifier(Modifier::BOLD);
+    let entry = |keys: String, action: &'static str| {
+        Line::from(vec![
+            Span::styled(format!("  {keys:<14}"), key_style),
+            Span::raw(action),
+        ])
+    };
+
+    let needle = query.to_lowercase();
+    let mut text = vec![Line::from("")];
+    text.extend(
+        BINDINGS
+            .iter()
+            .filter(|b| b.matches(&needle))
+            .map(|b| entry(b.key_names(), b.help)),
+    );
+    if needle.is_empty() {
+        text.push(Line::from(""));
+        text.push(Line::from("In popups"));
+        text.extend(
+            POPUP_KEYS
+                .iter()
+                .map(|&(keys, action)| entry(keys.to_string(), action)),
+        );
+        let [source, bands] = mem_color_legend(app.memory_pressure);
+        text.push(Line::from(""));
+        text.push(Line::from(format!("Memory colour: {source}")));
+        text.push(Line::from(Span::styled(
+            format!("  {bands}"),
+            Style::default().fg(Color::DarkGray),
+        )));
+    } else if text.len() == 1 {
+        text.push(Line::from(Span::styled(
+            "  No binding matches.",
+            Style::default().fg(Color::DarkGray),
+        )));
+    }
+
+    let footer = if editing || !query.is_empty() {
+        format!(" /{query}{} ", if editing { "_" } else { "" })
+    } else {
+        " /: search  Esc: close ".to_string()
+    };
+    let block = bordered(" Help ")
+        .title_alignment(Alignment::Center)
+        .title_bottom(Line::from(footer).right_aligned());
+    let area = centered(
+        f.area(),
+        68,
+        BINDINGS.len() as u16 + POPUP_KEYS.len() as u16 + 8,
+    );
+
+    f.render_widget(Clear, area);
+    f.render_widget(Paragraph::new(text).block(block), area);
+}
+
+/// Renders the system memory breakdown above a chart of compressor activity.
+fn draw_memory_popup(f: &mut Frame, app: &App) {
+    let text = memory_lines(app);
+    let block = bordered(" Memory ")
+        .title_alignment(Alignment::Center)
+        .title_bottom(Line::from(" Esc: close ").right_aligned());
+    let height = text.len() as u16 + 4 + MEMORY_CHART_HEIGHT;
+    let area = centered(f.area(), 72, height);
+
+    f.render_widget(Clear, area);
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+    let [lines, chart] =
+        Layout::vertical([Constraint::Min(1), Constraint::Length(MEMORY_CHART_HEIGHT)])
+            .areas(inner);
+    f.render_widget(Paragraph::new(text), lines);
+    draw_compressor_chart(f, app, chart);
+}
+
+/// Lines of the memory popup: the breakdown, the compressor's sizes and
+/// ratio, and its current activity.
+fn memory_lines(app: &App) -> Vec<Line<'static>> {
+    let label =
+        |name: &str| Span::styled(format!("  {name:<12}"), Style::default().fg(Color::Yellow));
+    let opt = |bytes: Option<u64>| bytes.map_or_else(|| UNAVAILABLE.to_string(), fmt_bytes);
+
+    let compressed = match app.compressor {
+        None => UNAVAILABLE.to_string(),
+        Some(c) => match c.ratio() {
+            None => "nothing compressed".to_string(),
+            Some(ratio) => format!(
+                "{} → {}, ratio {ratio:.1}:1",
+                fmt_bytes(c.original),
+                fmt_bytes(c.stored)
+            ),
+        },
+    };
+    let activity = app.compressor_rates.map_or_else(
+        || UNAVAILABLE.to_string(),
+        |(compressions, decompressions)| {
+            format!(
+                "{}/s compressed · {}/s decompressed (pages)",
+                fmt_thousands(compressions.round() as usize),
+                fmt_thousands(decompressions.round() as usize)
+            )
+        },
+    );
+
+    vec![
+        Line::from(""),
+        Line::from(vec![
+            label("Used"),
+            Span::raw(format!(
+                "{} of {} ({} memory)",
+                fmt_bytes(app.used_memory),
+                fmt_bytes(app.total_memory),
+                app.memory_mode.label()
+            )),
+        ]),
+        Line::from(vec![label("Wired"), Span::raw(opt(app.wired_memory))]),
+        Line::from(vec![label("Cached"), Span::raw(opt(app.cached_memory))]),
+        Line::from(vec![label("Compressed"), Span::raw(compressed)]),
+        Line::from(vec![label("Compressor"), Span::raw(activity)]),
+        Line::from(vec![
+            label("Swap"),
+            Span::raw(format!(
+                "{} of {}",
+                fmt_bytes(app.used_swap),
+                fmt_bytes(app.total_swap)
+            )),
+        ]),
+        Line::from(vec![
+            label("Pressure"),
+            Span::raw(
+                app.memory_pressure
+                    .map_or(UNAVAILABLE, |p| p.id())
+                    .to_string(),
+            ),
+        ]),
+    ]
+}
+
+/// Charts compressions and decompressions per second over the CPU
+/// chart's window.
+///
+/// Sustained compressor churn tends to precede swapping, so it is worth
+/// seeing before the swap figure moves.
+fn draw_compressor_chart(f: &mut Frame, app: &App, area: Rect) {
+    let block = bordered(" Compressor pages/s ").title_alignment(Alignment::Center);
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+    if inner.width < CHART_MIN_SIZE.0 || inner.height < CHART_MIN_SIZE.1 {
+        return;
+    }
+
+    let (right, window) = (app.history_bounds()[1], app.window());
+    let compressions: Vec<(f64, f64)> = app
+        .compression_history
+        .window_points(right, window)
+        .collect();
+    let decompressions: Vec<(f64, f64)> = app
+        .decompression_history
+        .window_points(right, window)
+        .collect();
+    let high = compressions
+        .iter()
+        .chain(&decompressions)
+        .fold(1.0_f64, |hi, &(_, y)| hi.max(y));
+    let labels: Vec<String> = [0.0, high / 2.0, high]
+        .iter()
+        .map(|&y| fmt_thousands(y.round() as usize))
+        .collect();
+
+    let datasets = vec![
+        Dataset::default()
+            .name("compressed")
+            .marker(symbols::Marker::Braille)
+            .graph_type(GraphType::Line)
+            .style(Style::default().fg(Color::Magenta))
+            .data(&compressions),
+        Dataset::default()
+            .name("decompressed")
+            .marker(symbols::Marker::Braille)
+            .graph_type(GraphType::Line)
+            .style(Style::default().fg(Color::Cyan))
+            .data(&decompressions),
+    ];
+    let chart = Chart::new(datasets)
+        .x_axis(
+            Axis::default()
+                .style(Style::default().fg(Color::DarkGray))
+                .bounds([0.0, window]),
+        )
+        .y_axis(
+            Axis::default()
+                .style(Style::default().fg(Color::DarkGray))
+                .bounds([0.0, high])
+                .labels(labels),
+        );
+    f.render_widget(chart, inner);
+}
+
+/// Renders per-device read/write rates above a chart of the selected
+/// device's recent history.
+fn draw_disks_popup(f: &mut Frame, app: &App, selected: usize) {
+    let disks = &app.disks;
+    let rate = |bps: Option<f64>| {
+        bps.map_or_else(
+            || {
+                format!(
+                    "{UNAVAILABLE:>width$}",
+                    width = rate_width(app.config.rate_base)
+                )
+            },
+            |bps| disk_rate(app, bps),
+        )
+    };
+
+    let mut text = vec![
+        Line::from(""),
+        Line::from(Span::styled(
+            format!(
+                "   {:<8} {:>w$}  {:>w$}  VOLUMES",
+                "DEVICE",
+                "READ",
+                "WRITE",
+                w = rate_width(app.config.rate_base)
+            ),
+            Style::default()
+                .fg(Color::Yellow)
+                .add_modifier(Modifier::BOLD),
+        )),
+    ];
+    for (i, device) in disks.devices.iter().enumerate() {
+        let row = format!(
+            "{:<8} {}  {}  {}",
+            device.name(),
+            rate(device.read_rate),
+            rate(device.write_rate),
+            device.volumes.join(", ")
+        );
+        text.push(if i == selected {
+            Line::from(Span::styled(
+                format!(">> {row}"),
+                Style::default().bg(Color::DarkGray),
+            ))
+        } else {
+            Line::from(format!("   {row}"))
+        });
+    }
+    if disks.devices.is_empty() {
+        let reason = match disks.error {
+            Some(err) => format!("   disk statistics unavailable (error {err})"),
+            None => "   no disks found".to_string(),
+        };
+        text.push(Line::from(Span::styled(
+            reason,
+            Style::default().fg(Color::DarkGray),
+        )));
+    }
+
+    let block = bordered(" Disk I/O ")
+        .title_alignment(Alignment::Center)
+        .title_bottom(Line::from(" j/k: select  Esc: close ").right_aligned());
+    let height = text.len() as u16 + 4 + DISK_CHART_HEIGHT;
+    let area = centered(f.area(), 80, height);
+
+    f.render_widget(Clear, area);
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+    let [lines, chart] =
+        Layout::vertical([Constraint::Min(1), Constraint::Length(DISK_CHART_HEIGHT)]).areas(inner);
+    f.render_widget(Paragraph::new(text), lines);
+    let device = disks
+        .devices
+        .get(selected.min(disks.devices.len().saturating_sub(1)));
+    if let Some(device) = device {
+        draw_disk_chart(f, app, device, chart);
+    }
+}
+
+/// Charts one device's read and write rates over its kept history.
+fn draw_disk_chart(f: &mut Frame, app: &App, device: &Device, area: Rect) {
+    let title = format!(" {} · last {}s ", device.name(), DEVICE_HISTORY);
+    let block = bordered(&title).title_alignment(Alignment::Center);
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+    if inner.width < CHART_MIN_SIZE.0 || inner.height < CHART_MIN_SIZE.1 {
+        return;
+    }
+
+    let (end, span) = (device.read_history.end(), DEVICE_HISTORY as f64);
+    let reads: Vec<(f64, f64)> = device.read_history.window_points(end, span).collect();
+    let writes: Vec<(f64, f64)> = device.write_history.window_points(end, span).collect();
+    let high = reads
+        .iter()
+        .chain(&writes)
+        .fold(1.0_f64, |hi, &(_, y)| hi.max(y));
+    let labels: Vec<String> = [0.0, high / 2.0, high]
+        .iter()
+        .map(|&y| disk_rate(app, y).trim_start().to_string())
+        .collect();
+
+    let datasets = vec![
+        Dataset::default()
+            .name("read")
+            .marker(symbols::Marker::Braille)
+            .graph_type(GraphType::Line)
+            .style(Style::default().fg(Color::Green))
+            .data(&reads),
+        Dataset::default()
+            .name("write")
+            .marker(symbols::Marker::Braille)
+            .graph_type(GraphType::Line)
+            .style(Style::default().fg(Color::Red))
+            .data(&writes),
+    ];
+    let chart = Chart::new(datasets)
+        .x_axis(
+            Axis::default()
+                .style(Style::default().fg(Color::DarkGray))
+                .bounds([0.0, span]),
+        )
+        .y_axis(
+            Axis::default()
+                .style(Style::default().fg(Color::DarkGray))
+                .bounds([0.0, high])
+                .labels(labels),
+        );
+    f.render_widget(chart, inner);
+}
+
+/// Renders the locked process's descendant timeline, oldest first.
+fn draw_children_popup(f: &mut Frame, app: &App, scroll: usize) {
+    let Some(timeline) = &app.children else {
+        return;
+    };
+    let area = centered(f.area(), 84, f.area().height.saturating_sub(4));
+    let visible = usize::from(area.height.saturating_sub(5));
+
+    let mut text = vec![
+        Line::from(""),
+        Line::from(Span::styled(
+            format!(
+                "  {:>7} {:>7}  {:<CHILD_NAME_WIDTH$} {:>8} {:>8} {:>8} {:>9}",
+                "PID", "PPID", "NAME", "START", "END", "PEAK CPU", "PEAK MEM"
+            ),
+            Style::default()
+                .fg(Color::Yellow)
+                .add_modifier(Modifier::BOLD),
+        )),
+    ];
+    if timeline.records.is_empty() {
+        text.push(Line::from("  No children seen yet."));
+    }
+    let first = scroll.min(timeline.records.len().saturating_sub(visible));
+    text.extend(timeline.records.iter().skip(first).take(visible).map(|r| {
+        let start = if r.preexisting {
+            "before".to_string()
+        } else {
+            fmt_offset(timeline.since, r.started)
+        };
+        let end = r
+            .ended
+            .map_or_else(|| "running".to_string(), |t| fmt_offset(timeline.since, t));
+        let line = format!(
+            "  {:>7} {:>7}  {} {start:>8} {end:>8} {:>7.1}% {:>9}",
+            r.pid,
+            fmt_opt(r.parent),
+            fit(&r.name, CHILD_NAME_WIDTH),
+            r.peak_cpu,
+            fmt_bytes(r.peak_memory)
+        );
+        let style = if r.ended.is_some() {
+            Style::default().fg(Color::DarkGray)
+        } else {
+            Style::default()
+        };
+        Line::from(Span::styled(line, style))
+    }));
+
+    let mut title = format!(
+        " Children of {} ({}) ",
+        timeline.parent_name, timeline.parent
+    );
+    if timeline.dropped > 0 {
+        title.push_str(&format!("· {} older dropped ", timeline.dropped));
+    }
+    let block = bordered(&title)
+        .title_alignment(Alignment::Center)
+        .title_bottom(Line::from(" j/k: scroll  e: export JSON  Esc: close ").right_aligned());
+
+    f.render_widget(Clear, area);
+    f.render_widget(Paragraph::new(text).block(block), area);
+}
+
+/// Formats `time` relative to `since` as `+m:ss`, or `-m:ss` before it.
+fn fmt_offset(since: SystemTime, time: SystemTime) -> String {
+    let (sign, offset) = match time.duration_since(since) {
+        Ok(after) => ('+', after),
+        Err(before) => ('-', before.duration()),
+    };
+    let secs = offset.as_secs();
+    format!("{sign}{}:{:02}", secs / 60, secs % 60)
+}
+
+/// Renders the progress or result of a call-stack sampling run.
+fn draw_sample_popup(f: &mut Frame, job: &SampleJob) {
+    let mut text = vec![Line::from("")];
+
+    match &job.status {
+        SampleStatus::Running => {
+            text.push(Line::from(format!(
+                "  Sampling… {:.1}s elapsed",
+                job.started.elapsed().as_secs_f64()
+            )));
+        }
+        SampleStatus::Failed(msg) => {
+            text.push(Line::from(Span::styled(
+                format!("  {msg}"),
+                Style::default().fg(Color::Red),
+            )));
+        }
+        SampleStatus::Done(report) => {
+            if report.hottest.is_empty() {
+                text.push(Line::from("  No top-of-stack symbols found in the report."));
+            } else {
+                text.push(Line::from(Span::styled(
+                    format!("  {:>7}  Hottest symbols", "Samples"),
+                    Style::default()
+                        .fg(Color::Yellow)
+                        .add_modifier(Modifier::BOLD),
+                )));
+                text.extend(
+                    report
+                        .hottest
+                        .iter()
+                        .map(|(symbol, count)| Line::from(format!("  {count:>7}  {symbol}"))),
+                );
+            }
+            text.push(Line::from(""));
+            text.push(Line::from(vec![
+                Span::raw("  Full report: "),
+                Span::styled(
+                    report.path.display().to_string(),
+                    Style::default().fg(Color::Cyan),
+                ),
+            ]));
+        }
+    }
+
+    let title = format!(" Sample: {} ({}) ", job.name, job.pid);
+    let block = bordered(&title)
+        .title_alignment(Alignment::Center)
+        .title_bottom(Line::from(" Esc: close ").right_aligned());
+    let area = centered(f.area(), 80, 16);
+
+    f.render_widget(Clear, area);
+    f.render_widget(
+        Paragraph::new(text).block(block).wrap(Wrap { trim: false }),
+        area,
+    );
+}
+
+// ── Recorded CSV viewer ─────────────────────────────────────
+
+/// Width of the inspect panel beside the replay chart.
+const REPLAY_PANEL_WIDTH: u16 = 34;
+
+/// Key hints shown under the replay chart.
+const REPLAY_KEYS: &str = " h/l step  H/L page  g/G start/live  z/Z zoom  q quit";
+
+/// Renders the `--view` screen: chart with a cursor line, values at the cursor, key hints.
+pub fn draw_replay(f: &mut Frame, replay: &Replay) {
+    let full = f.area();
+    if full.is_empty() {
+        return;
+    }
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(1)
+        .constraints([Constraint::Min(1), Constraint::Length(1)])
+        .split(full);
+    let cols = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Min(1), Constraint::Length(REPLAY_PANEL_WIDTH)])
+        .split(rows[0]);
+
+    draw_replay_chart(f, replay, cols[0]);
+    draw_replay_panel(f, replay, cols[1]);
+
+    let status = match &replay.error {
+        Some(err) => Span::styled(format!(" {err}"), Style::default().fg(Color::Red)),
+        None if replay.following() => Span::styled(" live", Style::default().fg(Color::Green)),
+        None => Span::styled(" paused", Style::default().fg(Color::Yellow)),
+    };
+    let hints = Span::styled(REPLAY_KEYS, Style::default().fg(Color::DarkGray));
+    f.render_widget(Paragraph::new(Line::from(vec![status, hints])), rows[1]);
+}
+
+/// Renders the recorded CPU split and memory use with a vertical cursor line.
+fn draw_replay_chart(f: &mut Frame, replay: &Replay, area: Rect) {
+    // Recorded times are Unix seconds; the chart plots over [0, window].
+    let bounds = replay.bounds();
+    let x = |time: f64| time - bounds[0];
+    let rows = &replay.recording.rows;
+    let visible = rows
+        .iter()
+        .filter(|r| r.time >= bounds[0] && r.time <= bounds[1]);
+    let system: Vec<(f64, f64)> = visible.clone().map(|r| (x(r.time), r.cpu_system)).collect();
+    let user: Vec<(f64, f64)> = visible.clone().map(|r| (x(r.time), r.cpu_user)).collect();
+    let memory: Vec<(f64, f64)> = visible
+        .filter_map(|r| Some((x(r.time), mem_pct(r.mem_used?, r.mem_total?))))
+        .collect();
+    let current = replay.cursor().map(|i| &rows[i]);
+    let cursor: Vec<(f64, f64)> =
+        current.map_or_else(Vec::new, |r| vec![(x(r.time), 0.0), (x(r.time), 100.0)]);
+
+    let series: [ChartSeries; 3] = [
+        (
+            "System",
+            Color::Red,
+            current.map_or(0.0, |r| r.cpu_system),
+            system,
+        ),
+        (
+            "User",
+            Color::Cyan,
+            current.map_or(0.0, |r| r.cpu_user),
+            user,
+        ),
+        (
+            "Memory",
+            Color::Magenta,
+            current
+                .and_then(|r| Some(mem_pct(r.mem_used?, r.mem_total?)))
+                .unwrap_or(0.0),
+            memory,
+        ),
+    ];
+    let mut datasets: Vec<Dataset> = series
+        .iter()
+        .map(|(_, color, _, data)| {
+            Dataset::default()
+                .marker(symbols::Marker::Braille)
+                .graph_type(GraphType::Line)
+                .style(Style::default().fg(*color))
+                .data(data)
+        })
+        .collect();
+    let markers: Vec<[(f64, f64); 2]> = replay
+        .recording
+        .events
+        .iter()
+        .filter(|e| e.time >= bounds[0] && e.time <= bounds[1])
+        .map(|e| [(x(e.time), 0.0), (x(e.time), 100.0)])
+        .collect();
+    for marker in &markers {
+        datasets.push(
+            Dataset::default()
+                .marker(symbols::Marker::Braille)
+                .graph_type(GraphType::Line)
+                .style(Style::default().fg(Color::Yellow))
+                .data(marker),
+        );
+    }
+    datasets.push(
+        Dataset::default()
+            .marker(symbols::Marker::Braille)
+            .graph_type(GraphType::Line)
+            .style(Style::default().fg(Color::White))
+            .data(&cursor),
+    );
+    let legend: Vec<_> = series
+        .iter()
+        .map(|&(name, color, value, _)| (name, color, value, true))
+        .collect();
+
+    let mut title = format!(
+        " {} · {} ",
+        replay.recording.path.display(),
+        fmt_duration(replay.window())
+    );
+    if !replay.recording.events.is_empty() {
+        title.push_str(&format!(
+            "· {} alerts (yellow) ",
+            replay.recording.events.len()
+        ));
+    }
+    let block = bordered(&title)
+        .title_alignment(Alignment::Center)
+        .title(legend_line(&legend));
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+    if inner.width < CHART_MIN_SIZE.0 || inner.height < CHART_MIN_SIZE.1 {
+        return;
+    }
+
+    let chart = Chart::new(datasets)
+        .x_axis(
+            Axis::default()
+                .style(Style::default().fg(Color::DarkGray))
+                .bounds([0.0, bounds[1] - bounds[0]]),
+        )
+        .y_axis(
+            Axis::default()
+                .style(Style::default().fg(Color::DarkGray))
+                .bounds([0.0, 100.0])
+                .labels(CPU_Y_LABELS),
+        );
+    f.render_widget(chart, inner);
+}
+
+/// Renders every recorded value of the row under the cursor.
+fn draw_replay_panel(f: &mut Frame, replay: &Replay, area: Rect) {
+    let rows = &replay.recording.rows;
+    let mut text = Vec::new();
+    let label =
+        |name: &str| Span::styled(format!("  {name:<10}"), Style::default().fg(Color::Yellow));
+    let opt =
+        |v: Option<u64>, fmt: fn(u64) -> String| v.map_or_else(|| UNAVAILABLE.to_string(), fmt);
+    match replay.cursor() {
+        None => text.push(Line::from("  No samples yet.")),
+        Some(i) => {
+            let r = &rows[i];
+            let time = std::time::UNIX_EPOCH + std::time::Duration::from_secs_f64(r.time.max(0.0));
+            text.push(Line::from(format!("  {}", json::timestamp(time))));
+            text.push(Line::from(format!("  sample {} of {}", i + 1, rows.len())));
+            text.push(Line::from(""));
+            text.push(Line::from(vec![
+                label("User"),
+                Span::raw(format!("{:.2}%", r.cpu_user)),
+            ]));
+            text.push(Line::from(vec![
+                label("System"),
+                Span::raw(format!("{:.2}%", r.cpu_system)),
+            ]));
+            text.push(Line::from(vec![
+                label("Idle"),
+                Span::raw(
+                    r.cpu_idle
+                        .map_or_else(|| UNAVAILABLE.to_string(), |v| format!("{v:.2}%")),
+                ),
+            ]));
+            text.push(Line::from(vec![
+                label("Memory"),
+                Span::raw(format!(
+                    "{} / {}",
+                    opt(r.mem_used, fmt_bytes),
+                    opt(r.mem_total, fmt_bytes)
+                )),
+            ]));
+            text.push(Line::from(vec![
+                label("Swap"),
+                Span::raw(opt(r.swap_used, fmt_bytes)),
+            ]));
+            text.push(Line::from(vec![
+                label("Pressure"),
+                Span::raw(
+                    r.memory_pressure
+                        .clone()
+                        .unwrap_or_else(|| UNAVAILABLE.to_string()),
+                ),
+            ]));
+            text.push(Line::from(vec![
+                label("Threads"),
+                Span::raw(opt(r.threads, |n| fmt_thousands(n as usize))),
+            ]));
+            text.push(Line::from(vec![
+                label("Processes"),
+                Span::raw(opt(r.processes, |n| fmt_thousands(n as usize))),
+            ]));
+            let events: Vec<_> = replay.recording.events_at(i).collect();
+            if !events.is_empty() {
+                text.push(Line::from(""));
+                text.push(Line::from(label("Alerts")));
+                for event in events {
+                    text.push(Line::from(Span::styled(
+                        format!("  {}", event.message),
+                        Style::default().fg(Color::Yellow),
+                    )));
+                }
+            }
+        }
+    }
+    if replay.recording.skipped > 0 {
+        text.push(Line::from(""));
+        text.push(Line::from(Span::styled(
+            format!("  {} malformed rows skipped", replay.recording.skipped),
+            Style::default().fg(Color::DarkGray),
+        )));
+    }
+    f.render_widget(Paragraph::new(text).block(bordered(" Sample ")), area);
+}
+
+/// `used` as a percentage of `total`.
+fn mem_pct(used: u64, total: u64) -> f64 {
+    if total > 0 {
+        used as f64 / total as f64 * 100.0
+    } else {
+        0.0
+    }
+}
+
+// ── Plain output ────────────────────────────────────────────
+
+/// Processes listed in each `--plain` report.
+const PLAIN_ROWS: usize = 20;
+
+/// Renders one `--plain` report: headline figures as sentences, then the
+/// top rows of the table as space-aligned text with no colour or box drawing.
+pub fn plain_report(app: &App) -> String {
+    let mem_pct = if app.total_memory > 0 {
+        app.used_memory as f64 / app.total_memory as f64 * 100.0
+    } else {
+        0.0
+    };
+    let view = &app.view;
+    let order = if view.descending {
+        "descending"
+    } else {
+        "ascending"
+    };
+    let filter = match &view.preset {
+        Some(name) => format!(", filter preset {name}"),
+        None if !view.filter.is_empty() => format!(", filter {}", view.filter),
+        None => String::new(),
+    };
+
+    let mut out = format!(
+        "CPU: {:.1}% user, {:.1}% system, {:.1}% idle.\n\
+         Memory: {} used of {} ({mem_pct:.0}%). Swap: {} used of {}.\n\
+         Processes: {}, threads: {}.\n\
+         Top {} of {} by {}, {order}{filter}:\n",
+        app.user_pct,
+        app.system_pct,
+        app.idle_pct,
+        fmt_bytes(app.used_memory),
+        fmt_bytes(app.total_memory),
+        fmt_bytes(app.used_swap),
+        fmt_bytes(app.total_swap),
+        app.processes.len(),
+        app.thread_count,
+        view.process_count().min(PLAIN_ROWS),
+        view.process_count(),
+        view.sort.label(),
+    );
+
+    let mut lines: Vec<Vec<String>> = vec![
+        view.columns
+            .iter()
+            .map(|&c| COLUMNS[c].title.to_string())
+            .collect(),
+    ];
+    lines.extend(
+        view.rows
+            .iter()
+            .filter_map(|row| match row {
+                ViewRow::Process { index, prefix } => Some((&app.processes[*index], prefix)),
+                ViewRow::Separator(_) => None,
+            })
+            .take(PLAIN_ROWS)
+            .map(|(p, prefix)| {
+                let pinned = app.alerts.saturated.contains(&p.pid);
+                view.columns
+                    .iter()
+                    .map(|&c| cell_text(COLUMNS[c].sort, p, prefix, pinned, app.config.memory_unit))
+                    .collect()
+            }),
+    );
+
+    let widths: Vec<usize> = (0..view.columns.len())
+        .map(|i| {
+            lines
+                .iter()
+                .map(|l| display_width(&l[i]))
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+    for line in &lines {
+        let cells: Vec<String> = line
+            .iter()
+            .zip(&widths)
+            .zip(&view.columns)
+            .map(|((text, &width), &c)| pad(text, width, COLUMNS[c].right_aligned))
+            .collect();
+        out.push_str(cells.join("  ").trim_end());
+        out.push('\n');
+    }
+    out
+}
+
+// ── Helpers ─────────────────────────────────────────────────
+
+/// Returns a `width` × `height` rect centred in `area`, clamped to fit.
+fn centered(area: Rect, width: u16, height: u16) -> Rect {
+    let width = width.min(area.width);
+    let height = height.min(area.height);
+    Rect {
+        x: area.x + (area.width - width) / 2,
+        y: area.y + (area.height - height) / 2,
+        width,
+        height,
+    }
+}
+
+/// Builds a right-aligned chart legend: `■ System 8.1%  ■ User 29.4%`.
+///
+/// Each entry is `(name, color, current value, visible)`; hidden series
+/// are dimmed and struck through so it's clear they're toggled off.
+fn legend_line(entries: &[(&str, Color, f64, bool)]) -> Line<'static> {
+    let mut spans = vec![Span::raw(" ")];
+    for &(name, color, value, visible) in entries {
+        let (swatch, label) = if visible {
+            (Style::default().fg(color), Style::default())
+        } else {
+            let off = Style::default()
+                .fg(Color::DarkGray)
+                .add_modifier(Modifier::CROSSED_OUT);
+            (off, off)
+        };
+        spans.push(Span::styled("■ ", swatch));
+        spans.push(Span::styled(format!("{name} {value:.1}%"), label));
+        spans.push(Span::raw("  "));
+    }
+    spans.pop();
+    spans.push(Span::raw(" "));
+    Line::from(spans).right_aligned()
+}
+
+/// Creates a bordered block with an optional title.
+fn bordered(title: &str) -> Block<'_> {
+    Block::default()
+        .title(title)
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::DarkGray))
+}
+
+/// Formats CPU-seconds like htop's TIME+: `m:ss.cc`, or `h:mm:ss` past an hour.
+fn fmt_cpu_time(secs: f64) -> String {
+    let total = secs.max(0.0);
+    let whole = total as u64;
+    if whole >= 3600 {
+        format!("{}:{:02}:{:02}", whole / 3600, whole / 60 % 60, whole % 60)
+    } else {
+        format!("{}:{:05.2}", whole / 60, total % 60.0)
+    }
+}
+
+/// Formats an optional value, rendering unavailable ones as [`UNAVAILABLE`].
+fn fmt_opt<T: ToString>(value: Option<T>) -> String {
+    value.map_or_else(|| UNAVAILABLE.to_string(), |v| v.to_string())
+}
+
+/// Formats a whole-second duration as its largest unit, e.g. `3m` or `24h`.
+fn fmt_duration(secs: f64) -> String {
+    let secs = secs as u64;
+    if secs >= 3600 && secs.is_multiple_of(3600) {
+        format!("{}h", secs / 3600)
+    } else if secs >= 60 && secs.is_multiple_of(60) {
+        format!("{}m", secs / 60)
+    } else {
+        format!("{secs}s")
+    }
+}
+
+/// Formats a count with K / M suffixes for thousands / millions.
+fn fmt_thousands(n: usize) -> String {
+    if n >= 1_000_000 {
+        format!("{:.1}M", n as f64 / 1_000_000.0)
+    } else if n >= 1_000 {
+        format!("{:.1}K", n as f64 / 1_000.0)
+    } else {
+        format!("{n}")
+    }
+}
+
+/// Battery charge with the time to empty, then the drain rate if
+/// `room` allows a second line.
+fn battery_lines(app: &App, reading: Reading, room: usize) -> Vec<Line<'static>> {
+    let remaining = if reading.charging {
+        "charging".to_string()
+    } else if !reading.on_battery {
+        "on AC".to_string()
+    } else {
+        app.battery
+            .time_to_empty()
+            .map_or_else(String::new, fmt_remaining)
+    };
+    let mut lines = vec![Line::from(vec![
+        Span::raw("  Battery:    "),
+        Span::styled(
+            format!("{:.0}%", reading.percent),
+            Style::default().fg(battery_color(reading.percent)),
+        ),
+        Span::styled(
+            format!(" {remaining}"),
+            Style::default().fg(Color::DarkGray),
+        ),
+    ])];
+    if room > 1 && reading.on_battery && !reading.charging {
+        let rate = app
+            .battery
+            .drain_rate()
+            .map_or_else(|| "measuring".to_string(), |r| format!("-{r:.1}%/h"));
+        lines.push(Line::from(Span::styled(
+            format!("  ({rate})"),
+            Style::default().fg(Color::DarkGray),
+        )));
+    }
+    lines.truncate(room);
+    lines
+}
+
+/// Picks a colour for the battery charge.
+fn battery_color(percent: f64) -> Color {
+    match percent as u32 {
+        0..=10 => Color::Red,
+        11..=25 => Color::Yellow,
+        _ => Color::Green,
+    }
+}
+
+/// Picks a colour for the memory reading.
+///
+/// The kernel's pressure level decides when it is readable: macOS keeps
+/// memory "used" as cache, so a high share with normal pressure is
+/// healthy. Without it, the used share of total memory is banded instead.
+fn mem_color(used: f64, total: f64, pressure: Option<MemoryPressure>) -> Color {
+    match pressure {
+        Some(MemoryPressure::Normal) => return Color::Green,
+        Some(MemoryPressure::Warning) => return Color::Yellow,
+        Some(MemoryPressure::Critical) => return Color::Red,
+        None => {}
+    }
+    if total <= 0.0 {
+        return Color::White;
+    }
+    let pct = used / total * 100.0;
+    match pct as u32 {
+        0..=60 => Color::Green,
+        61..=85 => Color::Yellow,
+        _ => Color::Red,
+    }
+}
+
+/// The help overlay's explanation of what [`mem_color`] is using.
+fn mem_color_legend(pressure: Option<MemoryPressure>) -> [&'static str; 2] {
+    match pressure {
+        Some(_) => [
+            "by memory pressure",
+            "green normal · yellow warning · red critical",
+        ],
+        None => [
+            "by share used (pressure unavailable)",
+            "green ≤ 60 % · yellow ≤ 85 % · red above",
+        ],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ratatui::Terminal;
+    use ratatui::backend::TestBackend;
+    use ratatui::buffer::Buffer;
+
+    use super::*;
+    use crate::cli::Options;
+    use crate::config::Config;
+    use crate::fixtures::{Fixture, FixtureOptions};
+    use crate::monitor::{Monitor, MonitorOptions};
+
+    /// Slot width of the long-session comparisons: not a binary fraction,
+    /// so sample x values round the way a real tick interval's do.
+    const SLOT: Duration = Duration::from_millis(100);
+
+    /// An app that has ticked once on a churn-free fixture of `processes`.
+    fn fixture_app(processes: usize) -> App {
+        configured_app(Config::default(), processes)
+    }
+
+    /// Like [`fixture_app`], under `config`.
+    fn configured_app(config: Config, processes: usize) -> App {
+        let options = FixtureOptions {
+            processes,
+            churn: 0.0,
+            ..FixtureOptions::default()
+        };
+        let monitor = Monitor::from_snapshots(App::monitor_options(&config), Fixture::new(options));
+        let mut app = App::with_monitor(&Options::default(), config, monitor, None);
+        app.tick();
+        app
+    }
+
+    /// A fixture app whose CPU history holds `hours` of samples taken
+    /// `tick_interval` apart. The newest samples are the same whatever
+    /// the length and every 500th is late, so only the session's age differs.
+    fn session_app(tick_interval: Duration, hours: f64) -> App {
+        let config = Config {
+            tick_interval,
+            ..Config::default()
+        };
+        let mut app = configured_app(config, 5);
+        let slot = tick_interval.as_secs_f64();
+        let last = (hours * 3600.0 / slot).round() as usize;
+        let load = |back: usize, rate: f64| (back as f64 * rate).sin() * 20.0 + 25.0;
+        for i in 0..=last {
+            let (x, back) = (i as f64 * slot, last - i);
+            let sys = app.system_history.push_at(x, load(back, 0.37));
+            let usr = app.user_history.push_at(x, load(back, 0.011));
+            for ((x, sys), (_, usr)) in sys.into_iter().zip(usr) {
+                app.cpu_minutes.push(x, sys + usr);
+            }
+            if back % 500 == 250 {
+                app.mark_late(Duration::from_secs(3));
+            }
+        }
+        app
+    }
+
+    /// Draws `app` at `width` × `height`, returning the whole buffer.
+    fn screen(app: &mut App, width: u16, height: u16) -> Buffer {
+        let mut terminal = Terminal::new(TestBackend::new(width, height)).unwrap();
+        terminal.draw(|f| draw(f, app)).unwrap();
+        terminal.backend().buffer().clone()
+    }
+
+    /// Draws `app` at `width` × `height`, returning the screen's lines.
+    fn render(app: &mut App, width: u16, height: u16) -> Vec<String> {
+        let buffer = screen(app, width, height);
+        (0..height)
+            .map(|y| (0..width).map(|x| buffer[(x, y)].symbol()).collect())
+            .collect()
+    }
+
+    /// The short-table notice on screen, if any.
+    fn notice(lines: &[String]) -> Option<String> {
+        lines
+            .iter()
+            .find(|l| l.contains("— PgDn") || l.contains("— PgUp"))
+            .map(|l| l.trim_matches(|c: char| c == '│' || c == ' ').to_string())
+    }
+
+    #[test]
+    fn numeric_columns_line_up_under_their_headers() {
+        let mut app = fixture_app(5);
+        let lines: Vec<Vec<char>> = render(&mut app, 100, 30)
+            .iter()
+            .map(|l| l.chars().collect())
+            .collect();
+        let header = lines
+            .iter()
+            .position(|l| String::from_iter(l).contains("PID Process"))
+            .unwrap();
+        let title_at = |title: &str| {
+            let text = String::from_iter(&lines[header]);
+            text[..text.find(title).unwrap()].chars().count()
+        };
+        // The header is followed by a blank line, then one line per process.
+        let rows = &lines[header + 2..header + 7];
+        for title in ["PID", "CPU %", "Memory", "Threads"] {
+            let last = title_at(title) + title.chars().count() - 1;
+            for row in rows {
+                let text = String::from_iter(row);
+                assert_ne!(row[last], ' ', "{title} ends at {last}: {text:?}");
+                assert!(
+                    row[last + 1] == ' ' || row[last + 1] == '│',
+                    "{title} ends at {last}: {text:?}"
+                );
+            }
+        }
+        // The process name stays left-aligned under its header.
+        let first = title_at("Process");
+        for row in rows {
+            assert_ne!(row[first], ' ', "{:?}", String::from_iter(row));
+            assert_eq!(row[first - 1], ' ', "{:?}", String::from_iter(row));
+        }
+    }
+
+    #[test]
+    fn short_table_counts_the_rows_cut_off() {
+        // Heights where the table fits its header and two or three rows.
+        for (height, rows) in [(20, 2), (24, 3)] {
+            let mut app = fixture_app(60);
+            let lines = render(&mut app, 100, height);
+            assert_eq!(app.page_rows, rows, "height {height}");
+            assert_eq!(
+                notice(&lines).as_deref(),
+                Some(format!("… {} more — PgDn / resize", 60 - rows).as_str())
+            );
+        }
+
+        // Ten rows or more need no notice.
+        let mut app = fixture_app(60);
+        let lines = render(&mut app, 100, 40);
+        assert!(app.page_rows >= SMALL_TABLE_ROWS);
+        assert_eq!(notice(&lines), None);
+    }
+
+    #[test]
+    fn page_down_walks_a_short_table_without_skipping_rows() {
+        const ROWS: usize = 61;
+        for height in [20, 24] {
+            let mut app = fixture_app(ROWS);
+            render(&mut app, 100, height);
+            let step = app.page_rows;
+            let mut offsets = Vec::new();
+            loop {
+                let lines = render(&mut app, 100, height);
+                let offset = app.table_state.offset();
+                offsets.push(offset);
+                let notice = notice(&lines).unwrap();
+                if offset + step >= ROWS {
+                    assert_eq!(notice, format!("… {offset} above — PgUp / resize"));
+                    assert_eq!(app.table_state.selected(), Some(ROWS - 1));
+                    break;
+                }
+                // The cursor keeps its place at the top of the page.
+                assert_eq!(app.table_state.selected(), Some(offset));
+                assert_eq!(
+                    notice,
+                    format!("… {} more — PgDn / resize", ROWS - offset - step)
+                );
+                app.page(1);
+            }
+            // Each page starts where the last one ended; the final page is
+            // kept full, so it ends on the last row.
+            let (last, pages) = offsets.split_last().unwrap();
+            for (i, &offset) in pages.iter().enumerate() {
+                assert_eq!(offset, i * step, "height {height}");
+            }
+            assert_eq!(*last, ROWS - step, "height {height}");
+        }
+    }
+
+    #[test]
+    fn degenerate_sizes_draw_without_panicking() {
+        use crossterm::event::KeyCode::{self, Char, Enter, F};
+        // Each list of keys opens a popup or switches a layout on.
+        let setups: &[&[KeyCode]] = &[
+            &[],
+            &[Char('?')],
+            &[Char('?'), Char('/'), Char('k')],
+            &[Char('o')],
+            &[F(2)],
+            &[Char('F')],
+            &[Char('/'), Char('w')],
+            &[Enter],
+            &[Char('a')],
+            &[Char('C')],
+            &[Char('M')],
+            &[Char('I')],
+            &[Char('e')],
+            &[Char('x')],
+            &[Char('K')],
+            &[Char('K'), Char('g')],
+            &[Char('n')],
+            &[Char('D')],
+            &[Char('d')],
+            &[Char('t')],
+            &[Char('c')],
+        ];
+        let plain = render(&mut fixture_app(30), 120, 40);
+        for keys in setups {
+            let mut app = fixture_app(30);
+            for &key in *keys {
+                crate::tui::handle_key(&mut app, key);
+            }
+            for (width, height) in [(1, 1), (200, 3), (10, 50), (0, 0), (3, 200)] {
+                let lines = render(&mut app, width, height);
+                assert_eq!(
+                    lines.len(),
+                    usize::from(height),
+                    "{keys:?} at {width}x{height}"
+                );
+            }
+            // The keys did change what is drawn.
+            if !keys.is_empty() {
+                assert_ne!(render(&mut app, 120, 40), plain, "{keys:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn memory_color_follows_pressure_over_the_used_share() {
+        // Pressure decides whatever share is used.
+        for used in [0.0, 50.0, 99.0] {
+            assert_eq!(
+                mem_color(used, 100.0, Some(MemoryPressure::Normal)),
+                Color::Green
+            );
+            assert_eq!(
+                mem_color(used, 100.0, Some(MemoryPressure::Warning)),
+                Color::Yellow
+            );
+            assert_eq!(
+                mem_color(used, 100.0, Some(MemoryPressure::Critical)),
+                Color::Red
+            );
+        }
+        assert_eq!(
+            mem_color(0.0, 0.0, Some(MemoryPressure::Critical)),
+            Color::Red
+        );
+        assert_eq!(
+            mem_color_legend(Some(MemoryPressure::Normal))[0],
+            "by memory pressure"
+        );
+    }
+
+    #[test]
+    fn memory_color_falls_back_to_the_used_share() {
+        for (used, color) in [
+            (0.0, Color::Green),
+            (60.0, Color::Green),
+            (61.0, Color::Yellow),
+            (85.0, Color::Yellow),
+            (86.0, Color::Red),
+            (100.0, Color::Red),
+        ] {
+            assert_eq!(mem_color(used, 100.0, None), color, "{used} %");
+            // Only the share counts, not the scale.
+            assert_eq!(
+                mem_color(used * 1e9, 100.0 * 1e9, None),
+                color,
+                "{used} % of 100 GB"
+            );
+        }
+        assert_eq!(mem_color(0.0, 0.0, None), Color::White);
+        assert_eq!(mem_color(5.0, 0.0, None), Color::White);
+        assert_eq!(
+            mem_color_legend(None)[0],
+            "by share used (pressure unavailable)"
+        );
+    }
+
+    #[test]
+    fn long_sessions_chart_like_fresh_ones() {
+        let mut fresh = session_app(SLOT, 0.05);
+        let mut long = session_app(SLOT, 72.0);
+        assert_eq!(screen(&mut fresh, 120, 40), screen(&mut long, 120, 40));
+    }
+
+    #[test]
+    fn long_sessions_chart_like_fresh_ones_in_minute_buckets() {
+        // The six-hour window, past the fine tier, on a session just longer.
+        let mut fresh = session_app(Duration::from_secs(1), 6.5);
+        let mut long = session_app(Duration::from_secs(1), 72.0);
+        for app in [&mut fresh, &mut long] {
+            app.zoom = crate::app::ZOOM_WINDOWS
+                .iter()
+                .position(|&w| w == 21_600.0)
+                .unwrap();
+            assert!(app.coarse_zoom());
+        }
+        assert_eq!(screen(&mut fresh, 120, 40), screen(&mut long, 120, 40));
+    }
+
+    #[test]
+    fn elevated_badge_follows_the_monitor_options() {
+        for privileged in [false, true] {
+            let config = Config::default();
+            let options = MonitorOptions {
+                privileged,
+                ..App::monitor_options(&config)
+            };
+            let fixture = Fixture::new(FixtureOptions {
+                processes: 5,
+                ..FixtureOptions::default()
+            });
+            let monitor = Monitor::from_snapshots(options, fixture);
+            let mut app = App::with_monitor(&Options::default(), config, monitor, None);
+            app.tick();
+            assert_eq!(app.privileged, privileged);
+            let badge = render(&mut app, 120, 40)
+                .iter()
+                .any(|l| l.contains(" ELEVATED "));
+            assert_eq!(badge, privileged);
+        }
     }
 }
@@ -0,0 +1,653 @@
+//! Process-table view: sort order, name filter, and tree layout.
+//!
+//! The view never copies process data; it produces [`ViewRow`]s that
+//! index into the snapshot held by [`App`](crate::app::App), in display
+//! order, with any tree-drawing prefix precomputed.
+
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::mem;
+
+use ratatui::layout::{Constraint, Layout, Rect};
+
+use crate::app::KERNEL_TASK;
+use crate::monitor::{Pid, ProcessSample};
+use crate::pattern::{self, Pattern};
+
+/// Column the process table is ordered by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortKey {
+    /// Process identifier.
+    Pid,
+    /// Process name, case-insensitive.
+    Name,
+    /// Instantaneous CPU usage.
+    Cpu,
+    /// CPU-seconds accumulated over the session.
+    CpuTime,
+    /// Resident memory.
+    Memory,
+    /// Thread count; unreadable counts sort lowest.
+    Threads,
+    /// Runnable threads, then total threads; unreadable counts sort lowest.
+    Running,
+    /// Thread-count change per minute; unknown growth sorts lowest.
+    ThreadGrowth,
+}
+
+/// Static description of one process-table column.
+#[derive(Debug, Clone, Copy)]
+pub struct Column {
+    /// Identifier used by the `[table] columns` config key.
+    pub id: &'static str,
+    /// Header text.
+    pub title: &'static str,
+    /// Layout width of the column.
+    pub width: Constraint,
+    /// Sort key applied when this column is chosen.
+    pub sort: SortKey,
+    /// Whether the column is shown when the config doesn't list columns.
+    pub default_visible: bool,
+    /// Whether header and cells are right-aligned, as for numeric columns.
+    pub right_aligned: bool,
+}
+
+/// Process-table columns in display order.
+///
+/// Headers, widths, and the sort menu are all generated from this table,
+/// and `[table] columns` in the config is validated against it.
+pub const COLUMNS: [Column; 8] = [
+    Column {
+        id: "pid",
+        title: "PID",
+        width: Constraint::Length(8),
+        sort: SortKey::Pid,
+        default_visible: true,
+        right_aligned: true,
+    },
+    Column {
+        id: "name",
+        title: "Process",
+        width: Constraint::Min(20),
+        sort: SortKey::Name,
+        default_visible: true,
+        right_aligned: false,
+    },
+    Column {
+        id: "cpu",
+        title: "CPU %",
+        width: Constraint::Length(10),
+        sort: SortKey::Cpu,
+        default_visible: true,
+        right_aligned: true,
+    },
+    Column {
+        id: "cpu_time",
+        title: "Σ CPU",
+        width: Constraint::Length(10),
+        sort: SortKey::CpuTime,
+        default_visible: false,
+        right_aligned: true,
+    },
+    Column {
+        id: "memory",
+        title: "Memory",
+        width: Constraint::Length(12),
+        sort: SortKey::Memory,
+        default_visible: true,
+        right_aligned: true,
+    },
+    Column {
+        id: "threads",
+        title: "Threads",
+        width: Constraint::Length(8),
+        sort: SortKey::Threads,
+        default_visible: true,
+        right_aligned: true,
+    },
+    Column {
+        id: "run",
+        title: "Run",
+        width: Constraint::Length(9),
+        sort: SortKey::Running,
+        default_visible: false,
+        right_aligned: true,
+    },
+    Column {
+        id: "thread_growth",
+        title: "Δ Thr/min",
+        width: Constraint::Length(10),
+        sort: SortKey::ThreadGrowth,
+        default_visible: false,
+        right_aligned: true,
+    },
+];
+
+/// Blank cells between two process-table columns.
+pub const COLUMN_SPACING: u16 = 1;
+
+/// Returns the index in [`COLUMNS`] of the column with config id `id`.
+pub fn column_by_id(id: &str) -> Option<usize> {
+    COLUMNS.iter().position(|c| c.id == id)
+}
+
+impl SortKey {
+    /// Index of this key's column in [`COLUMNS`].
+    pub fn column_index(self) -> usize {
+        COLUMNS.iter().position(|c| c.sort == self).unwrap_or(0)
+    }
+
+    /// Header of the column this key sorts by.
+    pub fn label(self) -> &'static str {
+        COLUMNS[self.column_index()].title
+    }
+
+    /// Returns the key of the next column in [`COLUMNS`], wrapping around.
+    pub fn next(self) -> Self {
+        COLUMNS[(self.column_index() + 1) % COLUMNS.len()].sort
+    }
+
+    /// Whether this key naturally sorts largest-first.
+    pub fn default_descending(self) -> bool {
+        matches!(
+            self,
+            Self::Cpu
+                | Self::CpuTime
+                | Self::Memory
+                | Self::Threads
+                | Self::Running
+                | Self::ThreadGrowth
+        )
+    }
+
+    /// Compares two processes by this key, ascending.
+    pub fn compare(self, a: &ProcessSample, b: &ProcessSample) -> Ordering {
+        match self {
+            Self::Pid => a.pid.cmp(&b.pid),
+            Self::Name => a
+                .sort_name
+                .cmp(&b.sort_name)
+                .then_with(|| a.name.cmp(&b.name)),
+            Self::Cpu => a
+                .cpu_usage
+                .partial_cmp(&b.cpu_usage)
+                .unwrap_or(Ordering::Equal),
+            Self::CpuTime => a.cpu_time.total_cmp(&b.cpu_time),
+            Self::Memory => a.memory.cmp(&b.memory),
+            Self::Threads => a.threads.cmp(&b.threads),
+            Self::Running => a.running.cmp(&b.running).then(a.threads.cmp(&b.threads)),
+            Self::ThreadGrowth => {
+                let growth = |p: &ProcessSample| p.thread_growth.unwrap_or(f64::NEG_INFINITY);
+                growth(a).total_cmp(&growth(b))
+            }
+        }
+    }
+}
+
+/// Lower bounds (exclusive) of the CPU bands, hottest first.
+///
+/// Shared by row colouring and the band separators so they agree.
+pub const CPU_BAND_FLOORS: [f32; 3] = [50.0, 10.0, 1.0];
+
+/// Fractions of the table's largest value that start the relative bands, hottest first.
+pub const RELATIVE_BAND_FLOORS: [f64; 2] = [0.9, 0.8];
+
+/// Returns the relative band of `value` against `peak`: 0 for the top
+/// decile, 1 for the next, 2 for the rest.
+pub fn relative_band(value: f64, peak: f64) -> usize {
+    if peak <= 0.0 {
+        return RELATIVE_BAND_FLOORS.len();
+    }
+    RELATIVE_BAND_FLOORS
+        .iter()
+        .position(|&floor| value >= floor * peak)
+        .unwrap_or(RELATIVE_BAND_FLOORS.len())
+}
+
+/// Separator labels for each band in [`cpu_band`] order.
+/// Rows kept in order by the partial sort of high-count mode.
+pub const PARTIAL_SORT_ROWS: usize = 500;
+
+const CPU_BAND_LABELS: [&str; 4] = ["> 50 %", "10–50 %", "1–10 %", "< 1 %"];
+
+/// Returns the CPU band of `cpu`: 0 for above 50 %, up to 3 for below 1 %.
+pub fn cpu_band(cpu: f32) -> usize {
+    CPU_BAND_FLOORS
+        .iter()
+        .position(|&floor| cpu > floor)
+        .unwrap_or(CPU_BAND_FLOORS.len())
+}
+
+/// One displayed table row.
+#[derive(Debug, Clone)]
+pub enum ViewRow {
+    /// A process from the snapshot.
+    Process {
+        /// Index into the process snapshot.
+        index: usize,
+        /// Tree-drawing prefix (`│  ├─ `) placed before the name; empty in flat mode.
+        prefix: String,
+    },
+    /// Non-selectable rule above the first process of a CPU band.
+    Separator(&'static str),
+}
+
+impl ViewRow {
+    /// Snapshot index of a process row; `None` for separators.
+    pub fn index(&self) -> Option<usize> {
+        match self {
+            Self::Process { index, .. } => Some(*index),
+            Self::Separator(_) => None,
+        }
+    }
+}
+
+/// User-controlled view settings plus the rows they produce.
+#[derive(Debug, Clone)]
+pub struct ProcessView {
+    /// Column the table is ordered by.
+    pub sort: SortKey,
+    /// Whether the sort is largest-first.
+    pub descending: bool,
+    /// Case-insensitive name pattern (see [`compile_filter`]); empty shows everything.
+    pub filter: String,
+    /// Name of the `[filters]` preset that set `filter`, if any.
+    pub preset: Option<String>,
+    /// Whether processes are laid out as a parent/child tree.
+    pub tree: bool,
+    /// Whether the tree marks where a child starts its own process group.
+    pub groups: bool,
+    /// Whether CPU band separators are inserted when sorting by CPU.
+    pub bands: bool,
+    /// Whether `kernel_task` is kept out of the top ranks of the CPU sort.
+    pub demote_kernel: bool,
+    /// Visible columns, as indices into [`COLUMNS`], in display order.
+    pub columns: Vec<usize>,
+    /// Whether only processes run from external volumes are shown.
+    pub external_only: bool,
+    /// Whether only the first [`PARTIAL_SORT_ROWS`] processes are kept in order.
+    pub partial_sort: bool,
+    /// Process left out of the rows whatever the filter (syswatch itself with `[ui] hide_self`).
+    pub hidden: Option<Pid>,
+    /// Highest CPU percentage among the displayed rows.
+    pub peak_cpu: f32,
+    /// Largest resident memory among the displayed rows.
+    pub peak_memory: u64,
+    /// Displayed rows, in order.
+    pub rows: Vec<ViewRow>,
+}
+
+impl Default for ProcessView {
+    fn default() -> Self {
+        Self {
+            sort: SortKey::Cpu,
+            descending: true,
+            filter: String::new(),
+            preset: None,
+            tree: false,
+            groups: false,
+            bands: false,
+            demote_kernel: false,
+            columns: (0..COLUMNS.len())
+                .filter(|&i| COLUMNS[i].default_visible)
+                .collect(),
+            external_only: false,
+            partial_sort: false,
+            hidden: None,
+            peak_cpu: 0.0,
+            peak_memory: 0,
+            rows: Vec::new(),
+        }
+    }
+}
+
+impl ProcessView {
+    /// Switches to `key`, using that column's natural direction.
+    pub fn set_sort(&mut self, key: SortKey) {
+        self.sort = key;
+        self.descending = key.default_descending();
+    }
+
+    /// Sorts `procs` in place by the current key and direction.
+    ///
+    /// With `partial_sort` only the first [`PARTIAL_SORT_ROWS`] end up in
+    /// order; the rest follow in no particular order.
+    pub fn sort(&self, procs: &mut [ProcessSample]) {
+        let compare = |a: &ProcessSample, b: &ProcessSample| self.compare(a, b);
+        if self.partial_sort {
+            partial_sort_by(procs, compare);
+        } else {
+            procs.sort_by(compare);
+        }
+    }
+
+    /// Order of `a` and `b` under the current key and direction.
+    fn compare(&self, a: &ProcessSample, b: &ProcessSample) -> Ordering {
+        let demote = self.demote_kernel && self.sort == SortKey::Cpu;
+        let kernel = |p: &ProcessSample| demote && p.name == KERNEL_TASK;
+        let ord = self.sort.compare(a, b);
+        let ord = if self.descending { ord.reverse() } else { ord };
+        kernel(a).cmp(&kernel(b)).then(ord)
+    }
+
+    /// Layout widths of the visible columns, in display order.
+    pub fn widths(&self) -> Vec<Constraint> {
+        self.columns.iter().map(|&c| COLUMNS[c].width).collect()
+    }
+
+    /// Splits a table-wide `area` into the visible columns' cells.
+    ///
+    /// This is the layout ratatui's `Table` applies to [`widths`] after
+    /// `selection` cells for the highlight symbol, so anything drawn
+    /// outside the table with it lines up with the table's columns.
+    ///
+    /// [`widths`]: Self::widths
+    pub fn column_areas(&self, area: Rect, selection: u16) -> Vec<Rect> {
+        let [_, columns] =
+            Layout::horizontal([Constraint::Length(selection), Constraint::Fill(0)]).areas(area);
+        Layout::horizontal(self.widths())
+            .spacing(COLUMN_SPACING)
+            .split(columns)
+            .to_vec()
+    }
+
+    /// Number of process (non-separator) rows.
+    pub fn process_count(&self) -> usize {
+        self.rows.iter().filter(|r| r.index().is_some()).count()
+    }
+
+    /// Whether band separators apply to the current layout.
+    pub fn shows_bands(&self) -> bool {
+        self.bands && self.sort == SortKey::Cpu && !self.tree
+    }
+
+    /// Rebuilds `rows` from an already sorted snapshot.
+    pub fn rebuild(&mut self, procs: &[ProcessSample]) {
+        let matcher = if self.filter.is_empty() {
+            None
+        } else {
+            // A half-typed pattern such as `node|(` falls back to a literal match.
+            compile_filter(&self.filter)
+                .or_else(|_| compile_filter(&pattern::escape(&self.filter)))
+                .ok()
+        };
+        let matches: Vec<bool> = procs
+            .iter()
+            .map(|p| {
+                Some(p.pid) != self.hidden
+                    && (!self.external_only || p.volume.is_some())
+                    && matcher.as_ref().is_none_or(|m| m.is_match(&p.name))
+            })
+            .collect();
+
+        self.rows = if self.tree {
+            tree_rows(procs, &matches, self.groups)
+        } else {
+            let mut shown: Vec<usize> = (0..procs.len()).filter(|&i| matches[i]).collect();
+            // A partial sort only ordered the head of the whole list, where
+            // few matches may fall; order the head of the matches instead.
+            if self.partial_sort && (matcher.is_some() || self.external_only) {
+                partial_sort_by(&mut shown, |&a, &b| self.compare(&procs[a], &procs[b]));
+            }
+            shown
+                .into_iter()
+                .map(|index| ViewRow::Process {
+                    index,
+                    prefix: String::new(),
+                })
+                .collect()
+        };
+
+        if self.shows_bands() {
+            self.rows = with_band_separators(procs, mem::take(&mut self.rows));
+        }
+
+        (self.peak_cpu, self.peak_memory) = self
+            .rows
+            .iter()
+            .filter_map(ViewRow::index)
+            .map(|i| &procs[i])
+            .fold((0.0_f32, 0), |(cpu, mem), p| {
+                (cpu.max(p.cpu_usage), mem.max(p.memory))
+            });
+    }
+}
+
+/// Puts the first [`PARTIAL_SORT_ROWS`] of `items` in order, leaving the
+/// rest behind them in no particular order.
+fn partial_sort_by<T>(items: &mut [T], mut compare: impl FnMut(&T, &T) -> Ordering) {
+    if items.len() > PARTIAL_SORT_ROWS {
+        items.select_nth_unstable_by(PARTIAL_SORT_ROWS, &mut compare);
+        items[..PARTIAL_SORT_ROWS].sort_by(compare);
+    } else {
+        items.sort_by(compare);
+    }
+}
+
+/// Compiles a name filter as a case-insensitive [`Pattern`].
+///
+/// Used for both the interactive `/` filter and `[filters]` presets so
+/// they match the same way. Case folding covers any script, not just
+/// ASCII.
+///
+/// ```
+/// use syswatch::view::compile_filter;
+///
+/// let filter = compile_filter("ÉDIT").unwrap();
+/// assert!(filter.is_match("🚀 Éditeur"));
+/// assert!(compile_filter("οδος").unwrap().is_match("ΟΔΟΣ-Sync"));
+/// assert!(compile_filter("日本").unwrap().is_match("日本語アプリ"));
+/// // A combining accent is its own character: `e` matches, `é` does not.
+/// assert!(compile_filter("cafe").unwrap().is_match("Cafe\u{301}"));
+/// assert!(!compile_filter("café").unwrap().is_match("Cafe\u{301}"));
+/// ```
+pub fn compile_filter(text: &str) -> Result<Pattern, String> {
+    Pattern::new(&format!(
+        "(?i){}",
+        text.strip_prefix("(?i)").unwrap_or(text)
+    ))
+}
+
+/// Inserts a separator wherever consecutive rows fall into different CPU bands.
+fn with_band_separators(procs: &[ProcessSample], rows: Vec<ViewRow>) -> Vec<ViewRow> {
+    let mut out = Vec::with_capacity(rows.len() + CPU_BAND_LABELS.len());
+    let mut prev = None;
+
+    for row in rows {
+        if let Some(index) = row.index() {
+            let band = cpu_band(procs[index].cpu_usage);
+            if prev.is_some_and(|p| p != band) {
+                out.push(ViewRow::Separator(CPU_BAND_LABELS[band]));
+            }
+            prev = Some(band);
+        }
+        out.push(row);
+    }
+    out
+}
+
+/// Lays processes out depth-first under their parents.
+///
+/// Siblings keep the snapshot's sort order. A filtered-out process is
+/// still shown when one of its descendants matches, so matches keep
+/// their ancestry. With `groups`, a child in a different process group
+/// than its parent — a pipeline or job started from a shell — hangs off
+/// a double-lined connector (`╞═`), marking the group boundary.
+fn tree_rows(procs: &[ProcessSample], matches: &[bool], groups: bool) -> Vec<ViewRow> {
+    let position: HashMap<Pid, usize> = procs.iter().enumerate().map(|(i, p)| (p.pid, i)).collect();
+
+    let mut children = vec![Vec::new(); procs.len()];
+    let mut roots = Vec::new();
+    for (i, p) in procs.iter().enumerate() {
+        match p.parent.and_then(|ppid| position.get(&ppid)) {
+            Some(&parent) if parent != i => children[parent].push(i),
+            _ => roots.push(i),
+        }
+    }
+
+    let mut keep = vec![None; procs.len()];
+    for &root in &roots {
+        mark_kept(root, &children, matches, &mut keep);
+    }
+
+    let mut rows = Vec::new();
+    let visible = |list: &[usize]| -> Vec<usize> {
+        list.iter()
+            .copied()
+            .filter(|&i| keep[i] == Some(true))
+            .collect()
+    };
+
+    // Stack of (index, parent index, ancestor "is last sibling" flags).
+    let roots = visible(&roots);
+    let mut stack: Vec<(usize, Option<usize>, Vec<bool>)> =
+        roots.iter().rev().map(|&i| (i, None, Vec::new())).collect();
+
+    while let Some((index, parent, lasts)) = stack.pop() {
+        let mut prefix = String::new();
+        if let Some((&last, ancestors)) = lasts.split_last() {
+            for &ancestor_last in ancestors {
+                prefix.push_str(if ancestor_last { "   " } else { "│  " });
+            }
+            let new_group =
+                groups && parent.is_some_and(|parent| procs[parent].pgid != procs[index].pgid);
+            prefix.push_str(match (last, new_group) {
+                (true, false) => "└─ ",
+                (false, false) => "├─ ",
+                (true, true) => "╘═ ",
+                (false, true) => "╞═ ",
+            });
+        }
+        rows.push(ViewRow::Process { index, prefix });
+
+        let kids = visible(&children[index]);
+        for (n, &child) in kids.iter().enumerate().rev() {
+            let mut child_lasts = lasts.clone();
+            child_lasts.push(n + 1 == kids.len());
+            stack.push((child, Some(index), child_lasts));
+        }
+    }
+
+    rows
+}
+
+/// Computes whether `index` or any descendant matches, memoised in `keep`.
+fn mark_kept(
+    index: usize,
+    children: &[Vec<usize>],
+    matches: &[bool],
+    keep: &mut [Option<bool>],
+) -> bool {
+    if let Some(k) = keep[index] {
+        return k;
+    }
+    // Provisional value guards against parent/child cycles.
+    keep[index] = Some(false);
+
+    let mut kept = matches[index];
+    for &child in &children[index] {
+        kept |= mark_kept(child, children, matches, keep);
+    }
+    keep[index] = Some(kept);
+    kept
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Names the filter is matched against, in snapshot order.
+    fn shown(view: &mut ProcessView, procs: &[ProcessSample], filter: &str) -> Vec<String> {
+        view.filter = filter.to_string();
+        view.rebuild(procs);
+        view.rows
+            .iter()
+            .filter_map(ViewRow::index)
+            .map(|i| procs[i].name.clone())
+            .collect()
+    }
+
+    #[test]
+    fn filter_is_a_caseless_pattern_with_a_literal_fallback() {
+        let procs: Vec<_> = ["node", "Python3", "cargo", "zsh", "node|("]
+            .iter()
+            .enumerate()
+            .map(|(i, name)| ProcessSample::new(i as Pid + 100, name))
+            .collect();
+        let mut view = ProcessView::default();
+
+        assert_eq!(
+            shown(&mut view, &procs, "NODE|python"),
+            ["node", "Python3", "node|("]
+        );
+        assert_eq!(shown(&mut view, &procs, r"^\w+\d$"), ["Python3"]);
+        // A half-typed pattern is taken literally.
+        assert_eq!(shown(&mut view, &procs, "node|("), ["node|("]);
+        assert_eq!(
+            shown(&mut view, &procs, ""),
+            procs.iter().map(|p| p.name.clone()).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn nested_quantifier_filter_stays_fast_on_long_names() {
+        // Used to freeze the TUI: the old matcher backtracked exponentially.
+        let procs: Vec<_> = (0..500)
+            .map(|i| {
+                ProcessSample::new(
+                    i,
+                    &format!("com.apple.WebKit.WebContent_{i:03}_helper_process"),
+                )
+            })
+            .collect();
+        let mut view = ProcessView::default();
+        assert!(shown(&mut view, &procs, r"(\w+)+z").is_empty());
+    }
+
+    #[test]
+    fn partial_sort_orders_the_head_of_the_filtered_rows() {
+        // The busiest 5 000 processes are `worker`s, so after a partial
+        // sort of the whole list every `node` lies in the unordered tail.
+        let procs: Vec<_> = (0..10_000)
+            .map(|i| {
+                let name = if i % 2 == 0 { "worker" } else { "node" };
+                ProcessSample {
+                    cpu_usage: if i % 2 == 0 { 50.0 } else { 0.0 }
+                        + (i * 7919 % 10_000) as f32 / 1000.0,
+                    ..ProcessSample::new(i as Pid + 1, name)
+                }
+            })
+            .collect();
+        let mut view = ProcessView {
+            partial_sort: true,
+            ..ProcessView::default()
+        };
+        let mut sorted = procs.clone();
+        view.sort(&mut sorted);
+        let cpu: Vec<f32> = {
+            view.filter = "node".to_string();
+            view.rebuild(&sorted);
+            view.rows
+                .iter()
+                .filter_map(ViewRow::index)
+                .map(|i| sorted[i].cpu_usage)
+                .collect()
+        };
+        assert_eq!(cpu.len(), 5_000);
+        let head = &cpu[..PARTIAL_SORT_ROWS];
+        assert!(head.windows(2).all(|w| w[0] >= w[1]), "head out of order");
+        // Nothing behind the head is busier than its last row.
+        assert!(
+            cpu[PARTIAL_SORT_ROWS..]
+                .iter()
+                .all(|&c| c <= head[PARTIAL_SORT_ROWS - 1])
+        );
+        let mut busiest: Vec<f32> = procs
+            .iter()
+            .filter(|p| p.name == "node")
+            .map(|p| p.cpu_usage)
+            .collect();
+        busiest.sort_by(|a, b| b.total_cmp(a));
+        assert_eq!(head, &busiest[..PARTIAL_SORT_ROWS]);
+    }
+}
@@ -0,0 +1,114 @@
+//! Which volume each process's executable lives on.
+//!
+//! Executables on an external disk or a mounted disk image explain some
+//! odd stalls: the volume spun down, or the image was ejected under the
+//! running process. [`Volumes`] lists the mount table through `sysinfo`
+//! at most every [`MOUNT_POLL`], and classifies each PID once by the
+//! longest mount point its executable path starts with. Classifications
+//! are kept until the mount table changes, so steady ticks only look up
+//! a cached answer.
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use sysinfo::{DiskRefreshKind, Disks};
+
+use crate::monitor::Pid;
+
+/// How often the mount table is re-read to detect changes.
+pub const MOUNT_POLL: Duration = Duration::from_secs(5);
+
+/// Where ejectable volumes and mounted disk images appear on macOS.
+const EXTERNAL_ROOT: &str = "/Volumes/";
+
+/// One mounted volume.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Mount {
+    /// Mount point.
+    path: PathBuf,
+    /// Whether it is an external disk or disk image rather than an internal one.
+    external: bool,
+}
+
+/// Mount table and per-PID classification cache.
+#[derive(Debug)]
+pub struct Volumes {
+    /// Mounts, longest path first so the first prefix match is the best one.
+    mounts: Vec<Mount>,
+    /// When the mount table was last read.
+    polled: Instant,
+    /// Start time and external mount point (if any) per classified PID.
+    classified: HashMap<Pid, (u64, Option<Arc<str>>)>,
+}
+
+impl Default for Volumes {
+    fn default() -> Self {
+        Self {
+            mounts: read_mounts(),
+            polled: Instant::now(),
+            classified: HashMap::new(),
+        }
+    }
+}
+
+impl Volumes {
+    /// Re-reads the mount table if [`MOUNT_POLL`] has passed, dropping
+    /// every classification when it changed.
+    pub fn poll(&mut self) {
+        if self.polled.elapsed() < MOUNT_POLL {
+            return;
+        }
+        self.polled = Instant::now();
+        let mounts = read_mounts();
+        if mounts != self.mounts {
+            self.mounts = mounts;
+            self.classified.clear();
+        }
+    }
+
+    /// Mount point of the external volume holding `exe`, if it is on one.
+    ///
+    /// Keyed by PID and start time, so a reused PID is classified afresh.
+    pub fn classify(&mut self, pid: Pid, started: u64, exe: Option<&Path>) -> Option<Arc<str>> {
+        if let Some((start, volume)) = self.classified.get(&pid)
+            && *start == started
+        {
+            return volume.clone();
+        }
+        let volume = exe.and_then(|exe| {
+            self.mounts
+                .iter()
+                .find(|m| exe.starts_with(&m.path))
+                .filter(|m| m.external)
+                .map(|m| Arc::from(m.path.to_string_lossy()))
+        });
+        self.classified.insert(pid, (started, volume.clone()));
+        volume
+    }
+
+    /// Forgets PIDs that are no longer alive.
+    pub fn prune(&mut self, alive: &HashSet<Pid>) {
+        self.classified.retain(|pid, _| alive.contains(pid));
+    }
+}
+
+/// Lists the mounted volumes, longest mount point first.
+fn read_mounts() -> Vec<Mount> {
+    let disks = Disks::new_with_refreshed_list_specifics(DiskRefreshKind::nothing());
+    let mut mounts: Vec<Mount> = disks
+        .list()
+        .iter()
+        .map(|d| Mount {
+            path: d.mount_point().to_path_buf(),
+            external: d.is_removable()
+                || d.mount_point().to_string_lossy().starts_with(EXTERNAL_ROOT),
+        })
+        .collect();
+    mounts.sort_by(|a, b| {
+        let len = |m: &Mount| m.path.as_os_str().len();
+        len(b).cmp(&len(a)).then_with(|| a.path.cmp(&b.path))
+    });
+    mounts
+}
@@ -0,0 +1,111 @@
+//! Webhook delivery of alerts.
+//!
+//! Payloads are POSTed from a worker thread so a slow endpoint never
+//! stalls the UI. Each delivery gets a short timeout and a couple of
+//! retries; failures come back over a channel for the status bar.
+
+use std::io;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+use crate::alerts::{Alert, AlertKind};
+use crate::http::{self, Url};
+
+/// Connect / read / write timeout per attempt.
+const TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Attempts per payload, including the first.
+const ATTEMPTS: u32 = 3;
+
+/// Pause between attempts.
+const RETRY_DELAY: Duration = Duration::from_millis(500);
+
+/// Background sender for one webhook URL.
+#[derive(Debug)]
+pub struct Webhook {
+    payloads: Sender<String>,
+    /// Delivery failures reported by the worker.
+    errors: Receiver<String>,
+    /// Failed deliveries over the session.
+    pub failures: u64,
+}
+
+impl Webhook {
+    /// Starts a worker posting to `url`.
+    pub fn spawn(url: Url) -> Self {
+        let (payloads, rx) = mpsc::channel::<String>();
+        let (err_tx, errors) = mpsc::channel();
+        thread::spawn(move || {
+            for body in rx {
+                if let Err(msg) = deliver(&url, &body) {
+                    let _ = err_tx.send(msg);
+                }
+            }
+        });
+        Self {
+            payloads,
+            errors,
+            failures: 0,
+        }
+    }
+
+    /// Queues `body` for delivery.
+    pub fn send(&self, body: String) {
+        // The worker only exits when this handle is dropped.
+        let _ = self.payloads.send(body);
+    }
+
+    /// Drains failures reported since the last call, returning the newest one.
+    pub fn take_error(&mut self) -> Option<String> {
+        let mut newest = None;
+        for msg in self.errors.try_iter() {
+            self.failures += 1;
+            newest = Some(msg);
+        }
+        newest
+    }
+}
+
+/// POSTs `body`, retrying transport errors and 5xx responses.
+fn deliver(url: &Url, body: &str) -> Result<u16, String> {
+    let mut last_err = String::new();
+    for attempt in 1..=ATTEMPTS {
+        match post(url, body) {
+            Ok(status) if status < 500 => {
+                return if (200..300).contains(&status) {
+                    Ok(status)
+                } else {
+                    Err(format!("webhook {url} answered HTTP {status}"))
+                };
+            }
+            Ok(status) => last_err = format!("webhook {url} answered HTTP {status}"),
+            Err(err) => last_err = format!("webhook {url}: {err}"),
+        }
+        if attempt < ATTEMPTS {
+            thread::sleep(RETRY_DELAY);
+        }
+    }
+    Err(last_err)
+}
+
+/// One POST attempt.
+fn post(url: &Url, body: &str) -> io::Result<u16> {
+    http::post(url, "application/json", &[], body.as_bytes(), TIMEOUT)
+}
+
+/// Sends a sample alert to `url` synchronously, for `--test-webhook`.
+///
+/// Returns the final HTTP status, or the error after all retries.
+pub fn test(url: &Url, host: &str) -> Result<u16, String> {
+    let sample = Alert {
+        kind: AlertKind::ProcessCpu,
+        subject: Some((std::process::id(), "syswatch-test".to_string())),
+        value: 99.5,
+        threshold: 90.0,
+        detail: None,
+        time: SystemTime::now(),
+        notify: true,
+    };
+    deliver(url, &sample.to_json(host))
+}
@@ -0,0 +1,106 @@
+//! Instance locks between real syswatch processes sharing a `$HOME`.
+
+use std::fs;
+use std::path::Path;
+use std::process::{Child, Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use syswatch::instance::{Claims, InstanceLock, LockError, lock_dir};
+use syswatch::socket;
+
+/// Longest wait for a started instance to write its lock file or socket.
+const STARTUP: Duration = Duration::from_secs(10);
+
+/// Starts `syswatch --plain` with `args`, in the test's `$HOME`.
+fn spawn(args: &[&str]) -> Child {
+    Command::new(env!("CARGO_BIN_EXE_syswatch"))
+        .arg("--plain")
+        .args(args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap()
+}
+
+/// Waits until `child` has created `path`, asserting it is still running then.
+fn wait_for(child: &mut Child, path: &Path) {
+    let deadline = Instant::now() + STARTUP;
+    while !path.exists() {
+        assert_eq!(child.try_wait().unwrap(), None, "exited before {path:?}");
+        assert!(Instant::now() < deadline, "no {path:?} after {STARTUP:?}");
+        thread::sleep(Duration::from_millis(20));
+    }
+}
+
+/// Waits until `child` has written its lock file in `dir`.
+fn wait_for_lock(child: &mut Child, dir: &Path) {
+    wait_for(child, &dir.join(child.id().to_string()));
+}
+
+/// Stops `child` with Ctrl-C and returns what it printed to stderr.
+fn stop(child: Child) -> String {
+    unsafe { libc::kill(child.id() as libc::pid_t, libc::SIGINT) };
+    let output = child.wait_with_output().unwrap();
+    String::from_utf8_lossy(&output.stderr).into_owned()
+}
+
+#[test]
+fn only_explicit_paths_conflict() {
+    let home = std::env::temp_dir().join(format!("syswatch-instance-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&home);
+    fs::create_dir_all(&home).unwrap();
+    // The only test in this binary, so nothing else reads `$HOME` meanwhile.
+    unsafe { std::env::set_var("HOME", &home) };
+    let dir = lock_dir().unwrap();
+    let explicit = home.join("explicit.sock");
+
+    let mut claimed = spawn(&["--socket", explicit.to_str().unwrap()]);
+    wait_for_lock(&mut claimed, &dir);
+    let mut first = spawn(&[]);
+    wait_for_lock(&mut first, &dir);
+    wait_for(&mut first, &socket::default_path().unwrap());
+    // A second instance on the default socket starts too, just without it.
+    let mut second = spawn(&[]);
+    wait_for_lock(&mut second, &dir);
+
+    // A lock left by an exited process is stale.
+    let mut exited = Command::new("true").spawn().unwrap();
+    exited.wait().unwrap();
+    let stale = dir.join(exited.id().to_string());
+    fs::write(&stale, "").unwrap();
+
+    let default = Claims {
+        socket: socket::default_path(),
+        record: None,
+    };
+    let lock = InstanceLock::acquire(&default, false).unwrap();
+    let mut running = vec![claimed.id(), first.id(), second.id()];
+    running.sort_unstable();
+    assert_eq!(lock.others, running);
+    assert!(!stale.exists());
+    let own = dir.join(std::process::id().to_string());
+    assert!(own.exists());
+    drop(lock);
+    assert!(!own.exists());
+
+    let wanted = Claims {
+        socket: Some(explicit.clone()),
+        record: None,
+    };
+    match InstanceLock::acquire(&wanted, false) {
+        Err(LockError::Conflict(conflict)) => {
+            assert_eq!(conflict.pid, claimed.id());
+            assert_eq!(conflict.option, "--socket");
+            assert_eq!(conflict.path, explicit);
+        }
+        other => panic!("expected a conflict, got {other:?}"),
+    }
+    assert!(InstanceLock::acquire(&wanted, true).is_ok());
+
+    stop(claimed);
+    assert!(!stop(first).contains("query socket"));
+    assert!(stop(second).contains("running without a query socket"));
+    fs::remove_dir_all(&home).unwrap();
+}
@@ -0,0 +1,89 @@
+//! Drives the live TUI loop with scripted keys over fixture snapshots.
+
+use crossterm::event::KeyCode;
+use ratatui::Terminal;
+use ratatui::backend::TestBackend;
+use syswatch::app::App;
+use syswatch::cli::Options;
+use syswatch::config::Config;
+use syswatch::fixtures::{Fixture, FixtureOptions};
+use syswatch::input::{Script, Step};
+use syswatch::monitor::{Monitor, Pid};
+use syswatch::view::SortKey;
+
+/// A fixed process list: no PIDs come or go between snapshots.
+fn fixture() -> FixtureOptions {
+    FixtureOptions {
+        processes: 200,
+        churn: 0.0,
+        ..FixtureOptions::default()
+    }
+}
+
+/// An app on the fixture, sorted by PID so CPU jitter can't reorder rows.
+fn app(config: &str) -> App {
+    let config = Config::parse(config).unwrap();
+    let monitor = Monitor::from_snapshots(App::monitor_options(&config), Fixture::new(fixture()));
+    let mut app = App::with_monitor(&Options::default(), config, monitor, None);
+    app.view.set_sort(SortKey::Pid);
+    app.tick();
+    app
+}
+
+/// PIDs of the fixture's processes whose name contains `needle`, ascending.
+fn pids_matching(needle: &str) -> Vec<Pid> {
+    let mut pids: Vec<Pid> = Fixture::new(fixture())
+        .next()
+        .unwrap()
+        .processes
+        .iter()
+        .filter(|p| p.name.to_lowercase().contains(needle))
+        .map(|p| p.pid)
+        .collect();
+    pids.sort_unstable();
+    pids
+}
+
+#[test]
+fn filter_navigate_and_quit() {
+    let app = app("[tick]\ninterval = 0.2\n[ui]\naccelerate_keys = false\n");
+    // Wait two ticks, filter for "helper", move down twice, then quit.
+    let script = Script::new([
+        Step::Ticks(2),
+        Step::Text("/helper".into()),
+        Step::Key(KeyCode::Enter),
+        Step::Text("jjq".into()),
+    ]);
+    let mut terminal = Terminal::new(TestBackend::new(120, 40)).unwrap();
+    let app = syswatch::tui::run(&mut terminal, app, &script, &mut Vec::new()).unwrap();
+
+    assert!(!app.running);
+    assert_eq!(app.view.filter, "helper");
+    assert!(!app.filter_editing);
+    let helpers = pids_matching("helper");
+    assert!(helpers.len() > 3);
+    assert_eq!(app.view.process_count(), helpers.len());
+    assert_eq!(app.table_state.selected(), Some(2));
+    assert_eq!(app.selected_process().map(|p| p.pid), Some(helpers[2]));
+
+    let screen: String = terminal
+        .backend()
+        .buffer()
+        .content
+        .iter()
+        .map(|c| c.symbol())
+        .collect();
+    assert!(screen.contains("/helper"));
+}
+
+#[test]
+fn title_goes_to_the_given_writer() {
+    let app = app("[ui]\ntitle = true\ntitle_template = \"{procs} procs\"\n");
+    let script = Script::new([Step::Key(KeyCode::Char('q'))]);
+    let mut terminal = Terminal::new(TestBackend::new(80, 24)).unwrap();
+    let mut title = Vec::new();
+    syswatch::tui::run(&mut terminal, app, &script, &mut title).unwrap();
+
+    let title = String::from_utf8(title).unwrap();
+    assert!(title.contains("\x1b]0;200 procs\x07"), "{title:?}");
+}